@@ -0,0 +1,280 @@
+//index suggestions for a workload of queries, from the columns their conditions compare and their ORDER BY sorts by
+//nothing is measured: a suggestion is a guess at what an index would help, not a cost estimate
+use crate::statement::{Statement, Expression, TableReference, BinaryOperator, UnaryOperator, Constraint, IndexColumn};
+use crate::rewrite::for_each_select_mut;
+use crate::fold::fold;
+use crate::normalize::swapped_operator;
+
+/// An index on a table, with its columns in index order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Index {
+    pub table_name: String,
+    pub columns: Vec<String>,
+}
+
+/// What the advisor knows of the schema: the columns of every table (from `CREATE TABLE`) and the indexes there are, one for every `CREATE INDEX` and for every `PRIMARY KEY` column.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    pub tables: Vec<(String, Vec<String>)>,
+    pub indexes: Vec<Index>,
+}
+
+impl Catalog {
+    /// Builds the catalog from the DDL of a schema, other statements are skipped.
+    pub fn from_statements(stmts: &[Statement]) -> Catalog {
+        let mut catalog = Catalog::default();
+        for stmt in stmts {
+            match stmt {
+                //the columns of a table created from a query are not known, so nothing is said to be missing from it
+                Statement::CreateTable { query: Some(_), .. } => {}
+                Statement::CreateTable { table_name, column_list, .. } => {
+                    catalog.tables.push((table_name.clone(), column_list.iter().map(|column| column.column_name.clone()).collect()));
+                    for column in column_list.iter().filter(|column| column.constraints.contains(&Constraint::PrimaryKey)) {
+                        catalog.indexes.push(Index { table_name: table_name.clone(), columns: vec![column.column_name.clone()] });
+                    }
+                }
+                Statement::CreateIndex { table_name, columns, .. } => {
+                    catalog.indexes.push(Index { table_name: table_name.clone(), columns: columns.iter().map(|column| column.name.clone()).collect() });
+                }
+                _ => {}
+            }
+        }
+        catalog
+    }
+
+    //helper, whether the catalog knows the table and it has no such column
+    fn lacks_column(&self, table_name: &str, column: &str) -> bool {
+        self.tables.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(table_name))
+            .is_some_and(|(_, columns)| !columns.iter().any(|name| name.eq_ignore_ascii_case(column)))
+    }
+}
+
+/// A suggested index.
+/// 1. `index` – The table and the columns: the ones compared for equality first, then one compared with a range or the `ORDER BY` columns.
+/// 2. `queries` – How many queries of the workload the index may help.
+/// 3. `reason` – How the first query that asked for it uses the columns, `customer_id =, created_at >`, `join on customer_id` or `ORDER BY created_at`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Suggestion {
+    pub index: Index,
+    pub queries: usize,
+    pub reason: String,
+}
+
+impl Suggestion {
+    /// The suggestion as a `CREATE INDEX` statement, named after the table and its columns.
+    pub fn to_statement(&self) -> Statement {
+        let name = format!("idx_{}_{}", self.index.table_name, self.index.columns.join("_")).to_lowercase();
+        Statement::CreateIndex {
+            name,
+            table_name: self.index.table_name.clone(),
+            columns: self.index.columns.iter().map(|name| IndexColumn { name: name.clone(), descending: false }).collect(),
+            unique: false,
+            if_not_exists: false,
+        }
+    }
+}
+
+//one index a single query asks for: `equality` leading columns the query compares with `=`, in any order
+struct Candidate {
+    index: Index,
+    equality: usize,
+    reason: String,
+}
+
+/// Suggests indexes for the queries of a workload. Every `SELECT`, subqueries included, and every `DELETE` is looked at on its own: per table, the columns its `WHERE` and `ON` conditions compare with `=` to a constant or a parameter (or a column of an outer query) come first, then the first column compared with `<`, `<=`, `>` or `>=`, or else the `ORDER BY` columns when the query reads only that table. The columns a join condition compares with `=` to a column of another table make a candidate of their own, to look up the rows of the join. A candidate an index of the catalog already serves (its leading columns, the `=` ones in any order) is dropped, and so is one that a longer suggestion starts with, which takes over its queries. Unqualified columns are put with the only table of the query, or with the one table of the query the catalog says has the column. The suggestions come most used first.
+pub fn suggest_indexes(catalog: &Catalog, workload: &[Statement]) -> Vec<Suggestion> {
+    let mut candidates = Vec::new();
+    for stmt in workload {
+        if let Statement::Delete { table, where_clause } = stmt {
+            let sources = vec![(table.clone(), table.clone())];
+            candidates.extend(query_candidates(catalog, &sources, where_clause.iter().collect(), &[]));
+        }
+        let mut stmt = stmt.clone();
+        for_each_select_mut(&mut stmt, &mut |select| {
+            if let Statement::Select { from, r#where, orderby, .. } = select {
+                let mut sources = Vec::new();
+                let mut conditions: Vec<&Expression> = r#where.iter().collect();
+                for table in from.iter() {
+                    table_sources(table, &mut sources, &mut conditions);
+                }
+                candidates.extend(query_candidates(catalog, &sources, conditions, orderby));
+            }
+        });
+    }
+
+    let mut suggestions: Vec<(Candidate, usize)> = Vec::new();
+    for candidate in candidates {
+        if catalog.indexes.iter().any(|index| serves(index, &candidate)) {
+            continue;
+        }
+        match suggestions.iter_mut().find(|(suggested, _)| suggested.index == candidate.index) {
+            Some((_, queries)) => *queries += 1,
+            None => suggestions.push((candidate, 1)),
+        }
+    }
+    //a suggestion another one starts with is folded into the longer one
+    suggestions.sort_by_key(|(candidate, _)| std::cmp::Reverse(candidate.index.columns.len()));
+    let mut kept: Vec<(Candidate, usize)> = Vec::new();
+    for (candidate, queries) in suggestions {
+        match kept.iter_mut().find(|(longer, _)| serves(&longer.index, &candidate)) {
+            Some((_, longer_queries)) => *longer_queries += queries,
+            None => kept.push((candidate, queries)),
+        }
+    }
+    let mut kept: Vec<Suggestion> = kept.into_iter()
+        .map(|(candidate, queries)| Suggestion { index: candidate.index, queries, reason: candidate.reason })
+        .collect();
+    kept.sort_by(|a, b| b.queries.cmp(&a.queries).then_with(|| a.index.table_name.to_lowercase().cmp(&b.index.table_name.to_lowercase())));
+    kept
+}
+
+//the tables a FROM item reads, as (name the query refers to it by, table name), and the ON conditions of its joins
+//derived tables are queries of their own, reached through for_each_select_mut
+fn table_sources<'a>(table: &'a TableReference, sources: &mut Vec<(String, String)>, conditions: &mut Vec<&'a Expression>) {
+    match table {
+        TableReference::Table { name, alias, .. } => sources.push((alias.as_ref().unwrap_or(name).clone(), name.clone())),
+        TableReference::Pivot { table, .. } | TableReference::Unpivot { table, .. } => table_sources(table, sources, conditions),
+        TableReference::Join { left, right, on, .. } => {
+            table_sources(left, sources, conditions);
+            table_sources(right, sources, conditions);
+            conditions.extend(on.iter());
+        }
+        TableReference::Derived { .. } | TableReference::Function { .. } => {}
+    }
+}
+
+//the candidates of one query, one per table with a column worth indexing
+fn query_candidates(catalog: &Catalog, sources: &[(String, String)], conditions: Vec<&Expression>, orderby: &[Expression]) -> Vec<Candidate> {
+    let mut parts = Vec::new();
+    for condition in conditions {
+        conjuncts(condition, &mut parts);
+    }
+    //(source index, column, operator) of every comparison of a column of the query with a value, and the columns of join conditions
+    let mut compared: Vec<(usize, String, BinaryOperator)> = Vec::new();
+    let mut joined: Vec<(usize, String)> = Vec::new();
+    for part in parts {
+        let Expression::BinaryOperation { left_operand, operator, right_operand } = part else { continue };
+        if !matches!(operator, BinaryOperator::Equal | BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual | BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual) {
+            continue;
+        }
+        let (left, right) = (column_source(catalog, sources, left_operand), column_source(catalog, sources, right_operand));
+        match (left, right) {
+            //a join condition is a lookup on either side
+            (Some((left_source, left_column)), Some((right_source, right_column))) if left_source != right_source && *operator == BinaryOperator::Equal => {
+                joined.push((left_source, left_column));
+                joined.push((right_source, right_column));
+            }
+            (Some((source, column)), None) if is_value(sources, right_operand) => compared.push((source, column, *operator)),
+            (None, Some((source, column))) if is_value(sources, left_operand) => {
+                compared.push((source, column, swapped_operator(*operator).unwrap()));
+            }
+            _ => {}
+        }
+    }
+
+    //a table gets one candidate for filtering its rows and one for looking them up in a join
+    let mut candidates = Vec::new();
+    for (i, (_, table_name)) in sources.iter().enumerate() {
+        let mut columns: Vec<String> = Vec::new();
+        let mut reason: Vec<String> = Vec::new();
+        for (_, column) in joined.iter().filter(|(source, _)| *source == i) {
+            if !columns.iter().any(|name| name.eq_ignore_ascii_case(column)) {
+                columns.push(column.clone());
+                reason.push(format!("join on {}", column));
+            }
+        }
+        if !columns.is_empty() {
+            let equality = columns.len();
+            candidates.push(Candidate { index: Index { table_name: table_name.clone(), columns }, equality, reason: reason.join(", ") });
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut reason: Vec<String> = Vec::new();
+        for (_, column, _) in compared.iter().filter(|(source, _, operator)| *source == i && *operator == BinaryOperator::Equal) {
+            if !columns.iter().any(|name| name.eq_ignore_ascii_case(column)) {
+                columns.push(column.clone());
+                reason.push(format!("{} =", column));
+            }
+        }
+        let equality = columns.len();
+        let range = compared.iter().find(|(source, column, operator)| {
+            *source == i && *operator != BinaryOperator::Equal && !columns.iter().any(|name| name.eq_ignore_ascii_case(column))
+        });
+        if let Some((_, column, operator)) = range {
+            columns.push(column.clone());
+            reason.push(format!("{} {}", column, operator));
+        } else if sources.len() == 1 && !orderby.is_empty() {
+            let sorted: Option<Vec<String>> = orderby.iter().map(|item| column_source(catalog, sources, sort_key(item)).map(|(_, column)| column)).collect();
+            let sorted: Vec<String> = sorted.unwrap_or_default().into_iter().filter(|column| !columns.iter().any(|name| name.eq_ignore_ascii_case(column))).collect();
+            if !sorted.is_empty() {
+                reason.push(format!("ORDER BY {}", sorted.join(", ")));
+                columns.extend(sorted);
+            }
+        }
+        if !columns.is_empty() {
+            candidates.push(Candidate { index: Index { table_name: table_name.clone(), columns }, equality, reason: reason.join(", ") });
+        }
+    }
+    candidates
+}
+
+//helper, the source and name of a column of the query, None for anything else
+fn column_source(catalog: &Catalog, sources: &[(String, String)], expr: &Expression) -> Option<(usize, String)> {
+    match expr {
+        Expression::CompoundIdentifier(parts) if parts.len() >= 2 => {
+            let qualifier = &parts[parts.len() - 2];
+            let source = sources.iter().position(|(name, _)| name.eq_ignore_ascii_case(qualifier))?;
+            Some((source, parts[parts.len() - 1].clone()))
+        }
+        Expression::Identifier(column) => {
+            let candidates: Vec<usize> = (0..sources.len()).filter(|&i| !catalog.lacks_column(&sources[i].1, column)).collect();
+            match candidates.as_slice() {
+                [source] => Some((*source, column.clone())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+//helper, whether an expression has the same value for every row of the query: a constant, a parameter, or a column of an outer query
+fn is_value(sources: &[(String, String)], expr: &Expression) -> bool {
+    match expr {
+        Expression::Placeholder(_) => true,
+        Expression::CompoundIdentifier(parts) if parts.len() >= 2 => !sources.iter().any(|(name, _)| name.eq_ignore_ascii_case(&parts[parts.len() - 2])),
+        _ => fold(expr).is_some(),
+    }
+}
+
+//helper, an ORDER BY item without its direction
+fn sort_key(item: &Expression) -> &Expression {
+    match item {
+        Expression::UnaryOperation { operand, operator: UnaryOperator::Asc | UnaryOperator::Desc } => operand,
+        other => other,
+    }
+}
+
+//helper, the AND-ed parts of a condition
+fn conjuncts<'a>(condition: &'a Expression, out: &mut Vec<&'a Expression>) {
+    match condition {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand } => {
+            conjuncts(left_operand, out);
+            conjuncts(right_operand, out);
+        }
+        other => out.push(other),
+    }
+}
+
+//whether an index serves what a candidate asks for: its first columns are the candidate's `=` columns in any order,
+//followed by the rest of the candidate's columns in the candidate's order
+fn serves(index: &Index, candidate: &Candidate) -> bool {
+    let wanted = &candidate.index.columns;
+    if !index.table_name.eq_ignore_ascii_case(&candidate.index.table_name) || index.columns.len() < wanted.len() {
+        return false;
+    }
+    let same = |a: &String, b: &String| a.eq_ignore_ascii_case(b);
+    let (leading, rest) = index.columns[..wanted.len()].split_at(candidate.equality);
+    leading.iter().all(|column| wanted[..candidate.equality].iter().any(|name| same(name, column)))
+        && rest.iter().zip(&wanted[candidate.equality..]).all(|(a, b)| same(a, b))
+}
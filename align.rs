@@ -0,0 +1,63 @@
+//pairing up two sequences by their longest common subsequence, for the formatter's source maps and the node diff
+
+/// How big the table of `common_sequence` may get, two sequences of a few thousand items. Past it items are paired greedily instead, which takes time in proportion to the sequences rather than to their product and no memory beyond the pairs.
+pub const MAX_ALIGNMENT_CELLS: usize = 16_000_000;
+
+/// The positions of the items of the longest sequence two sequences of `a_len` and `b_len` items have in common, in order, `same(i, j)` telling whether item `i` of the first is item `j` of the second. Sequences whose table would have more than `MAX_ALIGNMENT_CELLS` cells are paired greedily: every item of the first takes the next equal item of the second, which finds a common sequence but not always the longest.
+pub fn common_sequence(a_len: usize, b_len: usize, same: impl Fn(usize, usize) -> bool) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    if a_len.saturating_mul(b_len) > MAX_ALIGNMENT_CELLS {
+        let mut j = 0;
+        for i in 0..a_len {
+            if let Some(found) = (j..b_len).find(|&k| same(i, k)) {
+                pairs.push((i, found));
+                j = found + 1;
+            }
+        }
+        return pairs;
+    }
+    //lengths[i][j] is the length of the longest common sequence of a[i..] and b[j..]
+    let mut lengths = vec![vec![0u32; b_len + 1]; a_len + 1];
+    for i in (0..a_len).rev() {
+        for j in (0..b_len).rev() {
+            lengths[i][j] = if same(i, j) { lengths[i + 1][j + 1] + 1 } else { lengths[i + 1][j].max(lengths[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < a_len && j < b_len {
+        if same(i, j) {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_common_sequence() {
+        let (a, b) = (b"abcbdab", b"bdcaba");
+        let pairs = common_sequence(a.len(), b.len(), |i, j| a[i] == b[j]);
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+        assert!(pairs.iter().all(|&(i, j)| a[i] == b[j]));
+    }
+
+    #[test]
+    fn large_sequences_are_paired_greedily() {
+        let a = vec![1u8; 5000];
+        let b = vec![1u8; 5000];
+        assert!(a.len() * b.len() > MAX_ALIGNMENT_CELLS);
+        let pairs = common_sequence(a.len(), b.len(), |i, j| a[i] == b[j]);
+        assert_eq!(pairs.len(), 5000);
+        assert_eq!(pairs[4999], (4999, 4999));
+    }
+}
@@ -0,0 +1,646 @@
+//facts about statements that can be read off the AST alone, without a schema or a database
+use std::fmt;
+use crate::statement::{
+    Statement,
+    InsertSource,
+    Expression,
+    TableReference,
+    CopyDirection,
+    GroupingElement,
+    Constraint,
+    BinaryOperator,
+    AlterAction,
+    ObjectType,
+    ShowObject,
+    ConflictAction,
+    MergeAction,
+    With,
+};
+use crate::formatter::{format_expression, format_name};
+
+/// Whether a statement reads a table or writes to it. A `CREATE TABLE`, `INSERT`, `DELETE`, the target of a `MERGE`, `ALTER TABLE`, `RENAME TABLE` (under both names), `TRUNCATE` or `DROP TABLE` writes its tables, everything named in a `FROM` clause or a subquery is read, and so is the table of `DESCRIBE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableAccess {
+    Read,
+    Write,
+}
+
+/// One table named by a statement and how the statement uses it.
+#[derive(Debug, PartialEq)]
+pub struct TableUse {
+    pub name: String,
+    pub access: TableAccess,
+}
+
+//every table the statement names, in the order they appear in the statement, subqueries included
+//a table used several times is listed every time
+pub fn table_uses(stmt: &Statement) -> Vec<TableUse> {
+    let mut uses = Vec::new();
+    statement_tables(stmt, &mut uses);
+    uses
+}
+
+//the tables a statement writes to, each name once, for permission checks in front of a database
+pub fn written_tables(stmt: &Statement) -> Vec<String> {
+    tables_with_access(stmt, TableAccess::Write)
+}
+
+//the tables a statement reads from, each name once; a table it writes as well, like the target of
+//`INSERT INTO t SELECT ... FROM t`, is in both this list and written_tables
+pub fn read_tables(stmt: &Statement) -> Vec<String> {
+    tables_with_access(stmt, TableAccess::Read)
+}
+
+//helper, distinct names with the given access, in order of first appearance
+fn tables_with_access(stmt: &Statement, access: TableAccess) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for table in table_uses(stmt) {
+        if table.access == access && !names.contains(&table.name) {
+            names.push(table.name);
+        }
+    }
+    names
+}
+
+//helper, the tables the common table expressions of a query read, and the names the rest of the query reads them by
+//common table expressions are not tables, only what their queries read is. One can be read by the queries after it, and
+//by its own in WITH RECURSIVE
+fn with_tables<'a>(with: &'a Option<With>, uses: &mut Vec<TableUse>) -> Vec<&'a String> {
+    let mut ctes: Vec<&String> = Vec::new();
+    let mut query_uses = Vec::new();
+    if let Some(with) = with {
+        for cte in &with.ctes {
+            if with.recursive {
+                ctes.push(&cte.name);
+            }
+            statement_tables(&cte.query, &mut query_uses);
+            uses.extend(query_uses.drain(..).filter(|table| !ctes.iter().any(|cte| cte.eq_ignore_ascii_case(&table.name))));
+            if !with.recursive {
+                ctes.push(&cte.name);
+            }
+        }
+    }
+    ctes
+}
+
+fn statement_tables(stmt: &Statement, uses: &mut Vec<TableUse>) {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            let ctes = with_tables(with, uses);
+            let mut query_uses = Vec::new();
+            for table in from {
+                table_reference_tables(table, &mut query_uses);
+            }
+            for expr in columns.iter().chain(r#where).chain(having).chain(qualify).chain(orderby).chain(limit) {
+                expression_tables(expr, &mut query_uses);
+            }
+            for element in group_by {
+                match element {
+                    GroupingElement::Expression(expr) => expression_tables(expr, &mut query_uses),
+                    GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => {
+                        for expr in sets.iter().flatten() {
+                            expression_tables(expr, &mut query_uses);
+                        }
+                    }
+                }
+            }
+            uses.extend(query_uses.into_iter().filter(|table| !ctes.iter().any(|cte| cte.eq_ignore_ascii_case(&table.name))));
+        }
+        Statement::SetOperation { with, left, right, orderby, limit, .. } => {
+            let ctes = with_tables(with, uses);
+            let mut query_uses = Vec::new();
+            statement_tables(left, &mut query_uses);
+            statement_tables(right, &mut query_uses);
+            for expr in orderby.iter().chain(limit) {
+                expression_tables(expr, &mut query_uses);
+            }
+            uses.extend(query_uses.into_iter().filter(|table| !ctes.iter().any(|cte| cte.eq_ignore_ascii_case(&table.name))));
+        }
+        Statement::Values { rows } => {
+            for expr in rows.iter().flatten() {
+                expression_tables(expr, uses);
+            }
+        }
+        Statement::CreateTable { table_name, column_list, query, .. } => {
+            uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write });
+            for constraint in column_list.iter().flat_map(|column| &column.constraints) {
+                if let Constraint::Check(expr) = constraint {
+                    expression_tables(expr, uses);
+                }
+            }
+            if let Some(query) = query {
+                statement_tables(query, uses);
+            }
+        }
+        Statement::Insert { table_name, source, on_conflict, .. } => {
+            uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write });
+            if let InsertSource::Query(query) = source {
+                statement_tables(query, uses);
+            }
+            for expr in source.rows().iter().flatten().chain(on_conflict.iter().flat_map(|on_conflict| on_conflict.expressions())) {
+                expression_tables(expr, uses);
+            }
+        }
+        Statement::Delete { table, where_clause } => {
+            uses.push(TableUse { name: table.clone(), access: TableAccess::Write });
+            if let Some(expr) = where_clause {
+                expression_tables(expr, uses);
+            }
+        }
+        Statement::Merge { table_name, source, on, clauses, .. } => {
+            uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write });
+            table_reference_tables(source, uses);
+            for expr in std::iter::once(on).chain(clauses.iter().flat_map(|clause| clause.expressions())) {
+                expression_tables(expr, uses);
+            }
+        }
+        Statement::AlterTable { table_name, action } => {
+            uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write });
+            match action {
+                AlterAction::AddColumn(column) => {
+                    for constraint in &column.constraints {
+                        if let Constraint::Check(expr) = constraint {
+                            expression_tables(expr, uses);
+                        }
+                    }
+                }
+                //the table is written under its new name as well
+                AlterAction::RenameTo(new_name) => uses.push(TableUse { name: new_name.clone(), access: TableAccess::Write }),
+                AlterAction::DropColumn(_) | AlterAction::RenameColumn { .. } => {}
+            }
+        }
+        Statement::RenameTable { renames } => {
+            for rename in renames {
+                uses.push(TableUse { name: rename.old_name.clone(), access: TableAccess::Write });
+                uses.push(TableUse { name: rename.new_name.clone(), access: TableAccess::Write });
+            }
+        }
+        Statement::Truncate { table_name } => uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write }),
+        //ANALYZE only reads the rows for statistics, VACUUM rewrites the table
+        Statement::Analyze { table_name } => uses.extend(table_name.iter().map(|name| TableUse { name: name.clone(), access: TableAccess::Read })),
+        Statement::Vacuum { table_name, .. } => uses.extend(table_name.iter().map(|name| TableUse { name: name.clone(), access: TableAccess::Write })),
+        Statement::CreateIndex { table_name, .. } | Statement::CreateTrigger { table_name, .. } => uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write }),
+        Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } => {}
+        //a view is read like a table, an index is not something queries name
+        Statement::Drop { object_type: ObjectType::Table | ObjectType::View, names, .. } => {
+            uses.extend(names.iter().map(|name| TableUse { name: name.clone(), access: TableAccess::Write }));
+        }
+        Statement::Drop { object_type: ObjectType::Index, .. } => {}
+        Statement::Describe { table_name } => uses.push(TableUse { name: table_name.clone(), access: TableAccess::Read }),
+        Statement::Show { .. } => {}
+        Statement::SetVariable { value, rest, .. } => {
+            for expr in std::iter::once(value).chain(rest) {
+                expression_tables(expr, uses);
+            }
+        }
+        Statement::Call { args, .. } | Statement::Execute { args, .. } => args.iter().for_each(|arg| expression_tables(arg, uses)),
+        Statement::Copy { table_name, direction, .. } => {
+            let access = if *direction == CopyDirection::From { TableAccess::Write } else { TableAccess::Read };
+            uses.push(TableUse { name: table_name.clone(), access });
+        }
+        Statement::LoadData { table_name, .. } => uses.push(TableUse { name: table_name.clone(), access: TableAccess::Write }),
+        Statement::Prepare { statement, .. } => statement_tables(statement, uses),
+        //what an unparsed statement uses is not known
+        Statement::Deallocate { .. } | Statement::Unparsed { .. } => {}
+    }
+}
+
+fn table_reference_tables(table: &TableReference, uses: &mut Vec<TableUse>) {
+    match table {
+        TableReference::Table { name, .. } => uses.push(TableUse { name: name.clone(), access: TableAccess::Read }),
+        TableReference::Derived { subquery, .. } => statement_tables(subquery, uses),
+        TableReference::Function { args, .. } => {
+            for arg in args {
+                expression_tables(arg, uses);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            table_reference_tables(table, uses);
+            for expr in std::iter::once(&pivot.aggregate).chain(&pivot.values) {
+                expression_tables(expr, uses);
+            }
+        }
+        TableReference::Unpivot { table, .. } => table_reference_tables(table, uses),
+        TableReference::Join { left, right, on, .. } => {
+            table_reference_tables(left, uses);
+            table_reference_tables(right, uses);
+            if let Some(on) = on {
+                expression_tables(on, uses);
+            }
+        }
+    }
+}
+
+//tables are only reached through the subqueries in an expression
+fn expression_tables(expr: &Expression, uses: &mut Vec<TableUse>) {
+    match expr {
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => statement_tables(subquery, uses),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_tables(left_operand, uses);
+            expression_tables(right_operand, uses);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_tables(operand, uses),
+        Expression::Alias { expr, .. } => expression_tables(expr, uses),
+        Expression::Function { args, over, .. } => {
+            let window = over.iter().flat_map(|window| window.partition_by.iter().chain(&window.order_by));
+            for expr in args.iter().chain(window) {
+                expression_tables(expr, uses);
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) | Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {}
+    }
+}
+
+//one line describing the shape of a statement, for scanning large scripts:
+//`SELECT 3 cols FROM orders JOIN customers WHERE 2 predicates ORDER BY 1`, `CREATE TABLE users 4 cols`
+//a condition counts one predicate per AND-ed part
+pub fn summary(stmt: &Statement) -> String {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            let mut parts: Vec<String> = with.iter().map(|with| format!("WITH {}", count(with.ctes.len(), "cte", "ctes"))).collect();
+            parts.push(format!("SELECT {}", count(columns.len(), "col", "cols")));
+            if !from.is_empty() {
+                parts.push(format!("FROM {}", from.iter().map(table_summary).collect::<Vec<_>>().join(", ")));
+            }
+            if let Some(condition) = r#where {
+                parts.push(format!("WHERE {}", count(predicates(condition), "predicate", "predicates")));
+            }
+            if !group_by.is_empty() {
+                parts.push(format!("GROUP BY {}", group_by.len()));
+            }
+            if let Some(condition) = having {
+                parts.push(format!("HAVING {}", count(predicates(condition), "predicate", "predicates")));
+            }
+            if let Some(condition) = qualify {
+                parts.push(format!("QUALIFY {}", count(predicates(condition), "predicate", "predicates")));
+            }
+            if !orderby.is_empty() {
+                parts.push(format!("ORDER BY {}", orderby.len()));
+            }
+            if let Some(limit) = limit {
+                parts.push(format!("LIMIT {}", format_expression(limit)));
+            }
+            parts.join(" ")
+        }
+        Statement::SetOperation { with, left, operator, all, right, orderby, limit } => {
+            let mut parts: Vec<String> = with.iter().map(|with| format!("WITH {}", count(with.ctes.len(), "cte", "ctes"))).collect();
+            let operator = if *all { format!("{} ALL", operator) } else { operator.to_string() };
+            parts.extend([summary(left), operator, summary(right)]);
+            if !orderby.is_empty() {
+                parts.push(format!("ORDER BY {}", orderby.len()));
+            }
+            if let Some(limit) = limit {
+                parts.push(format!("LIMIT {}", format_expression(limit)));
+            }
+            parts.join(" ")
+        }
+        Statement::Values { rows } => format!("VALUES {}", count(rows.len(), "row", "rows")),
+        Statement::CreateTable { table_name, query: Some(query), temporary, .. } => {
+            format!("CREATE {}TABLE {} AS {}", if *temporary { "TEMPORARY " } else { "" }, format_name(table_name), summary(query))
+        }
+        Statement::CreateTable { table_name, column_list, temporary, .. } => {
+            format!("CREATE {}TABLE {} {}", if *temporary { "TEMPORARY " } else { "" }, format_name(table_name), count(column_list.len(), "col", "cols"))
+        }
+        Statement::Insert { table_name, source, on_conflict, .. } => {
+            let upsert = match on_conflict.as_ref().map(|on_conflict| &on_conflict.action) {
+                Some(ConflictAction::Nothing) => " ON CONFLICT DO NOTHING",
+                Some(ConflictAction::Update { .. }) => " ON CONFLICT DO UPDATE",
+                None => "",
+            };
+            let source = match source {
+                InsertSource::Values(rows) => count(rows.len(), "row", "rows"),
+                InsertSource::Query(query) => summary(query),
+            };
+            format!("INSERT INTO {} {}{}", format_name(table_name), source, upsert)
+        }
+        Statement::Delete { table, where_clause } => match where_clause {
+            Some(condition) => format!("DELETE FROM {} WHERE {}", format_name(table), count(predicates(condition), "predicate", "predicates")),
+            None => format!("DELETE FROM {} all rows", format_name(table)),
+        },
+        //the arms in order, `MATCHED UPDATE, NOT MATCHED INSERT`
+        Statement::Merge { table_name, source, on, clauses, .. } => {
+            let arms: Vec<String> = clauses.iter().map(|clause| {
+                let action = match clause.action {
+                    MergeAction::Update { .. } => "UPDATE",
+                    MergeAction::Delete => "DELETE",
+                    MergeAction::Insert { .. } => "INSERT",
+                    MergeAction::Nothing => "DO NOTHING",
+                };
+                format!("{}MATCHED {}", if clause.matched { "" } else { "NOT " }, action)
+            }).collect();
+            let on = count(predicates(on), "predicate", "predicates");
+            format!("MERGE INTO {} USING {} ON {} {}", format_name(table_name), table_summary(source), on, arms.join(", "))
+        }
+        Statement::AlterTable { table_name, action } => match action {
+            AlterAction::AddColumn(column) => format!("ALTER TABLE {} ADD COLUMN {}", format_name(table_name), format_name(&column.column_name)),
+            AlterAction::DropColumn(column_name) => format!("ALTER TABLE {} DROP COLUMN {}", format_name(table_name), format_name(column_name)),
+            AlterAction::RenameColumn { column_name, new_name } => {
+                format!("ALTER TABLE {} RENAME COLUMN {} TO {}", format_name(table_name), format_name(column_name), format_name(new_name))
+            }
+            AlterAction::RenameTo(new_name) => format!("ALTER TABLE {} RENAME TO {}", format_name(table_name), format_name(new_name)),
+        },
+        Statement::RenameTable { renames } => {
+            let renames: Vec<String> = renames.iter().map(|rename| format!("{} TO {}", format_name(&rename.old_name), format_name(&rename.new_name))).collect();
+            format!("RENAME TABLE {}", renames.join(", "))
+        }
+        Statement::Truncate { table_name } => format!("TRUNCATE TABLE {}", format_name(table_name)),
+        Statement::Analyze { table_name } => format!("ANALYZE {}", table_name.as_deref().map_or("*".to_string(), format_name)),
+        Statement::Vacuum { full, table_name } => {
+            format!("VACUUM{} {}", if *full { " FULL" } else { "" }, table_name.as_deref().map_or("*".to_string(), format_name))
+        }
+        Statement::CreateDatabase { name, .. } => format!("CREATE DATABASE {}", format_name(name)),
+        Statement::CreateSchema { name, .. } => format!("CREATE SCHEMA {}", format_name(name)),
+        Statement::CreateIndex { name, table_name, columns, .. } => {
+            format!("CREATE INDEX {} ON {} {}", format_name(name), format_name(table_name), count(columns.len(), "col", "cols"))
+        }
+        Statement::CreateFunction { name, args, .. } => format!("CREATE FUNCTION {} {}", format_name(name), count(args.len(), "arg", "args")),
+        Statement::CreateProcedure { name, args, .. } => format!("CREATE PROCEDURE {} {}", format_name(name), count(args.len(), "arg", "args")),
+        Statement::CreateTrigger { name, timing, event, table_name, .. } => {
+            format!("CREATE TRIGGER {} {} {} ON {}", format_name(name), timing, event, format_name(table_name))
+        }
+        Statement::Drop { object_type, names, .. } => {
+            let names: Vec<String> = names.iter().map(|name| format_name(name)).collect();
+            format!("DROP {} {}", object_type, names.join(", "))
+        }
+        Statement::Show { object: ShowObject::Tables } => "SHOW TABLES".to_string(),
+        Statement::Show { object: ShowObject::Databases } => "SHOW DATABASES".to_string(),
+        Statement::Describe { table_name } => format!("DESCRIBE {}", format_name(table_name)),
+        Statement::SetVariable { name, .. } => format!("SET {}", format_name(name)),
+        Statement::Call { name, args } => format!("CALL {} {}", format_name(name), count(args.len(), "arg", "args")),
+        Statement::Copy { table_name, direction, file, .. } => {
+            let file = file.as_deref().unwrap_or(if *direction == CopyDirection::From { "STDIN" } else { "STDOUT" });
+            format!("COPY {} {} {}", format_name(table_name), direction, file)
+        }
+        Statement::LoadData { table_name, file, .. } => format!("LOAD DATA {} INTO {}", file, format_name(table_name)),
+        Statement::Prepare { name, statement, .. } => format!("PREPARE {} AS {}", format_name(name), summary(statement)),
+        Statement::Execute { name, args } => format!("EXECUTE {} {}", format_name(name), count(args.len(), "arg", "args")),
+        Statement::Deallocate { name: Some(name) } => format!("DEALLOCATE {}", format_name(name)),
+        Statement::Deallocate { name: None } => "DEALLOCATE ALL".to_string(),
+        Statement::Unparsed { raw, .. } => format!("UNPARSED {}", raw.split_whitespace().next().unwrap_or_default().to_uppercase()),
+    }
+}
+
+//helper, `1 col`, `3 cols`
+fn count(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+//tables by name, without aliases, anything else by what it is
+fn table_summary(table: &TableReference) -> String {
+    match table {
+        TableReference::Table { name, .. } => format_name(name),
+        TableReference::Derived { .. } => "(subquery)".to_string(),
+        TableReference::Function { name, .. } => format!("{}()", format_name(name)),
+        TableReference::Pivot { table, .. } => format!("{} PIVOT", table_summary(table)),
+        TableReference::Unpivot { table, .. } => format!("{} UNPIVOT", table_summary(table)),
+        TableReference::Join { left, right, kind, .. } => format!("{} {} {}", table_summary(left), kind, table_summary(right)),
+    }
+}
+
+fn predicates(condition: &Expression) -> usize {
+    match condition {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand } => predicates(left_operand) + predicates(right_operand),
+        _ => 1,
+    }
+}
+
+/// How a subquery is used.
+/// 1. `Scalar` – As a value, `(SELECT max(price) FROM orders)`.
+/// 2. `Exists` – Under `EXISTS` or `NOT EXISTS`.
+/// 3. `Derived` – As a table of a `FROM` clause, `LATERAL` ones included.
+/// 4. `Cte` – As a common table expression of a `WITH` clause.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubqueryKind {
+    Scalar,
+    Exists,
+    Derived,
+    Cte,
+}
+
+impl fmt::Display for SubqueryKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            SubqueryKind::Scalar => "scalar",
+            SubqueryKind::Exists => "exists",
+            SubqueryKind::Derived => "derived",
+            SubqueryKind::Cte => "cte",
+        })
+    }
+}
+
+/// One subquery of a statement.
+/// 1. `kind` – How the subquery is used.
+/// 2. `depth` – The number of queries around it, 1 for a subquery of the statement itself.
+/// 3. `query` – The subquery.
+/// 4. `outer_columns` – The columns of queries around it that it refers to, directly or in its own subqueries, as written (`u.id`), each once in order of appearance. A subquery is correlated when there is one, it then has to be evaluated again for every row of the query it depends on.
+#[derive(Debug, PartialEq)]
+pub struct Subquery {
+    pub kind: SubqueryKind,
+    pub depth: usize,
+    pub query: Statement,
+    pub outer_columns: Vec<String>,
+}
+
+impl Subquery {
+    pub fn is_correlated(&self) -> bool {
+        !self.outer_columns.is_empty()
+    }
+}
+
+//every subquery of a statement, outer ones before the ones inside them, each with the outer columns it uses
+//a qualified column belongs to the innermost query with a FROM item of that name, an unqualified one to the innermost query
+//with a FROM clause as there is no schema to tell which table has it, so `x` in `SELECT (SELECT x) FROM t` is a column of t
+pub fn subqueries(stmt: &Statement) -> Vec<Subquery> {
+    let mut found = Vec::new();
+    query_subqueries(stmt, &mut Vec::new(), &mut Vec::new(), &mut found);
+    found
+}
+
+//walk one query with its FROM items as the innermost scope, column references of the query and everything in it are
+//added to `columns` with the index of the scope they belong to
+fn query_subqueries(stmt: &Statement, scopes: &mut Vec<Vec<String>>, columns: &mut Vec<(usize, String)>, found: &mut Vec<Subquery>) {
+    match stmt {
+        Statement::Select { with, columns: select_list, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            //the queries of the WITH clause do not see the FROM clause after them
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                subquery(&cte.query, SubqueryKind::Cte, scopes, columns, found);
+            }
+            let mut scope = Vec::new();
+            for table in from {
+                from_names(table, &mut scope);
+            }
+            scopes.push(scope);
+            for table in from {
+                table_subqueries(table, scopes, columns, found);
+            }
+            let grouping = group_by.iter().flat_map(|element| match element {
+                GroupingElement::Expression(expr) => vec![expr],
+                GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => sets.iter().flatten().collect(),
+            });
+            let clauses = r#where.iter().chain(having).chain(qualify).chain(orderby).chain(limit);
+            for expr in select_list.iter().chain(grouping).chain(clauses) {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+            scopes.pop();
+        }
+        //both sides are queries of the same level as the operation, not subqueries of it
+        Statement::SetOperation { with, left, right, orderby, limit, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                subquery(&cte.query, SubqueryKind::Cte, scopes, columns, found);
+            }
+            query_subqueries(left, scopes, columns, found);
+            query_subqueries(right, scopes, columns, found);
+            //ORDER BY names columns of the combined rows, a scope of their own keeps them from being taken for outer columns
+            scopes.push(vec![String::new()]);
+            for expr in orderby.iter().chain(limit) {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+            scopes.pop();
+        }
+        //a query without a FROM clause, its values can only refer to outer columns
+        Statement::Values { rows } => {
+            scopes.push(Vec::new());
+            for expr in rows.iter().flatten() {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+            scopes.pop();
+        }
+        Statement::Insert { table_name, source, on_conflict, .. } => {
+            match source {
+                InsertSource::Values(rows) => {
+                    for expr in rows.iter().flatten() {
+                        expression_subqueries(expr, scopes, columns, found);
+                    }
+                }
+                InsertSource::Query(query) => query_subqueries(query, scopes, columns, found),
+            }
+            //the values of DO UPDATE see the row in the table and the one that was to be inserted
+            if let Some(on_conflict) = on_conflict {
+                scopes.push(vec![table_name.clone(), "excluded".to_string()]);
+                for expr in on_conflict.expressions() {
+                    expression_subqueries(expr, scopes, columns, found);
+                }
+                scopes.pop();
+            }
+        }
+        Statement::Delete { table, where_clause } => {
+            scopes.push(vec![table.clone()]);
+            if let Some(expr) = where_clause {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+            scopes.pop();
+        }
+        //the condition and the arms see the target and the source
+        Statement::Merge { table_name, alias, source, on, clauses } => {
+            let mut scope = vec![alias.as_ref().unwrap_or(table_name).clone()];
+            from_names(source, &mut scope);
+            scopes.push(scope);
+            table_subqueries(source, scopes, columns, found);
+            for expr in std::iter::once(on).chain(clauses.iter().flat_map(|clause| clause.expressions())) {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+            scopes.pop();
+        }
+        //the query of CREATE TABLE ... AS is a statement of its own rather than a subquery, only what is in it is found
+        Statement::CreateTable { query: Some(query), .. } | Statement::Prepare { statement: query, .. } => query_subqueries(query, scopes, columns, found),
+        Statement::CreateTable { .. } | Statement::AlterTable { .. } | Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Execute { .. } | Statement::Deallocate { .. } => {}
+    }
+}
+
+//record the subquery, then what it refers to outside of itself once everything in it has been walked
+fn subquery(query: &Statement, kind: SubqueryKind, scopes: &mut Vec<Vec<String>>, columns: &mut Vec<(usize, String)>, found: &mut Vec<Subquery>) {
+    let depth = scopes.len();
+    let index = found.len();
+    found.push(Subquery { kind, depth, query: query.clone(), outer_columns: Vec::new() });
+    let start = columns.len();
+    query_subqueries(query, scopes, columns, found);
+    let mut outer_columns: Vec<String> = Vec::new();
+    for (scope, name) in &columns[start..] {
+        if *scope < depth && !outer_columns.contains(name) {
+            outer_columns.push(name.clone());
+        }
+    }
+    found[index].outer_columns = outer_columns;
+}
+
+fn table_subqueries(table: &TableReference, scopes: &mut Vec<Vec<String>>, columns: &mut Vec<(usize, String)>, found: &mut Vec<Subquery>) {
+    match table {
+        TableReference::Table { .. } => {}
+        TableReference::Derived { subquery: query, .. } => subquery(query, SubqueryKind::Derived, scopes, columns, found),
+        TableReference::Function { args, .. } => {
+            for arg in args {
+                expression_subqueries(arg, scopes, columns, found);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            table_subqueries(table, scopes, columns, found);
+            for expr in std::iter::once(&pivot.aggregate).chain(std::iter::once(&pivot.column)).chain(&pivot.values) {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+        }
+        TableReference::Unpivot { table, .. } => table_subqueries(table, scopes, columns, found),
+        TableReference::Join { left, right, on, .. } => {
+            table_subqueries(left, scopes, columns, found);
+            table_subqueries(right, scopes, columns, found);
+            if let Some(on) = on {
+                expression_subqueries(on, scopes, columns, found);
+            }
+        }
+    }
+}
+
+fn expression_subqueries(expr: &Expression, scopes: &mut Vec<Vec<String>>, columns: &mut Vec<(usize, String)>, found: &mut Vec<Subquery>) {
+    match expr {
+        Expression::Subquery(query) => subquery(query, SubqueryKind::Scalar, scopes, columns, found),
+        Expression::Exists { subquery: query, .. } => subquery(query, SubqueryKind::Exists, scopes, columns, found),
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {
+            if let Some(scope) = column_scope(scopes, expr) {
+                columns.push((scope, format_expression(expr)));
+            }
+        }
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_subqueries(left_operand, scopes, columns, found);
+            expression_subqueries(right_operand, scopes, columns, found);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_subqueries(operand, scopes, columns, found),
+        Expression::Alias { expr, .. } => expression_subqueries(expr, scopes, columns, found),
+        Expression::Function { args, over, .. } => {
+            let window = over.iter().flat_map(|window| window.partition_by.iter().chain(&window.order_by));
+            for expr in args.iter().chain(window) {
+                expression_subqueries(expr, scopes, columns, found);
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) => {}
+    }
+}
+
+//helper, the index of the scope a column reference belongs to, see `subqueries`
+//a qualifier no query has, like a schema name, is taken to be the innermost query's
+fn column_scope(scopes: &[Vec<String>], expr: &Expression) -> Option<usize> {
+    let innermost = scopes.len().checked_sub(1)?;
+    let scope = match expr {
+        Expression::CompoundIdentifier(parts) if parts.len() >= 2 => {
+            let qualifier = &parts[parts.len() - 2];
+            scopes.iter().rposition(|scope| scope.iter().any(|name| name.eq_ignore_ascii_case(qualifier)))
+        }
+        _ => scopes.iter().rposition(|scope| !scope.is_empty()),
+    };
+    Some(scope.unwrap_or(innermost))
+}
+
+//the names a FROM item can be referred to by in its query: tables and table functions by alias or name,
+//derived tables and PIVOT/UNPIVOT results by alias, joins by the names of both sides
+pub fn from_names(table: &TableReference, out: &mut Vec<String>) {
+    match table {
+        TableReference::Table { name, alias, .. } | TableReference::Function { name, alias, .. } => out.push(alias.as_ref().unwrap_or(name).clone()),
+        TableReference::Derived { alias, .. } => out.extend(alias.clone()),
+        TableReference::Pivot { table, alias, .. } | TableReference::Unpivot { table, alias, .. } => {
+            from_names(table, out);
+            out.extend(alias.clone());
+        }
+        TableReference::Join { left, right, .. } => {
+            from_names(left, out);
+            from_names(right, out);
+        }
+    }
+}
@@ -0,0 +1,765 @@
+//random AST generation for round-trip testing, compiled only with `--cfg 'feature="arbitrary"'`
+//every generated statement is something the parser is able to produce, so formatting it and parsing the
+//output again must give back the exact same AST. `check_roundtrips` runs that loop and collects mismatches.
+
+use crate::statement::{
+    Statement,
+    InsertSource,
+    Expression,
+    Literal,
+    BinaryOperator,
+    UnaryOperator,
+    TableColumn,
+    AlterAction,
+    TableRename,
+    IndexColumn,
+    ObjectType,
+    ShowObject,
+    TableReference,
+    TableSample,
+    PivotClause,
+    UnpivotClause,
+    JoinKind,
+    GroupingElement,
+    SetOperator,
+    WindowSpec,
+    Hint,
+    DBType,
+    Constraint,
+    OnConflict,
+    ConflictAction,
+    Assignment,
+    MergeClause,
+    MergeAction,
+    With,
+    Cte,
+    TriggerTiming,
+    TriggerEvent,
+    FunctionArg,
+    CopyDirection,
+    CopyOption,
+    DuplicateHandling,
+    LoadFormat,
+};
+use crate::token::{Token, Keyword};
+use crate::tokenizer::Tokenizer;
+use crate::parser::Parser;
+use crate::formatter::format_statement;
+#[cfg(feature = "serde")]
+use crate::json::{ToJson, FromJson};
+
+/// Source of randomness for `Arbitrary` implementations. It is a xorshift generator, so a seed always reproduces the same sequence of ASTs, and a depth budget that keeps expression trees finite.
+pub struct Gen {
+    state: u64,
+    depth: usize,
+}
+
+impl Gen {
+    pub fn new(seed: u64) -> Self {
+        //xorshift gets stuck on a zero state
+        Gen { state: seed ^ 0x9e37_79b9_7f4a_7c15, depth: 4 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    //uniform value in 0..n
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    //a vector with between min and max elements
+    pub fn vec_of<T: Arbitrary>(&mut self, min: u64, max: u64) -> Vec<T> {
+        let len = min + self.below(max - min + 1);
+        (0..len).map(|_| T::arbitrary(self)).collect()
+    }
+
+    //identifier, now and then a keyword or a name with odd characters that the formatter has to quote
+    pub fn identifier(&mut self) -> String {
+        if self.chance(3) {
+            let keyword = Keyword::ALL[self.below(Keyword::ALL.len() as u64) as usize];
+            return keyword.as_str().to_lowercase();
+        }
+        if self.chance(2) {
+            return format!("odd name`{}", self.below(100));
+        }
+        self.plain_identifier()
+    }
+
+    //identifier that the tokenizer does not turn into a keyword and that needs no quotes
+    pub fn plain_identifier(&mut self) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz_0123456789";
+        let len = 1 + self.below(8) as usize;
+        let mut name = String::new();
+        name.push((b'a' + self.below(26) as u8) as char);
+        for _ in 1..len {
+            name.push(ALPHABET[self.below(ALPHABET.len() as u64) as usize] as char);
+        }
+        match Tokenizer::new(&name).next() {
+            Some(Token::Identifier(_)) => name,
+            _ => format!("{}_", name),
+        }
+    }
+
+    //expression for a boolean context, the parser rejects conditions that are certainly not boolean
+    //so AND, OR and NOT only combine comparisons here
+    pub fn condition(&mut self) -> Expression {
+        if self.depth > 0 && self.chance(30) {
+            self.depth -= 1;
+            let expr = match self.below(3) {
+                0 => Expression::UnaryOperation { operand: Box::new(self.condition()), operator: UnaryOperator::Not },
+                1 => Expression::BinaryOperation { left_operand: Box::new(self.condition()), operator: BinaryOperator::And, right_operand: Box::new(self.condition()) },
+                _ => Expression::BinaryOperation { left_operand: Box::new(self.condition()), operator: BinaryOperator::Or, right_operand: Box::new(self.condition()) },
+            };
+            self.depth += 1;
+            return expr;
+        }
+        if self.chance(10) {
+            let operator = if self.chance(50) { UnaryOperator::IsNull } else { UnaryOperator::IsNotNull };
+            return Expression::UnaryOperation { operand: Box::new(Expression::arbitrary(self)), operator };
+        }
+        let operator = match self.below(6) {
+            0 => BinaryOperator::Equal,
+            1 => BinaryOperator::NotEqual,
+            2 => BinaryOperator::LessThan,
+            3 => BinaryOperator::LessThanOrEqual,
+            4 => BinaryOperator::GreaterThan,
+            _ => BinaryOperator::GreaterThanOrEqual,
+        };
+        Expression::BinaryOperation { left_operand: Box::new(Expression::arbitrary(self)), operator, right_operand: Box::new(Expression::arbitrary(self)) }
+    }
+
+    //ORDER BY items, each optionally with ASC or DESC
+    fn order_by(&mut self) -> Vec<Expression> {
+        (0..self.below(3)).map(|_| {
+            let expr = Expression::arbitrary(self);
+            match self.below(3) {
+                0 => Expression::UnaryOperation { operand: Box::new(expr), operator: UnaryOperator::Asc },
+                1 => Expression::UnaryOperation { operand: Box::new(expr), operator: UnaryOperator::Desc },
+                _ => expr,
+            }
+        }).collect()
+    }
+
+    //sets for ROLLUP, CUBE and GROUPING SETS, only the latter may contain the empty set
+    fn grouping_sets(&mut self, allow_empty: bool) -> Vec<Vec<Expression>> {
+        let min = if allow_empty { 0 } else { 1 };
+        (0..1 + self.below(3)).map(|_| self.vec_of(min, 3)).collect()
+    }
+
+    fn optional_identifier(&mut self) -> Option<String> {
+        if self.chance(50) { Some(self.identifier()) } else { None }
+    }
+
+    //string contents, quotes and backslashes included to exercise escaping
+    pub fn string(&mut self) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 '\"\\";
+        let len = self.below(12) as usize;
+        (0..len).map(|_| ALPHABET[self.below(ALPHABET.len() as u64) as usize] as char).collect()
+    }
+}
+
+/// Types that can be generated at random. This mirrors the `Arbitrary` trait of the `arbitrary` crate, without the dependency.
+pub trait Arbitrary: Sized {
+    fn arbitrary(g: &mut Gen) -> Self;
+}
+
+impl Gen {
+    //a query, the only kind of statement allowed as a subquery: a SELECT, or now and then two queries combined
+    pub fn query(&mut self) -> Statement {
+        if self.chance(5) {
+            return self.values();
+        }
+        if self.depth == 0 || !self.chance(10) {
+            return self.select();
+        }
+        let with = if self.chance(10) { Some(self.with()) } else { None };
+        self.depth -= 1;
+        let (left, right) = (self.query(), self.query());
+        self.depth += 1;
+        Statement::SetOperation {
+            with,
+            left: Box::new(left),
+            operator: SetOperator::arbitrary(self),
+            all: self.chance(50),
+            right: Box::new(right),
+            orderby: self.order_by(),
+            limit: if self.chance(20) { Some(Expression::Literal(Literal::Integer(self.below(1000) as i64))) } else { None },
+        }
+    }
+
+    //a VALUES query, rows of one to three values
+    pub fn values(&mut self) -> Statement {
+        let width = 1 + self.below(3);
+        let rows = (0..1 + self.below(3)).map(|_| (0..width).map(|_| Expression::arbitrary(self)).collect()).collect();
+        Statement::Values { rows }
+    }
+
+    //a SELECT statement
+    pub fn select(&mut self) -> Statement {
+        let orderby = self.order_by();
+        let columns = (0..1 + self.below(4)).map(|_| match self.below(6) {
+            0 => Expression::Wildcard,
+            1 => Expression::Alias { expr: Box::new(Expression::arbitrary(self)), alias: self.identifier() },
+            _ => Expression::arbitrary(self),
+        }).collect();
+        let with = if self.depth > 0 && self.chance(10) { Some(self.with()) } else { None };
+        Statement::Select {
+            with,
+            hints: if self.chance(10) { self.vec_of(1, 2) } else { vec![] },
+            columns,
+            from: self.vec_of(1, 2),
+            r#where: if self.chance(50) { Some(self.condition()) } else { None },
+            group_by: if self.chance(30) { self.vec_of(1, 3) } else { vec![] },
+            having: if self.chance(20) { Some(self.condition()) } else { None },
+            qualify: if self.chance(10) { Some(self.condition()) } else { None },
+            orderby,
+            limit: match self.below(10) {
+                0 => Some(Expression::Literal(Literal::Integer(self.below(1000) as i64))),
+                1 => Some(Expression::Placeholder("?".to_string())),
+                _ => None,
+            },
+        }
+    }
+
+    //the body of a trigger: the tokens of a DELETE, or of a BEGIN ... END block of one or two of them
+    fn trigger_body(&mut self) -> Vec<Token> {
+        let block = self.chance(30);
+        let mut sql = String::new();
+        for _ in 0..if block { 1 + self.below(2) } else { 1 } {
+            let delete = Statement::Delete { table: self.identifier(), where_clause: if self.chance(70) { Some(self.condition()) } else { None } };
+            sql.push_str(&format_statement(&delete));
+            sql.push(' ');
+        }
+        if block {
+            sql = format!("BEGIN {} END", sql);
+        }
+        let mut body = Tokenizer::new(&sql).tokenize().unwrap_or_default();
+        //a single statement ends with the semicolon of the CREATE TRIGGER
+        if !block {
+            body.pop();
+        }
+        body
+    }
+
+    //a WITH clause of one to three common table expressions, with different names as the parser requires
+    fn with(&mut self) -> With {
+        self.depth -= 1;
+        let mut ctes: Vec<Cte> = Vec::new();
+        for _ in 0..1 + self.below(3) {
+            let name = self.identifier();
+            if ctes.iter().any(|cte| cte.name.eq_ignore_ascii_case(&name)) {
+                continue;
+            }
+            let columns = if self.chance(30) { (0..1 + self.below(3)).map(|_| self.identifier()).collect() } else { vec![] };
+            ctes.push(Cte { name, columns, query: Box::new(self.query()) });
+        }
+        self.depth += 1;
+        With { recursive: self.chance(20), ctes }
+    }
+}
+
+impl Arbitrary for Statement {
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.chance(70) {
+            g.query()
+        } else if g.chance(50) {
+            let (column_list, query) = if g.chance(20) { (Vec::new(), Some(Box::new(g.query()))) } else { (g.vec_of(1, 5), None) };
+            Statement::CreateTable {
+                table_name: g.identifier(),
+                column_list,
+                query,
+                if_not_exists: g.chance(30),
+                temporary: g.chance(20),
+            }
+        } else if g.chance(20) {
+            Statement::Drop {
+                object_type: match g.below(3) {
+                    0 => ObjectType::Table,
+                    1 => ObjectType::Index,
+                    _ => ObjectType::View,
+                },
+                names: (0..1 + g.below(3)).map(|_| g.identifier()).collect(),
+                if_exists: g.chance(50),
+            }
+        } else if g.chance(10) {
+            Statement::Truncate { table_name: g.identifier() }
+        } else if g.chance(5) {
+            Statement::RenameTable {
+                renames: (0..1 + g.below(3)).map(|_| TableRename { old_name: g.identifier(), new_name: g.identifier() }).collect(),
+            }
+        } else if g.chance(5) {
+            let table_name = if g.chance(70) { Some(g.identifier()) } else { None };
+            if g.chance(50) {
+                Statement::Analyze { table_name }
+            } else {
+                Statement::Vacuum { full: g.chance(50), table_name }
+            }
+        } else if g.chance(10) {
+            match g.below(3) {
+                0 => Statement::Show { object: ShowObject::Tables },
+                1 => Statement::Show { object: ShowObject::Databases },
+                _ => Statement::Describe { table_name: g.identifier() },
+            }
+        } else if g.chance(10) {
+            let name = g.identifier();
+            let count = if g.chance(20) { 3 } else { 1 };
+            let mut values: Vec<Expression> = (0..count).map(|_| if g.chance(20) { Expression::Identifier("on".to_string()) } else { Expression::arbitrary(g) }).collect();
+            let value = values.remove(0);
+            Statement::SetVariable { name, value, rest: values }
+        } else if g.chance(5) {
+            Statement::Call { name: g.identifier(), args: (0..g.below(3)).map(|_| Expression::arbitrary(g)).collect() }
+        } else if g.chance(5) {
+            match g.below(3) {
+                0 => Statement::Prepare { name: g.identifier(), param_types: (0..g.below(3)).map(|_| DBType::arbitrary(g)).collect(), statement: Box::new(g.query()) },
+                1 => Statement::Execute { name: g.identifier(), args: (0..g.below(3)).map(|_| Expression::arbitrary(g)).collect() },
+                _ => Statement::Deallocate { name: if g.chance(80) { Some(g.identifier()) } else { None } },
+            }
+        } else if g.chance(5) {
+            let columns = (0..g.below(3)).map(|_| g.identifier()).collect();
+            if g.chance(50) {
+                let direction = if g.chance(50) { CopyDirection::From } else { CopyDirection::To };
+                let file = if g.chance(70) { Some(g.string()) } else { None };
+                let options = (0..g.below(3)).map(|_| CopyOption::arbitrary(g)).collect();
+                Statement::Copy { table_name: g.identifier(), columns, direction, file, options }
+            } else {
+                let duplicates = match g.below(3) {
+                    0 => Some(DuplicateHandling::Replace),
+                    1 => Some(DuplicateHandling::Ignore),
+                    _ => None,
+                };
+                let ignore_lines = if g.chance(30) { Some(g.below(10)) } else { None };
+                Statement::LoadData { local: g.chance(50), file: g.string(), duplicates, table_name: g.identifier(), format: LoadFormat::arbitrary(g), ignore_lines, columns }
+            }
+        } else if g.chance(10) {
+            let (name, if_not_exists) = (g.identifier(), g.chance(50));
+            match g.below(5) {
+                0 => Statement::CreateDatabase { name, if_not_exists },
+                1 => Statement::CreateSchema { name, if_not_exists },
+                2 => Statement::CreateTrigger {
+                    name,
+                    timing: if g.chance(50) { TriggerTiming::Before } else { TriggerTiming::After },
+                    event: match g.below(3) {
+                        0 => TriggerEvent::Insert,
+                        1 => TriggerEvent::Update,
+                        _ => TriggerEvent::Delete,
+                    },
+                    table_name: g.identifier(),
+                    body: g.trigger_body(),
+                },
+                3 => {
+                    let args = (0..g.below(3)).map(|_| FunctionArg::arbitrary(g)).collect();
+                    let (body, language) = (g.identifier(), g.optional_identifier());
+                    if g.chance(50) {
+                        Statement::CreateFunction { or_replace: g.chance(30), name, args, returns: DBType::arbitrary(g), body, language }
+                    } else {
+                        Statement::CreateProcedure { or_replace: g.chance(30), name, args, body, language }
+                    }
+                }
+                _ => Statement::CreateIndex {
+                    name,
+                    table_name: g.identifier(),
+                    columns: (0..1 + g.below(3)).map(|_| IndexColumn { name: g.identifier(), descending: g.chance(30) }).collect(),
+                    unique: g.chance(30),
+                    if_not_exists,
+                },
+            }
+        } else if g.chance(20) {
+            let action = match g.below(4) {
+                0 => AlterAction::AddColumn(TableColumn::arbitrary(g)),
+                1 => AlterAction::DropColumn(g.identifier()),
+                2 => AlterAction::RenameColumn { column_name: g.identifier(), new_name: g.identifier() },
+                _ => AlterAction::RenameTo(g.identifier()),
+            };
+            Statement::AlterTable { table_name: g.identifier(), action }
+        } else if g.chance(15) {
+            Statement::Merge {
+                table_name: g.identifier(),
+                alias: g.optional_identifier(),
+                source: TableReference::arbitrary(g),
+                on: g.condition(),
+                clauses: g.vec_of(1, 3),
+            }
+        } else if g.chance(30) {
+            Statement::Delete {
+                table: g.identifier(),
+                where_clause: if g.chance(70) { Some(g.condition()) } else { None },
+            }
+        } else {
+            //every row has the same number of values; the columns of a query are not counted, the parser does not either
+            let width = 1 + g.below(4);
+            let columns = if g.chance(50) { Some((0..width).map(|_| g.identifier()).collect()) } else { None };
+            let source = if g.chance(25) {
+                InsertSource::Query(Box::new(g.query()))
+            } else {
+                InsertSource::Values((0..1 + g.below(3)).map(|_| g.vec_of(width, width)).collect())
+            };
+            Statement::Insert {
+                table_name: g.identifier(),
+                columns,
+                source,
+                on_conflict: if g.chance(20) { Some(OnConflict::arbitrary(g)) } else { None },
+            }
+        }
+    }
+}
+
+impl Arbitrary for CopyOption {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let value = match g.below(3) {
+            0 => None,
+            1 => Some(g.identifier()),
+            _ => Some(g.string()),
+        };
+        CopyOption { name: g.plain_identifier().to_ascii_uppercase(), value }
+    }
+}
+
+impl Arbitrary for LoadFormat {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let optional_string = |g: &mut Gen| if g.chance(40) { Some(g.string()) } else { None };
+        let fields_enclosed_by = optional_string(g);
+        LoadFormat {
+            fields_terminated_by: optional_string(g),
+            optionally_enclosed: fields_enclosed_by.is_some() && g.chance(50),
+            fields_enclosed_by,
+            fields_escaped_by: optional_string(g),
+            lines_starting_by: optional_string(g),
+            lines_terminated_by: optional_string(g),
+        }
+    }
+}
+
+impl Arbitrary for FunctionArg {
+    fn arbitrary(g: &mut Gen) -> Self {
+        FunctionArg { name: g.optional_identifier(), data_type: DBType::arbitrary(g) }
+    }
+}
+
+impl Arbitrary for OnConflict {
+    fn arbitrary(g: &mut Gen) -> Self {
+        //DO UPDATE needs a target, DO NOTHING does not
+        let target: Vec<String> = (0..g.below(3)).map(|_| g.identifier()).collect();
+        let action = if target.is_empty() || g.chance(40) {
+            ConflictAction::Nothing
+        } else {
+            ConflictAction::Update {
+                assignments: (0..1 + g.below(3)).map(|_| Assignment { column: g.identifier(), value: Expression::arbitrary(g) }).collect(),
+                where_clause: if g.chance(30) { Some(g.condition()) } else { None },
+            }
+        };
+        OnConflict { target, action }
+    }
+}
+
+impl Arbitrary for MergeClause {
+    fn arbitrary(g: &mut Gen) -> Self {
+        //UPDATE and DELETE only go with WHEN MATCHED, INSERT with WHEN NOT MATCHED
+        let matched = g.chance(50);
+        let action = match g.below(4) {
+            0 => MergeAction::Nothing,
+            _ if !matched => {
+                let width = 1 + g.below(3);
+                let columns = if g.chance(50) { Some((0..width).map(|_| g.identifier()).collect()) } else { None };
+                MergeAction::Insert { columns, values: g.vec_of(width, width) }
+            }
+            1 => MergeAction::Delete,
+            _ => MergeAction::Update {
+                assignments: (0..1 + g.below(3)).map(|_| Assignment { column: g.identifier(), value: Expression::arbitrary(g) }).collect(),
+            },
+        };
+        MergeClause { matched, condition: if g.chance(30) { Some(g.condition()) } else { None }, action }
+    }
+}
+
+impl Arbitrary for Literal {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(9) {
+            0 => Literal::Integer(g.below(100_000) as i64 - 50_000),
+            1 => {
+                let sign = if g.chance(30) { "-" } else { "" };
+                //1.5, .25, 3e7, 0.5E-2 and integers too large for i64
+                let number = match g.below(5) {
+                    0 => format!("{}.{}", g.below(1000), g.below(1000)),
+                    1 => format!(".{}", g.below(1000)),
+                    2 => format!("{}e{}", g.below(100), g.below(20)),
+                    3 => format!("{}.{}E-{}", g.below(10), g.below(100), g.below(20)),
+                    _ => (i64::MAX as u64 + 2 + g.below(1000)).to_string(),
+                };
+                Literal::Decimal(format!("{}{}", sign, number))
+            }
+            2 => Literal::Boolean(g.chance(50)),
+            3 => Literal::String(g.string()),
+            4 => Literal::NationalString(g.string()),
+            5 => Literal::BitString(format!("{:b}", g.below(256))),
+            6 => Literal::HexString(format!("{:X}", g.below(65_536))),
+            7 => Literal::Null,
+            _ => Literal::Integer(g.below(100_000) as i64),
+        }
+    }
+}
+
+//ASC and DESC are never generated here, the parser only accepts them at the top of an ORDER BY item
+impl Arbitrary for Expression {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let leaf = g.depth == 0 || g.chance(40);
+        if leaf {
+            return match g.below(5) {
+                0 | 1 => Expression::Literal(Literal::arbitrary(g)),
+                2 => Expression::CompoundIdentifier(vec![g.identifier(), g.identifier()]),
+                3 if g.chance(20) => Expression::Placeholder(if g.chance(50) { "?".to_string() } else { format!("${}", 1 + g.below(20)) }),
+                _ => Expression::Identifier(g.identifier()),
+            };
+        }
+
+        g.depth -= 1;
+        let expr = if g.chance(70) {
+            Expression::BinaryOperation {
+                left_operand: Box::new(Expression::arbitrary(g)),
+                operator: BinaryOperator::arbitrary(g),
+                right_operand: Box::new(Expression::arbitrary(g)),
+            }
+        } else if g.chance(30) {
+            Expression::IsDistinctFrom {
+                left_operand: Box::new(Expression::arbitrary(g)),
+                right_operand: Box::new(Expression::arbitrary(g)),
+                negated: g.chance(50),
+            }
+        } else if g.chance(20) {
+            Expression::Exists { subquery: Box::new(g.query()), negated: g.chance(50) }
+        } else if g.chance(20) {
+            Expression::Subquery(Box::new(g.query()))
+        } else if g.chance(30) {
+            let args = if g.chance(20) { vec![Expression::Wildcard] } else { g.vec_of(0, 3) };
+            let over = if g.chance(30) { Some(WindowSpec { partition_by: g.vec_of(0, 2), order_by: g.order_by() }) } else { None };
+            Expression::Function { name: g.identifier(), args, over }
+        } else {
+            let operator = match g.below(3) {
+                0 => UnaryOperator::Not,
+                1 => UnaryOperator::Plus,
+                _ => UnaryOperator::Minus,
+            };
+            Expression::UnaryOperation { operand: Box::new(Expression::arbitrary(g)), operator }
+        };
+        g.depth += 1;
+        expr
+    }
+}
+
+impl Arbitrary for TableReference {
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.depth > 0 && g.chance(20) {
+            g.depth -= 1;
+            let kind = match g.below(5) {
+                0 => JoinKind::Inner,
+                1 => JoinKind::Left,
+                2 => JoinKind::Right,
+                3 => JoinKind::Full,
+                _ => JoinKind::Cross,
+            };
+            let on = if kind != JoinKind::Cross && g.chance(80) { Some(g.condition()) } else { None };
+            let join = TableReference::Join {
+                left: Box::new(TableReference::arbitrary(g)),
+                right: Box::new(TableReference::arbitrary(g)),
+                kind,
+                on,
+            };
+            g.depth += 1;
+            return join;
+        }
+
+        if g.depth > 0 && g.chance(10) {
+            g.depth -= 1;
+            let table = Box::new(TableReference::arbitrary(g));
+            let pivoted = if g.chance(50) {
+                let pivot = PivotClause {
+                    aggregate: Expression::Function { name: g.identifier(), args: g.vec_of(1, 1), over: None },
+                    column: Expression::Identifier(g.identifier()),
+                    values: g.vec_of(1, 3),
+                };
+                TableReference::Pivot { table, pivot, alias: g.optional_identifier() }
+            } else {
+                let unpivot = UnpivotClause {
+                    value_column: g.identifier(),
+                    name_column: g.identifier(),
+                    columns: (0..1 + g.below(3)).map(|_| g.identifier()).collect(),
+                };
+                TableReference::Unpivot { table, unpivot, alias: g.optional_identifier() }
+            };
+            g.depth += 1;
+            return pivoted;
+        }
+
+        match g.below(6) {
+            0 if g.depth > 0 => {
+                g.depth -= 1;
+                let derived = TableReference::Derived { lateral: g.chance(50), subquery: Box::new(g.query()), alias: g.optional_identifier() };
+                g.depth += 1;
+                derived
+            }
+            1 => TableReference::Function {
+                lateral: g.chance(50),
+                name: g.identifier(),
+                args: g.vec_of(0, 2),
+                alias: g.optional_identifier(),
+            },
+            2 => TableReference::Table {
+                name: g.identifier(),
+                alias: g.optional_identifier(),
+                sample: Some(TableSample {
+                    method: if g.chance(50) { "SYSTEM".to_string() } else { "BERNOULLI".to_string() },
+                    percentage: Expression::Literal(Literal::Integer(g.below(101) as i64)),
+                    repeatable: if g.chance(50) { Some(Expression::Literal(Literal::Integer(g.below(1000) as i64))) } else { None },
+                }),
+            },
+            _ => TableReference::Table { name: g.identifier(), alias: g.optional_identifier(), sample: None },
+        }
+    }
+}
+
+impl Arbitrary for Hint {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Hint { name: g.plain_identifier().to_uppercase(), args: (0..g.below(3)).map(|_| g.plain_identifier()).collect() }
+    }
+}
+
+impl Arbitrary for GroupingElement {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(6) {
+            0 => GroupingElement::Rollup(g.grouping_sets(false)),
+            1 => GroupingElement::Cube(g.grouping_sets(false)),
+            2 => GroupingElement::GroupingSets(g.grouping_sets(true)),
+            _ => GroupingElement::Expression(Expression::arbitrary(g)),
+        }
+    }
+}
+
+impl Arbitrary for SetOperator {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(3) {
+            0 => SetOperator::Union,
+            1 => SetOperator::Intersect,
+            _ => SetOperator::Except,
+        }
+    }
+}
+
+impl Arbitrary for BinaryOperator {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(27) {
+            0 => BinaryOperator::Plus,
+            1 => BinaryOperator::Minus,
+            2 => BinaryOperator::Multiply,
+            3 => BinaryOperator::Divide,
+            4 => BinaryOperator::GreaterThan,
+            5 => BinaryOperator::GreaterThanOrEqual,
+            6 => BinaryOperator::LessThan,
+            7 => BinaryOperator::LessThanOrEqual,
+            8 => BinaryOperator::Equal,
+            9 => BinaryOperator::NotEqual,
+            10 => BinaryOperator::And,
+            11 => BinaryOperator::Or,
+            12 => BinaryOperator::JsonGet,
+            13 => BinaryOperator::JsonGetText,
+            14 => BinaryOperator::JsonPath,
+            15 => BinaryOperator::JsonPathText,
+            16 => BinaryOperator::JsonContains,
+            17 => BinaryOperator::JsonContainedBy,
+            18 => BinaryOperator::RegexMatch,
+            19 => BinaryOperator::RegexIMatch,
+            20 => BinaryOperator::RegexNotMatch,
+            21 => BinaryOperator::RegexNotIMatch,
+            22 => BinaryOperator::Regexp,
+            23 => BinaryOperator::Rlike,
+            24 => BinaryOperator::NotRegexp,
+            25 => BinaryOperator::NotRlike,
+            _ => BinaryOperator::Concat,
+        }
+    }
+}
+
+impl Arbitrary for TableColumn {
+    fn arbitrary(g: &mut Gen) -> Self {
+        TableColumn {
+            column_name: g.identifier(),
+            column_type: DBType::arbitrary(g),
+            constraints: g.vec_of(0, 2),
+        }
+    }
+}
+
+impl Arbitrary for DBType {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(8) {
+            0 => DBType::Int,
+            1 => DBType::Bool,
+            2 => {
+                let precision = 1 + g.below(38) as usize;
+                DBType::Decimal(precision, g.below(precision as u64 + 1) as usize)
+            }
+            3 => DBType::Double,
+            4 => DBType::Char(1 + g.below(100) as usize),
+            5 => DBType::Timestamp { with_time_zone: g.chance(50) },
+            //names that are not the name of a known type, so they read back as custom types
+            6 => {
+                let name = ["citext", "geography", "hstore", "ltree", "vector"][g.below(5) as usize].to_string();
+                let args = (0..g.below(3)).map(|_| if g.chance(50) { Expression::Identifier(g.identifier()) } else { Expression::Literal(Literal::Integer(g.below(5000) as i64)) }).collect();
+                DBType::Custom(name, args)
+            }
+            _ => DBType::Varchar(1 + g.below(1000) as usize),
+        }
+    }
+}
+
+impl Arbitrary for Constraint {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(3) {
+            0 => Constraint::NotNull,
+            1 => Constraint::PrimaryKey,
+            _ => Constraint::Check(g.condition()),
+        }
+    }
+}
+
+/// A statement whose formatted SQL did not parse back into the same AST.
+#[derive(Debug)]
+pub struct RoundtripFailure {
+    pub seed: u64,
+    pub sql: String,
+    pub error: String,
+}
+
+//format the statement, parse it again and compare; with the JSON form compiled in, it has to load back as well
+pub fn check_roundtrip(stmt: &Statement) -> Result<(), String> {
+    #[cfg(feature = "serde")]
+    if Statement::from_json(&stmt.to_json()).as_ref() != Ok(stmt) {
+        return Err("AST does not load back from its JSON form".to_string());
+    }
+    let sql = format_statement(stmt);
+    let tokens: Vec<_> = Tokenizer::new(&sql).keep_hints(true).collect();
+    match Parser::new(tokens).custom_types(true).parse_statement() {
+        Ok(ref reparsed) if reparsed == stmt => Ok(()),
+        Ok(reparsed) => Err(format!("reparsed AST differs: {:?}", reparsed)),
+        Err(err) => Err(err),
+    }
+}
+
+//generate `count` statements starting at `seed` and report every mismatch
+pub fn check_roundtrips(seed: u64, count: u64) -> Vec<RoundtripFailure> {
+    let mut failures = Vec::new();
+    for case_seed in seed..seed + count {
+        let stmt = Statement::arbitrary(&mut Gen::new(case_seed));
+        if let Err(error) = check_roundtrip(&stmt) {
+            failures.push(RoundtripFailure { seed: case_seed, sql: format_statement(&stmt), error });
+        }
+    }
+    failures
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::tokenizer::Tokenizer;
+use crate::parser::{Parser, ParseMode};
+use crate::dialect::Dialect;
+use crate::conformance::collect_sql_files;
+
+/// Result of parsing one file of a batch.
+/// 1. `path` – The file.
+/// 2. `outcome` – The number of statements in the file, or the first error (reading or parsing) that stopped it.
+/// 3. `elapsed` – Time spent reading and parsing the file.
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub outcome: Result<usize, String>,
+    pub elapsed: Duration,
+}
+
+/// Results of a batch, one entry per file in the order the files were given, and the wall clock time of the whole batch.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub files: Vec<FileReport>,
+    pub elapsed: Duration,
+}
+
+impl BatchReport {
+    pub fn statements(&self) -> usize {
+        self.files.iter().filter_map(|file| file.outcome.as_ref().ok()).sum()
+    }
+
+    pub fn errors(&self) -> usize {
+        self.files.iter().filter(|file| file.outcome.is_err()).count()
+    }
+}
+
+//expand the paths of a batch into files: directories are walked for `.sql` files (sorted) when `recursive`,
+//and are an error otherwise
+pub fn expand_paths(paths: &[PathBuf], recursive: bool) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+        } else if recursive {
+            let mut found = Vec::new();
+            collect_sql_files(path, &mut found)?;
+            found.sort();
+            files.extend(found);
+        } else {
+            let message = format!("{} is a directory (use --recursive to parse the .sql files in it)", path.display());
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        }
+    }
+    Ok(files)
+}
+
+//parse every file with `jobs` threads, the report keeps the order of `files` whatever order they finish in
+pub fn run_batch(files: &[PathBuf], dialect: Dialect, mode: ParseMode, keep_hints: bool, jobs: usize) -> BatchReport {
+    let started = Instant::now();
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<FileReport>>> = Mutex::new(files.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, files.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = files.get(index) else { break };
+                let report = parse_file(path, dialect, mode, keep_hints);
+                results.lock().unwrap()[index] = Some(report);
+            });
+        }
+    });
+
+    BatchReport {
+        files: results.into_inner().unwrap().into_iter().flatten().collect(),
+        elapsed: started.elapsed(),
+    }
+}
+
+//read and parse a single file of a batch
+pub fn parse_file(path: &Path, dialect: Dialect, mode: ParseMode, keep_hints: bool) -> FileReport {
+    let started = Instant::now();
+    let outcome = fs::read(path).map_err(|err| err.to_string()).and_then(|source| {
+        let tokens = Tokenizer::from_bytes(&source, dialect).keep_hints(keep_hints).tokenize().map_err(|err| err.to_string())?;
+        Parser::with_dialect(tokens, dialect).mode(mode).source(&source).parse_statements().map(|stmts| stmts.len())
+    });
+    FileReport { path: path.to_path_buf(), outcome, elapsed: started.elapsed() }
+}
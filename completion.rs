@@ -0,0 +1,462 @@
+//parsing of incomplete input, the text before the cursor in an editor, for completion and similar IDE features
+use crate::token::{Token, Keyword};
+use crate::tokenizer::{Tokenizer, SpannedToken, StatementEnds};
+use crate::parser::Parser;
+use crate::dialect::Dialect;
+use crate::statement::{Statement, TableReference, TableColumn};
+use crate::analysis::table_uses;
+
+/// What could be made of the input before the cursor.
+/// 1. `statements` – The statements before the last semicolon that parse.
+/// 2. `current` – The statement being written, cut back to its longest beginning that is a statement on its own: `SELECT a FROM t` for `SELECT a FROM t WHERE x =`. `None` while not even that much is there, like in the select list before `FROM`.
+#[derive(Debug, PartialEq, Default)]
+pub struct PartialAst {
+    pub statements: Vec<Statement>,
+    pub current: Option<Statement>,
+}
+
+/// What may come at the cursor.
+/// 1. `clause` – The clause the cursor is in, in the innermost query or parentheses.
+/// 2. `word` – The word being typed, the part of a name or keyword between its start and the cursor. Empty after whitespace or punctuation. The suggestions replace it, and it is what they are filtered by.
+/// 3. `expected` – What can be written at the cursor. Empty inside a string literal or a comment, and where only punctuation or a number can follow.
+#[derive(Debug, PartialEq)]
+pub struct ExpectedNext {
+    pub clause: Clause,
+    pub word: String,
+    pub expected: Vec<Expected>,
+}
+
+/// Part of a statement the cursor can be in. `Columns` is the column list of `CREATE TABLE`, `Check` the condition of a `CHECK` constraint and `TypeArguments` the length or precision of a type, `VARCHAR(`. `Insert` is the table and column list of `INSERT INTO`, `Values` its rows, `OnConflict` its `ON CONFLICT` clause up to `SET` and `Set` the assignments after it, `Delete` the table of `DELETE FROM` (its condition is `Where`), `Drop` the names of `DROP TABLE`, `DROP INDEX` or `DROP VIEW`, `AlterTable` an `ALTER TABLE` with its action, `Truncate` the table of `TRUNCATE`, `CreateIndex` a `CREATE INDEX` with its table and columns, `Show` what `SHOW` lists, `Describe` the table of `DESCRIBE` and `With` the names and column lists of a `WITH` clause (the query of a common table expression is a `Select` of its own). `CreateTable` also stands for what follows `CREATE` before it is known what is created.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Clause {
+    Start,
+    Select,
+    From,
+    Where,
+    GroupBy,
+    Having,
+    Qualify,
+    OrderBy,
+    Limit,
+    CreateTable,
+    Columns,
+    Check,
+    TypeArguments,
+    Insert,
+    Values,
+    OnConflict,
+    Set,
+    Delete,
+    Drop,
+    AlterTable,
+    Truncate,
+    CreateIndex,
+    Show,
+    Describe,
+    With,
+}
+
+/// One kind of thing that can be written at the cursor. `ColumnName` has the qualifier written before the cursor, `o` for `o.`, and is also what starts a new column definition in `CREATE TABLE`. `Operator` stands for any binary operator, `AND` and `OR` included.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    Keyword(Keyword),
+    Expression,
+    Operator,
+    TableName,
+    ColumnName { qualifier: Option<String> },
+    Alias,
+    DataType,
+}
+
+//clauses of a SELECT in the order they are written
+const SELECT_CLAUSES: &[(Clause, Keyword)] = &[
+    (Clause::From, Keyword::From),
+    (Clause::Where, Keyword::Where),
+    (Clause::GroupBy, Keyword::Group),
+    (Clause::Having, Keyword::Having),
+    (Clause::Qualify, Keyword::Qualify),
+    (Clause::OrderBy, Keyword::Order),
+    (Clause::Limit, Keyword::Limit),
+];
+
+//parse the text before the cursor, see `parse_prefix_with_dialect`
+pub fn parse_prefix(sql: &str) -> (PartialAst, ExpectedNext) {
+    parse_prefix_with_dialect(sql, Dialect::default())
+}
+
+//parse input that ends at the cursor: no closing semicolon needed, and it may stop in the middle of a clause or a word
+//statements before the last semicolon are parsed as usual, the one being written is parsed as far as it goes,
+//and what it has so far decides what can come next
+pub fn parse_prefix_with_dialect(sql: &str, dialect: Dialect) -> (PartialAst, ExpectedNext) {
+    let mut tokens: Vec<SpannedToken> = Vec::new();
+    let mut in_literal = false;
+    for result in Tokenizer::with_dialect(sql, dialect).keep_comments(true).spanned() {
+        match result {
+            //the cursor is inside a -- comment that reaches it, a /* */ one that reaches it is closed
+            Ok(SpannedToken { token: Token::Comment { .. }, span }) => in_literal = span.end == sql.len() && sql[span.start..].starts_with("--"),
+            Ok(spanned) => tokens.push(spanned),
+            //an unterminated string, quoted name or comment runs up to the cursor
+            Err(err) => in_literal = err.span.end == sql.len(),
+        }
+    }
+
+    //complete statements, each parsed on its own so one with an error does not hide the others
+    let mut ends = StatementEnds::default();
+    let semicolons: Vec<usize> = (0..tokens.len())
+        .filter(|&i| ends.ends_statement(&tokens[i].token, tokens.get(i + 1).map_or(&Token::Eof, |next| &next.token)))
+        .collect();
+    let split = semicolons.last().map_or(0, |pos| pos + 1);
+    let (done, current) = tokens.split_at(split);
+    let mut partial = PartialAst::default();
+    let starts = std::iter::once(0).chain(semicolons.iter().map(|pos| pos + 1));
+    for stmt in starts.zip(&semicolons).map(|(start, end)| &done[start..*end]).filter(|stmt| !stmt.is_empty()) {
+        let mut stmt_tokens: Vec<Token> = stmt.iter().map(|spanned| spanned.token.clone()).collect();
+        stmt_tokens.push(Token::Semicolon);
+        if let Ok(stmt) = Parser::with_dialect(stmt_tokens, dialect).parse_statement() {
+            partial.statements.push(stmt);
+        }
+    }
+
+    //a name or keyword that reaches the cursor is the word being typed, not part of what comes before it
+    let mut current: Vec<Token> = current.iter().map(|spanned| spanned.token.clone()).collect();
+    let mut word = String::new();
+    if let Some(last) = tokens.last().filter(|last| last.span.end == sql.len() && split < tokens.len()) {
+        let text = &sql[last.span.start..last.span.end];
+        let is_word = matches!(last.token, Token::Identifier(_) | Token::Keyword(_)) && text.starts_with(|ch: char| ch.is_alphabetic() || ch == '_');
+        if is_word && !in_literal {
+            word = text.to_string();
+            current.pop();
+        }
+    }
+
+    partial.current = longest_statement(&current, dialect);
+    let clause = clause_at(&current);
+    let expected = if in_literal { Vec::new() } else { expected_after(&current, clause, dialect) };
+    (partial, ExpectedNext { clause, word, expected })
+}
+
+/// The words that can be written at the cursor with a schema to take names from, the `CREATE TABLE` statements of the tables that exist: the keywords of `expected`, the tables of the schema where a table name can be written and its columns where a column or an expression can. Only words that start with the word being typed (case-insensitively) are suggested, keywords in upper case and names as they are written in the schema.
+///
+/// A qualified column is completed from the table the qualifier names, an alias in the statement being written or a table of the schema. Unqualified columns come from the tables the statement uses as far as it has been parsed, or from every table of the schema before it names one, in the select list before `FROM` for instance.
+pub fn suggestions(partial: &PartialAst, next: &ExpectedNext, schema: &[Statement]) -> Vec<String> {
+    let tables: Vec<(&String, &[TableColumn])> = schema.iter().filter_map(|stmt| match stmt {
+        Statement::CreateTable { table_name, column_list, .. } => Some((table_name, column_list.as_slice())),
+        _ => None,
+    }).collect();
+    let columns_of = |name: &str| -> Vec<String> {
+        let table = tables.iter().filter(|(table, _)| table.eq_ignore_ascii_case(name));
+        table.flat_map(|(_, columns)| columns.iter()).map(|column| column.column_name.clone()).collect()
+    };
+    let used: Vec<String> = partial.current.as_ref().map(|stmt| table_uses(stmt).into_iter().map(|table| table.name).collect()).unwrap_or_default();
+
+    let mut words: Vec<String> = Vec::new();
+    for expected in &next.expected {
+        match expected {
+            Expected::Keyword(keyword) => words.push(keyword.as_str().to_string()),
+            //the name of a table or column being created is a new one
+            Expected::TableName if next.clause != Clause::CreateTable => words.extend(tables.iter().map(|(name, _)| name.to_string())),
+            Expected::ColumnName { qualifier: Some(qualifier) } => {
+                let table = partial.current.as_ref().and_then(|stmt| aliased_table(stmt, qualifier)).unwrap_or(qualifier);
+                words.extend(columns_of(table));
+            }
+            Expected::ColumnName { qualifier: None } | Expected::Expression if next.clause != Clause::Columns => {
+                if used.is_empty() {
+                    words.extend(tables.iter().flat_map(|(_, columns)| columns.iter()).map(|column| column.column_name.clone()));
+                } else {
+                    words.extend(used.iter().flat_map(|table| columns_of(table)));
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut suggestions: Vec<String> = Vec::new();
+    for word in words {
+        let typed = word.len() >= next.word.len() && word.is_char_boundary(next.word.len()) && word[..next.word.len()].eq_ignore_ascii_case(&next.word);
+        if typed && !suggestions.iter().any(|suggestion| suggestion.eq_ignore_ascii_case(&word)) {
+            suggestions.push(word);
+        }
+    }
+    suggestions
+}
+
+//helper, the table an alias of the FROM clause of a query stands for
+fn aliased_table<'a>(stmt: &'a Statement, qualifier: &str) -> Option<&'a String> {
+    match stmt {
+        Statement::Select { from, .. } => from.iter().find_map(|table| table_with_alias(table, qualifier)),
+        Statement::CreateTable { query: Some(query), .. } => aliased_table(query, qualifier),
+        //the row an upsert was to insert
+        Statement::Insert { table_name, .. } if qualifier.eq_ignore_ascii_case("excluded") => Some(table_name),
+        _ => None,
+    }
+}
+
+fn table_with_alias<'a>(table: &'a TableReference, qualifier: &str) -> Option<&'a String> {
+    match table {
+        TableReference::Table { name, alias: Some(alias), .. } if alias.eq_ignore_ascii_case(qualifier) => Some(name),
+        TableReference::Join { left, right, .. } => table_with_alias(left, qualifier).or_else(|| table_with_alias(right, qualifier)),
+        TableReference::Pivot { table, .. } | TableReference::Unpivot { table, .. } => table_with_alias(table, qualifier),
+        _ => None,
+    }
+}
+
+//the statement made of the most tokens from the start of `tokens`, closed with a semicolon
+fn longest_statement(tokens: &[Token], dialect: Dialect) -> Option<Statement> {
+    (1..=tokens.len()).rev().find_map(|len| {
+        let mut stmt_tokens = tokens[..len].to_vec();
+        stmt_tokens.push(Token::Semicolon);
+        Parser::with_dialect(stmt_tokens, dialect).parse_statement().ok()
+    })
+}
+
+//the clause at the end of the tokens, every open parenthesis is a level of its own
+fn clause_at(tokens: &[Token]) -> Clause {
+    let mut levels = vec![Clause::Start];
+    for (i, tok) in tokens.iter().enumerate() {
+        let current = *levels.last().unwrap();
+        match tok {
+            Token::LeftParentheses => levels.push(match current {
+                Clause::CreateTable => Clause::Columns,
+                Clause::Columns if i > 0 && tokens[i - 1] == Token::Keyword(Keyword::Check) => Clause::Check,
+                Clause::Columns => Clause::TypeArguments,
+                Clause::AlterTable if i > 0 && tokens[i - 1] == Token::Keyword(Keyword::Check) => Clause::Check,
+                Clause::AlterTable => Clause::TypeArguments,
+                //groups, function arguments and subqueries, a SELECT inside changes it
+                other => other,
+            }),
+            Token::RightParentheses if levels.len() > 1 => {
+                levels.pop();
+            }
+            Token::Keyword(Keyword::Select) => *levels.last_mut().unwrap() = Clause::Select,
+            Token::Keyword(Keyword::Create) => *levels.last_mut().unwrap() = Clause::CreateTable,
+            Token::Keyword(Keyword::Index) if current == Clause::CreateTable => *levels.last_mut().unwrap() = Clause::CreateIndex,
+            Token::Keyword(Keyword::Insert) => *levels.last_mut().unwrap() = Clause::Insert,
+            Token::Keyword(Keyword::Values) => *levels.last_mut().unwrap() = Clause::Values,
+            Token::Keyword(Keyword::On) if current == Clause::Values => *levels.last_mut().unwrap() = Clause::OnConflict,
+            Token::Keyword(Keyword::Set) if current == Clause::OnConflict => *levels.last_mut().unwrap() = Clause::Set,
+            Token::Keyword(Keyword::Delete) => *levels.last_mut().unwrap() = Clause::Delete,
+            Token::Keyword(Keyword::Drop) if current != Clause::AlterTable => *levels.last_mut().unwrap() = Clause::Drop,
+            Token::Keyword(Keyword::Alter) => *levels.last_mut().unwrap() = Clause::AlterTable,
+            Token::Keyword(Keyword::Truncate) if i == 0 => *levels.last_mut().unwrap() = Clause::Truncate,
+            Token::Keyword(Keyword::Show) if i == 0 => *levels.last_mut().unwrap() = Clause::Show,
+            Token::Keyword(Keyword::Describe | Keyword::Desc) if i == 0 => *levels.last_mut().unwrap() = Clause::Describe,
+            Token::Keyword(Keyword::With) => *levels.last_mut().unwrap() = Clause::With,
+            Token::Keyword(Keyword::From) if current == Clause::Delete => {}
+            Token::Keyword(keyword) if current != Clause::Columns && current != Clause::AlterTable => {
+                if let Some((clause, _)) = SELECT_CLAUSES.iter().find(|(_, starts)| starts == keyword) {
+                    *levels.last_mut().unwrap() = *clause;
+                }
+            }
+            _ => {}
+        }
+    }
+    *levels.last().unwrap()
+}
+
+fn expected_after(tokens: &[Token], clause: Clause, dialect: Dialect) -> Vec<Expected> {
+    let keywords = |keywords: &[Keyword]| keywords.iter().map(|keyword| Expected::Keyword(*keyword)).collect::<Vec<_>>();
+    let Some(last) = tokens.last() else {
+        return keywords(&[Keyword::Select, Keyword::Values, Keyword::Create, Keyword::Insert, Keyword::Delete, Keyword::Merge, Keyword::Drop, Keyword::Alter, Keyword::Rename, Keyword::Truncate, Keyword::Analyze, Keyword::Vacuum, Keyword::Show, Keyword::Describe, Keyword::Set, Keyword::Call, Keyword::Copy, Keyword::Load, Keyword::Prepare, Keyword::Execute, Keyword::Deallocate, Keyword::With]);
+    };
+    let before = tokens.len().checked_sub(2).map(|i| &tokens[i]);
+    let starts_item = |tok: Option<&Token>| matches!(tok, Some(Token::LeftParentheses | Token::Comma));
+
+    match clause {
+        Clause::CreateTable => match last {
+            Token::Keyword(Keyword::Create) => keywords(&[Keyword::Table, Keyword::Index, Keyword::Unique, Keyword::Trigger, Keyword::Function, Keyword::Procedure, Keyword::Database, Keyword::Schema]),
+            Token::Keyword(Keyword::Unique) => keywords(&[Keyword::Index]),
+            Token::Keyword(Keyword::Table) => vec![Expected::TableName, Expected::Keyword(Keyword::If)],
+            Token::Keyword(Keyword::Database | Keyword::Schema) => keywords(&[Keyword::If]),
+            Token::Keyword(Keyword::If) => keywords(&[Keyword::Not]),
+            Token::Keyword(Keyword::Not) => keywords(&[Keyword::Exists]),
+            Token::Keyword(Keyword::Exists) if tokens.contains(&Token::Keyword(Keyword::Table)) => vec![Expected::TableName],
+            //after the table name, a column list or AS SELECT
+            Token::Keyword(Keyword::As) => keywords(&[Keyword::Select]),
+            _ if matches!(before, Some(Token::Keyword(Keyword::Table | Keyword::Exists))) && tokens.contains(&Token::Keyword(Keyword::Table)) => keywords(&[Keyword::As]),
+            _ => Vec::new(),
+        },
+        Clause::Columns => match last {
+            Token::LeftParentheses | Token::Comma => vec![Expected::ColumnName { qualifier: None }],
+            Token::Keyword(Keyword::Not) => keywords(&[Keyword::Null]),
+            Token::Keyword(Keyword::Primary) => keywords(&[Keyword::Key]),
+            Token::Keyword(Keyword::Check) => Vec::new(),
+            _ if starts_item(before) => vec![Expected::DataType],
+            _ => keywords(&[Keyword::Primary, Keyword::Not, Keyword::Check]),
+        },
+        Clause::TypeArguments => Vec::new(),
+        Clause::Insert => match last {
+            Token::Keyword(Keyword::Insert) => keywords(&[Keyword::Into]),
+            Token::Keyword(Keyword::Into) => vec![Expected::TableName],
+            Token::LeftParentheses | Token::Comma => vec![Expected::ColumnName { qualifier: None }],
+            //after the table name or the column list, a column list can only be followed by `,` or `)`
+            Token::RightParentheses => keywords(&[Keyword::Values, Keyword::Select, Keyword::With]),
+            _ if before == Some(&Token::Keyword(Keyword::Into)) => keywords(&[Keyword::Values, Keyword::Select, Keyword::With]),
+            _ => Vec::new(),
+        },
+        Clause::Delete => match last {
+            Token::Keyword(Keyword::Delete) => keywords(&[Keyword::From]),
+            Token::Keyword(Keyword::From) => vec![Expected::TableName],
+            _ => keywords(&[Keyword::Where]),
+        },
+        Clause::Drop => match last {
+            Token::Keyword(Keyword::Drop) => keywords(&[Keyword::Table, Keyword::Index, Keyword::View]),
+            //there is no schema to complete index names from
+            Token::Keyword(Keyword::Index) => keywords(&[Keyword::If]),
+            Token::Keyword(Keyword::Table | Keyword::View) => vec![Expected::TableName, Expected::Keyword(Keyword::If)],
+            Token::Keyword(Keyword::If) => keywords(&[Keyword::Exists]),
+            _ if tokens.contains(&Token::Keyword(Keyword::Index)) => Vec::new(),
+            Token::Keyword(Keyword::Exists) | Token::Comma => vec![Expected::TableName],
+            _ => Vec::new(),
+        },
+        Clause::CreateIndex => match last {
+            Token::Keyword(Keyword::Index) => keywords(&[Keyword::If]),
+            Token::Keyword(Keyword::If) => keywords(&[Keyword::Not]),
+            Token::Keyword(Keyword::Not) => keywords(&[Keyword::Exists]),
+            Token::Keyword(Keyword::On) => vec![Expected::TableName],
+            Token::LeftParentheses | Token::Comma => vec![Expected::ColumnName { qualifier: None }],
+            //after the index name
+            _ if matches!(before, Some(Token::Keyword(Keyword::Index | Keyword::Exists))) => keywords(&[Keyword::On]),
+            _ => Vec::new(),
+        },
+        Clause::Truncate => match last {
+            Token::Keyword(Keyword::Truncate) => vec![Expected::Keyword(Keyword::Table), Expected::TableName],
+            Token::Keyword(Keyword::Table) => vec![Expected::TableName],
+            _ => Vec::new(),
+        },
+        Clause::Show => match last {
+            Token::Keyword(Keyword::Show) => keywords(&[Keyword::Tables, Keyword::Databases]),
+            _ => Vec::new(),
+        },
+        Clause::Describe => match last {
+            Token::Keyword(Keyword::Describe | Keyword::Desc) => vec![Expected::TableName],
+            _ => Vec::new(),
+        },
+        Clause::With => match last {
+            Token::Keyword(Keyword::With) => keywords(&[Keyword::Recursive]),
+            Token::Keyword(Keyword::Recursive) if before == Some(&Token::Keyword(Keyword::With)) => Vec::new(),
+            Token::LeftParentheses if before == Some(&Token::Keyword(Keyword::As)) => keywords(&[Keyword::Select, Keyword::With]),
+            //after the query of a common table expression comes the next one or the query of the statement, after a column list AS
+            Token::RightParentheses => {
+                let mut open = tokens.len() - 1;
+                while open > 0 && depth(&tokens[open..]) < 0 {
+                    open -= 1;
+                }
+                if open > 0 && tokens[open - 1] == Token::Keyword(Keyword::As) { keywords(&[Keyword::Select]) } else { keywords(&[Keyword::As]) }
+            }
+            _ if name(last).is_some() && matches!(before, Some(Token::Keyword(Keyword::With | Keyword::Recursive) | Token::Comma)) => keywords(&[Keyword::As]),
+            _ => Vec::new(),
+        },
+        Clause::AlterTable => match last {
+            Token::Keyword(Keyword::Alter) => keywords(&[Keyword::Table]),
+            Token::Keyword(Keyword::Table) => vec![Expected::TableName],
+            Token::Keyword(Keyword::Add | Keyword::Drop) => vec![Expected::Keyword(Keyword::Column), Expected::ColumnName { qualifier: None }],
+            Token::Keyword(Keyword::Rename) => vec![Expected::Keyword(Keyword::Column), Expected::Keyword(Keyword::To), Expected::ColumnName { qualifier: None }],
+            Token::Keyword(Keyword::Column) => vec![Expected::ColumnName { qualifier: None }],
+            //the new name, of the table right after RENAME
+            Token::Keyword(Keyword::To) if before == Some(&Token::Keyword(Keyword::Rename)) => vec![Expected::TableName],
+            Token::Keyword(Keyword::To) => vec![Expected::ColumnName { qualifier: None }],
+            Token::Keyword(Keyword::Not) => keywords(&[Keyword::Null]),
+            Token::Keyword(Keyword::Primary) => keywords(&[Keyword::Key]),
+            Token::Keyword(Keyword::Check) => Vec::new(),
+            _ if before == Some(&Token::Keyword(Keyword::Table)) => keywords(&[Keyword::Add, Keyword::Drop, Keyword::Rename]),
+            //after the column of DROP or RENAME and the new name of RENAME nothing more is written
+            _ if tokens.iter().any(|tok| tok == &Token::Keyword(Keyword::Drop) || tok == &Token::Keyword(Keyword::To)) => Vec::new(),
+            _ if tokens.contains(&Token::Keyword(Keyword::Rename)) => keywords(&[Keyword::To]),
+            _ if matches!(before, Some(Token::Keyword(Keyword::Add | Keyword::Column))) => vec![Expected::DataType],
+            _ => keywords(&[Keyword::Primary, Keyword::Not, Keyword::Check]),
+        },
+        //only the `(` of a row follows VALUES
+        Clause::Values if last == &Token::Keyword(Keyword::Values) => Vec::new(),
+        Clause::Values if last == &Token::RightParentheses && depth(tokens) == 0 => vec![Expected::Keyword(Keyword::On)],
+        Clause::OnConflict => match last {
+            Token::Keyword(Keyword::On) => keywords(&[Keyword::Conflict]),
+            Token::Keyword(Keyword::Conflict) | Token::RightParentheses => keywords(&[Keyword::Do]),
+            Token::LeftParentheses | Token::Comma => vec![Expected::ColumnName { qualifier: None }],
+            Token::Keyword(Keyword::Do) => keywords(&[Keyword::Nothing, Keyword::Update]),
+            Token::Keyword(Keyword::Update) => keywords(&[Keyword::Set]),
+            _ => Vec::new(),
+        },
+        //the column of an assignment, then its `=`, the value is an expression like any other
+        Clause::Set if last == &Token::Keyword(Keyword::Set) || (last == &Token::Comma && depth(tokens) == 0) => vec![Expected::ColumnName { qualifier: None }],
+        Clause::Set if name(last).is_some() && matches!(before, Some(Token::Keyword(Keyword::Set) | Token::Comma)) && depth(tokens) == 0 => vec![Expected::Operator],
+        _ => match last {
+            Token::Period => vec![Expected::ColumnName { qualifier: before.and_then(name) }],
+            Token::Comma if clause == Clause::From => vec![Expected::TableName],
+            Token::Comma => vec![Expected::Expression],
+            Token::LeftParentheses if clause == Clause::From && matches!(before, Some(Token::Keyword(Keyword::From | Keyword::Join | Keyword::Lateral) | Token::Comma)) => {
+                keywords(&[Keyword::Select])
+            }
+            Token::LeftParentheses => vec![Expected::Expression, Expected::Keyword(Keyword::Select)],
+            Token::Keyword(Keyword::From | Keyword::Join) => vec![Expected::TableName],
+            Token::Keyword(Keyword::Group | Keyword::Order | Keyword::Partition) => keywords(&[Keyword::By]),
+            Token::Keyword(Keyword::Left | Keyword::Right | Keyword::Full) => keywords(&[Keyword::Outer, Keyword::Join]),
+            Token::Keyword(Keyword::Inner | Keyword::Cross | Keyword::Outer) => keywords(&[Keyword::Join]),
+            Token::Keyword(Keyword::As) => vec![Expected::Alias],
+            Token::Keyword(Keyword::Union | Keyword::Intersect | Keyword::Except) => keywords(&[Keyword::All, Keyword::Distinct, Keyword::Select]),
+            Token::Keyword(Keyword::All | Keyword::Distinct) if matches!(before, Some(Token::Keyword(Keyword::Union | Keyword::Intersect | Keyword::Except))) => {
+                keywords(&[Keyword::Select])
+            }
+            Token::Keyword(Keyword::Is) => keywords(&[Keyword::Not, Keyword::Null, Keyword::Distinct, Keyword::True, Keyword::False]),
+            Token::Keyword(Keyword::Distinct) => keywords(&[Keyword::From]),
+            Token::Keyword(Keyword::Asc | Keyword::Desc) => keywords(&[Keyword::Limit]),
+            //`*` right where an operand starts is the wildcard, anywhere else a multiplication
+            Token::Star if matches!(before, Some(Token::Keyword(Keyword::Select) | Token::Comma | Token::Period | Token::LeftParentheses)) => {
+                after_operand(tokens, clause, dialect)
+            }
+            Token::Keyword(Keyword::Null | Keyword::True | Keyword::False) => after_operand(tokens, clause, dialect),
+            Token::Keyword(keyword) if keyword.is_reserved() => vec![Expected::Expression],
+            tok if tok.binary_operator().is_some() || dialect.binary_operator(tok).is_some() => vec![Expected::Expression],
+            _ => after_operand(tokens, clause, dialect),
+        },
+    }
+}
+
+//what can follow a complete operand, a table name or an alias: more of the clause or the start of a later one
+fn after_operand(tokens: &[Token], clause: Clause, dialect: Dialect) -> Vec<Expected> {
+    let mut expected = match clause {
+        Clause::Start => return Vec::new(),
+        Clause::Select => vec![Expected::Operator, Expected::Alias],
+        Clause::From => {
+            let mut expected = vec![Expected::Alias];
+            expected.extend([Keyword::Join, Keyword::Inner, Keyword::Left, Keyword::Right, Keyword::Full, Keyword::Cross].map(Expected::Keyword));
+            if tokens.contains(&Token::Keyword(Keyword::Join)) {
+                expected.push(Expected::Keyword(Keyword::On));
+            }
+            expected
+        }
+        Clause::OrderBy => vec![Expected::Operator, Expected::Keyword(Keyword::Asc), Expected::Keyword(Keyword::Desc)],
+        Clause::Set => vec![Expected::Operator, Expected::Keyword(Keyword::Where)],
+        _ => vec![Expected::Operator],
+    };
+    //the condition of a DELETE or of ON CONFLICT DO UPDATE is the last clause, unless the cursor is in a subquery of it
+    if matches!(tokens.first(), Some(Token::Keyword(Keyword::Delete | Keyword::Insert))) && depth(tokens) <= 0 {
+        return expected;
+    }
+    //later clauses of the SELECT, FROM being the only one that has to come
+    let later = match SELECT_CLAUSES.iter().position(|(later, _)| *later == clause) {
+        _ if clause == Clause::Select => &SELECT_CLAUSES[..1],
+        Some(pos) => &SELECT_CLAUSES[pos + 1..],
+        None => &[],
+    };
+    let later = later.iter().filter(|(later, _)| *later != Clause::Qualify || dialect.supports_qualify());
+    expected.extend(later.map(|(_, keyword)| Expected::Keyword(*keyword)));
+    //a query can be combined with another one before its ORDER BY
+    if matches!(clause, Clause::From | Clause::Where | Clause::GroupBy | Clause::Having | Clause::Qualify) {
+        expected.extend([Keyword::Union, Keyword::Intersect, Keyword::Except].map(Expected::Keyword));
+    }
+    expected
+}
+
+//helper, how many parentheses are open at the end of the tokens
+fn depth(tokens: &[Token]) -> isize {
+    tokens.iter().filter(|tok| **tok == Token::LeftParentheses).count() as isize
+        - tokens.iter().filter(|tok| **tok == Token::RightParentheses).count() as isize
+}
+
+//helper, the name a token stands for, non-reserved keywords in lower case like the parser has them
+fn name(tok: &Token) -> Option<String> {
+    match tok {
+        Token::Identifier(name) => Some(name.clone()),
+        Token::Keyword(keyword) if !keyword.is_reserved() => Some(keyword.as_str().to_lowercase()),
+        _ => None,
+    }
+}
@@ -0,0 +1,212 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::dialect::Dialect;
+use crate::formatter::{Layout, CommentPolicy, CommentPlacement, CommentAttachment, KeywordCase};
+use crate::lint::Severity;
+use crate::parser::ParseMode;
+
+/// Name of the configuration file, looked up in the current directory first and in the home directory after that.
+pub const FILE_NAME: &str = ".sqlparser.toml";
+
+/// Shared settings read from a `.sqlparser.toml` file. Every setting has a default, so a file only needs the ones a team wants to change; command line flags override what the file says.
+///
+/// ```toml
+/// dialect = "postgres"
+/// keep_hints = true
+/// mode = "permissive"
+/// output = "json"
+///
+/// [format]
+/// layout = "single_line"
+/// comments = "trailing"
+/// attach_comments = "statement"
+/// keyword_case = "preserve"
+///
+/// [lint]
+/// enable = ["some_rule"]
+/// disable = ["other_rule"]
+///
+/// [lint.severity]
+/// some_rule = "error"
+///
+/// [embedded]
+/// functions = ["query", "execute"]
+/// ```
+/// 1. `dialect` – The SQL dialect, one of the names accepted by `--dialect`.
+/// 2. `keep_hints` – Keep optimizer hint comments, like `--keep-hints`.
+/// 3. `mode` – How the parser handles SQL it does not understand, `strict` or `permissive` (like `--permissive`), see `ParseMode`.
+/// 4. `output` – How statements are printed, `debug` (the Rust structure), `json`, or `summary` (one line each).
+/// 5. `format` – Formatter style, `layout` is `multiline` or `single_line`; `comments` (`leading` or `trailing`) and `attach_comments` (`statement` or `clause`) are the `CommentPolicy` of `format`, and `keyword_case` (`upper`, `lower` or `preserve`) the case it writes keywords in.
+/// 6. `lint` – Names of lint rules to turn on or off, and in `[lint.severity]` the severity (`error`, `warning` or `off`) of any rule.
+/// 7. `embedded` – The functions and macros whose first argument `check-rust` takes for SQL, instead of `embedded::DEFAULT_FUNCTIONS`.
+#[derive(Debug, PartialEq, Default)]
+pub struct Config {
+    pub dialect: Dialect,
+    pub keep_hints: bool,
+    pub mode: ParseMode,
+    pub output: OutputFormat,
+    pub layout: Layout,
+    pub comments: CommentPolicy,
+    pub keyword_case: KeywordCase,
+    pub lint_enable: Vec<String>,
+    pub lint_disable: Vec<String>,
+    pub lint_severity: Vec<(String, Severity)>,
+    pub embedded_functions: Option<Vec<String>>,
+}
+
+/// How parsed statements are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Debug,
+    Json,
+    Summary,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Some(OutputFormat::Debug),
+            "json" => Some(OutputFormat::Json),
+            "summary" => Some(OutputFormat::Summary),
+            _ => None,
+        }
+    }
+}
+
+//value on the right hand side of `key = value`, the part of TOML the configuration needs
+enum Value {
+    String(String),
+    Bool(bool),
+    Array(Vec<String>),
+}
+
+impl Config {
+    //the configuration file that applies, `.sqlparser.toml` in the current directory or else in the home directory
+    pub fn find() -> Option<PathBuf> {
+        let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")).map(PathBuf::from);
+        let dirs = env::current_dir().ok().into_iter().chain(home);
+        dirs.map(|dir| dir.join(FILE_NAME)).find(|path| path.is_file())
+    }
+
+    //settings from the file found by `find`, the defaults when there is none
+    pub fn load() -> Result<Config, String> {
+        match Config::find() {
+            Some(path) => Config::from_file(&path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Config::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    //parse the text of a configuration file
+    //unknown sections, keys and values are errors, so a typo does not go unnoticed
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut section = String::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                section = name.trim().to_string();
+                if !["format", "lint", "lint.severity", "embedded"].contains(&section.as_str()) {
+                    return Err(format!("line {}: unknown section [{}]", line_no, section));
+                }
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), parse_value(value.trim()).map_err(|err| format!("line {}: {}", line_no, err))?),
+                None => return Err(format!("line {}: expected `key = value`, found {:?}", line_no, line)),
+            };
+            let invalid = |expected: &str| format!("line {}: {} must be {}", line_no, key, expected);
+
+            match (section.as_str(), key, value) {
+                ("", "dialect", Value::String(name)) => {
+                    config.dialect = Dialect::from_name(&name).ok_or_else(|| format!("line {}: unknown dialect {:?}", line_no, name))?;
+                }
+                ("", "keep_hints", Value::Bool(keep)) => config.keep_hints = keep,
+                ("", "mode", Value::String(name)) => {
+                    config.mode = ParseMode::from_name(&name).ok_or_else(|| invalid("\"strict\" or \"permissive\""))?;
+                }
+                ("", "output", Value::String(name)) => {
+                    config.output = OutputFormat::from_name(&name).ok_or_else(|| invalid("\"debug\", \"json\" or \"summary\""))?;
+                }
+                ("format", "layout", Value::String(name)) => {
+                    config.layout = match name.as_str() {
+                        "multiline" => Layout::Multiline,
+                        "single_line" => Layout::SingleLine,
+                        _ => return Err(invalid("\"multiline\" or \"single_line\"")),
+                    };
+                }
+                ("format", "comments", Value::String(name)) => {
+                    config.comments.placement = CommentPlacement::from_name(&name).ok_or_else(|| invalid("\"leading\" or \"trailing\""))?;
+                }
+                ("format", "attach_comments", Value::String(name)) => {
+                    config.comments.attachment = CommentAttachment::from_name(&name).ok_or_else(|| invalid("\"statement\" or \"clause\""))?;
+                }
+                ("format", "keyword_case", Value::String(name)) => {
+                    config.keyword_case = KeywordCase::from_name(&name).ok_or_else(|| invalid("\"upper\", \"lower\" or \"preserve\""))?;
+                }
+                ("lint", "enable", Value::Array(rules)) => config.lint_enable = rules,
+                ("lint", "disable", Value::Array(rules)) => config.lint_disable = rules,
+                ("embedded", "functions", Value::Array(functions)) => config.embedded_functions = Some(functions),
+                ("lint.severity", rule, Value::String(name)) => {
+                    let severity = Severity::from_name(&name).ok_or_else(|| invalid("\"error\", \"warning\" or \"off\""))?;
+                    config.lint_severity.push((rule.to_string(), severity));
+                }
+                ("", "dialect", _) | ("", "mode", _) | ("", "output", _) | ("format", "layout", _) | ("format", "comments", _) | ("format", "attach_comments", _) | ("format", "keyword_case", _) => {
+                    return Err(invalid("a string"))
+                }
+                ("", "keep_hints", _) => return Err(invalid("true or false")),
+                ("lint", "enable", _) | ("lint", "disable", _) | ("embedded", "functions", _) => return Err(invalid("an array of strings")),
+                ("lint.severity", _, _) => return Err(invalid("a string")),
+                ("", key, _) => return Err(format!("line {}: unknown key {}", line_no, key)),
+                (section, key, _) => return Err(format!("line {}: unknown key {} in [{}]", line_no, key, section)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+//helper, the line without a trailing `# comment` (a # inside a string does not start one)
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+//helper, a string in double quotes, true/false, or a one line array of strings
+fn parse_value(text: &str) -> Result<Value, String> {
+    match text {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    if let Some(items) = text.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+        let items = items.split(',').map(str::trim).filter(|item| !item.is_empty());
+        return items.map(parse_string).collect::<Result<_, _>>().map(Value::Array);
+    }
+    parse_string(text).map(Value::String)
+}
+
+fn parse_string(text: &str) -> Result<String, String> {
+    match text.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        Some(value) if !value.contains('"') => Ok(value.to_string()),
+        _ => Err(format!("expected a string in double quotes, true, false or an array, found {}", text)),
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use crate::tokenizer::Tokenizer;
+use crate::parser::{Parser, ParseMode, parse_expression_with_dialect};
+use crate::json::{JsonValue, ToJson, FromJson};
+use crate::statement::{Statement, Expression};
+use crate::rewrite::add_row_filter;
+use crate::dialect::Dialect;
+#[cfg(feature = "formatter")]
+use crate::formatter::{format_statement, for_dialect};
+
+/// Result of running a single corpus case.
+///
+/// A case is a `.sql` file next to exactly one expectation file:
+/// * `name.json` – the expected AST snapshot (a JSON array with one entry per statement in the file)
+/// * `name.err` – the expected parse failure, the error message must contain the (trimmed) file contents
+///
+/// Cases below a directory named after a dialect (`mysql/`, `postgres/`, ...) are tokenized and parsed with that dialect. Cases below a directory named `permissive/` are parsed in `ParseMode::Permissive`, their snapshot is an object with the `statements` and the clauses the parser `skipped`. Cases below a directory named `row_filter/` start with a `-- row filter on orders, payments: tenant_id = ?` line, and their snapshot is of the statements once `rewrite::add_row_filter` has added that filter for those tables. Optimizer hint comments are always kept, so snapshots show them. Every statement has to load back from its snapshot, and with the `formatter` feature the statements of a strict case have to parse back the same once formatted for its dialect.
+#[derive(Debug, PartialEq)]
+pub enum CaseOutcome {
+    Passed,
+    Failed(String),
+    //snapshot written by `--bless` because it was missing or different
+    Blessed,
+}
+
+#[derive(Debug)]
+pub struct CaseResult {
+    pub path: PathBuf,
+    pub outcome: CaseOutcome,
+}
+
+#[derive(Debug, Default)]
+pub struct CorpusReport {
+    pub results: Vec<CaseResult>,
+}
+
+impl CorpusReport {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.outcome == CaseOutcome::Passed).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|r| matches!(r.outcome, CaseOutcome::Failed(_))).count()
+    }
+
+    pub fn blessed(&self) -> usize {
+        self.results.iter().filter(|r| r.outcome == CaseOutcome::Blessed).count()
+    }
+}
+
+//parse a whole script, turning a panic inside the parser into an ordinary error; with a row filter (the tables and the
+//filter) the statements are rewritten with it
+pub fn parse_script(sql: &str, dialect: Dialect, mode: ParseMode, row_filter: Option<&(Vec<String>, Expression)>) -> Result<JsonValue, String> {
+    let result = panic::catch_unwind(|| {
+        let tokens = Tokenizer::with_dialect(sql, dialect).keep_hints(true).tokenize().map_err(|err| err.to_string())?;
+        let mut parser = Parser::with_dialect(tokens, dialect).mode(mode).source(sql.as_bytes());
+        let mut statements = parser.parse_statements()?;
+        if let Some((tables, filter)) = row_filter {
+            let tables: Vec<&str> = tables.iter().map(String::as_str).collect();
+            for stmt in statements.iter_mut() {
+                add_row_filter(stmt, &tables, filter);
+            }
+        }
+        //a snapshot is only of use to other programs if what it holds loads back
+        if let Some(i) = statements.iter().position(|stmt| Statement::from_json(&stmt.to_json()).as_ref() != Ok(stmt)) {
+            return Err(format!("statement {} does not load back from its JSON form", i + 1));
+        }
+        #[cfg(feature = "formatter")]
+        if mode == ParseMode::Strict {
+            if let Some(i) = statements.iter().position(|stmt| !formats_back(stmt, dialect)) {
+                return Err(format!("statement {} does not read back the same once formatted", i + 1));
+            }
+        }
+        Ok(match mode {
+            ParseMode::Strict => statements.to_json(),
+            ParseMode::Permissive => JsonValue::object(vec![
+                ("statements", statements.to_json()),
+                ("skipped", JsonValue::Array(parser.skipped().iter().map(ToJson::to_json).collect())),
+            ]),
+        })
+    });
+    match result {
+        Ok(Ok(json)) => Ok(json),
+        Ok(Err(err)) => Err(err),
+        Err(_) => Err("parser panicked".to_string()),
+    }
+}
+
+//helper, whether a statement written by the formatter, its strings and names as `format` writes them for the dialect,
+//parses back into the same statement in that dialect
+#[cfg(feature = "formatter")]
+fn formats_back(stmt: &Statement, dialect: Dialect) -> bool {
+    let sql = for_dialect(&format_statement(stmt), dialect);
+    let tokens = Tokenizer::with_dialect(&sql, dialect).keep_hints(true).tokenize();
+    let parsed = tokens.map_err(|err| err.to_string()).and_then(|tokens| Parser::with_dialect(tokens, dialect).parse_statements().map_err(|err| err.to_string()));
+    parsed.is_ok_and(|parsed| parsed.as_slice() == std::slice::from_ref(stmt))
+}
+
+//the dialect of a case is given by the closest enclosing directory named after a dialect (`mysql/escapes.sql`)
+pub fn case_dialect(sql_path: &Path) -> Dialect {
+    sql_path.ancestors()
+        .skip(1)
+        .filter_map(|dir| dir.file_name().and_then(|name| name.to_str()))
+        .find_map(Dialect::from_name)
+        .unwrap_or_default()
+}
+
+//cases below a directory named `permissive` are parsed in permissive mode
+pub fn case_mode(sql_path: &Path) -> ParseMode {
+    let permissive = sql_path.ancestors().skip(1).any(|dir| dir.file_name().is_some_and(|name| name == "permissive"));
+    if permissive { ParseMode::Permissive } else { ParseMode::Strict }
+}
+
+//the row filter of a case below a directory named `row_filter`, from its `-- row filter on <tables>: <filter>` first line
+pub fn case_row_filter(sql_path: &Path, sql: &str, dialect: Dialect) -> Result<Option<(Vec<String>, Expression)>, String> {
+    if !sql_path.ancestors().skip(1).any(|dir| dir.file_name().is_some_and(|name| name == "row_filter")) {
+        return Ok(None);
+    }
+    let header = sql.lines().next().and_then(|line| line.strip_prefix("-- row filter on ")).and_then(|rest| rest.split_once(':'));
+    let (tables, filter) = header.ok_or("a row filter case starts with a `-- row filter on <tables>: <filter>` line")?;
+    let tables = tables.split(',').map(|table| table.trim().to_string()).collect();
+    Ok(Some((tables, parse_expression_with_dialect(filter, dialect)?)))
+}
+
+//run one case, with `bless` a missing or outdated snapshot is (re)written instead of failing
+pub fn run_case(sql_path: &Path, bless: bool) -> io::Result<CaseResult> {
+    let sql = fs::read_to_string(sql_path)?;
+    let json_path = sql_path.with_extension("json");
+    let err_path = sql_path.with_extension("err");
+    let dialect = case_dialect(sql_path);
+    let parsed = case_row_filter(sql_path, &sql, dialect).and_then(|row_filter| parse_script(&sql, dialect, case_mode(sql_path), row_filter.as_ref()));
+
+    let outcome = if err_path.exists() {
+        let expected = fs::read_to_string(&err_path)?;
+        let expected = expected.trim();
+        match parsed {
+            Err(err) if err.contains(expected) => CaseOutcome::Passed,
+            Err(err) => CaseOutcome::Failed(format!("expected error containing {:?}, got {:?}", expected, err)),
+            Ok(_) => CaseOutcome::Failed(format!("expected error containing {:?}, but parsing succeeded", expected)),
+        }
+    } else {
+        match parsed {
+            Err(err) => CaseOutcome::Failed(format!("parse error: {}", err)),
+            Ok(actual) => {
+                let expected = if json_path.exists() {
+                    Some(JsonValue::parse(&fs::read_to_string(&json_path)?)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", json_path.display(), e)))?)
+                } else {
+                    None
+                };
+                match expected {
+                    Some(ref expected) if *expected == actual => CaseOutcome::Passed,
+                    _ if bless => {
+                        fs::write(&json_path, actual.to_pretty_string() + "\n")?;
+                        CaseOutcome::Blessed
+                    }
+                    Some(_) => CaseOutcome::Failed(format!("AST does not match snapshot, got {}", actual)),
+                    None => CaseOutcome::Failed("missing snapshot (run with --bless to create it)".to_string()),
+                }
+            }
+        }
+    };
+
+    Ok(CaseResult { path: sql_path.to_path_buf(), outcome })
+}
+
+//walk a directory recursively and run every `.sql` case in path order
+pub fn run_corpus(dir: &Path, bless: bool) -> io::Result<CorpusReport> {
+    let mut files = Vec::new();
+    collect_sql_files(dir, &mut files)?;
+    files.sort();
+
+    let mut report = CorpusReport::default();
+    for file in files {
+        report.results.push(run_case(&file, bless)?);
+    }
+    Ok(report)
+}
+
+//helper, collects all `.sql` files below a directory
+pub fn collect_sql_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sql_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "sql") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use std::fmt::{Display, Formatter};
+use crate::token::Token;
+use crate::statement::{BinaryOperator, Associativity, OperatorCategory, precedence};
+
+/// SQL dialects differ in small lexical and grammatical details. A `Dialect` is handed to the tokenizer (and later the parser) which ask it how to behave whenever the dialects disagree, instead of hard-coding one database's behaviour.
+///
+/// `Generic` is the default and keeps the original behaviour of the parser, `Ansi` sticks to the standard, the others follow their database.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Dialect {
+    #[default]
+    Generic,
+    Ansi,
+    MySql,
+    Postgres,
+    Snowflake,
+    BigQuery,
+}
+
+impl Dialect {
+    //look a dialect up by its (case insensitive) name, used for command line flags and corpus directories
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        match name.to_lowercase().as_str() {
+            "generic" => Some(Dialect::Generic),
+            "ansi" => Some(Dialect::Ansi),
+            "mysql" => Some(Dialect::MySql),
+            "postgres" | "postgresql" => Some(Dialect::Postgres),
+            "snowflake" => Some(Dialect::Snowflake),
+            "bigquery" => Some(Dialect::BigQuery),
+            _ => None,
+        }
+    }
+
+    //whether a backslash inside a string literal starts an escape sequence like \n or \u00e9
+    pub fn backslash_escapes(&self) -> bool {
+        matches!(self, Dialect::MySql)
+    }
+
+    //character around quoted identifiers, "order" in standard SQL, `order` in MySQL
+    //the generic dialect reads double quotes as strings, so it uses backticks as well
+    pub fn identifier_quote(&self) -> char {
+        match self {
+            Dialect::Ansi | Dialect::Postgres | Dialect::Snowflake => '"',
+            Dialect::Generic | Dialect::MySql | Dialect::BigQuery => '`',
+        }
+    }
+
+    //the binary operator a token stands for in this dialect
+    pub fn binary_operator(&self, tok: &Token) -> Option<BinaryOperator> {
+        match (self, tok) {
+            //MySQL reads || as a logical OR unless PIPES_AS_CONCAT is set
+            (Dialect::MySql, Token::DoublePipe) => Some(BinaryOperator::Or),
+            _ => tok.binary_operator(),
+        }
+    }
+
+    //binding power of a binary operator in this dialect, a higher number binds tighter
+    pub fn precedence(&self, operator: BinaryOperator) -> u8 {
+        match (self, operator.category()) {
+            //regular expression matches are ordinary operators in Postgres, tighter than comparisons
+            (Dialect::Postgres, OperatorCategory::Regex) => precedence::OTHER,
+            _ => operator.precedence(),
+        }
+    }
+
+    pub fn associativity(&self, operator: BinaryOperator) -> Associativity {
+        operator.associativity()
+    }
+
+    //whether SELECT accepts a QUALIFY clause filtering on window function results
+    pub fn supports_qualify(&self) -> bool {
+        matches!(self, Dialect::Generic | Dialect::Snowflake | Dialect::BigQuery)
+    }
+
+    //whether a /* comment may contain other /* */ comments, as in the standard and Postgres, instead of ending at the first */
+    pub fn nested_comments(&self) -> bool {
+        matches!(self, Dialect::Ansi | Dialect::Postgres)
+    }
+
+    //whether $$...$$ and $tag$...$tag$ are strings, as Postgres writes the bodies of functions, rather than parameters
+    pub fn dollar_quoted_strings(&self) -> bool {
+        matches!(self, Dialect::Postgres)
+    }
+
+    //whether a grouped query may only use columns it groups by outside of aggregates
+    //MySQL without ONLY_FULL_GROUP_BY takes the value of any row of the group instead
+    pub fn requires_grouped_columns(&self) -> bool {
+        !matches!(self, Dialect::MySql)
+    }
+}
+
+impl Display for Dialect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dialect::Generic => write!(f, "generic"),
+            Dialect::Ansi => write!(f, "ansi"),
+            Dialect::MySql => write!(f, "mysql"),
+            Dialect::Postgres => write!(f, "postgres"),
+            Dialect::Snowflake => write!(f, "snowflake"),
+            Dialect::BigQuery => write!(f, "bigquery"),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//structural differences between two statements, node by node rather than line by line
+//the trees are compared in their JSON form, where every kind of node is an object, an array or a value, so one walk
+//covers all of them and a node is named by the same field and variant names a JSON consumer sees
+use std::fmt;
+use crate::statement::Statement;
+use crate::json::{JsonValue, ToJson};
+use crate::align::common_sequence;
+
+/// What happened to a node between the two statements.
+/// 1. `Inserted` – The node is only in the new statement, an item added to a list or a field that was empty.
+/// 2. `Deleted` – The node is only in the old statement.
+/// 3. `Changed` – The node is in both but differs as a whole: another value, or another variant of its kind (a column that became a function call).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditKind {
+    Inserted,
+    Deleted,
+    Changed,
+}
+
+/// One difference between two statements.
+/// 1. `path` – Where the node is, from the root of the statement: variant names, field names and positions in lists, `["Select", "where", "BinaryOperation", "right_operand"]`. Positions count from 0, in the new statement except for a deleted item.
+/// 2. `kind` – What happened to the node.
+/// 3. `before` – The node in the old statement, in its JSON form; `None` for an insertion.
+/// 4. `after` – The node in the new statement; `None` for a deletion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AstEdit {
+    pub path: Vec<String>,
+    pub kind: EditKind,
+    pub before: Option<JsonValue>,
+    pub after: Option<JsonValue>,
+}
+
+//`changed Select.limit: {"Literal":{"Integer":10}} -> {"Literal":{"Integer":20}}`
+impl fmt::Display for AstEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.join(".");
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => write!(f, "changed {}: {} -> {}", path, before, after),
+            (Some(before), None) => write!(f, "deleted {}: {}", path, before),
+            (None, Some(after)) => write!(f, "inserted {}: {}", path, after),
+            (None, None) => write!(f, "{:?} {}", self.kind, path),
+        }
+    }
+}
+
+/// The nodes that differ between `old` and `new`, in the order they come in the statements; empty when the two are equal. The smallest differing nodes are reported: a changed literal deep in a condition is one edit at its path rather than a change of the whole statement. Items of a list are matched by the longest run of equal items the two lists have in common, so inserting a column in the middle of a select list is one insertion, and an item that is left over on both sides between two matched ones is compared field by field. A field that is empty on one side (`null`, an empty list) and set on the other is an insertion or a deletion.
+pub fn diff(old: &Statement, new: &Statement) -> Vec<AstEdit> {
+    let mut edits = Vec::new();
+    diff_values(&old.to_json(), &new.to_json(), &mut Vec::new(), &mut edits);
+    edits
+}
+
+//helper, the edits that turn `old` into `new`, below `path`
+fn diff_values(old: &JsonValue, new: &JsonValue, path: &mut Vec<String>, edits: &mut Vec<AstEdit>) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (JsonValue::Null, _) | (JsonValue::Array(_), _) if is_empty(old) => edits.push(edit(path, EditKind::Inserted, None, Some(new))),
+        (_, JsonValue::Null) | (_, JsonValue::Array(_)) if is_empty(new) => edits.push(edit(path, EditKind::Deleted, Some(old), None)),
+        (JsonValue::Array(old_items), JsonValue::Array(new_items)) => diff_lists(old_items, new_items, path, edits),
+        //a variant of another kind is a different node, not one whose fields changed
+        (JsonValue::Object(old_fields), JsonValue::Object(new_fields)) if !is_variant_change(old_fields, new_fields) => {
+            for (key, old_value) in old_fields {
+                let new_value = new.get(key).unwrap_or(&JsonValue::Null);
+                path.push(key.clone());
+                diff_values(old_value, new_value, path, edits);
+                path.pop();
+            }
+            for (key, new_value) in new_fields.iter().filter(|(key, _)| old.get(key).is_none()) {
+                path.push(key.clone());
+                diff_values(&JsonValue::Null, new_value, path, edits);
+                path.pop();
+            }
+        }
+        _ => edits.push(edit(path, EditKind::Changed, Some(old), Some(new))),
+    }
+}
+
+//helper, the edits between two lists: the items of their longest common run of equal items are left alone, the ones
+//between two of them are compared pairwise as far as both sides have items, the rest are insertions or deletions
+fn diff_lists(old: &[JsonValue], new: &[JsonValue], path: &mut Vec<String>, edits: &mut Vec<AstEdit>) {
+    let common = common_items(old, new);
+    let (mut i, mut j) = (0, 0);
+    for (next_i, next_j) in common.into_iter().chain(std::iter::once((old.len(), new.len()))) {
+        let paired = (next_i - i).min(next_j - j);
+        for k in 0..paired {
+            path.push((j + k).to_string());
+            diff_values(&old[i + k], &new[j + k], path, edits);
+            path.pop();
+        }
+        for (k, item) in old.iter().enumerate().take(next_i).skip(i + paired) {
+            path.push(k.to_string());
+            edits.push(edit(path, EditKind::Deleted, Some(item), None));
+            path.pop();
+        }
+        for (k, item) in new.iter().enumerate().take(next_j).skip(j + paired) {
+            path.push(k.to_string());
+            edits.push(edit(path, EditKind::Inserted, None, Some(item)));
+            path.pop();
+        }
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+}
+
+//helper, the positions of the items of the longest sequence of equal items the two lists have in common, in order
+fn common_items(a: &[JsonValue], b: &[JsonValue]) -> Vec<(usize, usize)> {
+    common_sequence(a.len(), b.len(), |i, j| a[i] == b[j])
+}
+
+//helper, whether two objects are enum variants with different names, objects of one key each that is not the same
+fn is_variant_change(old: &[(String, JsonValue)], new: &[(String, JsonValue)]) -> bool {
+    old.len() == 1 && new.len() == 1 && old[0].0 != new[0].0
+}
+
+//helper, null or an empty list, a field that holds nothing
+fn is_empty(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => true,
+        JsonValue::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+fn edit(path: &[String], kind: EditKind, before: Option<&JsonValue>, after: Option<&JsonValue>) -> AstEdit {
+    AstEdit { path: path.to_vec(), kind, before: before.cloned(), after: after.cloned() }
+}
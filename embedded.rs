@@ -0,0 +1,283 @@
+//SQL in the string literals of Rust source files, found by the functions they are passed to and checked where they are
+//the scanner knows just enough of Rust to tell strings apart from comments, char literals and lifetimes
+use std::fs;
+use std::io;
+use std::iter;
+use std::path::{Path, PathBuf};
+use crate::tokenizer::{Span, Origin};
+use crate::dialect::Dialect;
+use crate::lint::{self, Severity, Finding};
+
+/// Functions and macros whose first argument is taken for SQL when no others are configured: the query entry points of `sqlx`, `postgres`, `rusqlite` and `diesel`.
+pub const DEFAULT_FUNCTIONS: &[&str] = &[
+    "query", "query_as", "query_scalar", "query_one", "query_opt", "query_row", "query_map", "execute", "execute_batch",
+    "batch_execute", "simple_query", "prepare", "prepare_cached", "sql_query",
+];
+
+/// A string literal passed as the first argument of one of the query functions.
+/// 1. `function` – The name of the function or macro, without its path.
+/// 2. `sql` – The text of the literal, escapes decoded.
+/// 3. `origin` – Where the text starts in the source file, just after the opening quote.
+/// 4. `literal` – The whole literal in the source file, quotes included.
+/// 5. `verbatim` – Whether `sql` is the source text as it is, a raw string or one without escapes, so `origin` maps its spans exactly.
+#[derive(Debug, PartialEq)]
+pub struct EmbeddedSql {
+    pub function: String,
+    pub sql: String,
+    pub origin: Origin,
+    pub literal: Span,
+    pub verbatim: bool,
+}
+
+/// Finds the SQL passed to `functions` in Rust source: a call (`query("...")`, `conn.execute("...", [])`), a macro (`query!("...")`) or a call with type arguments (`query_as::<_, User>("...")`) whose first argument is a string literal, raw (`r#"..."#`) or not. Arguments that are not literals, such as a `format!` or a constant, are not followed. Literals in comments are skipped.
+pub fn extract_rust(source: &str, functions: &[String]) -> Vec<EmbeddedSql> {
+    let bytes = source.as_bytes();
+    let mut found = Vec::new();
+    //the function whose opening parenthesis was just read, its first argument is the next token
+    let mut pending: Option<String> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        i = skip_trivia(bytes, i);
+        if i >= bytes.len() {
+            break;
+        }
+        if let Some((content, end, verbatim)) = string_literal(bytes, i) {
+            if let Some(function) = pending.take() {
+                let text = &source[content.0..content.1];
+                let sql = if verbatim { text.to_string() } else { unescape(text) };
+                let (line, column) = line_column(source, content.0);
+                let (literal_line, literal_column) = line_column(source, i);
+                found.push(EmbeddedSql {
+                    function,
+                    sql,
+                    origin: Origin::new(content.0, line, column),
+                    literal: Span { start: i, end, line: literal_line, column: literal_column },
+                    verbatim,
+                });
+            }
+            i = end;
+            continue;
+        }
+        pending = None;
+        let ch = bytes[i];
+        if ch == b'_' || ch.is_ascii_alphabetic() {
+            let start = i;
+            while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                i += 1;
+            }
+            let name = &source[start..i];
+            if !functions.iter().any(|function| function == name) {
+                continue;
+            }
+            let mut j = skip_trivia(bytes, i);
+            if bytes.get(j) == Some(&b'!') {
+                j = skip_trivia(bytes, j + 1);
+            } else if bytes[j..].starts_with(b"::<") {
+                j = skip_trivia(bytes, skip_generics(bytes, j + 2));
+            }
+            if bytes.get(j) == Some(&b'(') {
+                pending = Some(name.to_string());
+                i = j + 1;
+            }
+        } else if ch == b'\'' {
+            i = skip_char_or_lifetime(bytes, i);
+        } else {
+            i += 1;
+        }
+    }
+    found
+}
+
+/// Checks the SQL passed to `functions` in Rust source with `lint::lint`, which reports statements that do not parse and what the semantic analyzer and the lint rules find. Findings are located in the Rust file; in a literal with escapes, whose text is not the source text, they are all put at the literal. Fixes are left out, they are edits of SQL rather than of Rust.
+pub fn check_rust(source: &str, functions: &[String], dialect: Dialect, severities: &[(&'static str, Severity)]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for embedded in extract_rust(source, functions) {
+        for mut finding in lint::lint_embedded(&embedded.sql, dialect, severities, embedded.origin) {
+            if !embedded.verbatim {
+                finding.span = embedded.literal;
+            }
+            finding.fix.clear();
+            findings.push(finding);
+        }
+    }
+    findings
+}
+
+/// The value of a Rust string literal written as in source, `"..."` with its escapes decoded or a raw `r#"..."#`, for tools that get literals as text (the `sql!` macro); `None` when `literal` is not one string literal.
+pub fn string_value(literal: &str) -> Option<String> {
+    let bytes = literal.as_bytes();
+    match string_literal(bytes, 0) {
+        Some(((start, end), after, verbatim)) if after == bytes.len() && end < after && !literal.starts_with('b') => {
+            let text = &literal[start..end];
+            Some(if verbatim { text.to_string() } else { unescape(text) })
+        }
+        _ => None,
+    }
+}
+
+//walk a directory for `.rs` files, skipping `target` directories with build output in them
+pub fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "target") {
+                collect_rust_files(&path, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+//helper, the first position from `i` that is not whitespace or inside a comment (block comments nest in Rust)
+fn skip_trivia(bytes: &[u8], mut i: usize) -> usize {
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if bytes[i..].starts_with(b"//") {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        } else if bytes[i..].starts_with(b"/*") {
+            let mut depth = 0;
+            while i < bytes.len() {
+                if bytes[i..].starts_with(b"/*") {
+                    depth += 1;
+                    i += 2;
+                } else if bytes[i..].starts_with(b"*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else {
+            return i;
+        }
+    }
+}
+
+//helper, the string literal starting at `i` (with a `b` prefix too, so byte strings are skipped whole): the byte range of
+//its text, where it ends, and whether it is raw or has no escapes; None when there is no string literal at `i`
+fn string_literal(bytes: &[u8], i: usize) -> Option<((usize, usize), usize, bool)> {
+    //an identifier ending in `r` or `b` is not a prefix
+    if i > 0 && (bytes[i - 1] == b'_' || bytes[i - 1].is_ascii_alphanumeric()) {
+        return None;
+    }
+    let mut j = i;
+    if bytes.get(j) == Some(&b'b') {
+        j += 1;
+    }
+    if bytes.get(j) == Some(&b'r') {
+        j += 1;
+        let hashes = bytes[j..].iter().take_while(|&&b| b == b'#').count();
+        j += hashes;
+        if bytes.get(j) != Some(&b'"') {
+            return None;
+        }
+        let start = j + 1;
+        let closing: Vec<u8> = iter::once(b'"').chain(iter::repeat_n(b'#', hashes)).collect();
+        let end = (start..bytes.len()).find(|&k| bytes[k..].starts_with(&closing)).unwrap_or(bytes.len());
+        return Some(((start, end), (end + closing.len()).min(bytes.len()), true));
+    }
+    if bytes.get(j) != Some(&b'"') {
+        return None;
+    }
+    let start = j + 1;
+    let mut k = start;
+    let mut escaped = false;
+    while k < bytes.len() && bytes[k] != b'"' {
+        if bytes[k] == b'\\' {
+            escaped = true;
+            k += 1;
+        }
+        k += 1;
+    }
+    let end = k.min(bytes.len());
+    Some(((start, end), (end + 1).min(bytes.len()), !escaped))
+}
+
+//helper, past a char literal (`'a'`, `'\n'`, `'\u{e9}'`) or a lifetime (`'a`), which both start with a quote
+fn skip_char_or_lifetime(bytes: &[u8], i: usize) -> usize {
+    if bytes.get(i + 1) == Some(&b'\\') {
+        let end = (i + 2..bytes.len()).find(|&k| bytes[k] == b'\'' && k > i + 2).unwrap_or(bytes.len());
+        return end + 1;
+    }
+    //one character, which may take several bytes, then the closing quote
+    let len = bytes.get(i + 1).map_or(1, |&b| match b {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    });
+    if bytes.get(i + 1 + len) == Some(&b'\'') {
+        i + 2 + len
+    } else {
+        i + 1
+    }
+}
+
+//helper, past the closing `>` of type arguments opened at `i`
+fn skip_generics(bytes: &[u8], mut i: usize) -> usize {
+    let mut depth = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'<' => depth += 1,
+            b'>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+//helper, the text of a string literal with its escapes decoded; a backslash at the end of a line skips the line break
+//and the whitespace that starts the next line
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('0') => out.push('\0'),
+            Some('x') => {
+                let code: String = chars.by_ref().take(2).collect();
+                out.extend(u8::from_str_radix(&code, 16).ok().map(char::from));
+            }
+            Some('u') => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                out.extend(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32));
+            }
+            Some('\n') => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+//helper, line and column (both from 1, columns in characters) of a byte offset
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |pos| pos + 1)..].chars().count() + 1;
+    (line, column)
+}
@@ -0,0 +1,986 @@
+//a toy in-memory database that runs parsed statements, so queries can be tried out without a real database
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use crate::statement::{Statement, InsertSource, Expression, Literal, BinaryOperator, UnaryOperator, TableReference, TableColumn, Constraint, DBType, AlterAction, ObjectType, ShowObject, JoinKind, SetOperator};
+use crate::formatter::{format_expression, format_type};
+#[cfg(feature = "serde")]
+use crate::json::JsonValue;
+use crate::vector::{self, Vector, CHUNK_SIZE};
+
+/// A table of the engine: its definition and its rows, each row holding one value per column in the order of `columns`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<Vec<Literal>>,
+}
+
+/// The rows a query returned, with the names of its columns: the alias of a column, the name of a column reference, or the SQL text of any other expression.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResultSet {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Literal>>,
+}
+
+impl ResultSet {
+    /// The rows as CSV, with a header line of the column names. Fields holding a comma, a quote or a line break are quoted, quotes doubled; NULL is an empty field and every line ends with a newline.
+    pub fn to_csv(&self) -> String {
+        let mut out = csv_line(self.columns.iter().map(|name| name.as_str()));
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(|value| if *value == Literal::Null { String::new() } else { value_text(value) }).collect();
+            out.push_str(&csv_line(fields.iter().map(|field| field.as_str())));
+        }
+        out
+    }
+
+    /// The rows as a JSON array with one object per row, keyed by column name. Numbers and booleans stay numbers and booleans, NULL is `null` and every other value is a string. Needs the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.rows.iter()
+            .map(|row| JsonValue::Object(self.columns.iter().cloned().zip(row.iter().map(json_value)).collect()))
+            .collect())
+    }
+}
+
+//helper, one line of CSV
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let fields: Vec<String> = fields
+        .map(|field| if field.contains([',', '"', '\n', '\r']) { format!("\"{}\"", field.replace('"', "\"\"")) } else { field.to_string() })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+//helper, a value of a result as plain JSON, unlike the tagged literals of the syntax tree
+#[cfg(feature = "serde")]
+fn json_value(value: &Literal) -> JsonValue {
+    match value {
+        Literal::Integer(n) => JsonValue::Number(n.to_string()),
+        Literal::Decimal(text) => JsonValue::Number(text.clone()),
+        Literal::Boolean(b) => JsonValue::Bool(*b),
+        Literal::Null => JsonValue::Null,
+        other => JsonValue::String(value_text(other)),
+    }
+}
+
+/// What running a statement did.
+/// 1. `Rows` – A query, with the rows it returned.
+/// 2. `Affected` – An `INSERT` or `DELETE`, with the number of rows it inserted or deleted.
+/// 3. `Done` – A statement that changed the tables themselves, or nothing (`CREATE TABLE IF NOT EXISTS` of a table that is there).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Rows(ResultSet),
+    Affected(usize),
+    Done,
+}
+
+/// An in-memory database for trying statements out. It runs `CREATE TABLE`, `DROP TABLE`, `ALTER TABLE`, `TRUNCATE`, `ANALYZE` and `VACUUM` (which have nothing to do but find the table), `INSERT`, `DELETE`, `SHOW TABLES`, `DESCRIBE` and queries with `WHERE`, `ORDER BY` and `LIMIT` on a table or on joins of tables, subqueries included. Joins on equal columns are hash joins, others compare every pair of rows. `WHERE` and the select list are computed a chunk of rows at a time where the expressions allow it (see `vector`). Values are checked against the column types and constraints when they are stored: `INT`, `BOOL`, `VARCHAR(n)` and `DECIMAL(p, s)` (rounded to its scale), `NOT NULL`, `PRIMARY KEY` and `CHECK`. Anything else, such as grouping or a function it does not know, is an error rather than a guess.
+#[derive(Debug, Default)]
+pub struct Engine {
+    tables: Vec<Table>,
+    row_at_a_time: bool,
+    //statements kept by PREPARE, by name
+    prepared: Vec<(String, Statement)>,
+    //the values of $1, $2, ... while EXECUTE runs a prepared statement
+    parameters: Vec<Literal>,
+}
+
+//a column of the rows a FROM clause produces, with the table name or alias that qualifies it
+struct Binding {
+    qualifier: String,
+    name: String,
+}
+
+//the rows of a FROM item, a table or the join of several
+struct Relation {
+    columns: Vec<Binding>,
+    rows: Vec<Vec<Literal>>,
+}
+
+//the row a column reference is evaluated against, with the rows of the queries around it for correlated subqueries
+struct Scope<'a> {
+    columns: &'a [Binding],
+    row: &'a [Literal],
+    outer: Option<&'a Scope<'a>>,
+}
+
+//scalar functions the engine knows, names in lower case
+const FUNCTIONS: &[&str] = &["lower", "upper", "length", "abs", "coalesce"];
+
+impl Engine {
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Evaluates `WHERE` and the select list row by row, instead of a chunk of rows at a time where it can (see `vector`). The results are the same, only slower; this is for comparing the two.
+    pub fn row_at_a_time(mut self, row_at_a_time: bool) -> Self {
+        self.row_at_a_time = row_at_a_time;
+        self
+    }
+
+    /// Drops every table and every prepared statement.
+    pub fn reset(&mut self) {
+        self.tables.clear();
+        self.prepared.clear();
+    }
+
+    /// Runs one statement. A statement that fails leaves the tables as they were, an `INSERT` of several rows stores none of them when one is rejected.
+    pub fn execute(&mut self, stmt: &Statement) -> Result<Outcome, String> {
+        match stmt {
+            Statement::Select { .. } | Statement::SetOperation { .. } | Statement::Values { .. } => self.query(stmt, None).map(Outcome::Rows),
+            Statement::CreateTable { table_name, if_not_exists, .. } if self.table(table_name).is_ok() => {
+                if *if_not_exists {
+                    Ok(Outcome::Done)
+                } else {
+                    Err(format!("Table {} already exists", table_name))
+                }
+            }
+            Statement::CreateTable { query: Some(_), .. } => Err("CREATE TABLE ... AS SELECT is not supported by the engine".to_string()),
+            Statement::CreateTable { table_name, column_list, .. } => {
+                self.tables.push(Table { name: table_name.clone(), columns: column_list.clone(), rows: Vec::new() });
+                Ok(Outcome::Done)
+            }
+            Statement::Drop { object_type: ObjectType::Table, names, if_exists } => {
+                for name in names {
+                    if !*if_exists {
+                        self.table(name)?;
+                    }
+                }
+                self.tables.retain(|table| !names.iter().any(|name| name.eq_ignore_ascii_case(&table.name)));
+                Ok(Outcome::Done)
+            }
+            Statement::Drop { object_type, .. } => Err(format!("DROP {} is not supported by the engine", object_type)),
+            Statement::Truncate { table_name } => {
+                self.table_mut(table_name)?.rows.clear();
+                Ok(Outcome::Done)
+            }
+            //the engine keeps no statistics and no dead rows, there is nothing to do once the table is found
+            Statement::Analyze { table_name } | Statement::Vacuum { table_name, .. } => {
+                if let Some(table_name) = table_name {
+                    self.table(table_name)?;
+                }
+                Ok(Outcome::Done)
+            }
+            Statement::AlterTable { table_name, action } => {
+                self.alter(table_name, action)?;
+                Ok(Outcome::Done)
+            }
+            //the pairs are checked in order against the names the earlier ones gave, and none is renamed when one fails
+            Statement::RenameTable { renames } => {
+                let mut names: Vec<String> = self.tables.iter().map(|table| table.name.clone()).collect();
+                for rename in renames {
+                    if names.iter().any(|name| name.eq_ignore_ascii_case(&rename.new_name)) {
+                        return Err(format!("Table {} already exists", rename.new_name));
+                    }
+                    let index = names.iter().position(|name| name.eq_ignore_ascii_case(&rename.old_name))
+                        .ok_or_else(|| format!("Table {} does not exist", rename.old_name))?;
+                    names[index] = rename.new_name.clone();
+                }
+                for (table, name) in self.tables.iter_mut().zip(names) {
+                    table.name = name;
+                }
+                Ok(Outcome::Done)
+            }
+            Statement::Insert { on_conflict: Some(_), .. } => Err("ON CONFLICT is not supported by the engine".to_string()),
+            Statement::Insert { table_name, columns, source, on_conflict: None } => self.insert(table_name, columns.as_deref(), source).map(Outcome::Affected),
+            Statement::Merge { .. } => Err("MERGE is not supported by the engine".to_string()),
+            Statement::Delete { table, where_clause } => {
+                let target = self.table(table)?;
+                let columns = bindings(&target.name, target);
+                let mut keep = Vec::new();
+                for row in &target.rows {
+                    let scope = Scope { columns: &columns, row, outer: None };
+                    let matches = match where_clause {
+                        Some(condition) => self.eval(condition, Some(&scope))? == Literal::Boolean(true),
+                        None => true,
+                    };
+                    keep.push(!matches);
+                }
+                let deleted = keep.iter().filter(|keep| !**keep).count();
+                let mut keep = keep.into_iter();
+                self.table_mut(table)?.rows.retain(|_| keep.next().unwrap_or(true));
+                Ok(Outcome::Affected(deleted))
+            }
+            Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } => {
+                Err("Only tables are supported by the engine".to_string())
+            }
+            Statement::Show { object: ShowObject::Tables } => Ok(Outcome::Rows(table_list(self.tables.iter().map(|table| table.name.as_str())))),
+            Statement::Show { object: ShowObject::Databases } => Err("The engine has a single database, SHOW DATABASES is not supported".to_string()),
+            Statement::Describe { table_name } => Ok(Outcome::Rows(describe(&self.table(table_name)?.columns))),
+            //the engine has no settings, SET is accepted so the statements at the start of a dump run
+            Statement::SetVariable { .. } => Ok(Outcome::Done),
+            Statement::Call { name, .. } => Err(format!("Procedure {} does not exist, the engine has no stored procedures", name)),
+            Statement::Copy { .. } | Statement::LoadData { .. } => Err("The engine does not read or write files, bulk loads are not supported".to_string()),
+            Statement::Prepare { name, .. } if self.prepared(name).is_some() => Err(format!("Prepared statement {} already exists", name)),
+            Statement::Prepare { name, statement, .. } => {
+                self.prepared.push((name.clone(), (**statement).clone()));
+                Ok(Outcome::Done)
+            }
+            //the arguments are evaluated first, they can not refer to the parameters of the statement they are for
+            Statement::Execute { name, args } => {
+                let statement = self.prepared(name).ok_or_else(|| format!("Prepared statement {} does not exist", name))?.clone();
+                let values = args.iter().map(|arg| self.eval(arg, None)).collect::<Result<Vec<_>, _>>()?;
+                self.parameters = values;
+                let outcome = self.execute(&statement);
+                self.parameters.clear();
+                outcome
+            }
+            Statement::Deallocate { name: Some(name) } => {
+                self.prepared(name).ok_or_else(|| format!("Prepared statement {} does not exist", name))?;
+                self.prepared.retain(|(prepared, _)| !prepared.eq_ignore_ascii_case(name));
+                Ok(Outcome::Done)
+            }
+            Statement::Deallocate { name: None } => {
+                self.prepared.clear();
+                Ok(Outcome::Done)
+            }
+            Statement::Unparsed { raw, .. } => Err(format!("The statement {:?} was not parsed, the engine can not run it", raw)),
+        }
+    }
+
+    fn prepared(&self, name: &str) -> Option<&Statement> {
+        self.prepared.iter().find(|(prepared, _)| prepared.eq_ignore_ascii_case(name)).map(|(_, statement)| statement)
+    }
+
+    fn table(&self, name: &str) -> Result<&Table, String> {
+        self.tables.iter().find(|table| table.name.eq_ignore_ascii_case(name)).ok_or_else(|| format!("Table {} does not exist", name))
+    }
+
+    fn table_mut(&mut self, name: &str) -> Result<&mut Table, String> {
+        self.tables.iter_mut().find(|table| table.name.eq_ignore_ascii_case(name)).ok_or_else(|| format!("Table {} does not exist", name))
+    }
+
+    fn alter(&mut self, table_name: &str, action: &AlterAction) -> Result<(), String> {
+        if let AlterAction::RenameTo(new_name) = action {
+            if self.table(new_name).is_ok() {
+                return Err(format!("Table {} already exists", new_name));
+            }
+        }
+        let table = self.table_mut(table_name)?;
+        let position = |table: &Table, name: &str| table.columns.iter().position(|column| column.column_name.eq_ignore_ascii_case(name));
+        match action {
+            AlterAction::AddColumn(column) => {
+                if position(table, &column.column_name).is_some() {
+                    return Err(format!("Column {} already exists in table {}", column.column_name, table.name));
+                }
+                //the rows there are get NULL in the new column, which its constraints have to allow
+                if !table.rows.is_empty() && column.constraints.iter().any(|constraint| matches!(constraint, Constraint::NotNull | Constraint::PrimaryKey)) {
+                    return Err(format!("Column {} can not be NULL, and table {} has rows", column.column_name, table.name));
+                }
+                table.columns.push(column.clone());
+                for row in table.rows.iter_mut() {
+                    row.push(Literal::Null);
+                }
+            }
+            AlterAction::DropColumn(name) => {
+                let index = position(table, name).ok_or_else(|| format!("Column {} does not exist in table {}", name, table.name))?;
+                table.columns.remove(index);
+                for row in table.rows.iter_mut() {
+                    row.remove(index);
+                }
+            }
+            AlterAction::RenameColumn { column_name, new_name } => {
+                let index = position(table, column_name).ok_or_else(|| format!("Column {} does not exist in table {}", column_name, table.name))?;
+                if position(table, new_name).is_some() {
+                    return Err(format!("Column {} already exists in table {}", new_name, table.name));
+                }
+                table.columns[index].column_name = new_name.clone();
+            }
+            AlterAction::RenameTo(new_name) => table.name = new_name.clone(),
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, table_name: &str, columns: Option<&[String]>, source: &InsertSource) -> Result<usize, String> {
+        let table = self.table(table_name)?;
+        //the position in the table of every value of a row
+        let targets: Vec<usize> = match columns {
+            Some(columns) => columns.iter().map(|name| {
+                table.columns.iter().position(|column| column.column_name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("Column {} does not exist in table {}", name, table.name))
+            }).collect::<Result<_, _>>()?,
+            None => (0..table.columns.len()).collect(),
+        };
+        //the rows of a query are read before any is inserted, so a query of the table itself sees it as it was
+        let rows: Vec<Vec<Literal>> = match source {
+            InsertSource::Values(rows) => rows.iter().map(|values| values.iter().map(|value| self.eval(value, None)).collect()).collect::<Result<_, _>>()?,
+            InsertSource::Query(query) => self.query(query, None)?.rows,
+        };
+        let mut stored: Vec<Vec<Literal>> = Vec::new();
+        for values in rows {
+            if values.len() != targets.len() {
+                return Err(format!("INSERT has {} values for {} columns", values.len(), targets.len()));
+            }
+            let mut row = vec![Literal::Null; table.columns.len()];
+            for (value, target) in values.into_iter().zip(&targets) {
+                let column = &table.columns[*target];
+                row[*target] = coerce(value, column)?;
+            }
+            let others: Vec<&Vec<Literal>> = stored.iter().chain(&table.rows).collect();
+            self.check_row(table, &row, &others)?;
+            stored.push(row);
+        }
+        let count = stored.len();
+        self.table_mut(table_name)?.rows.extend(stored);
+        Ok(count)
+    }
+
+    //the constraints of the table that go beyond the type of a column, `others` are the rows already there
+    fn check_row(&self, table: &Table, row: &[Literal], others: &[&Vec<Literal>]) -> Result<(), String> {
+        let columns = bindings(&table.name, table);
+        let scope = Scope { columns: &columns, row, outer: None };
+        for (index, column) in table.columns.iter().enumerate() {
+            for constraint in &column.constraints {
+                match constraint {
+                    Constraint::NotNull | Constraint::PrimaryKey if row[index] == Literal::Null => {
+                        return Err(format!("Column {} of table {} can not be NULL", column.column_name, table.name));
+                    }
+                    Constraint::PrimaryKey if others.iter().any(|other| other[index] == row[index]) => {
+                        return Err(format!("Duplicate value {} for PRIMARY KEY {} of table {}", row[index], column.column_name, table.name));
+                    }
+                    //NULL passes a CHECK, only false fails it
+                    Constraint::Check(condition) if self.eval(condition, Some(&scope))? == Literal::Boolean(false) => {
+                        return Err(format!("Row violates CHECK ({}) of table {}", format_expression(condition), table.name));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //a query, with the rows of the queries around it when it is a subquery
+    fn query(&self, select: &Statement, outer: Option<&Scope>) -> Result<ResultSet, String> {
+        if let Statement::SetOperation { with, left, operator, all, right, orderby, limit } = select {
+            if with.is_some() {
+                return Err("WITH is not supported by the engine".to_string());
+            }
+            return self.set_operation(self.query(left, outer)?, *operator, *all, self.query(right, outer)?, orderby, limit, outer);
+        }
+        //the columns of VALUES are named column1, column2, ... as in PostgreSQL
+        if let Statement::Values { rows } = select {
+            let columns = (1..=rows.first().map_or(0, Vec::len)).map(|i| format!("column{}", i)).collect();
+            let rows = rows.iter()
+                .map(|row| row.iter().map(|expr| self.eval(expr, outer)).collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(ResultSet { columns, rows });
+        }
+        let Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } = select else {
+            return Err("Expected a SELECT".to_string());
+        };
+        let unsupported = [(with.is_some(), "WITH"), (!group_by.is_empty(), "GROUP BY"), (having.is_some(), "HAVING"), (qualify.is_some(), "QUALIFY")];
+        if let Some((_, clause)) = unsupported.iter().find(|(used, _)| *used) {
+            return Err(format!("{} is not supported by the engine", clause));
+        }
+        //items separated by commas are joined like CROSS JOIN joins them
+        let mut items = from.iter();
+        let first = items.next().ok_or_else(|| "A query needs a FROM clause in the engine".to_string())?;
+        let mut source = self.relation(first, outer)?;
+        for item in items {
+            source = self.join(source, self.relation(item, outer)?, &JoinKind::Cross, None, outer)?;
+        }
+        let limit = self.limit(limit.as_ref(), outer)?;
+
+        let mut result = ResultSet::default();
+        for column in columns {
+            match column {
+                Expression::Wildcard => result.columns.extend(source.columns.iter().map(|binding| binding.name.clone())),
+                Expression::Alias { alias, .. } => result.columns.push(alias.clone()),
+                Expression::Identifier(name) => result.columns.push(name.clone()),
+                Expression::CompoundIdentifier(parts) => result.columns.push(parts[parts.len() - 1].clone()),
+                other => result.columns.push(format_expression(other)),
+            }
+        }
+        //every row that passes WHERE with its values and the keys it is ordered by, WHERE and the select list are computed
+        //for a chunk of rows at a time
+        let mut rows = Vec::new();
+        for chunk in source.rows.chunks(CHUNK_SIZE) {
+            let mut chunk: Vec<&[Literal]> = chunk.iter().map(Vec::as_slice).collect();
+            if let Some(condition) = r#where {
+                let passed = self.evaluate_chunk(condition, &chunk, &source.columns, outer)?;
+                let mut passed = passed.into_iter();
+                chunk.retain(|_| passed.next() == Some(Literal::Boolean(true)));
+            }
+            let mut values: Vec<Vec<Literal>> = vec![Vec::new(); chunk.len()];
+            for column in columns {
+                match column {
+                    Expression::Wildcard => {
+                        for (values, row) in values.iter_mut().zip(&chunk) {
+                            values.extend(row.iter().cloned());
+                        }
+                    }
+                    other => {
+                        for (values, value) in values.iter_mut().zip(self.evaluate_chunk(other, &chunk, &source.columns, outer)?) {
+                            values.push(value);
+                        }
+                    }
+                }
+            }
+            for (row, values) in chunk.into_iter().zip(values) {
+                let scope = Scope { columns: &source.columns, row, outer };
+                let mut keys = Vec::new();
+                for item in orderby {
+                    keys.push(self.sort_key(sort_expression(item).0, columns, &values, &scope)?);
+                }
+                rows.push((keys, values));
+            }
+        }
+        if !orderby.is_empty() {
+            let descending: Vec<bool> = orderby.iter().map(|item| sort_expression(item).1).collect();
+            sort(&mut rows, &descending)?;
+        }
+        result.rows = rows.into_iter().map(|(_, values)| values).take(limit.unwrap_or(usize::MAX)).collect();
+        Ok(result)
+    }
+
+    //UNION, INTERSECT and EXCEPT, without duplicate rows unless ALL is given. Rows are the same when each of their values is,
+    //NULLs included; the result has the column names of the left query, which ORDER BY refers to by name or position
+    #[allow(clippy::too_many_arguments)]
+    fn set_operation(&self, left: ResultSet, operator: SetOperator, all: bool, right: ResultSet, orderby: &[Expression], limit: &Option<Expression>, outer: Option<&Scope>) -> Result<ResultSet, String> {
+        if left.columns.len() != right.columns.len() {
+            return Err(format!("The queries of {} have {} and {} columns", operator, left.columns.len(), right.columns.len()));
+        }
+        let limit = self.limit(limit.as_ref(), outer)?;
+        let mut rows = Vec::new();
+        let mut kept = HashSet::new();
+        if operator == SetOperator::Union {
+            for row in left.rows.into_iter().chain(right.rows) {
+                if all || kept.insert(row_key(&row)?) {
+                    rows.push(row);
+                }
+            }
+        } else {
+            //with ALL a row of the right query takes away or matches one row of the left query only
+            let mut counts: HashMap<Vec<String>, usize> = HashMap::new();
+            for row in &right.rows {
+                *counts.entry(row_key(row)?).or_default() += 1;
+            }
+            for row in left.rows {
+                let key = row_key(&row)?;
+                let matched = match counts.get_mut(&key) {
+                    Some(count) if *count > 0 => {
+                        if all {
+                            *count -= 1;
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+                if matched == (operator == SetOperator::Intersect) && (all || kept.insert(key)) {
+                    rows.push(row);
+                }
+            }
+        }
+
+        let columns: Vec<Binding> = left.columns.iter().map(|name| Binding { qualifier: String::new(), name: name.clone() }).collect();
+        let mut sorted = Vec::new();
+        for row in rows {
+            let scope = Scope { columns: &columns, row: &row, outer };
+            let mut keys = Vec::new();
+            for item in orderby {
+                keys.push(self.sort_key(sort_expression(item).0, &[], &row, &scope)?);
+            }
+            sorted.push((keys, row));
+        }
+        if !orderby.is_empty() {
+            let descending: Vec<bool> = orderby.iter().map(|item| sort_expression(item).1).collect();
+            sort(&mut sorted, &descending)?;
+        }
+        Ok(ResultSet { columns: left.columns, rows: sorted.into_iter().map(|(_, row)| row).take(limit.unwrap_or(usize::MAX)).collect() })
+    }
+
+    //the number of rows LIMIT allows
+    fn limit(&self, limit: Option<&Expression>, outer: Option<&Scope>) -> Result<Option<usize>, String> {
+        match limit {
+            Some(limit) => match self.eval(limit, outer)? {
+                Literal::Integer(n) if n >= 0 => Ok(Some(n as usize)),
+                other => Err(format!("LIMIT has to be a number that is not negative, found {}", other)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    //the value of an expression for each row of a chunk. Where the expression allows it, it is computed a column at a time, the columns
+    //it uses taken out of the rows; a chunk that fails is evaluated again row by row, so errors are those of the rows the engine reaches
+    fn evaluate_chunk(&self, expr: &Expression, chunk: &[&[Literal]], columns: &[Binding], outer: Option<&Scope>) -> Result<Vec<Literal>, String> {
+        //a correlated subquery has columns of the queries around it, which are not in the chunk
+        if !self.row_at_a_time && outer.is_none() && vector::vectorizable(expr) {
+            let column = |expr: &Expression| {
+                let mut found = columns.iter().enumerate().filter(|(_, binding)| refers_to(expr, binding)).map(|(index, _)| index);
+                match (found.next(), found.next()) {
+                    (Some(index), None) => Ok(Vector::from_values(chunk.iter().map(|row| row[index].clone()).collect())),
+                    _ => Err(format!("Column {} is not a single column of the chunk", format_expression(expr))),
+                }
+            };
+            if let Ok(result) = vector::evaluate(expr, chunk.len(), &column) {
+                return Ok((0..chunk.len()).map(|index| result.value(index)).collect());
+            }
+        }
+        chunk.iter().map(|row| self.eval(expr, Some(&Scope { columns, row, outer }))).collect()
+    }
+
+    //the rows of one item of a FROM clause
+    fn relation(&self, item: &TableReference, outer: Option<&Scope>) -> Result<Relation, String> {
+        match item {
+            TableReference::Table { name, alias, sample: None } => {
+                let table = self.table(name)?;
+                Ok(Relation { columns: bindings(alias.as_ref().unwrap_or(name), table), rows: table.rows.clone() })
+            }
+            TableReference::Join { left, right, kind, on } => self.join(self.relation(left, outer)?, self.relation(right, outer)?, kind, on.as_ref(), outer),
+            _ => Err("Only tables and joins of tables are supported in FROM by the engine".to_string()),
+        }
+    }
+
+    //the join of two relations. Equal columns of the two sides in the condition (`a.id = b.a_id`, possibly among other AND-ed parts)
+    //make it a hash join: the rows of the right side are put in a hash table by those columns, and each row of the left side
+    //is only paired with the rows that have the same values. Without such columns every pair of rows is tried.
+    //Either way a pair is kept when the whole condition is true, outer joins add the rows of their side that were not paired, with NULLs
+    fn join(&self, left: Relation, right: Relation, kind: &JoinKind, on: Option<&Expression>, outer: Option<&Scope>) -> Result<Relation, String> {
+        let (left_width, right_width) = (left.columns.len(), right.columns.len());
+        let mut keys = Vec::new();
+        if let Some(condition) = on {
+            join_keys(condition, &left.columns, &right.columns, &mut keys);
+        }
+
+        let mut buckets: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+        if !keys.is_empty() {
+            for (index, row) in right.rows.iter().enumerate() {
+                let scope = Scope { columns: &right.columns, row, outer };
+                if let Some(key) = self.join_key(keys.iter().map(|(_, key)| *key), &scope)? {
+                    buckets.entry(key).or_default().push(index);
+                }
+            }
+        }
+        let every: Vec<usize> = (0..right.rows.len()).collect();
+
+        let columns: Vec<Binding> = left.columns.into_iter().chain(right.columns).collect();
+        let mut rows = Vec::new();
+        let mut paired = vec![false; right.rows.len()];
+        for left_row in &left.rows {
+            let candidates = if keys.is_empty() {
+                every.as_slice()
+            } else {
+                let scope = Scope { columns: &columns[..left_width], row: left_row, outer };
+                //a NULL key equals nothing
+                match self.join_key(keys.iter().map(|(key, _)| *key), &scope)? {
+                    Some(key) => buckets.get(&key).map_or(&[][..], |bucket| bucket.as_slice()),
+                    None => &[],
+                }
+            };
+            let mut found = false;
+            for &index in candidates {
+                let row: Vec<Literal> = left_row.iter().chain(&right.rows[index]).cloned().collect();
+                let keep = match on {
+                    Some(condition) => self.eval(condition, Some(&Scope { columns: &columns, row: &row, outer }))? == Literal::Boolean(true),
+                    None => true,
+                };
+                if keep {
+                    found = true;
+                    paired[index] = true;
+                    rows.push(row);
+                }
+            }
+            if !found && matches!(kind, JoinKind::Left | JoinKind::Full) {
+                rows.push(left_row.iter().cloned().chain(vec![Literal::Null; right_width]).collect());
+            }
+        }
+        if matches!(kind, JoinKind::Right | JoinKind::Full) {
+            for (row, _) in right.rows.into_iter().zip(paired).filter(|(_, paired)| !paired) {
+                rows.push(vec![Literal::Null; left_width].into_iter().chain(row).collect());
+            }
+        }
+        Ok(Relation { columns, rows })
+    }
+
+    //the values of the key columns of a row as text that hashes the same for equal values, `None` when one of them is NULL
+    fn join_key<'e>(&self, exprs: impl Iterator<Item = &'e Expression>, scope: &Scope) -> Result<Option<Vec<String>>, String> {
+        let mut key = Vec::new();
+        for expr in exprs {
+            match self.eval(expr, Some(scope))? {
+                Literal::Null => return Ok(None),
+                value => key.push(value_key(&value)?),
+            }
+        }
+        Ok(Some(key))
+    }
+
+    //what a row is ordered by for an item of ORDER BY: a number is the position of a column of the select list
+    //and a name can be the alias of one, anything else is evaluated against the row
+    fn sort_key(&self, expr: &Expression, columns: &[Expression], values: &[Literal], scope: &Scope) -> Result<Literal, String> {
+        match expr {
+            Expression::Literal(Literal::Integer(position)) => {
+                let index = usize::try_from(*position).ok().filter(|position| (1..=values.len()).contains(position))
+                    .ok_or_else(|| format!("ORDER BY {} is not a position of the select list", position))?;
+                Ok(values[index - 1].clone())
+            }
+            Expression::Identifier(name) => {
+                let aliased = columns.iter().position(|column| matches!(column, Expression::Alias { alias, .. } if alias.eq_ignore_ascii_case(name)));
+                match aliased {
+                    //a wildcard before the alias makes the positions of the select list and the values differ
+                    Some(index) if !columns.contains(&Expression::Wildcard) => Ok(values[index].clone()),
+                    _ => self.eval(expr, Some(scope)),
+                }
+            }
+            other => self.eval(other, Some(scope)),
+        }
+    }
+
+    fn eval(&self, expr: &Expression, scope: Option<&Scope>) -> Result<Literal, String> {
+        match expr {
+            Expression::Literal(literal) => Ok(literal.clone()),
+            Expression::Identifier(_) | Expression::CompoundIdentifier(_) => column_value(expr, scope),
+            Expression::Alias { expr, .. } => self.eval(expr, scope),
+            Expression::UnaryOperation { operand, operator } => {
+                match (operator, self.eval(operand, scope)?) {
+                    (UnaryOperator::Asc | UnaryOperator::Desc, _) => Err("ASC and DESC are only allowed in ORDER BY".to_string()),
+                    (UnaryOperator::IsNull, value) => Ok(Literal::Boolean(value == Literal::Null)),
+                    (UnaryOperator::IsNotNull, value) => Ok(Literal::Boolean(value != Literal::Null)),
+                    (_, Literal::Null) => Ok(Literal::Null),
+                    (UnaryOperator::Not, Literal::Boolean(b)) => Ok(Literal::Boolean(!b)),
+                    (UnaryOperator::Plus, value @ (Literal::Integer(_) | Literal::Decimal(_))) => Ok(value),
+                    (UnaryOperator::Minus, Literal::Integer(n)) => n.checked_neg().map(Literal::Integer).ok_or_else(|| "Integer out of range".to_string()),
+                    (UnaryOperator::Minus, Literal::Decimal(digits)) => Ok(decimal(-number(&Literal::Decimal(digits))?)),
+                    (_, value) => Err(format!("Expected a {} operand, found {}", if *operator == UnaryOperator::Not { "boolean" } else { "numeric" }, value)),
+                }
+            }
+            Expression::BinaryOperation { left_operand, operator: operator @ (BinaryOperator::And | BinaryOperator::Or), right_operand } => {
+                let absorbing = *operator == BinaryOperator::Or;
+                let left = truth(self.eval(left_operand, scope)?)?;
+                if left == Some(absorbing) {
+                    return Ok(Literal::Boolean(absorbing));
+                }
+                match (left, truth(self.eval(right_operand, scope)?)?) {
+                    (_, Some(right)) if right == absorbing => Ok(Literal::Boolean(absorbing)),
+                    (Some(_), Some(_)) => Ok(Literal::Boolean(!absorbing)),
+                    _ => Ok(Literal::Null),
+                }
+            }
+            Expression::BinaryOperation { left_operand, operator, right_operand } => {
+                let (left, right) = (self.eval(left_operand, scope)?, self.eval(right_operand, scope)?);
+                if left == Literal::Null || right == Literal::Null {
+                    return Ok(Literal::Null);
+                }
+                binary(*operator, &left, &right)
+            }
+            Expression::IsDistinctFrom { left_operand, right_operand, negated } => {
+                let distinct = match (self.eval(left_operand, scope)?, self.eval(right_operand, scope)?) {
+                    (Literal::Null, Literal::Null) => false,
+                    (Literal::Null, _) | (_, Literal::Null) => true,
+                    (left, right) => compare(&left, &right)? != Ordering::Equal,
+                };
+                Ok(Literal::Boolean(distinct != *negated))
+            }
+            Expression::Subquery(subquery) => {
+                let result = self.query(subquery, scope)?;
+                if result.columns.len() != 1 {
+                    return Err(format!("A scalar subquery has to return one column, this one returns {}", result.columns.len()));
+                }
+                match result.rows.as_slice() {
+                    [] => Ok(Literal::Null),
+                    [row] => Ok(row[0].clone()),
+                    _ => Err("A scalar subquery returned more than one row".to_string()),
+                }
+            }
+            Expression::Exists { subquery, negated } => Ok(Literal::Boolean(self.query(subquery, scope)?.rows.is_empty() == *negated)),
+            Expression::Function { name, args, over: None } if FUNCTIONS.iter().any(|function| function.eq_ignore_ascii_case(name)) => {
+                let args = args.iter().map(|arg| self.eval(arg, scope)).collect::<Result<Vec<_>, _>>()?;
+                function(&name.to_lowercase(), args)
+            }
+            Expression::Function { name, .. } => Err(format!("Function {} is not supported by the engine", name)),
+            //`$n` is the n-th argument of EXECUTE, `?` is not numbered and has no value
+            Expression::Placeholder(text) => text.strip_prefix('$')
+                .and_then(|n| n.parse::<usize>().ok())
+                .and_then(|n| self.parameters.get(n.checked_sub(1)?))
+                .cloned()
+                .ok_or_else(|| format!("Parameter {} has no value, the engine gives values to $1, $2, ... of a statement run with EXECUTE", text)),
+            Expression::Wildcard => Err("* is only allowed in the select list".to_string()),
+        }
+    }
+}
+
+//helper, the value of a column of the row in scope, a name is looked up from the innermost query outwards
+fn column_value(expr: &Expression, scope: Option<&Scope>) -> Result<Literal, String> {
+    let mut scope = scope;
+    while let Some(current) = scope {
+        let mut found = current.columns.iter().enumerate().filter(|(_, binding)| refers_to(expr, binding));
+        if let Some((index, _)) = found.next() {
+            if found.next().is_some() {
+                return Err(format!("Column {} is ambiguous, it is in more than one table", format_expression(expr)));
+            }
+            return Ok(current.row[index].clone());
+        }
+        scope = current.outer;
+    }
+    Err(format!("Column {} does not exist", format_expression(expr)))
+}
+
+//helper, whether a column reference names a column of the FROM clause
+fn refers_to(expr: &Expression, binding: &Binding) -> bool {
+    match expr {
+        Expression::CompoundIdentifier(parts) => {
+            parts[parts.len() - 2].eq_ignore_ascii_case(&binding.qualifier) && parts[parts.len() - 1].eq_ignore_ascii_case(&binding.name)
+        }
+        Expression::Identifier(name) => name.eq_ignore_ascii_case(&binding.name),
+        _ => false,
+    }
+}
+
+//helper, the columns of a table qualified with its name or alias
+fn bindings(qualifier: &str, table: &Table) -> Vec<Binding> {
+    table.columns.iter().map(|column| Binding { qualifier: qualifier.to_string(), name: column.column_name.clone() }).collect()
+}
+
+//helper, what a value is hashed by: 1 and 1.0 are equal, so numbers hash by value
+fn value_key(value: &Literal) -> Result<String, String> {
+    Ok(match value {
+        Literal::Integer(_) | Literal::Decimal(_) => format!("n{}", number(value)?),
+        Literal::String(s) | Literal::NationalString(s) => format!("s{}", s),
+        other => format!("{:?}", other),
+    })
+}
+
+//helper, what a whole row is hashed by in a set operation
+fn row_key(row: &[Literal]) -> Result<Vec<String>, String> {
+    row.iter().map(value_key).collect()
+}
+
+//helper, the pairs of column references of a join condition that compare a column of the left side with one of the right,
+//from the AND-ed parts of the condition
+fn join_keys<'e>(condition: &'e Expression, left: &[Binding], right: &[Binding], keys: &mut Vec<(&'e Expression, &'e Expression)>) {
+    match condition {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand } => {
+            join_keys(left_operand, left, right, keys);
+            join_keys(right_operand, left, right, keys);
+        }
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::Equal, right_operand } => {
+            //a column of one side only, a name both sides have is ambiguous
+            let only = |expr: &Expression, this: &[Binding], other: &[Binding]| {
+                this.iter().filter(|binding| refers_to(expr, binding)).count() == 1 && !other.iter().any(|binding| refers_to(expr, binding))
+            };
+            let (a, b) = (left_operand.as_ref(), right_operand.as_ref());
+            if only(a, left, right) && only(b, right, left) {
+                keys.push((a, b));
+            } else if only(b, left, right) && only(a, right, left) {
+                keys.push((b, a));
+            }
+        }
+        _ => {}
+    }
+}
+
+//the sort operator: rows in the order of their keys, each descending or not. NULL comes after every value, last in ascending
+//order and first in descending order, as in Postgres. The sort is stable, rows with equal keys stay in the order they were found
+fn sort(rows: &mut [(Vec<Literal>, Vec<Literal>)], descending: &[bool]) -> Result<(), String> {
+    let mut error = None;
+    rows.sort_by(|(a, _), (b, _)| {
+        for ((a, b), descending) in a.iter().zip(b).zip(descending) {
+            let order = match (a, b) {
+                (Literal::Null, Literal::Null) => Ordering::Equal,
+                (Literal::Null, _) => Ordering::Greater,
+                (_, Literal::Null) => Ordering::Less,
+                _ => compare(a, b).unwrap_or_else(|err| {
+                    error.get_or_insert(err);
+                    Ordering::Equal
+                }),
+            };
+            let order = if *descending { order.reverse() } else { order };
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        Ordering::Equal
+    });
+    error.map_or(Ok(()), Err)
+}
+
+//helper, an item of ORDER BY without its ASC or DESC, and whether it is DESC
+fn sort_expression(item: &Expression) -> (&Expression, bool) {
+    match item {
+        Expression::UnaryOperation { operand, operator: UnaryOperator::Asc } => (operand, false),
+        Expression::UnaryOperation { operand, operator: UnaryOperator::Desc } => (operand, true),
+        other => (other, false),
+    }
+}
+
+//helper, a boolean or NULL (`None`) as a truth value
+fn truth(value: Literal) -> Result<Option<bool>, String> {
+    match value {
+        Literal::Boolean(b) => Ok(Some(b)),
+        Literal::Null => Ok(None),
+        other => Err(format!("Expected a boolean, found {}", other)),
+    }
+}
+
+//helper, a numeric value as a float
+fn number(value: &Literal) -> Result<f64, String> {
+    match value {
+        Literal::Integer(n) => Ok(*n as f64),
+        Literal::Decimal(digits) => digits.parse().map_err(|_| format!("Invalid number {}", digits)),
+        other => Err(format!("Expected a number, found {}", other)),
+    }
+}
+
+//helper, a float as a decimal value
+fn decimal(value: f64) -> Literal {
+    Literal::Decimal(value.to_string())
+}
+
+//helper, the text of a string value of any kind
+fn text(value: &Literal) -> Option<&str> {
+    match value {
+        Literal::String(s) | Literal::NationalString(s) => Some(s),
+        _ => None,
+    }
+}
+
+//how two values that are not NULL are ordered: numbers by value, strings and booleans as such
+fn compare(left: &Literal, right: &Literal) -> Result<Ordering, String> {
+    match (left, right) {
+        (Literal::Integer(a), Literal::Integer(b)) => Ok(a.cmp(b)),
+        (Literal::Integer(_) | Literal::Decimal(_), Literal::Integer(_) | Literal::Decimal(_)) => {
+            number(left)?.partial_cmp(&number(right)?).ok_or_else(|| "Numbers can not be compared".to_string())
+        }
+        (Literal::Boolean(a), Literal::Boolean(b)) => Ok(a.cmp(b)),
+        _ => match (text(left), text(right)) {
+            (Some(a), Some(b)) => Ok(a.cmp(b)),
+            _ => Err(format!("Cannot compare {} with {}", left, right)),
+        },
+    }
+}
+
+/// An operator other than `AND` and `OR` applied to two values that are not NULL, as the engine computes it.
+pub fn binary(operator: BinaryOperator, left: &Literal, right: &Literal) -> Result<Literal, String> {
+    let order = || compare(left, right);
+    match operator {
+        BinaryOperator::Equal => Ok(Literal::Boolean(order()? == Ordering::Equal)),
+        BinaryOperator::NotEqual => Ok(Literal::Boolean(order()? != Ordering::Equal)),
+        BinaryOperator::LessThan => Ok(Literal::Boolean(order()? == Ordering::Less)),
+        BinaryOperator::LessThanOrEqual => Ok(Literal::Boolean(order()? != Ordering::Greater)),
+        BinaryOperator::GreaterThan => Ok(Literal::Boolean(order()? == Ordering::Greater)),
+        BinaryOperator::GreaterThanOrEqual => Ok(Literal::Boolean(order()? != Ordering::Less)),
+        BinaryOperator::Concat => match (text(left), text(right)) {
+            (Some(a), Some(b)) => Ok(Literal::String(format!("{}{}", a, b))),
+            _ => Err(format!("Cannot concatenate {} and {}", left, right)),
+        },
+        BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Multiply | BinaryOperator::Divide => arithmetic(operator, left, right),
+        other => Err(format!("Operator {} is not supported by the engine", other)),
+    }
+}
+
+//integers stay integers (division truncates, as in most databases), anything with a decimal is computed as a float
+fn arithmetic(operator: BinaryOperator, left: &Literal, right: &Literal) -> Result<Literal, String> {
+    if let (Literal::Integer(a), Literal::Integer(b)) = (left, right) {
+        let result = match operator {
+            BinaryOperator::Plus => a.checked_add(*b),
+            BinaryOperator::Minus => a.checked_sub(*b),
+            BinaryOperator::Multiply => a.checked_mul(*b),
+            _ if *b == 0 => return Err("Division by zero".to_string()),
+            _ => a.checked_div(*b),
+        };
+        return result.map(Literal::Integer).ok_or_else(|| "Integer out of range".to_string());
+    }
+    let (a, b) = (number(left)?, number(right)?);
+    match operator {
+        BinaryOperator::Plus => Ok(decimal(a + b)),
+        BinaryOperator::Minus => Ok(decimal(a - b)),
+        BinaryOperator::Multiply => Ok(decimal(a * b)),
+        _ if b == 0.0 => Err("Division by zero".to_string()),
+        _ => Ok(decimal(a / b)),
+    }
+}
+
+//one of FUNCTIONS applied to its arguments
+fn function(name: &str, mut args: Vec<Literal>) -> Result<Literal, String> {
+    if name == "coalesce" {
+        return Ok(args.into_iter().find(|arg| *arg != Literal::Null).unwrap_or(Literal::Null));
+    }
+    if args.len() != 1 {
+        return Err(format!("Function {} takes one argument, found {}", name, args.len()));
+    }
+    let arg = args.remove(0);
+    match (name, &arg) {
+        (_, Literal::Null) => Ok(Literal::Null),
+        ("lower", _) => text(&arg).map(|s| Literal::String(s.to_lowercase())).ok_or_else(|| format!("Expected a string, found {}", arg)),
+        ("upper", _) => text(&arg).map(|s| Literal::String(s.to_uppercase())).ok_or_else(|| format!("Expected a string, found {}", arg)),
+        ("length", _) => text(&arg).map(|s| Literal::Integer(s.chars().count() as i64)).ok_or_else(|| format!("Expected a string, found {}", arg)),
+        ("abs", Literal::Integer(n)) => n.checked_abs().map(Literal::Integer).ok_or_else(|| "Integer out of range".to_string()),
+        ("abs", _) => number(&arg).map(|n| decimal(n.abs())),
+        _ => unreachable!(),
+    }
+}
+
+//a value as it is stored in a column of the given type
+fn coerce(value: Literal, column: &TableColumn) -> Result<Literal, String> {
+    let mismatch = |value: &Literal| format!("Value {} does not fit column {} of type {}", value, column.column_name, format_type(&column.column_type));
+    match (&column.column_type, value) {
+        (_, Literal::Null) => Ok(Literal::Null),
+        (DBType::Int, value @ Literal::Integer(_)) | (DBType::Bool, value @ Literal::Boolean(_)) => Ok(value),
+        (DBType::Varchar(length), value) => match text(&value) {
+            Some(s) if s.chars().count() <= *length => Ok(Literal::String(s.to_string())),
+            _ => Err(mismatch(&value)),
+        },
+        (DBType::Decimal(precision, scale), value @ (Literal::Integer(_) | Literal::Decimal(_))) => {
+            let rounded = format!("{:.*}", scale, number(&value)?);
+            let integer_part = rounded.trim_start_matches('-').split('.').next().unwrap_or_default();
+            if integer_part.trim_start_matches('0').len() > precision.saturating_sub(*scale) {
+                return Err(mismatch(&value));
+            }
+            Ok(Literal::Decimal(rounded))
+        }
+        (DBType::Double, value @ (Literal::Integer(_) | Literal::Decimal(_))) => Ok(decimal(number(&value)?)),
+        //a fixed length string is padded with spaces up to its length
+        (DBType::Char(length), value) => match text(&value) {
+            Some(s) if s.chars().count() <= *length => Ok(Literal::String(format!("{:<width$}", s, width = *length))),
+            _ => Err(mismatch(&value)),
+        },
+        //timestamps are kept as the text they were written as, the engine has no date arithmetic
+        (DBType::Timestamp { .. }, value @ Literal::String(_)) => Ok(value),
+        (DBType::Custom(name, _), _) => Err(format!("Type {} of column {} is not supported by the engine", name, column.column_name)),
+        (_, value) => Err(mismatch(&value)),
+    }
+}
+
+/// The rows `SHOW TABLES` returns: a `table` column with the names, sorted.
+pub fn table_list<'a>(names: impl Iterator<Item = &'a str>) -> ResultSet {
+    let mut names: Vec<&str> = names.collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    ResultSet {
+        columns: vec!["table".to_string()],
+        rows: names.into_iter().map(|name| vec![Literal::String(name.to_string())]).collect(),
+    }
+}
+
+/// The rows `DESCRIBE` returns, one per column of the table: its `column` name, `type`, whether it is `nullable` (`YES` or `NO`), `key` (`PRI` for the primary key) and its `check` condition, `NULL` when it has none.
+pub fn describe(columns: &[TableColumn]) -> ResultSet {
+    let rows = columns.iter()
+        .map(|column| {
+            let not_null = column.constraints.iter().any(|constraint| matches!(constraint, Constraint::NotNull | Constraint::PrimaryKey));
+            let primary_key = column.constraints.contains(&Constraint::PrimaryKey);
+            let checks: Vec<String> = column.constraints.iter()
+                .filter_map(|constraint| match constraint {
+                    Constraint::Check(expr) => Some(format_expression(expr)),
+                    _ => None,
+                })
+                .collect();
+            vec![
+                Literal::String(column.column_name.clone()),
+                Literal::String(format_type(&column.column_type)),
+                Literal::String(if not_null { "NO" } else { "YES" }.to_string()),
+                Literal::String(if primary_key { "PRI" } else { "" }.to_string()),
+                if checks.is_empty() { Literal::Null } else { Literal::String(checks.join(" AND ")) },
+            ]
+        })
+        .collect();
+    ResultSet {
+        columns: ["column", "type", "nullable", "key", "check"].iter().map(|name| name.to_string()).collect(),
+        rows,
+    }
+}
+
+/// The text of a value in a result table: strings without their quotes, `NULL` for NULL and everything else as in SQL.
+pub fn value_text(value: &Literal) -> String {
+    match value {
+        Literal::String(s) | Literal::NationalString(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,120 @@
+//constant folding, the value of the parts of an expression that do not depend on any row
+use std::cmp::Ordering;
+use crate::statement::{Expression, Literal, BinaryOperator, UnaryOperator};
+
+/// The value of an expression that is a constant: literals and operators applied to them, `1 + 1` is `Integer(2)` and `1 = 2` is `Boolean(false)`. `AND` and `OR` follow SQL's three valued logic and are constant as soon as one operand decides them, `x > 1 AND 1 = 0` is `Boolean(false)` whatever `x` is. Returns `None` when the value depends on a column, a parameter or a function, and when it depends on the database: arithmetic that overflows, divides by zero or has a decimal operand, or strings compared by a collation (see `equal`).
+pub fn fold(expr: &Expression) -> Option<Literal> {
+    match expr {
+        Expression::Literal(literal) => Some(literal.clone()),
+        Expression::UnaryOperation { operand, operator } => {
+            match (operator, fold(operand)?) {
+                (UnaryOperator::Asc | UnaryOperator::Desc, _) => None,
+                (UnaryOperator::IsNull, value) => Some(Literal::Boolean(value == Literal::Null)),
+                (UnaryOperator::IsNotNull, value) => Some(Literal::Boolean(value != Literal::Null)),
+                (_, Literal::Null) => Some(Literal::Null),
+                (UnaryOperator::Not, Literal::Boolean(b)) => Some(Literal::Boolean(!b)),
+                (UnaryOperator::Plus, value @ (Literal::Integer(_) | Literal::Decimal(_))) => Some(value),
+                (UnaryOperator::Minus, Literal::Integer(n)) => n.checked_neg().map(Literal::Integer),
+                (UnaryOperator::Minus, Literal::Decimal(digits)) => Some(Literal::Decimal(match digits.strip_prefix('-') {
+                    Some(positive) => positive.to_string(),
+                    None => format!("-{}", digits),
+                })),
+                _ => None,
+            }
+        }
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand } => {
+            logical(fold(left_operand), fold(right_operand), false)
+        }
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::Or, right_operand } => {
+            logical(fold(left_operand), fold(right_operand), true)
+        }
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            let (left, right) = (fold(left_operand)?, fold(right_operand)?);
+            //every operator but AND and OR yields NULL for a NULL operand
+            if left == Literal::Null || right == Literal::Null {
+                return Some(Literal::Null);
+            }
+            binary(operator, &left, &right)
+        }
+        Expression::IsDistinctFrom { left_operand, right_operand, negated } => {
+            let distinct = match (fold(left_operand)?, fold(right_operand)?) {
+                (Literal::Null, Literal::Null) => false,
+                (Literal::Null, _) | (_, Literal::Null) => true,
+                (left, right) => !equal(&left, &right)?,
+            };
+            Some(Literal::Boolean(distinct != *negated))
+        }
+        _ => None,
+    }
+}
+
+/// Whether two constants are equal, `None` when that is not known without the database. Numbers are equal by value (`1 = 1.0`), booleans as expected. Strings are equal when they are the same, and unknown when they differ only in case or in trailing spaces, which case insensitive and space padding collations ignore. Anything else, such as a number and a string, is unknown too.
+pub fn equal(a: &Literal, b: &Literal) -> Option<bool> {
+    match (a, b) {
+        (Literal::Boolean(a), Literal::Boolean(b)) => Some(a == b),
+        (Literal::String(a), Literal::String(b)) if a == b => Some(true),
+        (Literal::String(a), Literal::String(b)) if a.trim_end_matches(' ').to_lowercase() == b.trim_end_matches(' ').to_lowercase() => None,
+        (Literal::String(_), Literal::String(_)) => Some(false),
+        _ => compare(a, b).map(|order| order == Ordering::Equal),
+    }
+}
+
+/// How two numbers are ordered, `None` when one of them is not a number. Integers are compared exactly, decimals by their nearest `f64`.
+pub fn compare(a: &Literal, b: &Literal) -> Option<Ordering> {
+    match (a, b) {
+        (Literal::Integer(a), Literal::Integer(b)) => Some(a.cmp(b)),
+        _ => number(a)?.partial_cmp(&number(b)?),
+    }
+}
+
+//helper, a numeric literal as a float
+fn number(literal: &Literal) -> Option<f64> {
+    match literal {
+        Literal::Integer(n) => Some(*n as f64),
+        Literal::Decimal(digits) => digits.parse().ok(),
+        _ => None,
+    }
+}
+
+//AND (`absorbing` false) and OR (`absorbing` true): one operand with the absorbing value decides, otherwise both have to be known
+fn logical(left: Option<Literal>, right: Option<Literal>, absorbing: bool) -> Option<Literal> {
+    let truth = |value: &Option<Literal>| match value {
+        Some(Literal::Boolean(b)) => Some(Some(*b)),
+        Some(Literal::Null) => Some(None),
+        _ => None,
+    };
+    let (left, right) = (truth(&left), truth(&right));
+    if left == Some(Some(absorbing)) || right == Some(Some(absorbing)) {
+        return Some(Literal::Boolean(absorbing));
+    }
+    match (left?, right?) {
+        (Some(_), Some(_)) => Some(Literal::Boolean(!absorbing)),
+        _ => Some(Literal::Null),
+    }
+}
+
+//an operator other than AND and OR applied to two constants that are not NULL
+fn binary(operator: &BinaryOperator, left: &Literal, right: &Literal) -> Option<Literal> {
+    let integers = match (left, right) {
+        (Literal::Integer(a), Literal::Integer(b)) => Some((*a, *b)),
+        _ => None,
+    };
+    match operator {
+        BinaryOperator::Plus => integers.and_then(|(a, b)| a.checked_add(b)).map(Literal::Integer),
+        BinaryOperator::Minus => integers.and_then(|(a, b)| a.checked_sub(b)).map(Literal::Integer),
+        BinaryOperator::Multiply => integers.and_then(|(a, b)| a.checked_mul(b)).map(Literal::Integer),
+        //integer division truncates in some databases and not in others, only an exact one is the same everywhere
+        BinaryOperator::Divide => integers.filter(|(a, b)| *b != 0 && a.checked_rem(*b) == Some(0)).and_then(|(a, b)| a.checked_div(b)).map(Literal::Integer),
+        BinaryOperator::Equal => equal(left, right).map(Literal::Boolean),
+        BinaryOperator::NotEqual => equal(left, right).map(|equal| Literal::Boolean(!equal)),
+        BinaryOperator::LessThan => compare(left, right).map(|order| Literal::Boolean(order == Ordering::Less)),
+        BinaryOperator::LessThanOrEqual => compare(left, right).map(|order| Literal::Boolean(order != Ordering::Greater)),
+        BinaryOperator::GreaterThan => compare(left, right).map(|order| Literal::Boolean(order == Ordering::Greater)),
+        BinaryOperator::GreaterThanOrEqual => compare(left, right).map(|order| Literal::Boolean(order != Ordering::Less)),
+        BinaryOperator::Concat => match (left, right) {
+            (Literal::String(a), Literal::String(b)) => Some(Literal::String(format!("{}{}", a, b))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,1093 @@
+use crate::token::{Token, Keyword};
+use crate::tokenizer::{Tokenizer, Span, SpannedToken, StatementEnds};
+use crate::parser::{Parser, ParseError};
+use crate::dialect::Dialect;
+use crate::align::common_sequence;
+use crate::statement::{
+    Statement,
+    InsertSource,
+    Expression,
+    Literal,
+    UnaryOperator,
+    TableColumn,
+    TableReference,
+    GroupingElement,
+    WindowSpec,
+    Hint,
+    DBType,
+    Constraint,
+    AlterAction,
+    ShowObject,
+    OnConflict,
+    ConflictAction,
+    MergeClause,
+    MergeAction,
+    With,
+    SetOperator,
+    FunctionArg,
+    CopyDirection,
+    CopyOption,
+};
+
+/// How the formatter lays out a statement: `Multiline` puts every clause (and every column of a `CREATE TABLE`) on its own line, `SingleLine` writes each statement on one line.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Layout {
+    #[default]
+    Multiline,
+    SingleLine,
+}
+
+/// Which node a comment written between two nodes goes with: `Leading` gives it to the node after it, `Trailing` to the node before it. A comment inside a node stays with that node, `Leading` prints it on a line of its own before the node and `Trailing` at the end of the node's line.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CommentPlacement {
+    #[default]
+    Leading,
+    Trailing,
+}
+
+impl CommentPlacement {
+    pub fn from_name(name: &str) -> Option<CommentPlacement> {
+        match name.to_ascii_lowercase().as_str() {
+            "leading" => Some(CommentPlacement::Leading),
+            "trailing" => Some(CommentPlacement::Trailing),
+            _ => None,
+        }
+    }
+}
+
+/// The nodes comments are attached to: whole statements, or the clauses of a statement (`WITH`, `SELECT`, `FROM`, `WHERE`, `GROUP BY`, `HAVING`, `QUALIFY`, `ORDER BY`, `LIMIT`, `VALUES`, `ON CONFLICT` and the `UNION`, `INTERSECT` or `EXCEPT` between two queries). A statement written on a single line has no clause lines, so with `Layout::SingleLine` comments always go with the statement.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CommentAttachment {
+    Statement,
+    #[default]
+    Clause,
+}
+
+impl CommentAttachment {
+    pub fn from_name(name: &str) -> Option<CommentAttachment> {
+        match name.to_ascii_lowercase().as_str() {
+            "statement" => Some(CommentAttachment::Statement),
+            "clause" => Some(CommentAttachment::Clause),
+            _ => None,
+        }
+    }
+}
+
+/// The case `recase_keywords` writes keywords in: `Upper` is what the formatter writes, `Lower` writes every keyword in lower case and `Preserve` writes each keyword the way it is spelled in the SQL the text was formatted from. Names are written as they are in the tree either way, which keeps the case they were written in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    Lower,
+    Preserve,
+}
+
+impl KeywordCase {
+    pub fn from_name(name: &str) -> Option<KeywordCase> {
+        match name.to_ascii_lowercase().as_str() {
+            "upper" => Some(KeywordCase::Upper),
+            "lower" => Some(KeywordCase::Lower),
+            "preserve" => Some(KeywordCase::Preserve),
+            _ => None,
+        }
+    }
+}
+
+/// Where `format_commented` puts the comments of a script.
+/// 1. `placement` – Whether a comment between two nodes goes with the one after it or the one before it.
+/// 2. `attachment` – Whether the nodes are statements or clauses.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CommentPolicy {
+    pub placement: CommentPlacement,
+    pub attachment: CommentAttachment,
+}
+/// Where formatted SQL comes from in the SQL it was formatted from, token by token, for tools that move positions between the two, such as a lint finding in the source onto the formatted text or back.
+/// 1. `mappings` – The span of a token in the formatted text and the span of the same token in the source, in the order of the formatted text. Tokens the formatter adds (the parentheses around a nested operation, the `AS` of an alias) and tokens it leaves out have none.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SourceMap {
+    pub mappings: Vec<(Span, Span)>,
+}
+
+impl SourceMap {
+    /// The span in the source of the token at byte `offset` of the formatted text, None between tokens and in a token the formatter added.
+    pub fn to_source(&self, offset: usize) -> Option<Span> {
+        self.mappings.iter().find(|(formatted, _)| formatted.start <= offset && offset < formatted.end).map(|(_, source)| *source)
+    }
+
+    /// The span in the formatted text of the token at byte `offset` of the source, None between tokens and in a token the formatter left out.
+    pub fn to_formatted(&self, offset: usize) -> Option<Span> {
+        self.mappings.iter().find(|(_, source)| source.start <= offset && offset < source.end).map(|(formatted, _)| *formatted)
+    }
+}
+
+//a statement or a clause with the comments that go with it
+struct Node {
+    statement: usize,
+    //the keyword of a clause and how many clauses with the same keyword come before it at the top level of the statement,
+    //None for the statement itself
+    clause: Option<(Keyword, usize)>,
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+//turn a statement back into SQL text, one clause per line, keywords in upper case
+pub fn format_statement(stmt: &Statement) -> String {
+    format_statement_with(stmt, Layout::Multiline)
+}
+
+//turn a statement back into SQL text with the given layout
+pub fn format_statement_with(stmt: &Statement, layout: Layout) -> String {
+    match layout {
+        Layout::Multiline => format!("{};", format_body(stmt, "\n")),
+        Layout::SingleLine => format!("{};", format_body(stmt, " ")),
+    }
+}
+
+//helper, the statement without its semicolon, clauses joined by `separator`
+//subqueries are written on a single line
+fn format_body(stmt: &Statement, separator: &str) -> String {
+    match stmt {
+        Statement::Select { with, hints, columns, from, r#where, group_by, having, qualify, orderby, limit } => {
+            let select = if hints.is_empty() { "SELECT".to_string() } else { format!("SELECT {}", format_hints(hints)) };
+            let mut clauses: Vec<String> = with.iter().map(format_with).collect();
+            clauses.extend([format!("{} {}", select, format_list(columns)), format!("FROM {}", format_from(from))]);
+            if let Some(expr) = r#where {
+                clauses.push(format!("WHERE {}", format_expression(expr)));
+            }
+            if !group_by.is_empty() {
+                let items: Vec<String> = group_by.iter().map(format_grouping_element).collect();
+                clauses.push(format!("GROUP BY {}", items.join(", ")));
+            }
+            if let Some(expr) = having {
+                clauses.push(format!("HAVING {}", format_expression(expr)));
+            }
+            if let Some(expr) = qualify {
+                clauses.push(format!("QUALIFY {}", format_expression(expr)));
+            }
+            if !orderby.is_empty() {
+                clauses.push(format!("ORDER BY {}", format_list(orderby)));
+            }
+            if let Some(expr) = limit {
+                clauses.push(format!("LIMIT {}", format_expression(expr)));
+            }
+            clauses.join(separator)
+        }
+        Statement::SetOperation { with, left, operator, all, right, orderby, limit } => {
+            let mut clauses: Vec<String> = with.iter().map(format_with).collect();
+            clauses.push(format_set_operand(left, *operator, false, separator));
+            clauses.push(if *all { format!("{} ALL", operator) } else { operator.to_string() });
+            clauses.push(format_set_operand(right, *operator, true, separator));
+            if !orderby.is_empty() {
+                clauses.push(format!("ORDER BY {}", format_list(orderby)));
+            }
+            if let Some(expr) = limit {
+                clauses.push(format!("LIMIT {}", format_expression(expr)));
+            }
+            clauses.join(separator)
+        }
+        Statement::CreateTable { table_name, column_list, query, if_not_exists, temporary } => {
+            let columns: Vec<String> = column_list.iter().map(format_column).collect();
+            let create = if *temporary { "CREATE TEMPORARY TABLE" } else { "CREATE TABLE" };
+            let if_not_exists = if *if_not_exists { " IF NOT EXISTS" } else { "" };
+            if let Some(query) = query {
+                format!("{}{} {} AS{}{}", create, if_not_exists, format_name(table_name), separator, format_body(query, separator))
+            } else if separator == "\n" {
+                format!("{}{} {} (\n    {}\n)", create, if_not_exists, format_name(table_name), columns.join(",\n    "))
+            } else {
+                format!("{}{} {} ({})", create, if_not_exists, format_name(table_name), columns.join(", "))
+            }
+        }
+        Statement::Values { rows } => {
+            let rows: Vec<String> = rows.iter().map(|row| format!("({})", format_list(row))).collect();
+            if separator == "\n" {
+                format!("VALUES\n    {}", rows.join(",\n    "))
+            } else {
+                format!("VALUES {}", rows.join(", "))
+            }
+        }
+        Statement::Insert { table_name, columns, source, on_conflict } => {
+            let mut insert = format!("INSERT INTO {}", format_name(table_name));
+            if let Some(columns) = columns {
+                let names: Vec<String> = columns.iter().map(|name| format_name(name)).collect();
+                insert.push_str(&format!(" ({})", names.join(", ")));
+            }
+            let mut text = match source {
+                InsertSource::Values(rows) => {
+                    let rows: Vec<String> = rows.iter().map(|row| format!("({})", format_list(row))).collect();
+                    if separator == "\n" {
+                        format!("{}\nVALUES\n    {}", insert, rows.join(",\n    "))
+                    } else {
+                        format!("{} VALUES {}", insert, rows.join(", "))
+                    }
+                }
+                //a query starting with VALUES keeps its parentheses, without them it reads back as the rows of the insert
+                InsertSource::Query(query) if starts_with_values(query) => {
+                    format!("{}{}({})", insert, separator, format_body(query, separator))
+                }
+                InsertSource::Query(query) => format!("{}{}{}", insert, separator, format_body(query, separator)),
+            };
+            if let Some(on_conflict) = on_conflict {
+                text.push_str(separator);
+                text.push_str(&format_on_conflict(on_conflict, separator));
+            }
+            text
+        }
+        Statement::Delete { table, where_clause } => {
+            let mut clauses = vec![format!("DELETE FROM {}", format_name(table))];
+            if let Some(expr) = where_clause {
+                clauses.push(format!("WHERE {}", format_expression(expr)));
+            }
+            clauses.join(separator)
+        }
+        Statement::Merge { table_name, alias, source, on, clauses } => {
+            let target = match alias {
+                Some(alias) => format!("MERGE INTO {} AS {}", format_name(table_name), format_name(alias)),
+                None => format!("MERGE INTO {}", format_name(table_name)),
+            };
+            //a join as the source reads back only in parentheses, its ON would be taken for the condition of the merge
+            let source = match source {
+                TableReference::Join { .. } => format!("({})", format_table(source)),
+                _ => format_table(source),
+            };
+            let mut lines = vec![target, format!("USING {} ON {}", source, format_expression(on))];
+            lines.extend(clauses.iter().map(format_merge_clause));
+            lines.join(separator)
+        }
+        Statement::AlterTable { table_name, action } => match action {
+            AlterAction::AddColumn(column) => format!("ALTER TABLE {} ADD COLUMN {}", format_name(table_name), format_column(column)),
+            AlterAction::DropColumn(column_name) => format!("ALTER TABLE {} DROP COLUMN {}", format_name(table_name), format_name(column_name)),
+            AlterAction::RenameColumn { column_name, new_name } => {
+                format!("ALTER TABLE {} RENAME COLUMN {} TO {}", format_name(table_name), format_name(column_name), format_name(new_name))
+            }
+            AlterAction::RenameTo(new_name) => format!("ALTER TABLE {} RENAME TO {}", format_name(table_name), format_name(new_name)),
+        },
+        Statement::RenameTable { renames } => {
+            let renames: Vec<String> = renames.iter().map(|rename| format!("{} TO {}", format_name(&rename.old_name), format_name(&rename.new_name))).collect();
+            format!("RENAME TABLE {}", renames.join(", "))
+        }
+        Statement::Truncate { table_name } => format!("TRUNCATE TABLE {}", format_name(table_name)),
+        Statement::Analyze { table_name } => match table_name {
+            Some(table_name) => format!("ANALYZE {}", format_name(table_name)),
+            None => "ANALYZE".to_string(),
+        },
+        Statement::Vacuum { full, table_name } => {
+            let mut text = if *full { "VACUUM FULL".to_string() } else { "VACUUM".to_string() };
+            if let Some(table_name) = table_name {
+                text.push(' ');
+                text.push_str(&format_name(table_name));
+            }
+            text
+        }
+        Statement::Show { object: ShowObject::Tables } => "SHOW TABLES".to_string(),
+        Statement::Show { object: ShowObject::Databases } => "SHOW DATABASES".to_string(),
+        Statement::Describe { table_name } => format!("DESCRIBE {}", format_name(table_name)),
+        Statement::SetVariable { name, value, rest } => {
+            //`on` reads back as the value without quotes, see Parser::parse_set_value
+            let values: Vec<String> = std::iter::once(value).chain(rest).map(|value| match value {
+                Expression::Identifier(word) if word == "on" => "on".to_string(),
+                value => format_expression(value),
+            }).collect();
+            format!("SET {} = {}", format_name(name), values.join(", "))
+        }
+        Statement::Call { name, args } => {
+            let args: Vec<String> = args.iter().map(format_expression).collect();
+            format!("CALL {}({})", format_name(name), args.join(", "))
+        }
+        Statement::Copy { table_name, columns, direction, file, options } => {
+            let stream = if *direction == CopyDirection::From { "STDIN" } else { "STDOUT" };
+            let file = file.as_deref().map_or(stream.to_string(), format_string);
+            let mut clauses = vec![format!("COPY {}{} {} {}", format_name(table_name), format_column_names(columns), direction, file)];
+            if !options.is_empty() {
+                let options: Vec<String> = options.iter().map(format_copy_option).collect();
+                clauses.push(format!("WITH ({})", options.join(", ")));
+            }
+            clauses.join(separator)
+        }
+        Statement::LoadData { local, file, duplicates, table_name, format, ignore_lines, columns } => {
+            let local = if *local { "LOCAL " } else { "" };
+            let duplicates = duplicates.map_or(String::new(), |duplicates| format!(" {}", duplicates));
+            let mut clauses = vec![format!("LOAD DATA {}INFILE {}{} INTO TABLE {}", local, format_string(file), duplicates, format_name(table_name))];
+            let by = |what: &str, value: &Option<String>| value.as_deref().map(|value| format!(" {} BY {}", what, format_string(value)));
+            let enclosed = if format.optionally_enclosed { "OPTIONALLY ENCLOSED" } else { "ENCLOSED" };
+            let fields: String = vec![by("TERMINATED", &format.fields_terminated_by), by(enclosed, &format.fields_enclosed_by), by("ESCAPED", &format.fields_escaped_by)].into_iter().flatten().collect();
+            if !fields.is_empty() {
+                clauses.push(format!("FIELDS{}", fields));
+            }
+            let lines: String = vec![by("STARTING", &format.lines_starting_by), by("TERMINATED", &format.lines_terminated_by)].into_iter().flatten().collect();
+            if !lines.is_empty() {
+                clauses.push(format!("LINES{}", lines));
+            }
+            if let Some(count) = ignore_lines {
+                clauses.push(format!("IGNORE {} LINES", count));
+            }
+            if !columns.is_empty() {
+                clauses.push(format_column_names(columns).trim_start().to_string());
+            }
+            clauses.join(separator)
+        }
+        Statement::Prepare { name, param_types, statement } => {
+            let types = if param_types.is_empty() {
+                String::new()
+            } else {
+                let types: Vec<String> = param_types.iter().map(format_type).collect();
+                format!(" ({})", types.join(", "))
+            };
+            format!("PREPARE {}{} AS{}{}", format_name(name), types, separator, format_body(statement, separator))
+        }
+        Statement::Execute { name, args } if args.is_empty() => format!("EXECUTE {}", format_name(name)),
+        Statement::Execute { name, args } => {
+            let args: Vec<String> = args.iter().map(format_expression).collect();
+            format!("EXECUTE {}({})", format_name(name), args.join(", "))
+        }
+        Statement::Deallocate { name: Some(name) } => format!("DEALLOCATE {}", format_name(name)),
+        Statement::Deallocate { name: None } => "DEALLOCATE ALL".to_string(),
+        //written back as it was read
+        Statement::Unparsed { raw, .. } => raw.clone(),
+        Statement::CreateDatabase { name, if_not_exists } | Statement::CreateSchema { name, if_not_exists } => {
+            let object = if let Statement::CreateDatabase { .. } = stmt { "DATABASE" } else { "SCHEMA" };
+            let if_not_exists = if *if_not_exists { " IF NOT EXISTS" } else { "" };
+            format!("CREATE {}{} {}", object, if_not_exists, format_name(name))
+        }
+        Statement::CreateIndex { name, table_name, columns, unique, if_not_exists } => {
+            let columns: Vec<String> = columns.iter().map(|column| format!("{}{}", format_name(&column.name), if column.descending { " DESC" } else { "" })).collect();
+            let unique = if *unique { "UNIQUE " } else { "" };
+            let if_not_exists = if *if_not_exists { " IF NOT EXISTS" } else { "" };
+            format!("CREATE {}INDEX{} {} ON {} ({})", unique, if_not_exists, format_name(name), format_name(table_name), columns.join(", "))
+        }
+        Statement::CreateFunction { or_replace, name, args, body, language, .. } | Statement::CreateProcedure { or_replace, name, args, body, language } => {
+            let args: Vec<String> = args.iter().map(format_function_arg).collect();
+            let or_replace = if *or_replace { "OR REPLACE " } else { "" };
+            let mut clauses = vec![match stmt {
+                Statement::CreateFunction { returns, .. } => {
+                    format!("CREATE {}FUNCTION {}({}) RETURNS {}", or_replace, format_name(name), args.join(", "), format_type(returns))
+                }
+                _ => format!("CREATE {}PROCEDURE {}({})", or_replace, format_name(name), args.join(", ")),
+            }];
+            if let Some(language) = language {
+                clauses.push(format!("LANGUAGE {}", format_name(language)));
+            }
+            clauses.push(format!("AS {}", format_string(body)));
+            clauses.join(separator)
+        }
+        Statement::CreateTrigger { name, timing, event, table_name, body } => {
+            let clauses = [
+                format!("CREATE TRIGGER {} {} {} ON {}", format_name(name), timing, event, format_name(table_name)),
+                "FOR EACH ROW".to_string(),
+                format_tokens(body),
+            ];
+            clauses.join(separator)
+        }
+        Statement::Drop { object_type, names, if_exists } => {
+            let names: Vec<String> = names.iter().map(|name| format_name(name)).collect();
+            let if_exists = if *if_exists { " IF EXISTS" } else { "" };
+            format!("DROP {}{} {}", object_type, if_exists, names.join(", "))
+        }
+    }
+}
+
+//format several statements separated by a blank line
+pub fn format_statements(stmts: &[Statement]) -> String {
+    format_statements_with(stmts, Layout::Multiline)
+}
+
+//format several statements, multiline ones separated by a blank line, single line ones by a line break
+pub fn format_statements_with(stmts: &[Statement], layout: Layout) -> String {
+    let separator = if layout == Layout::Multiline { "\n\n" } else { "\n" };
+    stmts.iter().map(|stmt| format_statement_with(stmt, layout)).collect::<Vec<_>>().join(separator)
+}
+
+/// Formats a script like `format_statements_with` without losing its comments. `tokens` are the tokens of the script with its comments kept (`Tokenizer::keep_comments(true)`); every comment goes with a statement or a clause as `policy` says, and is printed as written on a line of its own before it or at the end of its line (a `--` comment with more after it on its line as `/* */`). Comments after the last statement go with the last statement. Hints (`Tokenizer::keep_hints(true)`) right before or after a `SELECT` are printed with it, other hints like comments.
+///
+/// ```
+/// //SELECT a -- the key
+/// //FROM t;
+/// //with CommentPolicy { placement: Trailing, attachment: Clause }:
+/// //SELECT a -- the key
+/// //FROM t;
+/// //with CommentPolicy { placement: Leading, attachment: Statement }:
+/// //-- the key
+/// //SELECT a
+/// //FROM t;
+/// ```
+pub fn format_commented(tokens: Vec<Token>, dialect: Dialect, layout: Layout, policy: CommentPolicy) -> Result<String, ParseError> {
+    let stmts = Parser::with_dialect(tokens.clone(), dialect).parse_statements()?;
+    let attachment = if layout == Layout::SingleLine { CommentAttachment::Statement } else { policy.attachment };
+    //hints right before or after a SELECT are printed with their statement, the parser leaves the others out of the
+    //tree so they are kept as comments
+    let is_code = |tok: &&Token| !matches!(tok, Token::Hint(_) | Token::Comment { .. });
+    let selects = |tok: Option<&Token>| tok == Some(&Token::Keyword(Keyword::Select));
+    let tokens: Vec<Token> = tokens.iter().enumerate().filter_map(|(i, tok)| match tok {
+        Token::Hint(_) if selects(tokens[..i].iter().rev().find(is_code)) || selects(tokens[i + 1..].iter().find(is_code)) => None,
+        Token::Hint(text) => Some(Token::Comment { text: text.clone(), raw: format!("/*+ {} */", text) }),
+        other => Some(other.clone()),
+    }).collect();
+
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut pending = Vec::new();
+    let mut statement = 0;
+    let mut starts_statement = true;
+    let mut depth = 0;
+    let mut clauses = Vec::new();
+    let mut ends = StatementEnds::default();
+    for (i, tok) in tokens.iter().enumerate() {
+        if let Token::Comment { raw, .. } = tok {
+            pending.push(raw.clone());
+            continue;
+        }
+        let next = tokens[i + 1..].iter().find(|tok| !matches!(tok, Token::Comment { .. }));
+        let clause = match tok {
+            Token::Keyword(keyword) if depth == 0 && attachment == CommentAttachment::Clause && starts_clause(*keyword, next) => Some(*keyword),
+            _ => None,
+        };
+        if starts_statement || clause.is_some() {
+            if starts_statement {
+                clauses.clear();
+            }
+            let clause = clause.map(|keyword| {
+                let n = clauses.iter().filter(|seen| **seen == keyword).count();
+                clauses.push(keyword);
+                (keyword, n)
+            });
+            let mut node = Node { statement, clause, before: Vec::new(), after: Vec::new() };
+            match (policy.placement, nodes.last_mut()) {
+                (CommentPlacement::Trailing, Some(previous)) => previous.after.append(&mut pending),
+                _ => node.before.append(&mut pending),
+            }
+            nodes.push(node);
+            starts_statement = false;
+        } else if let Some(current) = nodes.last_mut() {
+            match policy.placement {
+                CommentPlacement::Leading => current.before.append(&mut pending),
+                CommentPlacement::Trailing => current.after.append(&mut pending),
+            }
+        }
+        match tok {
+            Token::LeftParentheses => depth += 1,
+            Token::RightParentheses => depth -= 1,
+            _ => {}
+        }
+        if ends.ends_statement(tok, next.unwrap_or(&Token::Eof)) {
+            statement += 1;
+            starts_statement = true;
+            depth = 0;
+        }
+    }
+    //a script of nothing but comments keeps them as they are
+    let Some(last) = nodes.last_mut() else {
+        return Ok(pending.join("\n"));
+    };
+    last.after.append(&mut pending);
+
+    let mut texts = Vec::new();
+    for (i, stmt) in stmts.iter().enumerate() {
+        let formatted = for_dialect(&format_statement_with(stmt, layout), dialect);
+        let lines: Vec<&str> = formatted.lines().collect();
+        let mut before = vec![Vec::new(); lines.len()];
+        let mut after = vec![Vec::new(); lines.len()];
+        //a clause that is not at the start of a line (the FROM of DELETE FROM) shares the line of the clause before it
+        let mut line = 0;
+        for node in nodes.iter_mut().filter(|node| node.statement == i) {
+            let (first, last) = match node.clause {
+                Some((keyword, n)) => {
+                    let starts = |text: &&str| text.strip_prefix(keyword.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with(' '));
+                    line = lines.iter().enumerate().filter(|(_, text)| starts(text)).nth(n).map_or(line, |(found, _)| found);
+                    (line, line)
+                }
+                None if attachment == CommentAttachment::Statement => (0, lines.len() - 1),
+                None => (0, 0),
+            };
+            before[first].append(&mut node.before);
+            after[last].append(&mut node.after);
+        }
+        let mut text = Vec::new();
+        for (j, line) in lines.iter().enumerate() {
+            text.extend(before[j].iter().cloned());
+            let mut line = line.to_string();
+            for (k, comment) in after[j].iter().enumerate() {
+                //only the last comment of a line can run to its end
+                line.push(' ');
+                line.push_str(&if k + 1 < after[j].len() { inline_comment(comment) } else { comment.clone() });
+            }
+            text.push(line);
+        }
+        texts.push(text.join("\n"));
+    }
+    let separator = if layout == Layout::Multiline { "\n\n" } else { "\n" };
+    Ok(texts.join(separator))
+}
+
+/// Formats only the statements of a script that have a line in one of the `changed` ranges (line numbers from 1, both ends included), for format-on-save in big files: every other byte of `sql` is kept as it is, the statements no range touches and whatever is between statements (comments before a statement included). A statement that is formatted takes the place of its text from its first token to its semicolon; it is formatted with its comments like `format_commented` does, hints kept, and its keywords recased like `recase_keywords`. Only those statements have to parse.
+///
+/// ```
+/// //select 1;            (line 1, not changed)
+/// //select a from t;     (line 2, changed)
+/// //becomes:
+/// //select 1;
+/// //SELECT a
+/// //FROM t;
+/// ```
+pub fn format_changed(sql: &str, changed: &[(usize, usize)], dialect: Dialect, layout: Layout, policy: CommentPolicy, case: KeywordCase) -> Result<String, ParseError> {
+    let mut text = String::new();
+    let mut written = 0;
+    for (first, last) in statement_bounds(sql, dialect) {
+        if !changed.iter().any(|&(from, to)| from <= last.line && first.line <= to) {
+            continue;
+        }
+        let statement = &sql[first.start..last.end];
+        let located = |err: String| format!("{} in the statement at line {}, column {}", err, first.line, first.column);
+        let tokens = Tokenizer::with_dialect(statement, dialect).keep_hints(true).keep_comments(true).tokenize().map_err(|err| located(err.to_string()))?;
+        let formatted = format_commented(tokens, dialect, layout, policy).map_err(located)?;
+        text.push_str(&sql[written..first.start]);
+        text.push_str(&recase_keywords(&formatted, statement.as_bytes(), dialect, case));
+        written = last.end;
+    }
+    text.push_str(&sql[written..]);
+    Ok(text)
+}
+
+//helper, the first and the last token of every statement of a script, the semicolon that ends it included; the
+//semicolons inside the BEGIN ... END body of a trigger do not end it. Input that does not form a token stays part of the
+//statement it is in, and empty statements (`;;`) are skipped
+fn statement_bounds(sql: &str, dialect: Dialect) -> Vec<(Span, Span)> {
+    let tokens: Vec<(Token, Span)> = Tokenizer::with_dialect(sql, dialect)
+        .keep_hints(true)
+        .spanned()
+        .map(|result| match result {
+            Ok(spanned) => (spanned.token, spanned.span),
+            Err(err) => (Token::Invalid(' '), err.span),
+        })
+        .collect();
+    let mut bounds = Vec::new();
+    let mut current: Option<(Span, Span)> = None;
+    let mut ends = StatementEnds::default();
+    for (i, (tok, span)) in tokens.iter().enumerate() {
+        if ends.ends_statement(tok, tokens.get(i + 1).map_or(&Token::Eof, |(next, _)| next)) {
+            if let Some((first, _)) = current.take() {
+                bounds.push((first, *span));
+            }
+            continue;
+        }
+        match &mut current {
+            Some((_, last)) => *last = *span,
+            None => current = Some((*span, *span)),
+        }
+    }
+    bounds.extend(current);
+    bounds
+}
+
+/// Writes the keywords of formatted SQL, the text of `format_commented` or `for_dialect`, in the given case. `source` is the SQL it was formatted from, and both are read in `dialect`; with `KeywordCase::Preserve` each keyword of the text takes the spelling of the same keyword in the same statement of the source, found in order, and a keyword the formatter adds (the `AS` of an alias, the `TABLE` of `TRUNCATE`) the case most keywords of the source are written in. Strings, names and comments are left as they are.
+///
+/// ```rust
+/// //select a from t where b = 1 order by a;
+/// //formatted with KeywordCase::Preserve:
+/// //select a
+/// //from t
+/// //where b = 1
+/// //order by a;
+/// ```
+pub fn recase_keywords(formatted: &str, source: &[u8], dialect: Dialect, case: KeywordCase) -> String {
+    //the keywords of the source with their spelling, and the semicolons that end its statements
+    let mut spelled: Vec<(Token, String)> = Vec::new();
+    if case == KeywordCase::Preserve {
+        for tok in Tokenizer::from_bytes(source, dialect).spanned().filter_map(Result::ok) {
+            if matches!(tok.token, Token::Keyword(_) | Token::Semicolon) {
+                spelled.push((tok.token, String::from_utf8_lossy(&source[tok.span.start..tok.span.end]).into_owned()));
+            }
+        }
+    }
+    let lower = spelled.iter().filter(|(tok, text)| tok != &Token::Semicolon && !text.chars().any(|c| c.is_ascii_uppercase())).count() * 2
+        > spelled.iter().filter(|(tok, _)| tok != &Token::Semicolon).count();
+
+    let mut text = String::new();
+    let mut written = 0;
+    let mut next = 0;
+    for tok in Tokenizer::with_dialect(formatted, dialect).spanned().filter_map(Result::ok) {
+        let keyword = match tok.token {
+            Token::Keyword(keyword) => keyword,
+            //the statement ends in the source as well
+            Token::Semicolon => {
+                next = spelled[next..].iter().position(|(tok, _)| tok == &Token::Semicolon).map_or(spelled.len(), |found| next + found + 1);
+                continue;
+            }
+            _ => continue,
+        };
+        text.push_str(&formatted[written..tok.span.start]);
+        let statement = spelled[next..].iter().position(|(tok, _)| tok == &Token::Semicolon).map_or(spelled.len(), |end| next + end);
+        let found = spelled[next..statement].iter().position(|(tok, _)| tok == &Token::Keyword(keyword));
+        match found {
+            Some(found) => {
+                text.push_str(&spelled[next + found].1);
+                next += found + 1;
+            }
+            None if case == KeywordCase::Lower || lower => text.push_str(&keyword.as_str().to_lowercase()),
+            None => text.push_str(keyword.as_str()),
+        }
+        written = tok.span.end;
+    }
+    text.push_str(&formatted[written..]);
+    text
+}
+
+/// Writes formatted SQL, the text of `format_statements`, so it reads back in `dialect`: its strings are escaped as `escape_strings` does, and its quoted names are quoted with the identifier quotes of the dialect (`format_name_in`), `"order"` rather than `` `order` `` in Ansi, Postgres and Snowflake, which read backticks as nothing at all. `format_commented` and `format_changed` do this already.
+pub fn for_dialect(formatted: &str, dialect: Dialect) -> String {
+    let escaped = escape_strings(formatted, dialect);
+    if dialect.identifier_quote() == Dialect::default().identifier_quote() {
+        return escaped;
+    }
+    let mut text = String::new();
+    let mut written = 0;
+    for tok in Tokenizer::with_dialect(&escaped, Dialect::default()).spanned().filter_map(Result::ok) {
+        if let Token::Identifier(name) = &tok.token {
+            if escaped[tok.span.start..].starts_with(Dialect::default().identifier_quote()) {
+                text.push_str(&escaped[written..tok.span.start]);
+                text.push_str(&format_name_in(name, dialect));
+                written = tok.span.end;
+            }
+        }
+    }
+    text.push_str(&escaped[written..]);
+    text
+}
+
+/// Writes the string literals of formatted SQL, the text of `format_statements`, so they read back in `dialect`. The formatter writes a string with its characters as they are, which is how every dialect without backslash escapes reads it; in one with them (MySQL) a backslash is doubled, so `'c\\'` stays a `c` and a backslash rather than losing its closing quote. `for_dialect` does this along with the quotes of names.
+pub fn escape_strings(formatted: &str, dialect: Dialect) -> String {
+    if !dialect.backslash_escapes() {
+        return formatted.to_string();
+    }
+    let mut text = String::new();
+    let mut written = 0;
+    for tok in Tokenizer::with_dialect(formatted, Dialect::default()).spanned().filter_map(Result::ok) {
+        if matches!(tok.token, Token::String { .. } | Token::NationalString { .. }) {
+            text.push_str(&formatted[written..tok.span.start]);
+            text.push_str(&formatted[tok.span.start..tok.span.end].replace('\\', "\\\\"));
+            written = tok.span.end;
+        }
+    }
+    text.push_str(&formatted[written..]);
+    text
+}
+
+/// Builds the `SourceMap` of formatted SQL, the text of `format_commented` with its keywords recased or not, against `source`, the SQL it was formatted from, both read in `dialect`. Statements are paired in order, and in each one the longest sequence of tokens the two have in common is mapped, so a token the formatter adds is not taken for a later one of the source. Comments are not mapped.
+pub fn source_map(formatted: &str, source: &[u8], dialect: Dialect) -> SourceMap {
+    let formatted = statement_tokens(Tokenizer::with_dialect(formatted, dialect));
+    let source = statement_tokens(Tokenizer::from_bytes(source, dialect));
+    let mut mappings = Vec::new();
+    for (written, read) in formatted.iter().zip(&source) {
+        for (i, j) in common_tokens(written, read) {
+            mappings.push((written[i].span, read[j].span));
+        }
+    }
+    SourceMap { mappings }
+}
+
+//helper, the tokens of a script (hints included) grouped by statement, each with its semicolon
+fn statement_tokens(tokenizer: Tokenizer) -> Vec<Vec<SpannedToken>> {
+    let tokens: Vec<SpannedToken> = tokenizer.keep_hints(true).spanned().filter_map(Result::ok).collect();
+    let mut statements = vec![Vec::new()];
+    let mut ends = StatementEnds::default();
+    for (i, tok) in tokens.iter().enumerate() {
+        statements.last_mut().unwrap().push(tok.clone());
+        if ends.ends_statement(&tok.token, tokens.get(i + 1).map_or(&Token::Eof, |next| &next.token)) {
+            statements.push(Vec::new());
+        }
+    }
+    statements
+}
+
+//helper, the positions of the tokens of the longest sequence `a` and `b` have in common, in order; strings are the same
+//whatever quotes they are written with
+fn common_tokens(a: &[SpannedToken], b: &[SpannedToken]) -> Vec<(usize, usize)> {
+    common_sequence(a.len(), b.len(), |i, j| match (&a[i].token, &b[j].token) {
+        (Token::String { value: x, .. }, Token::String { value: y, .. }) => x == y,
+        (x, y) => x == y,
+    })
+}
+
+//helper, whether a keyword at the top level of a statement starts a clause of its own line, `next` is the token after it
+fn starts_clause(keyword: Keyword, next: Option<&Token>) -> bool {
+    match keyword {
+        Keyword::With | Keyword::Select | Keyword::From | Keyword::Where | Keyword::Group | Keyword::Having
+        | Keyword::Qualify | Keyword::Order | Keyword::Limit | Keyword::Values | Keyword::Union | Keyword::Intersect | Keyword::Except => true,
+        Keyword::On => next == Some(&Token::Keyword(Keyword::Conflict)),
+        _ => false,
+    }
+}
+
+/// Writes tokens the parser keeps unparsed (the body of a trigger, a clause skipped in permissive mode) on one line, spaced so they read back as the same tokens.
+pub fn format_tokens(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for (i, tok) in tokens.iter().enumerate() {
+        let joined = i == 0
+            || matches!(tok, Token::Comma | Token::Period | Token::Semicolon | Token::RightParentheses)
+            || matches!(tokens[i - 1], Token::LeftParentheses | Token::Period);
+        if !joined {
+            text.push(' ');
+        }
+        match tok {
+            Token::Keyword(keyword) => text.push_str(keyword.as_str()),
+            Token::Identifier(name) => text.push_str(&format_name(name)),
+            Token::String { raw, .. } | Token::NationalString { raw, .. } | Token::EscapeString { raw, .. }
+            | Token::BitString { raw, .. } | Token::HexString { raw, .. } => text.push_str(raw),
+            Token::Number(n) => text.push_str(&n.to_string()),
+            Token::Decimal(digits) => text.push_str(digits),
+            Token::Comment { raw, .. } => text.push_str(&inline_comment(raw)),
+            Token::LeftParentheses => text.push('('),
+            Token::RightParentheses => text.push(')'),
+            other => text.push_str(&other.to_string()),
+        }
+    }
+    text
+}
+
+//helper, a kept comment as written, a line comment turned into a block comment so that code can follow it on its line
+fn inline_comment(raw: &str) -> String {
+    match raw.strip_prefix("--") {
+        Some(text) => format!("/* {} */", text.trim()),
+        None => raw.to_string(),
+    }
+}
+
+//turn an expression back into SQL text
+//nested operations are always wrapped in parentheses, so the output never depends on operator precedence
+pub fn format_expression(expr: &Expression) -> String {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            format!("{} {} {}", format_operand(left_operand), operator, format_operand(right_operand))
+        }
+        Expression::UnaryOperation { operand, operator } => match operator {
+            UnaryOperator::Asc | UnaryOperator::Desc => format!("{} {}", format_expression(operand), operator),
+            UnaryOperator::IsNull | UnaryOperator::IsNotNull => format!("{} {}", format_operand(operand), operator),
+            //NOT applied to EXISTS keeps its parentheses, otherwise it would read back as NOT EXISTS
+            UnaryOperator::Not if matches!(**operand, Expression::Exists { .. }) => format!("NOT ({})", format_expression(operand)),
+            UnaryOperator::Not => format!("NOT {}", format_operand(operand)),
+            //a minus in front of a number would read back as a negative literal, so the number gets parentheses
+            UnaryOperator::Minus if matches!(**operand, Expression::Literal(Literal::Integer(_) | Literal::Decimal(_))) => format!("-({})", format_expression(operand)),
+            UnaryOperator::Minus | UnaryOperator::Plus => format!("{}{}", operator, format_operand(operand)),
+        },
+        Expression::IsDistinctFrom { left_operand, right_operand, negated } => {
+            let not = if *negated { " NOT" } else { "" };
+            format!("{} IS{} DISTINCT FROM {}", format_operand(left_operand), not, format_operand(right_operand))
+        }
+        Expression::Exists { subquery, negated } => {
+            let not = if *negated { "NOT " } else { "" };
+            format!("{}EXISTS ({})", not, format_body(subquery, " "))
+        }
+        Expression::Subquery(subquery) => format!("({})", format_body(subquery, " ")),
+        Expression::Function { name, args, over: None } => format!("{}({})", format_name(name), format_list(args)),
+        Expression::Function { name, args, over: Some(window) } => format!("{}({}) OVER ({})", format_name(name), format_list(args), format_window(window)),
+        Expression::Alias { expr, alias } => format!("{} AS {}", format_expression(expr), format_name(alias)),
+        Expression::Wildcard => "*".to_string(),
+        Expression::Literal(literal) => format_literal(literal),
+        Expression::Placeholder(text) => text.clone(),
+        Expression::Identifier(name) => format_name(name),
+        Expression::CompoundIdentifier(parts) => parts.iter().map(|part| format_name(part)).collect::<Vec<_>>().join("."),
+    }
+}
+
+fn format_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Integer(n) => n.to_string(),
+        Literal::Decimal(text) => text.clone(),
+        Literal::String(s) => format_string(s),
+        Literal::NationalString(s) => format!("N{}", format_string(s)),
+        Literal::BitString(bits) => format!("B'{}'", bits),
+        Literal::HexString(hex) => format!("X'{}'", hex),
+        Literal::Boolean(true) => "TRUE".to_string(),
+        Literal::Boolean(false) => "FALSE".to_string(),
+        Literal::Null => "NULL".to_string(),
+    }
+}
+
+//helper, operands that are operations themselves get parentheses, and so do negative numbers
+fn format_operand(expr: &Expression) -> String {
+    match expr {
+        Expression::BinaryOperation { .. } | Expression::UnaryOperation { .. } | Expression::IsDistinctFrom { .. } => {
+            format!("({})", format_expression(expr))
+        }
+        Expression::Literal(literal) if literal.is_negative() => format!("({})", format_literal(literal)),
+        other => format_expression(other),
+    }
+}
+
+//helper, a name as it can be read back: quoted with backticks (the generic dialect's identifier quotes)
+//when it is a keyword or contains anything but letters, digits and underscores
+pub fn format_name(name: &str) -> String {
+    format_name_in(name, Dialect::default())
+}
+
+//helper, `format_name` for a dialect, quoted with its identifier quotes (`"order"` in standard SQL) and the quote
+//doubled inside the name
+pub fn format_name_in(name: &str, dialect: Dialect) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain && Keyword::lookup(name).is_none() {
+        name.to_string()
+    } else {
+        let quote = dialect.identifier_quote();
+        format!("{}{}{}", quote, name.replace(quote, &quote.to_string().repeat(2)), quote)
+    }
+}
+
+//helper, ` (a, b)` after a table name, nothing for no columns
+fn format_column_names(columns: &[String]) -> String {
+    if columns.is_empty() {
+        return String::new();
+    }
+    let columns: Vec<String> = columns.iter().map(|name| format_name(name)).collect();
+    format!(" ({})", columns.join(", "))
+}
+
+//helper, a COPY option with its value bare when it reads back as the same word or number, quoted otherwise
+fn format_copy_option(option: &CopyOption) -> String {
+    match &option.value {
+        None => option.name.clone(),
+        Some(value) if format_name(value) == *value || value.parse::<u64>().is_ok_and(|n| n.to_string() == *value) => format!("{} {}", option.name, value),
+        Some(value) => format!("{} {}", option.name, format_string(value)),
+    }
+}
+
+//helper, single quoted string with embedded quotes doubled (no backslash escapes, so it reads back the same in every dialect that has them off, `escape_strings` writes it for the others)
+fn format_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+//the WITH clause on a single line, its queries included
+fn format_with(with: &With) -> String {
+    let ctes: Vec<String> = with.ctes.iter().map(|cte| {
+        let columns = if cte.columns.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", cte.columns.iter().map(|column| format_name(column)).collect::<Vec<_>>().join(", "))
+        };
+        format!("{}{} AS ({})", format_name(&cte.name), columns, format_body(&cte.query, " "))
+    }).collect();
+    format!("WITH {}{}", if with.recursive { "RECURSIVE " } else { "" }, ctes.join(", "))
+}
+
+//helper, one side of a set operation, in parentheses when it has a WITH, ORDER BY or LIMIT clause of its own that would
+//otherwise belong to the whole, or when it would bind differently without them
+fn format_set_operand(operand: &Statement, operator: SetOperator, right: bool, separator: &str) -> String {
+    let parenthesized = match operand {
+        Statement::Select { with, orderby, limit, .. } => with.is_some() || !orderby.is_empty() || limit.is_some(),
+        Statement::SetOperation { with, operator: inner, orderby, limit, .. } => {
+            with.is_some() || !orderby.is_empty() || limit.is_some() || inner.precedence() < operator.precedence() || (right && inner.precedence() == operator.precedence())
+        }
+        _ => false,
+    };
+    if parenthesized {
+        format!("({})", format_body(operand, " "))
+    } else {
+        format_body(operand, separator)
+    }
+}
+
+fn format_from(from: &[TableReference]) -> String {
+    from.iter().map(format_table).collect::<Vec<_>>().join(", ")
+}
+
+fn format_table(table: &TableReference) -> String {
+    let (sql, alias) = match table {
+        TableReference::Table { name, alias, sample } => {
+            let mut sql = match alias {
+                Some(alias) => format!("{} AS {}", format_name(name), format_name(alias)),
+                None => format_name(name),
+            };
+            if let Some(sample) = sample {
+                sql.push_str(&format!(" TABLESAMPLE {} ({})", sample.method, format_expression(&sample.percentage)));
+                if let Some(seed) = &sample.repeatable {
+                    sql.push_str(&format!(" REPEATABLE ({})", format_expression(seed)));
+                }
+            }
+            return sql;
+        }
+        TableReference::Derived { lateral, subquery, alias } => {
+            (format!("{}({})", if *lateral { "LATERAL " } else { "" }, format_body(subquery, " ")), alias)
+        }
+        TableReference::Function { lateral, name, args, alias } => {
+            (format!("{}{}({})", if *lateral { "LATERAL " } else { "" }, format_name(name), format_list(args)), alias)
+        }
+        TableReference::Pivot { table, pivot, alias } => {
+            let values = format_list(&pivot.values);
+            let sql = format!("{} PIVOT ({} FOR {} IN ({}))", format_pivot_input(table), format_expression(&pivot.aggregate), format_expression(&pivot.column), values);
+            (sql, alias)
+        }
+        TableReference::Unpivot { table, unpivot, alias } => {
+            let columns: Vec<String> = unpivot.columns.iter().map(|column| format_name(column)).collect();
+            let sql = format!("{} UNPIVOT ({} FOR {} IN ({}))", format_pivot_input(table), format_name(&unpivot.value_column), format_name(&unpivot.name_column), columns.join(", "));
+            (sql, alias)
+        }
+        TableReference::Join { left, right, kind, on } => {
+            //a join on the right hand side needs parentheses, joins read back left to right
+            let right = match **right {
+                TableReference::Join { .. } => format!("({})", format_table(right)),
+                _ => format_table(right),
+            };
+            let mut sql = format!("{} {} {}", format_table(left), kind, right);
+            if let Some(on) = on {
+                sql.push_str(&format!(" ON {}", format_expression(on)));
+            }
+            return sql;
+        }
+    };
+    match alias {
+        Some(alias) => format!("{} AS {}", sql, format_name(alias)),
+        None => sql,
+    }
+}
+
+//helper, a join under PIVOT/UNPIVOT needs parentheses, otherwise the operator would only apply to its right side
+fn format_pivot_input(table: &TableReference) -> String {
+    match table {
+        TableReference::Join { .. } => format!("({})", format_table(table)),
+        _ => format_table(table),
+    }
+}
+
+fn format_grouping_element(element: &GroupingElement) -> String {
+    let (name, sets) = match element {
+        GroupingElement::Expression(expr) => return format_expression(expr),
+        GroupingElement::Rollup(sets) => ("ROLLUP", sets),
+        GroupingElement::Cube(sets) => ("CUBE", sets),
+        GroupingElement::GroupingSets(sets) => ("GROUPING SETS", sets),
+    };
+    //single expression sets are written without their parentheses
+    let sets: Vec<String> = sets.iter().map(|set| match set.as_slice() {
+        [expr] => format_expression(expr),
+        set => format!("({})", format_list(set)),
+    }).collect();
+    format!("{} ({})", name, sets.join(", "))
+}
+
+//all hints of a statement in one comment, arguments separated by spaces
+fn format_hints(hints: &[Hint]) -> String {
+    let hints: Vec<String> = hints.iter().map(|hint| match hint.args.is_empty() {
+        true => hint.name.clone(),
+        false => format!("{}({})", hint.name, hint.args.join(" ")),
+    }).collect();
+    format!("/*+ {} */", hints.join(" "))
+}
+
+fn format_window(window: &WindowSpec) -> String {
+    let mut parts = Vec::new();
+    if !window.partition_by.is_empty() {
+        parts.push(format!("PARTITION BY {}", format_list(&window.partition_by)));
+    }
+    if !window.order_by.is_empty() {
+        parts.push(format!("ORDER BY {}", format_list(&window.order_by)));
+    }
+    parts.join(" ")
+}
+
+//ON CONFLICT clause of an INSERT, the WHERE of DO UPDATE on a line of its own like other clauses
+//helper, whether the text of a query starts with VALUES, its own or that of the leftmost query it combines
+fn starts_with_values(query: &Statement) -> bool {
+    match query {
+        Statement::Values { .. } => true,
+        Statement::SetOperation { with: None, left, .. } => starts_with_values(left),
+        _ => false,
+    }
+}
+
+fn format_on_conflict(on_conflict: &OnConflict, separator: &str) -> String {
+    let mut text = "ON CONFLICT".to_string();
+    if !on_conflict.target.is_empty() {
+        let names: Vec<String> = on_conflict.target.iter().map(|name| format_name(name)).collect();
+        text.push_str(&format!(" ({})", names.join(", ")));
+    }
+    match &on_conflict.action {
+        ConflictAction::Nothing => text.push_str(" DO NOTHING"),
+        ConflictAction::Update { assignments, where_clause } => {
+            let assignments: Vec<String> = assignments.iter().map(|assignment| format!("{} = {}", format_name(&assignment.column), format_expression(&assignment.value))).collect();
+            text.push_str(&format!(" DO UPDATE SET {}", assignments.join(", ")));
+            if let Some(expr) = where_clause {
+                text.push_str(&format!("{}WHERE {}", separator, format_expression(expr)));
+            }
+        }
+    }
+    text
+}
+
+//one WHEN arm of a MERGE on a single line
+fn format_merge_clause(clause: &MergeClause) -> String {
+    let mut text = if clause.matched { "WHEN MATCHED".to_string() } else { "WHEN NOT MATCHED".to_string() };
+    if let Some(condition) = &clause.condition {
+        text.push_str(&format!(" AND {}", format_expression(condition)));
+    }
+    let action = match &clause.action {
+        MergeAction::Update { assignments } => {
+            let assignments: Vec<String> = assignments.iter().map(|assignment| format!("{} = {}", format_name(&assignment.column), format_expression(&assignment.value))).collect();
+            format!("UPDATE SET {}", assignments.join(", "))
+        }
+        MergeAction::Delete => "DELETE".to_string(),
+        MergeAction::Insert { columns, values } => {
+            let columns = columns.as_deref().map_or(String::new(), format_column_names);
+            format!("INSERT{} VALUES ({})", columns, format_list(values))
+        }
+        MergeAction::Nothing => "DO NOTHING".to_string(),
+    };
+    format!("{} THEN {}", text, action)
+}
+
+fn format_list(exprs: &[Expression]) -> String {
+    exprs.iter().map(format_expression).collect::<Vec<_>>().join(", ")
+}
+
+fn format_function_arg(arg: &FunctionArg) -> String {
+    match &arg.name {
+        Some(name) => format!("{} {}", format_name(name), format_type(&arg.data_type)),
+        None => format_type(&arg.data_type),
+    }
+}
+
+fn format_column(col: &TableColumn) -> String {
+    let mut sql = format!("{} {}", format_name(&col.column_name), format_type(&col.column_type));
+    for constraint in &col.constraints {
+        match constraint {
+            Constraint::NotNull => sql.push_str(" NOT NULL"),
+            Constraint::PrimaryKey => sql.push_str(" PRIMARY KEY"),
+            Constraint::Check(expr) => sql.push_str(&format!(" CHECK({})", format_expression(expr))),
+        }
+    }
+    sql
+}
+
+pub fn format_type(ty: &DBType) -> String {
+    match ty {
+        DBType::Int => "INT".to_string(),
+        DBType::Bool => "BOOL".to_string(),
+        DBType::Varchar(n) => format!("VARCHAR({})", n),
+        DBType::Decimal(precision, scale) => format!("DECIMAL({}, {})", precision, scale),
+        DBType::Double => "DOUBLE PRECISION".to_string(),
+        DBType::Char(n) => format!("CHAR({})", n),
+        DBType::Timestamp { with_time_zone: false } => "TIMESTAMP".to_string(),
+        DBType::Timestamp { with_time_zone: true } => "TIMESTAMP WITH TIME ZONE".to_string(),
+        DBType::Custom(name, args) if args.is_empty() => format_name(name),
+        DBType::Custom(name, args) => {
+            let args: Vec<String> = args.iter().map(format_expression).collect();
+            format!("{}({})", format_name(name), args.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(sql: &str, placement: CommentPlacement) -> String {
+        let tokens = Tokenizer::new(sql).keep_hints(true).keep_comments(true).tokenize().unwrap();
+        format_commented(tokens, Dialect::default(), Layout::Multiline, CommentPolicy { placement, attachment: CommentAttachment::Clause }).unwrap()
+    }
+
+    #[test]
+    fn hints_stay_after_select() {
+        assert_eq!(format("SELECT /*+ INDEX(t idx) */ a FROM t;", CommentPlacement::Leading), "SELECT /*+ INDEX(t idx) */ a\nFROM t;");
+        assert_eq!(format("SELECT a FROM /*+ x */ t;", CommentPlacement::Trailing), "SELECT a\nFROM t; /*+ x */");
+    }
+
+    #[test]
+    fn comments_keep_their_delimiters() {
+        assert_eq!(format("SELECT a /* key */ FROM t; -- end", CommentPlacement::Trailing), "SELECT a /* key */\nFROM t; -- end");
+        assert_eq!(format("-- first\nSELECT a -- key\n/* more */ FROM t;", CommentPlacement::Trailing), "-- first\nSELECT a /* key */ /* more */\nFROM t;");
+    }
+}
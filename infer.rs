@@ -0,0 +1,252 @@
+//schema inference for data dumps that come without their CREATE TABLE statements
+use crate::token::{Token, Keyword};
+use crate::tokenizer::StatementEnds;
+use crate::statement::{Statement, TableColumn, DBType, Constraint};
+
+//type of a column without any literal value to go by
+const DEFAULT_TYPE: DBType = DBType::Varchar(255);
+
+//what the values seen in a column have in common so far, widened as more values come in
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Unknown,
+    Bool,
+    //digits of the widest integer
+    Int(usize),
+    //digits before and after the decimal point
+    Decimal(usize, usize),
+    Text,
+}
+
+struct Column {
+    name: String,
+    kind: Kind,
+    //length of the longest value as written, for VARCHAR
+    max_len: usize,
+    nullable: bool,
+}
+
+struct Table {
+    name: String,
+    columns: Vec<Column>,
+}
+
+//one `INSERT INTO name [(columns)] VALUES (...), ...` of a script, every value a slice of tokens
+struct Insert<'a> {
+    table: String,
+    columns: Option<Vec<String>>,
+    rows: Vec<Vec<&'a [Token]>>,
+}
+
+//plausible CREATE TABLE statements for the tables a script inserts into, in order of their first INSERT
+//columns come from the INSERT column lists (column1, column2, ... when there is none), types from the literal values:
+//INT, DECIMAL(p, s) wide enough for every number, BOOL, and VARCHAR of the longest value when the values are mixed
+//or strings, a column that never holds NULL and is given in every INSERT is NOT NULL
+//statements other than INSERT are skipped, so the tokens of a whole dump can be passed in, and so are INSERTs of the
+//rows of a query, which have no values to look at
+pub fn infer_tables(tokens: &[Token]) -> Result<Vec<Statement>, String> {
+    let mut tables: Vec<Table> = Vec::new();
+    let mut ends = StatementEnds::default();
+    let mut i = 0;
+    let statements = tokens.split(|tok| {
+        i += 1;
+        ends.ends_statement(tok, tokens.get(i).unwrap_or(&Token::Eof))
+    });
+    for stmt in statements {
+        let stmt = stmt.strip_suffix(&[Token::Eof]).unwrap_or(stmt);
+        if stmt.first() != Some(&Token::Keyword(Keyword::Insert)) {
+            continue;
+        }
+        let Some(insert) = scan_insert(stmt)? else {
+            continue;
+        };
+        let index = match tables.iter().position(|table| table.name.eq_ignore_ascii_case(&insert.table)) {
+            Some(index) => index,
+            None => {
+                tables.push(Table { name: insert.table.clone(), columns: Vec::new() });
+                tables.len() - 1
+            }
+        };
+        add_rows(&mut tables[index], insert)?;
+    }
+    Ok(tables.into_iter().map(create_table).collect())
+}
+
+fn add_rows(table: &mut Table, insert: Insert) -> Result<(), String> {
+    let width = insert.columns.as_ref().map_or(insert.rows[0].len(), |columns| columns.len());
+    let names = insert.columns.unwrap_or_else(|| (1..=width).map(|i| format!("column{}", i)).collect());
+    //rows inserted before did not have the columns that are new here
+    let had_rows = !table.columns.is_empty();
+    let mut indexes = Vec::new();
+    for name in names {
+        match table.columns.iter().position(|column| column.name.eq_ignore_ascii_case(&name)) {
+            Some(index) => indexes.push(index),
+            None => {
+                table.columns.push(Column { name, kind: Kind::Unknown, max_len: 0, nullable: had_rows });
+                indexes.push(table.columns.len() - 1);
+            }
+        }
+    }
+    for (i, column) in table.columns.iter_mut().enumerate() {
+        if !indexes.contains(&i) {
+            column.nullable = true;
+        }
+    }
+
+    for (row_no, row) in insert.rows.iter().enumerate() {
+        if row.len() != width {
+            return Err(format!("INSERT into {}: row {} has {} values, expected {}", table.name, row_no + 1, row.len(), width));
+        }
+        for (value, &index) in row.iter().zip(&indexes) {
+            observe(&mut table.columns[index], value);
+        }
+    }
+    Ok(())
+}
+
+//widen a column for one value
+fn observe(column: &mut Column, value: &[Token]) {
+    let (kind, len) = match value {
+        [Token::Keyword(Keyword::Null)] => {
+            column.nullable = true;
+            return;
+        }
+        [Token::Keyword(Keyword::True)] => (Kind::Bool, 4),
+        [Token::Keyword(Keyword::False)] => (Kind::Bool, 5),
+        [Token::Number(n)] => (Kind::Int(n.to_string().len()), n.to_string().len()),
+        [Token::Minus, Token::Number(n)] => (Kind::Int(n.to_string().len()), n.to_string().len() + 1),
+        [Token::Decimal(text)] => (decimal_kind(text), text.len()),
+        [Token::Minus, Token::Decimal(text)] => (decimal_kind(text), text.len() + 1),
+        [Token::String { value, .. }] | [Token::NationalString { value, .. }] | [Token::EscapeString { value, .. }]
+        | [Token::BitString { value, .. }] | [Token::HexString { value, .. }] => (Kind::Text, value.chars().count()),
+        //function calls and other expressions say nothing about the type
+        _ => return,
+    };
+    column.kind = widen(column.kind, kind);
+    column.max_len = column.max_len.max(len);
+}
+
+fn widen(a: Kind, b: Kind) -> Kind {
+    match (a, b) {
+        (Kind::Unknown, kind) | (kind, Kind::Unknown) => kind,
+        (Kind::Bool, Kind::Bool) => Kind::Bool,
+        (Kind::Int(a), Kind::Int(b)) => Kind::Int(a.max(b)),
+        (Kind::Int(digits), Kind::Decimal(integer, scale)) | (Kind::Decimal(integer, scale), Kind::Int(digits)) => Kind::Decimal(integer.max(digits), scale),
+        (Kind::Decimal(a, a_scale), Kind::Decimal(b, b_scale)) => Kind::Decimal(a.max(b), a_scale.max(b_scale)),
+        _ => Kind::Text,
+    }
+}
+
+//digits before and after the point of a decimal literal, exponent applied: 1.5e-3 is 0.0015, DECIMAL(5, 4)
+fn decimal_kind(text: &str) -> Kind {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(pos) => (&text[..pos], text[pos + 1..].parse::<i64>().unwrap_or(0)),
+        None => (text, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let integer_digits = integer.trim_start_matches('0').len() as i64 + exponent;
+    let scale = fraction.len() as i64 - exponent;
+    Kind::Decimal(integer_digits.max(1) as usize, scale.max(0) as usize)
+}
+
+fn create_table(table: Table) -> Statement {
+    let column_list = table.columns.into_iter().map(|column| TableColumn {
+        column_type: match column.kind {
+            Kind::Unknown => DEFAULT_TYPE,
+            Kind::Bool => DBType::Bool,
+            Kind::Int(_) => DBType::Int,
+            Kind::Decimal(integer, scale) => DBType::Decimal(integer + scale, scale),
+            Kind::Text => DBType::Varchar(column.max_len.max(1)),
+        },
+        constraints: if column.nullable { vec![] } else { vec![Constraint::NotNull] },
+        column_name: column.name,
+    }).collect();
+    Statement::CreateTable { table_name: table.name, column_list, query: None, if_not_exists: false, temporary: false }
+}
+
+//read an INSERT statement off its tokens, without the parser: the values are kept as written, and a dump may
+//have schema qualified table names and statements the parser does not know
+fn scan_insert(tokens: &[Token]) -> Result<Option<Insert<'_>>, String> {
+    if tokens.get(1) != Some(&Token::Keyword(Keyword::Into)) {
+        return Err(format!("Expected INTO after INSERT, found {:?}", tokens.get(1).unwrap_or(&Token::Eof)));
+    }
+    let mut table = name(tokens.get(2), "table name")?;
+    let mut pos = 3;
+    //CREATE TABLE takes no schema, `shop.orders` gives the table orders
+    while tokens.get(pos) == Some(&Token::Period) {
+        table = name(tokens.get(pos + 1), "table name")?;
+        pos += 2;
+    }
+
+    //the rows of a query, in parentheses or not
+    let source = if tokens.get(pos) == Some(&Token::LeftParentheses) { tokens.get(pos + 1) } else { tokens.get(pos) };
+    if matches!(source, Some(Token::Keyword(Keyword::Select | Keyword::With))) {
+        return Ok(None);
+    }
+    let mut columns = None;
+    if tokens.get(pos) == Some(&Token::LeftParentheses) {
+        let (items, end) = parenthesized(tokens, pos)?;
+        let names = items.iter().map(|item| match item {
+            [tok] => name(Some(tok), "column name"),
+            _ => Err(format!("Expected column name in INSERT into {}", table)),
+        });
+        columns = Some(names.collect::<Result<Vec<_>, _>>()?);
+        pos = end;
+    }
+
+    if matches!(tokens.get(pos), Some(Token::Keyword(Keyword::Select | Keyword::With) | Token::LeftParentheses)) {
+        return Ok(None);
+    }
+    if tokens.get(pos) != Some(&Token::Keyword(Keyword::Values)) {
+        return Err(format!("Expected VALUES in INSERT into {}, found {:?}", table, tokens.get(pos).unwrap_or(&Token::Eof)));
+    }
+    pos += 1;
+    let mut rows = Vec::new();
+    loop {
+        if tokens.get(pos) != Some(&Token::LeftParentheses) {
+            return Err(format!("Expected ( to start a row of VALUES, found {:?}", tokens.get(pos).unwrap_or(&Token::Eof)));
+        }
+        let (row, end) = parenthesized(tokens, pos)?;
+        rows.push(row);
+        match tokens.get(end) {
+            Some(Token::Comma) => pos = end + 1,
+            //the ON CONFLICT of an upsert says nothing about the columns the rows do not
+            None | Some(Token::Keyword(Keyword::On)) => break,
+            Some(other) => return Err(format!("Expected , or ; after a row of VALUES, found {:?}", other)),
+        }
+    }
+    Ok(Some(Insert { table, columns, rows }))
+}
+
+//helper, the comma separated items of the list in parentheses opening at `start`, and the position after it
+fn parenthesized(tokens: &[Token], start: usize) -> Result<(Vec<&[Token]>, usize), String> {
+    let mut depth = 0;
+    let mut items = Vec::new();
+    let mut item_start = start + 1;
+    for (i, tok) in tokens.iter().enumerate().skip(start) {
+        match tok {
+            Token::LeftParentheses => depth += 1,
+            Token::RightParentheses => {
+                depth -= 1;
+                if depth == 0 {
+                    items.push(&tokens[item_start..i]);
+                    return Ok((items, i + 1));
+                }
+            }
+            Token::Comma if depth == 1 => {
+                items.push(&tokens[item_start..i]);
+                item_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    Err("Expected )".to_string())
+}
+
+fn name(tok: Option<&Token>, what: &str) -> Result<String, String> {
+    match tok {
+        Some(Token::Identifier(name)) => Ok(name.clone()),
+        Some(Token::Keyword(keyword)) if !keyword.is_reserved() => Ok(keyword.as_str().to_lowercase()),
+        other => Err(format!("Expected {}, found {:?}", what, other.unwrap_or(&Token::Eof))),
+    }
+}
@@ -0,0 +1,1615 @@
+use std::fmt::Write;
+use crate::statement::{
+    Statement,
+    InsertSource,
+    Expression,
+    Literal,
+    BinaryOperator,
+    UnaryOperator,
+    TableColumn,
+    TableReference,
+    TableSample,
+    PivotClause,
+    UnpivotClause,
+    JoinKind,
+    GroupingElement,
+    WindowSpec,
+    Hint,
+    DBType,
+    Constraint,
+    AlterAction,
+    TableRename,
+    IndexColumn,
+    ObjectType,
+    ShowObject,
+    OnConflict,
+    ConflictAction,
+    Assignment,
+    MergeClause,
+    MergeAction,
+    With,
+    Cte,
+    SetOperator,
+    TriggerTiming,
+    TriggerEvent,
+    FunctionArg,
+    CopyDirection,
+    CopyOption,
+    DuplicateHandling,
+    LoadFormat,
+};
+use crate::token::{Token, Keyword};
+use crate::parser::SkippedClause;
+use crate::tokenizer::{Span, Tokenizer};
+use crate::dialect::Dialect;
+
+/// The version of the JSON form of the syntax tree, written as `schema_version` in the documents of `to_document`. Within a version the form only grows: new statements, new variants and new fields can be added, but no field or variant is renamed or removed, and none changes what it holds. Anything else takes a new version. So a document loads with any release of its version that has every kind of node in it, a newer one included, since `FromJson` reads a missing field as empty and ignores fields it does not know.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A minimal JSON document model. It exists so that parsed ASTs can be written out as snapshots (and read back for comparison) without pulling in an external serialization crate. Numbers are kept as their literal text, which keeps `u64` values exact and makes comparison of snapshots a plain structural equality check.
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    //helper, builds an object with a single key, used for enum variants carrying data
+    pub fn tagged(tag: &str, value: JsonValue) -> JsonValue {
+        JsonValue::Object(vec![(tag.to_string(), value)])
+    }
+
+    //helper, builds an object from borrowed keys
+    pub fn object(fields: Vec<(&str, JsonValue)>) -> JsonValue {
+        JsonValue::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    //look up a key in an object, None for missing keys or non-objects
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    //serialize with two space indentation, used for snapshot files
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize) {
+        match self {
+            JsonValue::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    push_indent(out, indent + 1);
+                    item.write_pretty(out, indent + 1);
+                    if i + 1 < items.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+                push_indent(out, indent);
+                out.push(']');
+            }
+            JsonValue::Object(fields) if !fields.is_empty() => {
+                out.push_str("{\n");
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    push_indent(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_pretty(out, indent + 1);
+                    if i + 1 < fields.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+                push_indent(out, indent);
+                out.push('}');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    //parse a JSON document, the whole input must be consumed
+    pub fn parse(input: &str) -> Result<JsonValue, String> {
+        let mut reader = JsonReader { chars: input.chars().collect(), pos: 0 };
+        let value = reader.read_value()?;
+        reader.skip_whitespace();
+        if reader.pos < reader.chars.len() {
+            return Err(format!("Unexpected trailing character at offset {}", reader.pos));
+        }
+        Ok(value)
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+//helper, writes a quoted and escaped JSON string
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+//compact single line form
+impl std::fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{}", b),
+            JsonValue::Number(n) => write!(f, "{}", n),
+            JsonValue::String(s) => {
+                let mut out = String::new();
+                write_string(&mut out, s);
+                write!(f, "{}", out)
+            }
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    let mut out = String::new();
+                    write_string(&mut out, key);
+                    write!(f, "{}:{}", out, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+//recursive descent reader over the characters of a JSON document
+struct JsonReader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl JsonReader {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' at offset {}", expected, self.pos))
+        }
+    }
+
+    fn read_value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.read_object(),
+            Some('[') => self.read_array(),
+            Some('"') => Ok(JsonValue::String(self.read_string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => Ok(self.read_number()),
+            Some(_) => self.read_literal(),
+            None => Err("Unexpected end of JSON input".to_string()),
+        }
+    }
+
+    fn read_object(&mut self) -> Result<JsonValue, String> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.read_string()?;
+            self.expect(':')?;
+            let value = self.read_value()?;
+            fields.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                _ => return Err(format!("Expected ',' or '}}' at offset {}", self.pos)),
+            }
+        }
+    }
+
+    fn read_array(&mut self) -> Result<JsonValue, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.read_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(format!("Expected ',' or ']' at offset {}", self.pos)),
+            }
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        if self.peek() != Some('"') {
+            return Err(format!("Expected string at offset {}", self.pos));
+        }
+        self.pos += 1;
+        let mut content = String::new();
+        while let Some(ch) = self.peek() {
+            self.pos += 1;
+            match ch {
+                '"' => return Ok(content),
+                '\\' => {
+                    let escaped = self.peek().ok_or("Unterminated escape in JSON string")?;
+                    self.pos += 1;
+                    match escaped {
+                        'n' => content.push('\n'),
+                        'r' => content.push('\r'),
+                        't' => content.push('\t'),
+                        'b' => content.push('\u{8}'),
+                        'f' => content.push('\u{c}'),
+                        'u' => {
+                            let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                            let code = u32::from_str_radix(&hex, 16)
+                                .map_err(|_| format!("Invalid unicode escape at offset {}", self.pos))?;
+                            self.pos += 4;
+                            content.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        other => content.push(other),
+                    }
+                }
+                other => content.push(other),
+            }
+        }
+        Err("Unterminated JSON string".to_string())
+    }
+
+    fn read_number(&mut self) -> JsonValue {
+        let start = self.pos;
+        while let Some(ch) = self.peek() {
+            if ch.is_ascii_digit() || ch == '-' || ch == '+' || ch == '.' || ch == 'e' || ch == 'E' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        JsonValue::Number(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn read_literal(&mut self) -> Result<JsonValue, String> {
+        let rest: String = self.chars.iter().skip(self.pos).take(5).collect();
+        if rest.starts_with("null") {
+            self.pos += 4;
+            Ok(JsonValue::Null)
+        } else if rest.starts_with("true") {
+            self.pos += 4;
+            Ok(JsonValue::Bool(true))
+        } else if rest.starts_with("false") {
+            self.pos += 5;
+            Ok(JsonValue::Bool(false))
+        } else {
+            Err(format!("Unexpected character at offset {}", self.pos))
+        }
+    }
+}
+
+/// The statements of a script as a versioned document, `{"schema_version": 1, "statements": [...]}`, for programs that read the tree rather than the SQL. Each statement is written by `ToJson`.
+pub fn to_document(stmts: &[Statement]) -> JsonValue {
+    JsonValue::object(vec![
+        ("schema_version", JsonValue::Number(SCHEMA_VERSION.to_string())),
+        ("statements", JsonValue::Array(stmts.iter().map(ToJson::to_json).collect())),
+    ])
+}
+
+/// Loads the statements of a document written by `to_document`. A document without a `schema_version`, or of another version than `SCHEMA_VERSION`, is rejected rather than read in a way it may not have meant.
+pub fn load_document(text: &str) -> Result<Vec<Statement>, String> {
+    let document = JsonValue::parse(text)?;
+    match document.get("schema_version") {
+        Some(JsonValue::Number(version)) if *version == SCHEMA_VERSION.to_string() => field(&document, "statements"),
+        Some(JsonValue::Number(version)) => Err(format!("Schema version {} is not supported, this release reads version {}", version, SCHEMA_VERSION)),
+        _ => Err("Expected a document with a schema_version".to_string()),
+    }
+}
+
+/// Conversion of AST nodes into the JSON document model. The layout mirrors the `Debug` output: enum variants become single-key objects named after the variant (or plain strings for variants without data) and struct fields keep their Rust names.
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(|item| item.to_json()).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json(),
+            None => JsonValue::Null,
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Box<T> {
+    fn to_json(&self) -> JsonValue {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.clone())
+    }
+}
+
+impl ToJson for Statement {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Statement::Select { with, hints, columns, from, r#where, group_by, having, qualify, orderby, limit } => JsonValue::tagged("Select", JsonValue::object(vec![
+                ("with", with.to_json()),
+                ("hints", hints.to_json()),
+                ("columns", columns.to_json()),
+                ("from", from.to_json()),
+                ("where", r#where.to_json()),
+                ("group_by", group_by.to_json()),
+                ("having", having.to_json()),
+                ("qualify", qualify.to_json()),
+                ("orderby", orderby.to_json()),
+                ("limit", limit.to_json()),
+            ])),
+            Statement::SetOperation { with, left, operator, all, right, orderby, limit } => JsonValue::tagged("SetOperation", JsonValue::object(vec![
+                ("with", with.to_json()),
+                ("left", left.to_json()),
+                ("operator", operator.to_json()),
+                ("all", JsonValue::Bool(*all)),
+                ("right", right.to_json()),
+                ("orderby", orderby.to_json()),
+                ("limit", limit.to_json()),
+            ])),
+            Statement::Values { rows } => JsonValue::tagged("Values", JsonValue::object(vec![
+                ("rows", rows.to_json()),
+            ])),
+            Statement::CreateTable { table_name, column_list, query, if_not_exists, temporary } => JsonValue::tagged("CreateTable", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+                ("column_list", column_list.to_json()),
+                ("query", query.to_json()),
+                ("if_not_exists", JsonValue::Bool(*if_not_exists)),
+                ("temporary", JsonValue::Bool(*temporary)),
+            ])),
+            Statement::Insert { table_name, columns, source, on_conflict } => JsonValue::tagged("Insert", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+                ("columns", columns.to_json()),
+                ("source", source.to_json()),
+                ("on_conflict", on_conflict.to_json()),
+            ])),
+            Statement::Delete { table, where_clause } => JsonValue::tagged("Delete", JsonValue::object(vec![
+                ("table", table.to_json()),
+                ("where_clause", where_clause.to_json()),
+            ])),
+            Statement::Merge { table_name, alias, source, on, clauses } => JsonValue::tagged("Merge", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+                ("alias", alias.to_json()),
+                ("source", source.to_json()),
+                ("on", on.to_json()),
+                ("clauses", clauses.to_json()),
+            ])),
+            Statement::AlterTable { table_name, action } => JsonValue::tagged("AlterTable", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+                ("action", action.to_json()),
+            ])),
+            Statement::RenameTable { renames } => JsonValue::tagged("RenameTable", JsonValue::object(vec![
+                ("renames", renames.to_json()),
+            ])),
+            Statement::Truncate { table_name } => JsonValue::tagged("Truncate", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+            ])),
+            Statement::Analyze { table_name } => JsonValue::tagged("Analyze", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+            ])),
+            Statement::Vacuum { full, table_name } => JsonValue::tagged("Vacuum", JsonValue::object(vec![
+                ("full", JsonValue::Bool(*full)),
+                ("table_name", table_name.to_json()),
+            ])),
+            Statement::CreateDatabase { name, if_not_exists } => JsonValue::tagged("CreateDatabase", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("if_not_exists", JsonValue::Bool(*if_not_exists)),
+            ])),
+            Statement::CreateSchema { name, if_not_exists } => JsonValue::tagged("CreateSchema", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("if_not_exists", JsonValue::Bool(*if_not_exists)),
+            ])),
+            Statement::CreateIndex { name, table_name, columns, unique, if_not_exists } => JsonValue::tagged("CreateIndex", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("table_name", table_name.to_json()),
+                ("columns", columns.to_json()),
+                ("unique", JsonValue::Bool(*unique)),
+                ("if_not_exists", JsonValue::Bool(*if_not_exists)),
+            ])),
+            Statement::CreateFunction { or_replace, name, args, returns, body, language } => JsonValue::tagged("CreateFunction", JsonValue::object(vec![
+                ("or_replace", JsonValue::Bool(*or_replace)),
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+                ("returns", returns.to_json()),
+                ("body", body.to_json()),
+                ("language", language.to_json()),
+            ])),
+            Statement::CreateProcedure { or_replace, name, args, body, language } => JsonValue::tagged("CreateProcedure", JsonValue::object(vec![
+                ("or_replace", JsonValue::Bool(*or_replace)),
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+                ("body", body.to_json()),
+                ("language", language.to_json()),
+            ])),
+            Statement::CreateTrigger { name, timing, event, table_name, body } => JsonValue::tagged("CreateTrigger", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("timing", timing.to_json()),
+                ("event", event.to_json()),
+                ("table_name", table_name.to_json()),
+                ("body", JsonValue::Array(body.iter().map(ToJson::to_json).collect())),
+            ])),
+            Statement::Drop { object_type, names, if_exists } => JsonValue::tagged("Drop", JsonValue::object(vec![
+                ("object_type", object_type.to_json()),
+                ("names", names.to_json()),
+                ("if_exists", JsonValue::Bool(*if_exists)),
+            ])),
+            Statement::Show { object } => JsonValue::tagged("Show", JsonValue::object(vec![
+                ("object", object.to_json()),
+            ])),
+            Statement::Describe { table_name } => JsonValue::tagged("Describe", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+            ])),
+            Statement::SetVariable { name, value, rest } => JsonValue::tagged("SetVariable", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("value", value.to_json()),
+                ("rest", rest.to_json()),
+            ])),
+            Statement::Call { name, args } => JsonValue::tagged("Call", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+            ])),
+            Statement::Copy { table_name, columns, direction, file, options } => JsonValue::tagged("Copy", JsonValue::object(vec![
+                ("table_name", table_name.to_json()),
+                ("columns", columns.to_json()),
+                ("direction", direction.to_json()),
+                ("file", file.to_json()),
+                ("options", options.to_json()),
+            ])),
+            Statement::LoadData { local, file, duplicates, table_name, format, ignore_lines, columns } => JsonValue::tagged("LoadData", JsonValue::object(vec![
+                ("local", JsonValue::Bool(*local)),
+                ("file", file.to_json()),
+                ("duplicates", duplicates.to_json()),
+                ("table_name", table_name.to_json()),
+                ("format", format.to_json()),
+                ("ignore_lines", ignore_lines.map_or(JsonValue::Null, |count| JsonValue::Number(count.to_string()))),
+                ("columns", columns.to_json()),
+            ])),
+            Statement::Prepare { name, param_types, statement } => JsonValue::tagged("Prepare", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("param_types", param_types.to_json()),
+                ("statement", statement.to_json()),
+            ])),
+            Statement::Execute { name, args } => JsonValue::tagged("Execute", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+            ])),
+            Statement::Deallocate { name } => JsonValue::tagged("Deallocate", JsonValue::object(vec![
+                ("name", name.to_json()),
+            ])),
+            Statement::Unparsed { raw, span } => JsonValue::tagged("Unparsed", JsonValue::object(vec![
+                ("raw", raw.to_json()),
+                ("span", span.to_json()),
+            ])),
+        }
+    }
+}
+
+impl ToJson for Expression {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Expression::BinaryOperation { left_operand, operator, right_operand } => JsonValue::tagged("BinaryOperation", JsonValue::object(vec![
+                ("left_operand", left_operand.to_json()),
+                ("operator", operator.to_json()),
+                ("right_operand", right_operand.to_json()),
+            ])),
+            Expression::UnaryOperation { operand, operator } => JsonValue::tagged("UnaryOperation", JsonValue::object(vec![
+                ("operand", operand.to_json()),
+                ("operator", operator.to_json()),
+            ])),
+            Expression::IsDistinctFrom { left_operand, right_operand, negated } => JsonValue::tagged("IsDistinctFrom", JsonValue::object(vec![
+                ("left_operand", left_operand.to_json()),
+                ("right_operand", right_operand.to_json()),
+                ("negated", JsonValue::Bool(*negated)),
+            ])),
+            Expression::Exists { subquery, negated } => JsonValue::tagged("Exists", JsonValue::object(vec![
+                ("subquery", subquery.to_json()),
+                ("negated", JsonValue::Bool(*negated)),
+            ])),
+            Expression::Subquery(subquery) => JsonValue::tagged("Subquery", subquery.to_json()),
+            Expression::Function { name, args, over } => JsonValue::tagged("Function", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+                ("over", over.to_json()),
+            ])),
+            Expression::Alias { expr, alias } => JsonValue::tagged("Alias", JsonValue::object(vec![
+                ("expr", expr.to_json()),
+                ("alias", alias.to_json()),
+            ])),
+            Expression::Wildcard => JsonValue::String("Wildcard".to_string()),
+            Expression::Literal(literal) => JsonValue::tagged("Literal", literal.to_json()),
+            Expression::Placeholder(text) => JsonValue::tagged("Placeholder", text.to_json()),
+            Expression::Identifier(s) => JsonValue::tagged("Identifier", s.to_json()),
+            Expression::CompoundIdentifier(parts) => JsonValue::tagged("CompoundIdentifier", parts.to_json()),
+        }
+    }
+}
+
+impl ToJson for Literal {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Literal::Integer(n) => JsonValue::tagged("Integer", JsonValue::Number(n.to_string())),
+            Literal::Decimal(s) => JsonValue::tagged("Decimal", s.to_json()),
+            Literal::String(s) => JsonValue::tagged("String", s.to_json()),
+            Literal::NationalString(s) => JsonValue::tagged("NationalString", s.to_json()),
+            Literal::BitString(s) => JsonValue::tagged("BitString", s.to_json()),
+            Literal::HexString(s) => JsonValue::tagged("HexString", s.to_json()),
+            Literal::Boolean(b) => JsonValue::tagged("Boolean", JsonValue::Bool(*b)),
+            Literal::Null => JsonValue::String("Null".to_string()),
+        }
+    }
+}
+
+impl ToJson for TableReference {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            TableReference::Table { name, alias, sample } => JsonValue::tagged("Table", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("alias", alias.to_json()),
+                ("sample", sample.to_json()),
+            ])),
+            TableReference::Derived { lateral, subquery, alias } => JsonValue::tagged("Derived", JsonValue::object(vec![
+                ("lateral", JsonValue::Bool(*lateral)),
+                ("subquery", subquery.to_json()),
+                ("alias", alias.to_json()),
+            ])),
+            TableReference::Function { lateral, name, args, alias } => JsonValue::tagged("Function", JsonValue::object(vec![
+                ("lateral", JsonValue::Bool(*lateral)),
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+                ("alias", alias.to_json()),
+            ])),
+            TableReference::Pivot { table, pivot, alias } => JsonValue::tagged("Pivot", JsonValue::object(vec![
+                ("table", table.to_json()),
+                ("pivot", pivot.to_json()),
+                ("alias", alias.to_json()),
+            ])),
+            TableReference::Unpivot { table, unpivot, alias } => JsonValue::tagged("Unpivot", JsonValue::object(vec![
+                ("table", table.to_json()),
+                ("unpivot", unpivot.to_json()),
+                ("alias", alias.to_json()),
+            ])),
+            TableReference::Join { left, right, kind, on } => JsonValue::tagged("Join", JsonValue::object(vec![
+                ("left", left.to_json()),
+                ("right", right.to_json()),
+                ("kind", kind.to_json()),
+                ("on", on.to_json()),
+            ])),
+        }
+    }
+}
+
+impl ToJson for TableSample {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("method", self.method.to_json()),
+            ("percentage", self.percentage.to_json()),
+            ("repeatable", self.repeatable.to_json()),
+        ])
+    }
+}
+
+impl ToJson for PivotClause {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("aggregate", self.aggregate.to_json()),
+            ("column", self.column.to_json()),
+            ("values", self.values.to_json()),
+        ])
+    }
+}
+
+impl ToJson for UnpivotClause {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("value_column", self.value_column.to_json()),
+            ("name_column", self.name_column.to_json()),
+            ("columns", self.columns.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Hint {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("name", self.name.to_json()),
+            ("args", self.args.to_json()),
+        ])
+    }
+}
+
+impl ToJson for With {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("recursive", JsonValue::Bool(self.recursive)),
+            ("ctes", self.ctes.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Cte {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("name", self.name.to_json()),
+            ("columns", self.columns.to_json()),
+            ("query", self.query.to_json()),
+        ])
+    }
+}
+
+impl ToJson for InsertSource {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            InsertSource::Values(rows) => JsonValue::tagged("Values", rows.to_json()),
+            InsertSource::Query(query) => JsonValue::tagged("Query", query.to_json()),
+        }
+    }
+}
+
+impl ToJson for OnConflict {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("target", self.target.to_json()),
+            ("action", self.action.to_json()),
+        ])
+    }
+}
+
+impl ToJson for ConflictAction {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            ConflictAction::Nothing => JsonValue::String("Nothing".to_string()),
+            ConflictAction::Update { assignments, where_clause } => JsonValue::tagged("Update", JsonValue::object(vec![
+                ("assignments", assignments.to_json()),
+                ("where_clause", where_clause.to_json()),
+            ])),
+        }
+    }
+}
+
+impl ToJson for Assignment {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("column", self.column.to_json()),
+            ("value", self.value.to_json()),
+        ])
+    }
+}
+
+impl ToJson for MergeClause {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("matched", JsonValue::Bool(self.matched)),
+            ("condition", self.condition.to_json()),
+            ("action", self.action.to_json()),
+        ])
+    }
+}
+
+impl ToJson for MergeAction {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            MergeAction::Update { assignments } => JsonValue::tagged("Update", JsonValue::object(vec![
+                ("assignments", assignments.to_json()),
+            ])),
+            MergeAction::Delete => JsonValue::String("Delete".to_string()),
+            MergeAction::Insert { columns, values } => JsonValue::tagged("Insert", JsonValue::object(vec![
+                ("columns", columns.to_json()),
+                ("values", values.to_json()),
+            ])),
+            MergeAction::Nothing => JsonValue::String("Nothing".to_string()),
+        }
+    }
+}
+
+impl ToJson for WindowSpec {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("partition_by", self.partition_by.to_json()),
+            ("order_by", self.order_by.to_json()),
+        ])
+    }
+}
+
+impl ToJson for GroupingElement {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            GroupingElement::Expression(expr) => JsonValue::tagged("Expression", expr.to_json()),
+            GroupingElement::Rollup(sets) => JsonValue::tagged("Rollup", sets.to_json()),
+            GroupingElement::Cube(sets) => JsonValue::tagged("Cube", sets.to_json()),
+            GroupingElement::GroupingSets(sets) => JsonValue::tagged("GroupingSets", sets.to_json()),
+        }
+    }
+}
+
+impl ToJson for JoinKind {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for ObjectType {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for SetOperator {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for TriggerTiming {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for CopyDirection {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for CopyOption {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("name", self.name.to_json()),
+            ("value", self.value.to_json()),
+        ])
+    }
+}
+
+impl ToJson for DuplicateHandling {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for LoadFormat {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("fields_terminated_by", self.fields_terminated_by.to_json()),
+            ("fields_enclosed_by", self.fields_enclosed_by.to_json()),
+            ("optionally_enclosed", JsonValue::Bool(self.optionally_enclosed)),
+            ("fields_escaped_by", self.fields_escaped_by.to_json()),
+            ("lines_starting_by", self.lines_starting_by.to_json()),
+            ("lines_terminated_by", self.lines_terminated_by.to_json()),
+        ])
+    }
+}
+
+impl ToJson for TriggerEvent {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for Span {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("start", JsonValue::Number(self.start.to_string())),
+            ("end", JsonValue::Number(self.end.to_string())),
+            ("line", JsonValue::Number(self.line.to_string())),
+            ("column", JsonValue::Number(self.column.to_string())),
+        ])
+    }
+}
+
+impl ToJson for SkippedClause {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("statement", JsonValue::Number(self.statement.to_string())),
+            ("tokens", JsonValue::Array(self.tokens.iter().map(ToJson::to_json).collect())),
+            ("error", self.error.to_json()),
+        ])
+    }
+}
+
+//a token of a part the parser keeps unparsed, tagged with its kind like the enums of the tree
+impl ToJson for Token {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Token::Keyword(keyword) => JsonValue::tagged("Keyword", JsonValue::String(format!("{:?}", keyword))),
+            Token::Identifier(name) => JsonValue::tagged("Identifier", name.to_json()),
+            //strings as written, quotes and escapes included
+            Token::String { raw, .. } => JsonValue::tagged("String", raw.to_json()),
+            Token::NationalString { raw, .. } => JsonValue::tagged("NationalString", raw.to_json()),
+            Token::EscapeString { raw, .. } => JsonValue::tagged("EscapeString", raw.to_json()),
+            Token::BitString { raw, .. } => JsonValue::tagged("BitString", raw.to_json()),
+            Token::HexString { raw, .. } => JsonValue::tagged("HexString", raw.to_json()),
+            Token::Number(n) => JsonValue::tagged("Number", JsonValue::Number(n.to_string())),
+            Token::Decimal(digits) => JsonValue::tagged("Decimal", digits.to_json()),
+            Token::Placeholder(text) => JsonValue::tagged("Placeholder", text.to_json()),
+            Token::Hint(text) => JsonValue::tagged("Hint", text.to_json()),
+            //comments as written, with their delimiters
+            Token::Comment { raw, .. } => JsonValue::tagged("Comment", raw.to_json()),
+            Token::Invalid(c) => JsonValue::tagged("Invalid", c.to_string().to_json()),
+            other => JsonValue::String(format!("{:?}", other)),
+        }
+    }
+}
+
+impl ToJson for ShowObject {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for AlterAction {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            AlterAction::AddColumn(column) => JsonValue::tagged("AddColumn", column.to_json()),
+            AlterAction::DropColumn(column_name) => JsonValue::tagged("DropColumn", column_name.to_json()),
+            AlterAction::RenameColumn { column_name, new_name } => JsonValue::tagged("RenameColumn", JsonValue::object(vec![
+                ("column_name", column_name.to_json()),
+                ("new_name", new_name.to_json()),
+            ])),
+            AlterAction::RenameTo(new_name) => JsonValue::tagged("RenameTo", new_name.to_json()),
+        }
+    }
+}
+
+impl ToJson for TableRename {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("old_name", self.old_name.to_json()),
+            ("new_name", self.new_name.to_json()),
+        ])
+    }
+}
+
+//a column in ascending order is written as its name, the form from before DESC was read
+impl ToJson for IndexColumn {
+    fn to_json(&self) -> JsonValue {
+        match self.descending {
+            false => self.name.to_json(),
+            true => JsonValue::object(vec![
+                ("name", self.name.to_json()),
+                ("descending", JsonValue::Bool(true)),
+            ]),
+        }
+    }
+}
+
+impl ToJson for TableColumn {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("column_name", self.column_name.to_json()),
+            ("column_type", self.column_type.to_json()),
+            ("constraints", self.constraints.to_json()),
+        ])
+    }
+}
+
+impl ToJson for FunctionArg {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("name", self.name.to_json()),
+            ("data_type", self.data_type.to_json()),
+        ])
+    }
+}
+
+impl ToJson for DBType {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            DBType::Int => JsonValue::String("Int".to_string()),
+            DBType::Bool => JsonValue::String("Bool".to_string()),
+            DBType::Varchar(n) => JsonValue::tagged("Varchar", JsonValue::Number(n.to_string())),
+            DBType::Decimal(precision, scale) => JsonValue::tagged("Decimal", JsonValue::Array(vec![
+                JsonValue::Number(precision.to_string()),
+                JsonValue::Number(scale.to_string()),
+            ])),
+            DBType::Double => JsonValue::String("Double".to_string()),
+            DBType::Char(n) => JsonValue::tagged("Char", JsonValue::Number(n.to_string())),
+            DBType::Timestamp { with_time_zone } => JsonValue::tagged("Timestamp", JsonValue::object(vec![
+                ("with_time_zone", JsonValue::Bool(*with_time_zone)),
+            ])),
+            DBType::Custom(name, args) => JsonValue::tagged("Custom", JsonValue::object(vec![
+                ("name", name.to_json()),
+                ("args", args.to_json()),
+            ])),
+        }
+    }
+}
+
+impl ToJson for Constraint {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Constraint::NotNull => JsonValue::String("NotNull".to_string()),
+            Constraint::PrimaryKey => JsonValue::String("PrimaryKey".to_string()),
+            Constraint::Check(expr) => JsonValue::tagged("Check", expr.to_json()),
+        }
+    }
+}
+
+impl ToJson for BinaryOperator {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+impl ToJson for UnaryOperator {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(format!("{:?}", self))
+    }
+}
+
+/// Reading AST nodes back from the JSON form `ToJson` writes, so a tree written by one program can be loaded by another. A field that is missing or `null` reads as its empty value (`None`, `false` or no items) and fields that are not known are ignored, which is what lets a document written by an older release of the same schema version load; a missing field that has no empty value, such as a table name, is an error. Errors name the path to the value, `where: left_operand: Expected a string, found 1`.
+pub trait FromJson: Sized {
+    fn from_json(value: &JsonValue) -> Result<Self, String>;
+}
+
+//helper, a field of an object read as `T`, null when it is missing
+fn field<T: FromJson>(value: &JsonValue, key: &str) -> Result<T, String> {
+    T::from_json(value.get(key).unwrap_or(&JsonValue::Null)).map_err(|err| format!("{}: {}", key, err))
+}
+
+//helper, the tag and the content of an enum variant: a string for a variant without data (its content is null), an
+//object with a single key otherwise
+fn variant(value: &JsonValue) -> Result<(&str, &JsonValue), String> {
+    match value {
+        JsonValue::String(tag) => Ok((tag, &JsonValue::Null)),
+        JsonValue::Object(fields) if fields.len() == 1 => Ok((&fields[0].0, &fields[0].1)),
+        other => Err(format!("Expected a variant, a string or an object with one key, found {}", other)),
+    }
+}
+
+//helper, a variant without data, written with its `Debug` name
+fn unit_variant<T: Clone + std::fmt::Debug>(value: &JsonValue, variants: &[T], what: &str) -> Result<T, String> {
+    match value {
+        JsonValue::String(name) => variants.iter().find(|variant| format!("{:?}", variant) == *name).cloned()
+            .ok_or_else(|| format!("Unknown {} {:?}", what, name)),
+        other => Err(format!("Expected {} name, found {}", what, other)),
+    }
+}
+
+//helper, the error for a tag no variant has
+fn unknown_variant(what: &str, tag: &str) -> String {
+    format!("Unknown {} {:?}", what, tag)
+}
+
+//helper, a number read as an integer type
+fn number<T: std::str::FromStr>(value: &JsonValue) -> Result<T, String> {
+    match value {
+        JsonValue::Number(text) => text.parse().map_err(|_| format!("Expected an integer in range, found {}", text)),
+        other => Err(format!("Expected a number, found {}", other)),
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Array(items) => items.iter().enumerate()
+                .map(|(i, item)| T::from_json(item).map_err(|err| format!("{}: {}", i, err)))
+                .collect(),
+            JsonValue::Null => Ok(Vec::new()),
+            other => Err(format!("Expected an array, found {}", other)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Null => Ok(None),
+            other => T::from_json(other).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::String(s) => Ok(s.clone()),
+            other => Err(format!("Expected a string, found {}", other)),
+        }
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Bool(b) => Ok(*b),
+            JsonValue::Null => Ok(false),
+            other => Err(format!("Expected true or false, found {}", other)),
+        }
+    }
+}
+
+impl FromJson for u64 {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        number(value)
+    }
+}
+
+impl FromJson for usize {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        number(value)
+    }
+}
+
+impl FromJson for Statement {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Select" => Statement::Select {
+                with: field(v, "with")?,
+                hints: field(v, "hints")?,
+                columns: field(v, "columns")?,
+                from: field(v, "from")?,
+                r#where: field(v, "where")?,
+                group_by: field(v, "group_by")?,
+                having: field(v, "having")?,
+                qualify: field(v, "qualify")?,
+                orderby: field(v, "orderby")?,
+                limit: field(v, "limit")?,
+            },
+            "SetOperation" => Statement::SetOperation {
+                with: field(v, "with")?,
+                left: field(v, "left")?,
+                operator: field(v, "operator")?,
+                all: field(v, "all")?,
+                right: field(v, "right")?,
+                orderby: field(v, "orderby")?,
+                limit: field(v, "limit")?,
+            },
+            "Values" => Statement::Values { rows: field(v, "rows")? },
+            "CreateTable" => Statement::CreateTable {
+                table_name: field(v, "table_name")?,
+                column_list: field(v, "column_list")?,
+                query: field(v, "query")?,
+                if_not_exists: field(v, "if_not_exists")?,
+                temporary: field(v, "temporary")?,
+            },
+            "Insert" => Statement::Insert {
+                table_name: field(v, "table_name")?,
+                columns: field(v, "columns")?,
+                source: field(v, "source")?,
+                on_conflict: field(v, "on_conflict")?,
+            },
+            "Delete" => Statement::Delete { table: field(v, "table")?, where_clause: field(v, "where_clause")? },
+            "Merge" => Statement::Merge {
+                table_name: field(v, "table_name")?,
+                alias: field(v, "alias")?,
+                source: field(v, "source")?,
+                on: field(v, "on")?,
+                clauses: field(v, "clauses")?,
+            },
+            "AlterTable" => Statement::AlterTable { table_name: field(v, "table_name")?, action: field(v, "action")? },
+            "RenameTable" => Statement::RenameTable { renames: field(v, "renames")? },
+            "Truncate" => Statement::Truncate { table_name: field(v, "table_name")? },
+            "Analyze" => Statement::Analyze { table_name: field(v, "table_name")? },
+            "Vacuum" => Statement::Vacuum { full: field(v, "full")?, table_name: field(v, "table_name")? },
+            "CreateDatabase" => Statement::CreateDatabase { name: field(v, "name")?, if_not_exists: field(v, "if_not_exists")? },
+            "CreateSchema" => Statement::CreateSchema { name: field(v, "name")?, if_not_exists: field(v, "if_not_exists")? },
+            "CreateIndex" => Statement::CreateIndex {
+                name: field(v, "name")?,
+                table_name: field(v, "table_name")?,
+                columns: field(v, "columns")?,
+                unique: field(v, "unique")?,
+                if_not_exists: field(v, "if_not_exists")?,
+            },
+            "CreateFunction" => Statement::CreateFunction {
+                or_replace: field(v, "or_replace")?,
+                name: field(v, "name")?,
+                args: field(v, "args")?,
+                returns: field(v, "returns")?,
+                body: field(v, "body")?,
+                language: field(v, "language")?,
+            },
+            "CreateProcedure" => Statement::CreateProcedure {
+                or_replace: field(v, "or_replace")?,
+                name: field(v, "name")?,
+                args: field(v, "args")?,
+                body: field(v, "body")?,
+                language: field(v, "language")?,
+            },
+            "CreateTrigger" => Statement::CreateTrigger {
+                name: field(v, "name")?,
+                timing: field(v, "timing")?,
+                event: field(v, "event")?,
+                table_name: field(v, "table_name")?,
+                body: field(v, "body")?,
+            },
+            "Drop" => Statement::Drop { object_type: field(v, "object_type")?, names: field(v, "names")?, if_exists: field(v, "if_exists")? },
+            "Show" => Statement::Show { object: field(v, "object")? },
+            "Describe" => Statement::Describe { table_name: field(v, "table_name")? },
+            "SetVariable" => Statement::SetVariable { name: field(v, "name")?, value: field(v, "value")?, rest: field(v, "rest")? },
+            "Call" => Statement::Call { name: field(v, "name")?, args: field(v, "args")? },
+            "Copy" => Statement::Copy {
+                table_name: field(v, "table_name")?,
+                columns: field(v, "columns")?,
+                direction: field(v, "direction")?,
+                file: field(v, "file")?,
+                options: field(v, "options")?,
+            },
+            "LoadData" => Statement::LoadData {
+                local: field(v, "local")?,
+                file: field(v, "file")?,
+                duplicates: field(v, "duplicates")?,
+                table_name: field(v, "table_name")?,
+                format: field(v, "format")?,
+                ignore_lines: field(v, "ignore_lines")?,
+                columns: field(v, "columns")?,
+            },
+            "Prepare" => Statement::Prepare { name: field(v, "name")?, param_types: field(v, "param_types")?, statement: field(v, "statement")? },
+            "Execute" => Statement::Execute { name: field(v, "name")?, args: field(v, "args")? },
+            "Deallocate" => Statement::Deallocate { name: field(v, "name")? },
+            "Unparsed" => Statement::Unparsed { raw: field(v, "raw")?, span: field(v, "span")? },
+            other => return Err(unknown_variant("statement", other)),
+        })
+    }
+}
+
+impl FromJson for Expression {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "BinaryOperation" => Expression::BinaryOperation {
+                left_operand: field(v, "left_operand")?,
+                operator: field(v, "operator")?,
+                right_operand: field(v, "right_operand")?,
+            },
+            "UnaryOperation" => Expression::UnaryOperation { operand: field(v, "operand")?, operator: field(v, "operator")? },
+            "IsDistinctFrom" => Expression::IsDistinctFrom {
+                left_operand: field(v, "left_operand")?,
+                right_operand: field(v, "right_operand")?,
+                negated: field(v, "negated")?,
+            },
+            "Exists" => Expression::Exists { subquery: field(v, "subquery")?, negated: field(v, "negated")? },
+            "Subquery" => Expression::Subquery(FromJson::from_json(v)?),
+            "Function" => Expression::Function { name: field(v, "name")?, args: field(v, "args")?, over: field(v, "over")? },
+            "Alias" => Expression::Alias { expr: field(v, "expr")?, alias: field(v, "alias")? },
+            "Wildcard" => Expression::Wildcard,
+            "Literal" => Expression::Literal(FromJson::from_json(v)?),
+            "Placeholder" => Expression::Placeholder(FromJson::from_json(v)?),
+            "Identifier" => Expression::Identifier(FromJson::from_json(v)?),
+            "CompoundIdentifier" => Expression::CompoundIdentifier(FromJson::from_json(v)?),
+            other => return Err(unknown_variant("expression", other)),
+        })
+    }
+}
+
+impl FromJson for Literal {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Integer" => Literal::Integer(number(v)?),
+            "Decimal" => Literal::Decimal(FromJson::from_json(v)?),
+            "String" => Literal::String(FromJson::from_json(v)?),
+            "NationalString" => Literal::NationalString(FromJson::from_json(v)?),
+            "BitString" => Literal::BitString(FromJson::from_json(v)?),
+            "HexString" => Literal::HexString(FromJson::from_json(v)?),
+            "Boolean" => match v {
+                JsonValue::Bool(b) => Literal::Boolean(*b),
+                other => return Err(format!("Expected true or false, found {}", other)),
+            },
+            "Null" => Literal::Null,
+            other => return Err(unknown_variant("literal", other)),
+        })
+    }
+}
+
+impl FromJson for TableReference {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Table" => TableReference::Table { name: field(v, "name")?, alias: field(v, "alias")?, sample: field(v, "sample")? },
+            "Derived" => TableReference::Derived { lateral: field(v, "lateral")?, subquery: field(v, "subquery")?, alias: field(v, "alias")? },
+            "Function" => TableReference::Function {
+                lateral: field(v, "lateral")?,
+                name: field(v, "name")?,
+                args: field(v, "args")?,
+                alias: field(v, "alias")?,
+            },
+            "Pivot" => TableReference::Pivot { table: field(v, "table")?, pivot: field(v, "pivot")?, alias: field(v, "alias")? },
+            "Unpivot" => TableReference::Unpivot { table: field(v, "table")?, unpivot: field(v, "unpivot")?, alias: field(v, "alias")? },
+            "Join" => TableReference::Join { left: field(v, "left")?, right: field(v, "right")?, kind: field(v, "kind")?, on: field(v, "on")? },
+            other => return Err(unknown_variant("table reference", other)),
+        })
+    }
+}
+
+impl FromJson for TableSample {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(TableSample { method: field(value, "method")?, percentage: field(value, "percentage")?, repeatable: field(value, "repeatable")? })
+    }
+}
+
+impl FromJson for PivotClause {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(PivotClause { aggregate: field(value, "aggregate")?, column: field(value, "column")?, values: field(value, "values")? })
+    }
+}
+
+impl FromJson for UnpivotClause {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(UnpivotClause { value_column: field(value, "value_column")?, name_column: field(value, "name_column")?, columns: field(value, "columns")? })
+    }
+}
+
+impl FromJson for Hint {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(Hint { name: field(value, "name")?, args: field(value, "args")? })
+    }
+}
+
+impl FromJson for With {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(With { recursive: field(value, "recursive")?, ctes: field(value, "ctes")? })
+    }
+}
+
+impl FromJson for Cte {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(Cte { name: field(value, "name")?, columns: field(value, "columns")?, query: field(value, "query")? })
+    }
+}
+
+impl FromJson for InsertSource {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Values" => InsertSource::Values(FromJson::from_json(v)?),
+            "Query" => InsertSource::Query(FromJson::from_json(v)?),
+            other => return Err(unknown_variant("insert source", other)),
+        })
+    }
+}
+
+impl FromJson for OnConflict {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(OnConflict { target: field(value, "target")?, action: field(value, "action")? })
+    }
+}
+
+impl FromJson for ConflictAction {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Nothing" => ConflictAction::Nothing,
+            "Update" => ConflictAction::Update { assignments: field(v, "assignments")?, where_clause: field(v, "where_clause")? },
+            other => return Err(unknown_variant("conflict action", other)),
+        })
+    }
+}
+
+impl FromJson for Assignment {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(Assignment { column: field(value, "column")?, value: field(value, "value")? })
+    }
+}
+
+impl FromJson for MergeClause {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(MergeClause { matched: field(value, "matched")?, condition: field(value, "condition")?, action: field(value, "action")? })
+    }
+}
+
+impl FromJson for MergeAction {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Update" => MergeAction::Update { assignments: field(v, "assignments")? },
+            "Delete" => MergeAction::Delete,
+            "Insert" => MergeAction::Insert { columns: field(v, "columns")?, values: field(v, "values")? },
+            "Nothing" => MergeAction::Nothing,
+            other => return Err(unknown_variant("merge action", other)),
+        })
+    }
+}
+
+impl FromJson for WindowSpec {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(WindowSpec { partition_by: field(value, "partition_by")?, order_by: field(value, "order_by")? })
+    }
+}
+
+impl FromJson for GroupingElement {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Expression" => GroupingElement::Expression(FromJson::from_json(v)?),
+            "Rollup" => GroupingElement::Rollup(FromJson::from_json(v)?),
+            "Cube" => GroupingElement::Cube(FromJson::from_json(v)?),
+            "GroupingSets" => GroupingElement::GroupingSets(FromJson::from_json(v)?),
+            other => return Err(unknown_variant("grouping element", other)),
+        })
+    }
+}
+
+impl FromJson for JoinKind {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[JoinKind::Inner, JoinKind::Left, JoinKind::Right, JoinKind::Full, JoinKind::Cross], "join kind")
+    }
+}
+
+impl FromJson for ObjectType {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[ObjectType::Table, ObjectType::Index, ObjectType::View], "object type")
+    }
+}
+
+impl FromJson for SetOperator {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[SetOperator::Union, SetOperator::Intersect, SetOperator::Except], "set operator")
+    }
+}
+
+impl FromJson for TriggerTiming {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[TriggerTiming::Before, TriggerTiming::After], "trigger timing")
+    }
+}
+
+impl FromJson for TriggerEvent {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[TriggerEvent::Insert, TriggerEvent::Update, TriggerEvent::Delete], "trigger event")
+    }
+}
+
+impl FromJson for CopyDirection {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[CopyDirection::From, CopyDirection::To], "copy direction")
+    }
+}
+
+impl FromJson for CopyOption {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(CopyOption { name: field(value, "name")?, value: field(value, "value")? })
+    }
+}
+
+impl FromJson for DuplicateHandling {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[DuplicateHandling::Replace, DuplicateHandling::Ignore], "duplicate handling")
+    }
+}
+
+impl FromJson for LoadFormat {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(LoadFormat {
+            fields_terminated_by: field(value, "fields_terminated_by")?,
+            fields_enclosed_by: field(value, "fields_enclosed_by")?,
+            optionally_enclosed: field(value, "optionally_enclosed")?,
+            fields_escaped_by: field(value, "fields_escaped_by")?,
+            lines_starting_by: field(value, "lines_starting_by")?,
+            lines_terminated_by: field(value, "lines_terminated_by")?,
+        })
+    }
+}
+
+impl FromJson for ShowObject {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[ShowObject::Tables, ShowObject::Databases], "SHOW object")
+    }
+}
+
+impl FromJson for Span {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(Span { start: field(value, "start")?, end: field(value, "end")?, line: field(value, "line")?, column: field(value, "column")? })
+    }
+}
+
+//the tokens written without data, by their `Debug` names
+const PLAIN_TOKENS: &[Token] = &[
+    Token::RightParentheses, Token::LeftParentheses, Token::GreaterThan, Token::GreaterThanOrEqual, Token::LessThan,
+    Token::LessThanOrEqual, Token::Equal, Token::NotEqual, Token::Star, Token::Divide, Token::Minus, Token::Plus, Token::Arrow,
+    Token::LongArrow, Token::HashArrow, Token::HashLongArrow, Token::AtArrow, Token::ArrowAt, Token::Tilde, Token::TildeStar,
+    Token::NotTilde, Token::NotTildeStar, Token::DoublePipe, Token::Comma, Token::Period, Token::Semicolon, Token::Eof,
+];
+
+//strings are written as they are in the source, the token is read again from that text: in the generic dialect, or in
+//MySQL's when it has backslash escapes the generic dialect does not take
+impl FromJson for Token {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Keyword" => Token::Keyword(unit_variant(v, Keyword::ALL, "keyword")?),
+            "Identifier" => Token::Identifier(FromJson::from_json(v)?),
+            "String" | "NationalString" | "EscapeString" | "BitString" | "HexString" => {
+                let raw = String::from_json(v)?;
+                [Dialect::Generic, Dialect::MySql].iter()
+                    .filter_map(|&dialect| Tokenizer::with_dialect(&raw, dialect).tokenize().ok())
+                    .find_map(|tokens| match tokens.as_slice() {
+                        [token] | [token, Token::Eof] if token.to_json() == *value => Some(token.clone()),
+                        _ => None,
+                    })
+                    .ok_or_else(|| format!("Expected a {} as written in SQL, found {:?}", tag, raw))?
+            }
+            "Number" => Token::Number(number(v)?),
+            "Decimal" => Token::Decimal(FromJson::from_json(v)?),
+            "Placeholder" => Token::Placeholder(FromJson::from_json(v)?),
+            "Hint" => Token::Hint(FromJson::from_json(v)?),
+            "Comment" => {
+                let raw = String::from_json(v)?;
+                [Dialect::Generic, Dialect::Postgres].iter()
+                    .filter_map(|&dialect| Tokenizer::with_dialect(&raw, dialect).keep_comments(true).tokenize().ok())
+                    .find_map(|tokens| match tokens.as_slice() {
+                        [token @ Token::Comment { raw: read, .. }] | [token @ Token::Comment { raw: read, .. }, Token::Eof] if *read == raw => Some(token.clone()),
+                        _ => None,
+                    })
+                    .ok_or_else(|| format!("Expected a comment as written in SQL, found {:?}", raw))?
+            }
+            "Invalid" => {
+                let text = String::from_json(v)?;
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Token::Invalid(c),
+                    _ => return Err(format!("Expected a single character, found {:?}", text)),
+                }
+            }
+            _ => unit_variant(value, PLAIN_TOKENS, "token")?,
+        })
+    }
+}
+
+impl FromJson for AlterAction {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "AddColumn" => AlterAction::AddColumn(FromJson::from_json(v)?),
+            "DropColumn" => AlterAction::DropColumn(FromJson::from_json(v)?),
+            "RenameColumn" => AlterAction::RenameColumn { column_name: field(v, "column_name")?, new_name: field(v, "new_name")? },
+            "RenameTo" => AlterAction::RenameTo(FromJson::from_json(v)?),
+            other => return Err(unknown_variant("ALTER TABLE action", other)),
+        })
+    }
+}
+
+impl FromJson for TableRename {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(TableRename { old_name: field(value, "old_name")?, new_name: field(value, "new_name")? })
+    }
+}
+
+impl FromJson for IndexColumn {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::String(name) => Ok(IndexColumn { name: name.clone(), descending: false }),
+            _ => Ok(IndexColumn { name: field(value, "name")?, descending: field(value, "descending")? }),
+        }
+    }
+}
+
+impl FromJson for TableColumn {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(TableColumn { column_name: field(value, "column_name")?, column_type: field(value, "column_type")?, constraints: field(value, "constraints")? })
+    }
+}
+
+impl FromJson for FunctionArg {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(FunctionArg { name: field(value, "name")?, data_type: field(value, "data_type")? })
+    }
+}
+
+impl FromJson for DBType {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "Int" => DBType::Int,
+            "Bool" => DBType::Bool,
+            "Varchar" => DBType::Varchar(number(v)?),
+            "Decimal" => match v {
+                JsonValue::Array(items) if items.len() == 2 => DBType::Decimal(number(&items[0])?, number(&items[1])?),
+                other => return Err(format!("Expected [precision, scale], found {}", other)),
+            },
+            "Double" => DBType::Double,
+            "Char" => DBType::Char(number(v)?),
+            "Timestamp" => DBType::Timestamp { with_time_zone: field(v, "with_time_zone")? },
+            "Custom" => DBType::Custom(field(v, "name")?, field(v, "args")?),
+            other => return Err(unknown_variant("type", other)),
+        })
+    }
+}
+
+impl FromJson for Constraint {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let (tag, v) = variant(value)?;
+        Ok(match tag {
+            "NotNull" => Constraint::NotNull,
+            "PrimaryKey" => Constraint::PrimaryKey,
+            "Check" => Constraint::Check(FromJson::from_json(v)?),
+            other => return Err(unknown_variant("constraint", other)),
+        })
+    }
+}
+
+impl FromJson for BinaryOperator {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[
+            BinaryOperator::Plus, BinaryOperator::Minus, BinaryOperator::Multiply, BinaryOperator::Divide,
+            BinaryOperator::GreaterThan, BinaryOperator::GreaterThanOrEqual, BinaryOperator::LessThan, BinaryOperator::LessThanOrEqual,
+            BinaryOperator::Equal, BinaryOperator::NotEqual, BinaryOperator::And, BinaryOperator::Or,
+            BinaryOperator::JsonGet, BinaryOperator::JsonGetText, BinaryOperator::JsonPath, BinaryOperator::JsonPathText,
+            BinaryOperator::JsonContains, BinaryOperator::JsonContainedBy, BinaryOperator::RegexMatch, BinaryOperator::RegexIMatch,
+            BinaryOperator::RegexNotMatch, BinaryOperator::RegexNotIMatch, BinaryOperator::Regexp, BinaryOperator::Rlike,
+            BinaryOperator::NotRegexp, BinaryOperator::NotRlike, BinaryOperator::Concat,
+        ], "binary operator")
+    }
+}
+
+impl FromJson for UnaryOperator {
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        unit_variant(value, &[UnaryOperator::Not, UnaryOperator::Plus, UnaryOperator::Minus, UnaryOperator::Asc, UnaryOperator::Desc, UnaryOperator::IsNull, UnaryOperator::IsNotNull], "unary operator")
+    }
+}
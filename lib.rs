@@ -0,0 +1,70 @@
+//library entry point, the command line interface in main.rs is built on top of it
+//the tokenizer, the parser and the syntax tree are always there, everything else is behind a feature (`--cfg 'feature="..."'`)
+//so programs that only parse do not compile it: see "Features" in the README
+#[cfg(all(any(feature = "analyzer", feature = "engine", feature = "cli", feature = "arbitrary"), not(feature = "formatter")))]
+compile_error!("the analyzer, engine, cli and arbitrary features need the formatter feature");
+#[cfg(all(feature = "cli", not(all(feature = "analyzer", feature = "engine", feature = "serde"))))]
+compile_error!("the cli feature needs the analyzer, engine and serde features");
+#[cfg(all(feature = "wasm", not(feature = "serde")))]
+compile_error!("the wasm feature needs the serde feature");
+
+pub mod token;
+pub mod tokenizer;
+pub mod dialect;
+pub mod parser;
+pub mod statement;
+pub mod shared;
+#[cfg(any(feature = "formatter", feature = "serde"))]
+pub mod align;
+#[cfg(feature = "formatter")]
+pub mod formatter;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "serde")]
+pub mod diff;
+#[cfg(feature = "analyzer")]
+pub mod config;
+#[cfg(feature = "analyzer")]
+pub mod analysis;
+#[cfg(feature = "analyzer")]
+pub mod rewrite;
+#[cfg(feature = "analyzer")]
+pub mod infer;
+#[cfg(feature = "analyzer")]
+pub mod migration;
+#[cfg(feature = "analyzer")]
+pub mod normalize;
+#[cfg(feature = "analyzer")]
+pub mod completion;
+#[cfg(feature = "analyzer")]
+pub mod lint;
+#[cfg(feature = "analyzer")]
+pub mod semantic;
+#[cfg(feature = "analyzer")]
+pub mod fold;
+#[cfg(feature = "analyzer")]
+pub mod advisor;
+#[cfg(feature = "analyzer")]
+pub mod workload;
+#[cfg(feature = "analyzer")]
+pub mod prepared;
+#[cfg(feature = "analyzer")]
+pub mod embedded;
+#[cfg(all(feature = "analyzer", feature = "serde"))]
+pub mod similarity;
+#[cfg(feature = "engine")]
+pub mod engine;
+#[cfg(feature = "engine")]
+pub mod vector;
+#[cfg(feature = "cli")]
+pub mod conformance;
+#[cfg(feature = "cli")]
+pub mod batch;
+#[cfg(feature = "cli")]
+pub mod session;
+#[cfg(feature = "cli")]
+pub mod table_render;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
@@ -0,0 +1,1238 @@
+//lint rules over parsed scripts, with severities from the configuration and suppression comments in the script
+use std::fmt;
+use crate::token::{Token, Keyword};
+use crate::tokenizer::{Tokenizer, SpannedToken, Span, Origin, StatementEnds};
+use crate::parser::Parser;
+use crate::statement::{Statement, InsertSource, Constraint, Expression, Literal, TableReference, GroupingElement, JoinKind, BinaryOperator, UnaryOperator, AlterAction, OnConflict, ConflictAction};
+use crate::config::Config;
+use crate::dialect::Dialect;
+use crate::semantic;
+use crate::analysis::{from_names, table_uses, TableUse};
+use crate::rewrite::for_each_select_mut;
+use crate::normalize::swapped_operator;
+use crate::formatter::format_expression;
+use crate::fold::{fold, compare};
+
+/// Comment directive that turns rules off for the line below it: `-- sqlparser-disable-next-line select-star, null-comparison`. Without rule names every rule is turned off for that line.
+pub const DISABLE_NEXT_LINE: &str = "sqlparser-disable-next-line";
+
+/// Name of the pseudo rule for statements that do not parse, which are reported like findings so the other statements of a script are still checked.
+pub const SYNTAX: &str = "syntax";
+
+/// Name of the pseudo rule for the diagnostics of the semantic analyzer (see `semantic::analyze`), statements that parse but do not make sense.
+pub const SEMANTIC: &str = "semantic";
+
+/// Name of the pseudo rule for suppression comments that name a rule that does not exist, a misspelt name turns nothing off.
+pub const SUPPRESSION: &str = "suppression";
+
+/// How much a finding matters. A rule with severity `Off` is not run.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn from_name(name: &str) -> Option<Severity> {
+        match name.to_ascii_lowercase().as_str() {
+            "off" => Some(Severity::Off),
+            "warning" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Severity::Off => "off",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// What a rule gets to look at: one statement, its tokens (comments left out), where the statement is in the script, the script itself, which token spans index into, and the dialect it was parsed with.
+pub struct Context<'a> {
+    pub stmt: &'a Statement,
+    pub tokens: &'a [SpannedToken],
+    pub span: Span,
+    pub source: &'a str,
+    pub dialect: Dialect,
+}
+
+/// A text replacement in the script: the bytes from `start` to `end` become `replacement`. An insertion has `start == end`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+/// What a rule reports about one problem: where it is, what is wrong, and the edits that fix it when the fix is mechanical and safe to apply without looking (no edits otherwise).
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    pub span: Span,
+    pub message: String,
+    pub fix: Vec<Edit>,
+}
+
+/// A lint rule.
+/// 1. `name` – What the rule is called in the configuration and in suppression comments.
+/// 2. `description` – One sentence on what the rule looks for.
+/// 3. `severity` – Severity when the configuration does not say otherwise, `Off` for rules that have to be enabled.
+/// 4. `check` – The check, returning every problem found in the statement.
+pub struct Rule {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub severity: Severity,
+    pub check: fn(&Context) -> Vec<Problem>,
+}
+
+/// One problem found by a rule, with the edits of its fix (empty when there is none).
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    pub fix: Vec<Edit>,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}[{}]: {}", self.span.line, self.span.column, self.severity, self.rule, self.message)?;
+        if !self.fix.is_empty() {
+            write!(f, " (fixable)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every rule, in the order findings of one statement are reported.
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "select-star",
+        description: "`SELECT *` makes the result depend on the table definition, list the columns instead",
+        severity: Severity::Warning,
+        check: select_star,
+    },
+    Rule {
+        name: "null-comparison",
+        description: "`= NULL` and `!= NULL` are never true, use `IS NULL` or `IS NOT NULL`",
+        severity: Severity::Error,
+        check: null_comparison,
+    },
+    Rule {
+        name: "missing-primary-key",
+        description: "a table created without a PRIMARY KEY column",
+        severity: Severity::Off,
+        check: missing_primary_key,
+    },
+    Rule {
+        name: "keyword-case",
+        description: "reserved keywords not written in upper case",
+        severity: Severity::Off,
+        check: keyword_case,
+    },
+    Rule {
+        name: "explicit-alias",
+        description: "an alias without AS, `SELECT a b` reads like a missing comma",
+        severity: Severity::Off,
+        check: explicit_alias,
+    },
+    Rule {
+        name: "mixed-and-or",
+        description: "AND and OR in one condition without parentheses, `a AND b OR c` is `(a AND b) OR c`",
+        severity: Severity::Warning,
+        check: mixed_and_or,
+    },
+    Rule {
+        name: "join-condition",
+        description: "a JOIN without ON, an ON condition that does not use both sides, or comma separated tables no WHERE condition relates",
+        severity: Severity::Warning,
+        check: join_condition,
+    },
+    Rule {
+        name: "unused-alias",
+        description: "a table alias that no column reference of the statement is qualified with",
+        severity: Severity::Warning,
+        check: unused_alias,
+    },
+    Rule {
+        name: "dead-predicate",
+        description: "a condition with a part that is always true or never true, such as `1 = 1` or `x > 10 AND x < 5`",
+        severity: Severity::Warning,
+        check: dead_predicate,
+    },
+    Rule {
+        name: "unused-cte",
+        description: "a common table expression of a WITH clause that neither its query nor the other common table expressions read",
+        severity: Severity::Warning,
+        check: unused_cte,
+    },
+];
+
+//the severity every rule has under a configuration: the rule's default, `enable` turning on rules that are
+//off by default (as warnings), `disable` turning rules off, and `[lint.severity]` overriding both
+//rule names the configuration uses that are not rules are an error
+pub fn severities(config: &Config) -> Result<Vec<(&'static str, Severity)>, String> {
+    let names = config.lint_enable.iter().chain(&config.lint_disable).chain(config.lint_severity.iter().map(|(name, _)| name));
+    if let Some(name) = names.into_iter().find(|name| !is_rule(name)) {
+        return Err(format!("Unknown lint rule {}", name));
+    }
+    let rules = RULES.iter().map(|rule| (rule.name, rule.severity)).chain([(SYNTAX, Severity::Error), (SEMANTIC, Severity::Error), (SUPPRESSION, Severity::Warning)]);
+    Ok(rules.map(|(name, default)| {
+        let mut severity = default;
+        if config.lint_enable.iter().any(|enabled| enabled == name) && severity == Severity::Off {
+            severity = Severity::Warning;
+        }
+        if config.lint_disable.iter().any(|disabled| disabled == name) {
+            severity = Severity::Off;
+        }
+        if let Some((_, configured)) = config.lint_severity.iter().rev().find(|(rule, _)| rule == name) {
+            severity = *configured;
+        }
+        (name, severity)
+    }).collect())
+}
+
+//lint a script with the given rule severities (from `severities`), findings in script order
+//statements that do not parse are reported under the `syntax` rule, what the semantic analyzer finds in the ones that do under `semantic`, findings on a line right below a
+//`-- sqlparser-disable-next-line` comment naming their rule (or no rule at all) are left out, and names in such a comment that are not rules are reported under `suppression`
+pub fn lint(sql: &str, dialect: Dialect, severities: &[(&'static str, Severity)]) -> Vec<Finding> {
+    let severity_of = |name: &str| severities.iter().find(|(rule, _)| *rule == name).map_or(Severity::Off, |(_, severity)| *severity);
+
+    let mut comments = Vec::new();
+    //the tokens of every statement, and whether the statement has a lexical error (which is reported instead of parsing it)
+    let mut statements: Vec<(Vec<SpannedToken>, bool)> = vec![(Vec::new(), false)];
+    let mut findings = Vec::new();
+    let results: Vec<_> = Tokenizer::with_dialect(sql, dialect).keep_comments(true).spanned().collect();
+    let mut ends = StatementEnds::default();
+    for (i, result) in results.iter().enumerate() {
+        match result {
+            Ok(SpannedToken { token: Token::Comment { text, .. }, span }) => comments.push((text.clone(), *span)),
+            Ok(spanned) => {
+                let next = results[i + 1..].iter().filter_map(|result| result.as_ref().ok()).map(|next| &next.token).find(|tok| !matches!(tok, Token::Comment { .. }));
+                if ends.ends_statement(&spanned.token, next.unwrap_or(&Token::Eof)) {
+                    statements.push((Vec::new(), false));
+                } else {
+                    statements.last_mut().unwrap().0.push(spanned.clone());
+                }
+            }
+            Err(err) => {
+                findings.push(Finding { rule: SYNTAX, severity: severity_of(SYNTAX), message: err.message.clone(), span: err.span, fix: Vec::new() });
+                statements.last_mut().unwrap().1 = true;
+            }
+        }
+    }
+
+    for (tokens, _) in statements.iter().filter(|(tokens, lex_error)| !tokens.is_empty() && !lex_error) {
+        let span = Span {
+            end: tokens.last().unwrap().span.end,
+            ..tokens[0].span
+        };
+        let mut stmt_tokens: Vec<Token> = tokens.iter().map(|spanned| spanned.token.clone()).collect();
+        stmt_tokens.push(Token::Semicolon);
+        let stmt = match Parser::with_dialect(stmt_tokens, dialect).parse_statement() {
+            Ok(stmt) => stmt,
+            Err(message) => {
+                findings.push(Finding { rule: SYNTAX, severity: severity_of(SYNTAX), message, span, fix: Vec::new() });
+                continue;
+            }
+        };
+        for diagnostic in semantic::analyze_with_dialect(&stmt, tokens, dialect) {
+            findings.push(Finding { rule: SEMANTIC, severity: severity_of(SEMANTIC), message: diagnostic.message, span: diagnostic.span, fix: Vec::new() });
+        }
+        let context = Context { stmt: &stmt, tokens, span, source: sql, dialect };
+        for rule in RULES {
+            let severity = severity_of(rule.name);
+            if severity == Severity::Off {
+                continue;
+            }
+            for problem in (rule.check)(&context) {
+                findings.push(Finding { rule: rule.name, severity, message: problem.message, span: problem.span, fix: problem.fix });
+            }
+        }
+    }
+
+    for (text, span) in &comments {
+        for name in disabled_rules(text).unwrap_or_default().into_iter().filter(|name| !is_rule(name)) {
+            let message = format!("Unknown lint rule {} in a {} comment, it turns nothing off", name, DISABLE_NEXT_LINE);
+            findings.push(Finding { rule: SUPPRESSION, severity: severity_of(SUPPRESSION), message, span: *span, fix: Vec::new() });
+        }
+    }
+
+    findings.retain(|finding| finding.severity != Severity::Off && !is_suppressed(finding, &comments));
+    findings.sort_by_key(|finding| finding.span.start);
+    findings
+}
+
+/// `lint` for a script taken out of a larger file that starts at `origin` in it, see `Origin`. The spans of the findings and the offsets of their edits are in the host file, so `apply_fixes` can be given the host file's text.
+pub fn lint_embedded(sql: &str, dialect: Dialect, severities: &[(&'static str, Severity)], origin: Origin) -> Vec<Finding> {
+    let mut findings = lint(sql, dialect, severities);
+    for finding in &mut findings {
+        finding.span = origin.map(finding.span);
+        for edit in &mut finding.fix {
+            edit.start += origin.offset;
+            edit.end += origin.offset;
+        }
+    }
+    findings
+}
+
+//apply the fixes of the findings to the script they were found in, returns the fixed script and how many fixes went in
+//a fix is applied whole or not at all, one that overlaps a fix applied before it is left for the next round of linting
+pub fn apply_fixes(sql: &str, findings: &[Finding]) -> (String, usize) {
+    let mut edits: Vec<&Edit> = Vec::new();
+    let mut applied = 0;
+    for fix in findings.iter().map(|finding| &finding.fix).filter(|fix| !fix.is_empty()) {
+        let overlaps = |a: &Edit, b: &Edit| (a.start < b.end && b.start < a.end) || (a.start == b.start && (a.start == a.end || b.start == b.end));
+        if fix.iter().all(|edit| edits.iter().all(|taken| !overlaps(edit, taken))) {
+            edits.extend(fix);
+            applied += 1;
+        }
+    }
+    edits.sort_by_key(|edit| (edit.start, edit.end));
+
+    let mut fixed = String::with_capacity(sql.len());
+    let mut pos = 0;
+    for edit in edits {
+        fixed.push_str(&sql[pos..edit.start]);
+        fixed.push_str(&edit.replacement);
+        pos = edit.end;
+    }
+    fixed.push_str(&sql[pos..]);
+    (fixed, applied)
+}
+
+//helper, whether a comment on the line above the finding turns its rule off
+fn is_suppressed(finding: &Finding, comments: &[(String, Span)]) -> bool {
+    comments.iter().any(|(text, span)| {
+        span.line + 1 == finding.span.line && disabled_rules(text).is_some_and(|names| names.is_empty() || names.contains(&finding.rule))
+    })
+}
+
+//helper, the rule names of a suppression comment (none for every rule), None for a comment that is not one
+fn disabled_rules(text: &str) -> Option<Vec<&str>> {
+    let rules = text.strip_prefix(DISABLE_NEXT_LINE)?;
+    Some(rules.split(|c: char| c == ',' || c.is_whitespace()).filter(|name| !name.is_empty()).collect())
+}
+
+//helper, whether a name is the name of a rule or of a pseudo rule
+fn is_rule(name: &str) -> bool {
+    name == SYNTAX || name == SEMANTIC || name == SUPPRESSION || RULES.iter().any(|rule| rule.name == name)
+}
+
+//`*` where a select list item starts
+fn select_star(context: &Context) -> Vec<Problem> {
+    let tokens = context.tokens;
+    (1..tokens.len())
+        .filter(|&i| tokens[i].token == Token::Star && matches!(tokens[i - 1].token, Token::Keyword(Keyword::Select) | Token::Comma))
+        .map(|i| problem(tokens[i].span, "SELECT * selects every column of the tables, list the columns instead".to_string()))
+        .collect()
+}
+
+//`=` or `!=` with NULL on one side in a condition. The comparisons come from the syntax tree, the `=` of an
+//assignment (`SET a = NULL`, `UPDATE SET a = NULL` in ON CONFLICT and MERGE) is none
+fn null_comparison(context: &Context) -> Vec<Problem> {
+    let mut conditions = Vec::new();
+    statement_conditions(context.stmt, &mut conditions);
+    let mut found = Vec::new();
+    for (n, (_, condition)) in conditions.iter().enumerate() {
+        let span = condition_span(context, &conditions, n);
+        let mut comparisons = Vec::new();
+        null_comparisons(condition, &mut comparisons);
+        for (comparison, operand, negated) in comparisons {
+            let operator = if negated { UnaryOperator::IsNotNull } else { UnaryOperator::IsNull };
+            let test = Expression::UnaryOperation { operand: Box::new(operand.clone()), operator };
+            let message = format!("{} is never true, use {}", format_expression(comparison), format_expression(&test));
+            found.push(problem(span, message));
+        }
+    }
+    found
+}
+
+//helper, the comparisons with NULL in a condition with the other operand and whether it is `!=`, the conditions of
+//subqueries are conditions of their own
+fn null_comparisons<'a>(expr: &'a Expression, out: &mut Vec<(&'a Expression, &'a Expression, bool)>) {
+    let is_null = |operand: &Expression| matches!(operand, Expression::Literal(Literal::Null));
+    match expr {
+        Expression::BinaryOperation { left_operand, operator: operator @ (BinaryOperator::Equal | BinaryOperator::NotEqual), right_operand }
+            if is_null(left_operand) || is_null(right_operand) => {
+            let operand = if is_null(left_operand) { right_operand } else { left_operand };
+            out.push((expr, operand, *operator == BinaryOperator::NotEqual));
+        }
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            null_comparisons(left_operand, out);
+            null_comparisons(right_operand, out);
+        }
+        Expression::UnaryOperation { operand, .. } => null_comparisons(operand, out),
+        Expression::Alias { expr, .. } => null_comparisons(expr, out),
+        Expression::Function { args, .. } => {
+            for arg in args {
+                null_comparisons(arg, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn missing_primary_key(context: &Context) -> Vec<Problem> {
+    match context.stmt {
+        //a table created from a query cannot declare a key
+        Statement::CreateTable { table_name, column_list, query: None, .. } if !column_list.iter().any(|column| column.constraints.contains(&Constraint::PrimaryKey)) => {
+            vec![problem(context.span, format!("Table {} has no PRIMARY KEY", table_name))]
+        }
+        _ => Vec::new(),
+    }
+}
+
+//reserved keywords only, a non-reserved one may be a name like the column `key`
+fn keyword_case(context: &Context) -> Vec<Problem> {
+    let mut found = Vec::new();
+    for spanned in context.tokens {
+        let Token::Keyword(keyword) = spanned.token else { continue };
+        let text = &context.source[spanned.span.start..spanned.span.end];
+        if keyword.is_reserved() && text != keyword.as_str() {
+            let fix = vec![Edit { start: spanned.span.start, end: spanned.span.end, replacement: keyword.as_str().to_string() }];
+            found.push(Problem { span: spanned.span, message: format!("Keyword {} should be written {}", text, keyword.as_str()), fix });
+        }
+    }
+    found
+}
+
+//the aliases come from the statement, the tokens only tell where they are and whether AS is there,
+//so words like SETS in GROUPING SETS are never taken for aliases
+fn explicit_alias(context: &Context) -> Vec<Problem> {
+    let mut aliases = Vec::new();
+    statement_aliases(context.stmt, &mut aliases);
+    let tokens = context.tokens;
+    let mut found = Vec::new();
+    for i in 1..tokens.len() {
+        let name = match &tokens[i].token {
+            Token::Identifier(name) => name.clone(),
+            Token::Keyword(keyword) if !keyword.is_reserved() => keyword.as_str().to_lowercase(),
+            _ => continue,
+        };
+        //an alias follows what it names: a name, a literal, a closing parenthesis
+        let follows_operand = match &tokens[i - 1].token {
+            Token::Identifier(_) | Token::String { .. } | Token::Number(_) | Token::Decimal(_) | Token::Placeholder(_) | Token::RightParentheses => true,
+            Token::Keyword(keyword) => !keyword.is_reserved() || matches!(keyword, Keyword::Null | Keyword::True | Keyword::False),
+            _ => false,
+        };
+        if follows_operand && aliases.contains(&name) {
+            let fix = vec![Edit { start: tokens[i].span.start, end: tokens[i].span.start, replacement: "AS ".to_string() }];
+            found.push(Problem { span: tokens[i].span, message: format!("Alias {} without AS", name), fix });
+        }
+    }
+    found
+}
+
+//every column and table alias of the statement, subqueries included
+fn statement_aliases(stmt: &Statement, aliases: &mut Vec<String>) {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, having, qualify, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                statement_aliases(&cte.query, aliases);
+            }
+            for table in from {
+                table_aliases(table, aliases);
+            }
+            for expr in columns.iter().chain(r#where).chain(having).chain(qualify) {
+                expression_aliases(expr, aliases);
+            }
+        }
+        Statement::SetOperation { with, left, right, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                statement_aliases(&cte.query, aliases);
+            }
+            statement_aliases(left, aliases);
+            statement_aliases(right, aliases);
+        }
+        Statement::Values { rows } => rows.iter().flatten().for_each(|expr| expression_aliases(expr, aliases)),
+        Statement::Insert { source, on_conflict, .. } => {
+            if let InsertSource::Query(query) = source {
+                statement_aliases(query, aliases);
+            }
+            for expr in source.rows().iter().flatten().chain(on_conflict.iter().flat_map(|on_conflict| on_conflict.expressions())) {
+                expression_aliases(expr, aliases);
+            }
+        }
+        Statement::Delete { where_clause: Some(expr), .. } => expression_aliases(expr, aliases),
+        Statement::Merge { alias, source, on, clauses, .. } => {
+            aliases.extend(alias.clone());
+            table_aliases(source, aliases);
+            for expr in std::iter::once(on).chain(clauses.iter().flat_map(|clause| clause.expressions())) {
+                expression_aliases(expr, aliases);
+            }
+        }
+        Statement::CreateTable { query: Some(query), .. } | Statement::Prepare { statement: query, .. } => statement_aliases(query, aliases),
+        Statement::Execute { args, .. } => args.iter().for_each(|arg| expression_aliases(arg, aliases)),
+        Statement::Delete { .. } | Statement::CreateTable { .. } | Statement::AlterTable { .. } | Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Deallocate { .. } => {}
+    }
+}
+
+fn table_aliases(table: &TableReference, aliases: &mut Vec<String>) {
+    match table {
+        TableReference::Table { alias, .. } | TableReference::Function { alias, .. } => aliases.extend(alias.clone()),
+        TableReference::Derived { subquery, alias, .. } => {
+            statement_aliases(subquery, aliases);
+            aliases.extend(alias.clone());
+        }
+        TableReference::Pivot { table, alias, .. } | TableReference::Unpivot { table, alias, .. } => {
+            table_aliases(table, aliases);
+            aliases.extend(alias.clone());
+        }
+        TableReference::Join { left, right, .. } => {
+            table_aliases(left, aliases);
+            table_aliases(right, aliases);
+        }
+    }
+}
+
+//column aliases, and the aliases of subqueries in an expression
+fn expression_aliases(expr: &Expression, aliases: &mut Vec<String>) {
+    match expr {
+        Expression::Alias { expr, alias } => {
+            expression_aliases(expr, aliases);
+            aliases.push(alias.clone());
+        }
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => statement_aliases(subquery, aliases),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_aliases(left_operand, aliases);
+            expression_aliases(right_operand, aliases);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_aliases(operand, aliases),
+        Expression::Function { args, .. } => {
+            for arg in args {
+                expression_aliases(arg, aliases);
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) | Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {}
+    }
+}
+
+//AND binds tighter than OR, which is easy to forget when the conditions are written on one line
+//the tokens tell where the parentheses are, every list in parentheses and every clause is checked on its own
+fn mixed_and_or(context: &Context) -> Vec<Problem> {
+    let tokens = context.tokens;
+    //position of the closing parenthesis for every opening one
+    let mut closing = vec![None; tokens.len()];
+    let mut open = Vec::new();
+    for (i, spanned) in tokens.iter().enumerate() {
+        match spanned.token {
+            Token::LeftParentheses => open.push(i),
+            Token::RightParentheses => {
+                if let Some(start) = open.pop() {
+                    closing[start] = Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut found = Vec::new();
+    let mut levels = vec![(0, tokens.len())];
+    while let Some((start, end)) = levels.pop() {
+        //where the OR operands of the condition start, and whether they have an AND
+        let mut operands: Vec<(usize, bool)> = vec![(start, false)];
+        let mut i = start;
+        while i <= end {
+            let token = tokens.get(i).map(|spanned| &spanned.token);
+            match token {
+                Some(Token::LeftParentheses) if closing[i].is_some() => {
+                    let close = closing[i].unwrap();
+                    levels.push((i + 1, close));
+                    i = close + 1;
+                    continue;
+                }
+                Some(Token::Keyword(Keyword::Or)) => operands.push((i + 1, false)),
+                Some(Token::Keyword(Keyword::And)) => operands.last_mut().unwrap().1 = true,
+                _ if i == end || ends_condition(tokens, i) => {
+                    if operands.len() > 1 && operands.iter().any(|(_, has_and)| *has_and) {
+                        found.extend(parenthesize(context, &operands, i));
+                    }
+                    operands = vec![(i + 1, false)];
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+    found
+}
+
+//tokens that end a condition: commas, and reserved keywords other than the ones of expressions
+//(FROM ends one too, unless it is the FROM of IS DISTINCT FROM)
+fn ends_condition(tokens: &[SpannedToken], i: usize) -> bool {
+    match tokens[i].token {
+        Token::Comma | Token::RightParentheses => true,
+        Token::Keyword(Keyword::From) => i == 0 || tokens[i - 1].token != Token::Keyword(Keyword::Distinct),
+        Token::Keyword(keyword) => keyword.is_reserved() && !matches!(keyword,
+            Keyword::And | Keyword::Or | Keyword::Not | Keyword::Null | Keyword::True | Keyword::False
+            | Keyword::Is | Keyword::Distinct | Keyword::Exists | Keyword::Regexp | Keyword::Rlike),
+        _ => false,
+    }
+}
+
+//the problem for a condition mixing AND and OR, its OR operands starting at `operands` and the condition ending before `end`
+//the message shows how the condition is read, the fix puts the AND chains in parentheses,
+//and is only offered when the statement parses to the same tree with them (so a condition cut wrong is never changed)
+fn parenthesize(context: &Context, operands: &[(usize, bool)], end: usize) -> Option<Problem> {
+    let tokens = context.tokens;
+    let (start, _) = operands[0];
+    if start >= end {
+        return None;
+    }
+    let mut fix = Vec::new();
+    let mut grouped = Vec::new();
+    let mut with_parentheses: Vec<Token> = Vec::new();
+    let mut next = 0;
+    for (n, &(operand_start, has_and)) in operands.iter().enumerate() {
+        //an operand ends at the OR before the next one
+        let operand_end = operands.get(n + 1).map_or(end, |(next_start, _)| next_start - 1);
+        if operand_start >= operand_end {
+            return None;
+        }
+        let (first, last) = (tokens[operand_start].span, tokens[operand_end - 1].span);
+        let text = &context.source[first.start..last.end];
+        with_parentheses.extend(tokens[next..operand_start].iter().map(|spanned| spanned.token.clone()));
+        next = operand_end;
+        let operand_tokens = tokens[operand_start..operand_end].iter().map(|spanned| spanned.token.clone());
+        if has_and {
+            grouped.push(format!("({})", text));
+            fix.push(Edit { start: first.start, end: first.start, replacement: "(".to_string() });
+            fix.push(Edit { start: last.end, end: last.end, replacement: ")".to_string() });
+            with_parentheses.push(Token::LeftParentheses);
+            with_parentheses.extend(operand_tokens);
+            with_parentheses.push(Token::RightParentheses);
+        } else {
+            grouped.push(text.to_string());
+            with_parentheses.extend(operand_tokens);
+        }
+    }
+    with_parentheses.extend(tokens[next..].iter().map(|spanned| spanned.token.clone()));
+    with_parentheses.push(Token::Semicolon);
+    if Parser::with_dialect(with_parentheses, context.dialect).parse_statement().as_ref() != Ok(context.stmt) {
+        fix.clear();
+    }
+    let message = format!("AND and OR mixed without parentheses, this is read as {}", grouped.join(" OR "));
+    Some(Problem { span: tokens[start].span, message, fix })
+}
+
+//the FROM and JOIN keywords of a statement, handed out in the order they are written
+struct JoinKeywords<'a> {
+    tokens: &'a [SpannedToken],
+    from: std::vec::IntoIter<usize>,
+    join: std::vec::IntoIter<usize>,
+}
+
+//the AST is walked in the order the statement is written, so the n-th FROM (of a SELECT) and JOIN met on the way are the
+//n-th FROM and JOIN of the tokens, which is where the problems are reported
+fn join_condition(context: &Context) -> Vec<Problem> {
+    let tokens = context.tokens;
+    let positions = |wanted: Keyword| -> Vec<usize> {
+        (0..tokens.len()).filter(|&i| tokens[i].token == Token::Keyword(wanted)).collect()
+    };
+    //the FROM of IS DISTINCT FROM and of DELETE FROM belong to no SELECT
+    let from: Vec<usize> = positions(Keyword::From).into_iter()
+        .filter(|&i| i > 0 && !matches!(tokens[i - 1].token, Token::Keyword(Keyword::Distinct | Keyword::Delete)))
+        .collect();
+    let mut keywords = JoinKeywords { tokens, from: from.into_iter(), join: positions(Keyword::Join).into_iter() };
+    let mut found = Vec::new();
+    statement_joins(context.stmt, &mut keywords, &mut found);
+    found
+}
+
+fn statement_joins(stmt: &Statement, keywords: &mut JoinKeywords, found: &mut Vec<Problem>) {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                statement_joins(&cte.query, keywords, found);
+            }
+            for expr in columns {
+                expression_joins(expr, keywords, found);
+            }
+            if let Some(from_keyword) = keywords.from.next() {
+                comma_joins(from, r#where.as_ref(), &from_commas(keywords.tokens, from_keyword), keywords.tokens, found);
+            }
+            for table in from {
+                table_joins(table, keywords, found);
+            }
+            let grouping = group_by.iter().flat_map(|element| match element {
+                GroupingElement::Expression(expr) => vec![expr],
+                GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => sets.iter().flatten().collect(),
+            });
+            for expr in r#where.iter().chain(grouping).chain(having).chain(qualify).chain(orderby).chain(limit) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        Statement::SetOperation { with, left, right, orderby, limit, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                statement_joins(&cte.query, keywords, found);
+            }
+            statement_joins(left, keywords, found);
+            statement_joins(right, keywords, found);
+            for expr in orderby.iter().chain(limit) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        Statement::Insert { source, on_conflict, .. } => {
+            if let InsertSource::Query(query) = source {
+                statement_joins(query, keywords, found);
+            }
+            for expr in source.rows().iter().flatten().chain(on_conflict.iter().flat_map(|on_conflict| on_conflict.expressions())) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        Statement::Delete { where_clause: Some(expr), .. } => expression_joins(expr, keywords, found),
+        Statement::Merge { source, on, clauses, .. } => {
+            table_joins(source, keywords, found);
+            for expr in std::iter::once(on).chain(clauses.iter().flat_map(|clause| clause.expressions())) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn table_joins(table: &TableReference, keywords: &mut JoinKeywords, found: &mut Vec<Problem>) {
+    match table {
+        TableReference::Table { sample, .. } => {
+            for expr in sample.iter().flat_map(|sample| std::iter::once(&sample.percentage).chain(&sample.repeatable)) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        TableReference::Derived { subquery, .. } => statement_joins(subquery, keywords, found),
+        TableReference::Function { args, .. } => {
+            for arg in args {
+                expression_joins(arg, keywords, found);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            table_joins(table, keywords, found);
+            for expr in std::iter::once(&pivot.aggregate).chain(std::iter::once(&pivot.column)).chain(&pivot.values) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        TableReference::Unpivot { table, .. } => table_joins(table, keywords, found),
+        TableReference::Join { left, right, kind, on } => {
+            table_joins(left, keywords, found);
+            let join_keyword = keywords.join.next();
+            table_joins(right, keywords, found);
+            if let Some(span) = join_keyword.map(|i| keywords.tokens[i].span) {
+                let right_name = item_name(right);
+                match on {
+                    None if *kind != JoinKind::Cross => found.push(problem(span, format!("JOIN of {} has no ON condition, write CROSS JOIN if every combination of rows is wanted", right_name))),
+                    Some(condition) => {
+                        let (left_names, right_names) = (names_of(left), names_of(right));
+                        let uses = |names: &[String]| condition_uses(condition, names);
+                        let missing = match (uses(&left_names), uses(&right_names)) {
+                            (Some(false), Some(false)) => Some("either side of the join".to_string()),
+                            (Some(false), _) => Some(format!("the tables before {}", right_name)),
+                            (_, Some(false)) => Some(right_name.clone()),
+                            _ => None,
+                        };
+                        if let Some(missing) = missing {
+                            found.push(problem(span, format!("ON condition of the JOIN of {} does not use {}", right_name, missing)));
+                        }
+                    }
+                    None => {}
+                }
+            }
+            if let Some(on) = on {
+                expression_joins(on, keywords, found);
+            }
+        }
+    }
+}
+
+fn expression_joins(expr: &Expression, keywords: &mut JoinKeywords, found: &mut Vec<Problem>) {
+    match expr {
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => statement_joins(subquery, keywords, found),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_joins(left_operand, keywords, found);
+            expression_joins(right_operand, keywords, found);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_joins(operand, keywords, found),
+        Expression::Alias { expr, .. } => expression_joins(expr, keywords, found),
+        Expression::Function { args, over, .. } => {
+            let window = over.iter().flat_map(|window| window.partition_by.iter().chain(&window.order_by));
+            for expr in args.iter().chain(window) {
+                expression_joins(expr, keywords, found);
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) | Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {}
+    }
+}
+
+//every FROM item after the first needs an AND-ed part of the WHERE condition that may relate it to the items before it
+fn comma_joins(from: &[TableReference], condition: Option<&Expression>, commas: &[usize], tokens: &[SpannedToken], found: &mut Vec<Problem>) {
+    let mut conditions = Vec::new();
+    if let Some(condition) = condition {
+        conjuncts(condition, &mut conditions);
+    }
+    let mut before = Vec::new();
+    for (i, item) in from.iter().enumerate() {
+        let names = names_of(item);
+        if i > 0 && !conditions.iter().any(|condition| relates(condition, &before, &names)) {
+            if let Some(comma) = commas.get(i - 1) {
+                found.push(problem(tokens[*comma].span, format!("No WHERE condition relates {} to the tables before it, this is a cross join, write CROSS JOIN if it is meant", item_name(item))));
+            }
+        }
+        before.extend(names);
+    }
+}
+
+//the commas between the items of the FROM clause starting at `from_keyword`
+fn from_commas(tokens: &[SpannedToken], from_keyword: usize) -> Vec<usize> {
+    let mut commas = Vec::new();
+    let mut depth = 0;
+    for (i, spanned) in tokens.iter().enumerate().skip(from_keyword + 1) {
+        match spanned.token {
+            Token::LeftParentheses => depth += 1,
+            Token::RightParentheses if depth == 0 => break,
+            Token::RightParentheses => depth -= 1,
+            Token::Comma if depth == 0 => commas.push(i),
+            Token::Keyword(Keyword::Where | Keyword::Group | Keyword::Having | Keyword::Qualify | Keyword::Order | Keyword::Limit) if depth == 0 => break,
+            _ => {}
+        }
+    }
+    commas
+}
+
+//helper, the AND-ed parts of a condition
+fn conjuncts<'a>(condition: &'a Expression, out: &mut Vec<&'a Expression>) {
+    match condition {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand } => {
+            conjuncts(left_operand, out);
+            conjuncts(right_operand, out);
+        }
+        other => out.push(other),
+    }
+}
+
+//whether a condition may relate two groups of FROM items: it uses columns of both, or has columns without a qualifier
+//or a subquery, which may belong to either without a schema to tell
+fn relates(condition: &Expression, before: &[String], names: &[String]) -> bool {
+    let mut qualifiers = Vec::new();
+    if !column_qualifiers(condition, &mut qualifiers) {
+        return true;
+    }
+    let uses = |names: &[String]| qualifiers.iter().any(|qualifier| qualifier.as_ref().is_none_or(|qualifier| names.iter().any(|name| name.eq_ignore_ascii_case(qualifier))));
+    qualifiers.len() >= 2 && uses(before) && uses(names)
+}
+
+//whether an ON condition uses columns of one side of the join, `None` when it can not be told (columns without a qualifier or a subquery)
+fn condition_uses(condition: &Expression, names: &[String]) -> Option<bool> {
+    let mut qualifiers = Vec::new();
+    if !column_qualifiers(condition, &mut qualifiers) || qualifiers.iter().any(Option::is_none) {
+        return None;
+    }
+    Some(qualifiers.iter().flatten().any(|qualifier| names.iter().any(|name| name.eq_ignore_ascii_case(qualifier))))
+}
+
+//helper, the qualifier of every column reference in an expression (`None` for an unqualified one), false when there is a subquery
+fn column_qualifiers(expr: &Expression, out: &mut Vec<Option<String>>) -> bool {
+    match expr {
+        Expression::Identifier(_) => out.push(None),
+        Expression::CompoundIdentifier(parts) => out.push(Some(parts[parts.len() - 2].clone())),
+        Expression::Subquery(_) | Expression::Exists { .. } => return false,
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            return column_qualifiers(left_operand, out) && column_qualifiers(right_operand, out);
+        }
+        Expression::UnaryOperation { operand, .. } => return column_qualifiers(operand, out),
+        Expression::Alias { expr, .. } => return column_qualifiers(expr, out),
+        Expression::Function { args, over, .. } => {
+            let window = over.iter().flat_map(|window| window.partition_by.iter().chain(&window.order_by));
+            return args.iter().chain(window).all(|expr| column_qualifiers(expr, out));
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) => {}
+    }
+    true
+}
+
+//aliases of tables in FROM clauses, subqueries included, that no `alias.column` in the statement uses
+//the tokens tell what is used, so an alias is taken to be used when any query of the statement qualifies with its name
+fn unused_alias(context: &Context) -> Vec<Problem> {
+    let mut aliases = Vec::new();
+    let mut stmt = context.stmt.clone();
+    for_each_select_mut(&mut stmt, &mut |select| {
+        if let Statement::Select { from, .. } = select {
+            for table in from.iter() {
+                table_item_aliases(table, &mut aliases);
+            }
+        }
+    });
+    let tokens = context.tokens;
+    let name_at = |i: usize| match &tokens[i].token {
+        Token::Identifier(name) => Some(name.clone()),
+        Token::Keyword(keyword) if !keyword.is_reserved() => Some(keyword.as_str().to_lowercase()),
+        _ => None,
+    };
+    let qualifiers: Vec<String> = (0..tokens.len().saturating_sub(1))
+        .filter(|&i| tokens[i + 1].token == Token::Period)
+        .filter_map(name_at)
+        .collect();
+    let mut found = Vec::new();
+    for (alias, table_name) in aliases {
+        if qualifiers.iter().any(|qualifier| qualifier.eq_ignore_ascii_case(&alias)) {
+            continue;
+        }
+        //where the alias is defined: right after AS or the table name
+        let defined = (1..tokens.len()).find(|&i| {
+            name_at(i).is_some_and(|name| name.eq_ignore_ascii_case(&alias))
+                && (tokens[i - 1].token == Token::Keyword(Keyword::As) || name_at(i - 1).is_some_and(|name| name.eq_ignore_ascii_case(&table_name)))
+        });
+        let span = defined.map_or(context.span, |i| tokens[i].span);
+        found.push(problem(span, format!("Alias {} of table {} is never used, qualify columns with it or leave it out", alias, table_name)));
+    }
+    found
+}
+
+//helper, the aliases of the tables of a FROM item with the names of the tables, derived tables and table functions
+//are left out as they may need an alias whether it is used or not
+fn table_item_aliases(table: &TableReference, out: &mut Vec<(String, String)>) {
+    match table {
+        TableReference::Table { name, alias: Some(alias), .. } => out.push((alias.clone(), name.clone())),
+        TableReference::Pivot { table, .. } | TableReference::Unpivot { table, .. } => table_item_aliases(table, out),
+        TableReference::Join { left, right, .. } => {
+            table_item_aliases(left, out);
+            table_item_aliases(right, out);
+        }
+        TableReference::Table { .. } | TableReference::Derived { .. } | TableReference::Function { .. } => {}
+    }
+}
+
+//AND-ed parts of conditions that constant folding finds always true or never true, and parts that bound a column so
+//that no value is left together with an earlier part, `x > 10 AND x < 5`
+//conditions are found in the order they are written, so the n-th one with a keyword is reported at the n-th token of that keyword
+fn dead_predicate(context: &Context) -> Vec<Problem> {
+    let mut conditions = Vec::new();
+    statement_conditions(context.stmt, &mut conditions);
+    let mut found = Vec::new();
+    for (n, (keyword, condition)) in conditions.iter().enumerate() {
+        let span = condition_span(context, &conditions, n);
+        let clause = keyword.as_str();
+        let mut parts = Vec::new();
+        conjuncts(condition, &mut parts);
+        let mut bounds: Vec<(String, BinaryOperator, Literal, &Expression)> = Vec::new();
+        for part in parts {
+            let text = format_expression(part);
+            match fold(part) {
+                Some(Literal::Boolean(true)) => found.push(problem(span, format!("{} is always true, it can be left out of the {} condition", text, clause))),
+                Some(Literal::Boolean(false) | Literal::Null) => found.push(problem(span, format!("{} is never true, no row satisfies the {} condition", text, clause))),
+                _ => {
+                    let Some((column, operator, value)) = column_bound(part) else { continue };
+                    let contradicted = bounds.iter().find(|(earlier, earlier_operator, earlier_value, _)| {
+                        *earlier == column && contradicts((*earlier_operator, earlier_value), (operator, &value))
+                    });
+                    if let Some((_, _, _, earlier)) = contradicted {
+                        found.push(problem(span, format!("{} contradicts {}, no row satisfies the {} condition", text, format_expression(earlier), clause)));
+                    }
+                    bounds.push((column, operator, value, part));
+                }
+            }
+        }
+    }
+    found
+}
+
+//helper, where the n-th condition is reported: at its keyword, the n-th WHERE of the AST being the n-th WHERE token
+fn condition_span(context: &Context, conditions: &[(Keyword, &Expression)], n: usize) -> Span {
+    let keyword = conditions[n].0;
+    let nth = conditions[..n].iter().filter(|(earlier, _)| *earlier == keyword).count();
+    context.tokens.iter().filter(|spanned| spanned.token == Token::Keyword(keyword)).nth(nth).map_or(context.span, |spanned| spanned.span)
+}
+
+//helper, a comparison of a column with a constant as the column (lower case), the operator and the constant,
+//turned around when the constant comes first, `10 < x` is `x > 10`
+fn column_bound(expr: &Expression) -> Option<(String, BinaryOperator, Literal)> {
+    let Expression::BinaryOperation { left_operand, operator, right_operand } = expr else {
+        return None;
+    };
+    let operator = match operator {
+        BinaryOperator::Equal | BinaryOperator::NotEqual | BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual
+        | BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual => *operator,
+        _ => return None,
+    };
+    let is_column = |expr: &Expression| matches!(expr, Expression::Identifier(_) | Expression::CompoundIdentifier(_));
+    let (column, operator, value) = if is_column(left_operand) {
+        (left_operand, operator, fold(right_operand)?)
+    } else if is_column(right_operand) {
+        (right_operand, swapped_operator(operator)?, fold(left_operand)?)
+    } else {
+        return None;
+    };
+    if value == Literal::Null {
+        return None;
+    }
+    Some((format_expression(column).to_lowercase(), operator, value))
+}
+
+//helper, whether no value satisfies two bounds of one column
+fn contradicts((first, a): (BinaryOperator, &Literal), (second, b): (BinaryOperator, &Literal)) -> bool {
+    let holds = |value: &Literal, operator: BinaryOperator, bound: &Literal| {
+        fold(&Expression::BinaryOperation {
+            left_operand: Box::new(Expression::Literal(value.clone())),
+            operator,
+            right_operand: Box::new(Expression::Literal(bound.clone())),
+        })
+    };
+    let lower = |operator| matches!(operator, BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual);
+    let upper = |operator| matches!(operator, BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual);
+    //a range from a lower to an upper bound is empty when the bounds cross, or meet with one of them excluded
+    let empty = |low: &Literal, low_operator, high: &Literal, high_operator| match compare(low, high) {
+        Some(std::cmp::Ordering::Greater) => true,
+        Some(std::cmp::Ordering::Equal) => low_operator == BinaryOperator::GreaterThan || high_operator == BinaryOperator::LessThan,
+        _ => false,
+    };
+    match (first, second) {
+        (BinaryOperator::Equal, _) => holds(a, second, b) == Some(Literal::Boolean(false)),
+        (_, BinaryOperator::Equal) => holds(b, first, a) == Some(Literal::Boolean(false)),
+        _ if lower(first) && upper(second) => empty(a, first, b, second),
+        _ if upper(first) && lower(second) => empty(b, second, a, first),
+        _ => false,
+    }
+}
+
+//every condition of a statement with the keyword it follows, in the order they are written, subqueries included
+fn statement_conditions<'a>(stmt: &'a Statement, out: &mut Vec<(Keyword, &'a Expression)>) {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                statement_conditions(&cte.query, out);
+            }
+            for expr in columns {
+                expression_conditions(expr, out);
+            }
+            for table in from {
+                table_conditions(table, out);
+            }
+            if let Some(condition) = r#where {
+                out.push((Keyword::Where, condition));
+                expression_conditions(condition, out);
+            }
+            for element in group_by {
+                match element {
+                    GroupingElement::Expression(expr) => expression_conditions(expr, out),
+                    GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => {
+                        for expr in sets.iter().flatten() {
+                            expression_conditions(expr, out);
+                        }
+                    }
+                }
+            }
+            for (keyword, condition) in [(Keyword::Having, having), (Keyword::Qualify, qualify)] {
+                if let Some(condition) = condition {
+                    out.push((keyword, condition));
+                    expression_conditions(condition, out);
+                }
+            }
+            for expr in orderby.iter().chain(limit) {
+                expression_conditions(expr, out);
+            }
+        }
+        Statement::SetOperation { with, left, right, orderby, limit, .. } => {
+            for cte in with.iter().flat_map(|with| &with.ctes) {
+                statement_conditions(&cte.query, out);
+            }
+            statement_conditions(left, out);
+            statement_conditions(right, out);
+            for expr in orderby.iter().chain(limit) {
+                expression_conditions(expr, out);
+            }
+        }
+        Statement::CreateTable { column_list, query, .. } => {
+            for constraint in column_list.iter().flat_map(|column| &column.constraints) {
+                if let Constraint::Check(condition) = constraint {
+                    out.push((Keyword::Check, condition));
+                }
+            }
+            if let Some(query) = query {
+                statement_conditions(query, out);
+            }
+        }
+        Statement::AlterTable { action: AlterAction::AddColumn(column), .. } => {
+            for constraint in &column.constraints {
+                if let Constraint::Check(condition) = constraint {
+                    out.push((Keyword::Check, condition));
+                }
+            }
+        }
+        Statement::Values { rows } => {
+            for expr in rows.iter().flatten() {
+                expression_conditions(expr, out);
+            }
+        }
+        Statement::Insert { source, on_conflict, .. } => {
+            match source {
+                InsertSource::Values(rows) => {
+                    for expr in rows.iter().flatten() {
+                        expression_conditions(expr, out);
+                    }
+                }
+                InsertSource::Query(query) => statement_conditions(query, out),
+            }
+            if let Some(OnConflict { action: ConflictAction::Update { assignments, where_clause }, .. }) = on_conflict {
+                for assignment in assignments {
+                    expression_conditions(&assignment.value, out);
+                }
+                if let Some(condition) = where_clause {
+                    out.push((Keyword::Where, condition));
+                    expression_conditions(condition, out);
+                }
+            }
+        }
+        Statement::Delete { where_clause: Some(condition), .. } => {
+            out.push((Keyword::Where, condition));
+            expression_conditions(condition, out);
+        }
+        //the conditions of the arms follow an AND, which is no clause of its own to report them at
+        Statement::Merge { source, on, clauses, .. } => {
+            table_conditions(source, out);
+            out.push((Keyword::On, on));
+            expression_conditions(on, out);
+            for expr in clauses.iter().flat_map(|clause| clause.expressions()) {
+                expression_conditions(expr, out);
+            }
+        }
+        Statement::Prepare { statement, .. } => statement_conditions(statement, out),
+        Statement::Execute { args, .. } => {
+            for arg in args {
+                expression_conditions(arg, out);
+            }
+        }
+        Statement::Delete { .. } | Statement::AlterTable { .. } | Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Deallocate { .. } => {}
+    }
+}
+
+fn table_conditions<'a>(table: &'a TableReference, out: &mut Vec<(Keyword, &'a Expression)>) {
+    match table {
+        TableReference::Table { sample, .. } => {
+            for expr in sample.iter().flat_map(|sample| std::iter::once(&sample.percentage).chain(&sample.repeatable)) {
+                expression_conditions(expr, out);
+            }
+        }
+        TableReference::Derived { subquery, .. } => statement_conditions(subquery, out),
+        TableReference::Function { args, .. } => {
+            for arg in args {
+                expression_conditions(arg, out);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            table_conditions(table, out);
+            for expr in std::iter::once(&pivot.aggregate).chain(std::iter::once(&pivot.column)).chain(&pivot.values) {
+                expression_conditions(expr, out);
+            }
+        }
+        TableReference::Unpivot { table, .. } => table_conditions(table, out),
+        TableReference::Join { left, right, on, .. } => {
+            table_conditions(left, out);
+            table_conditions(right, out);
+            if let Some(condition) = on {
+                out.push((Keyword::On, condition));
+                expression_conditions(condition, out);
+            }
+        }
+    }
+}
+
+//the conditions of the subqueries in an expression
+fn expression_conditions<'a>(expr: &'a Expression, out: &mut Vec<(Keyword, &'a Expression)>) {
+    match expr {
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => statement_conditions(subquery, out),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_conditions(left_operand, out);
+            expression_conditions(right_operand, out);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_conditions(operand, out),
+        Expression::Alias { expr, .. } => expression_conditions(expr, out),
+        Expression::Function { args, over, .. } => {
+            let window = over.iter().flat_map(|window| window.partition_by.iter().chain(&window.order_by));
+            for expr in args.iter().chain(window) {
+                expression_conditions(expr, out);
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) | Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {}
+    }
+}
+
+//common table expressions that nothing reads, WITH clauses of subqueries included. A query reads one when a table it
+//uses has its name once the WITH clause is taken away; a recursive one reading itself does not count
+fn unused_cte(context: &Context) -> Vec<Problem> {
+    let mut unused = Vec::new();
+    let mut stmt = context.stmt.clone();
+    for_each_select_mut(&mut stmt, &mut |select| {
+        let (Statement::Select { with, .. } | Statement::SetOperation { with, .. }) = select else { return };
+        let Some(with) = with.take() else { return };
+        let reads = |uses: &[TableUse], name: &str| uses.iter().any(|table| table.name.eq_ignore_ascii_case(name));
+        let query_uses = table_uses(select);
+        for (i, cte) in with.ctes.iter().enumerate() {
+            let read = reads(&query_uses, &cte.name) || with.ctes.iter().enumerate().any(|(j, other)| j != i && reads(&table_uses(&other.query), &cte.name));
+            if !read {
+                unused.push(cte.name.clone());
+            }
+        }
+    });
+    let tokens = context.tokens;
+    let mut found = Vec::new();
+    for name in unused {
+        //where it is defined: the name right before AS or its column list
+        let defined = (0..tokens.len().saturating_sub(1)).find(|&i| {
+            let is_name = match &tokens[i].token {
+                Token::Identifier(text) => text.eq_ignore_ascii_case(&name),
+                Token::Keyword(keyword) => !keyword.is_reserved() && keyword.as_str().eq_ignore_ascii_case(&name),
+                _ => false,
+            };
+            is_name && matches!(tokens[i + 1].token, Token::Keyword(Keyword::As) | Token::LeftParentheses)
+        });
+        let span = defined.map_or(context.span, |i| tokens[i].span);
+        found.push(problem(span, format!("Common table expression {} is never read, leave it out of the WITH clause", name)));
+    }
+    found
+}
+
+//helper, from_names as a vector
+fn names_of(table: &TableReference) -> Vec<String> {
+    let mut names = Vec::new();
+    from_names(table, &mut names);
+    names
+}
+
+//helper, how a FROM item is named in messages
+fn item_name(table: &TableReference) -> String {
+    match names_of(table).as_slice() {
+        [] => "a subquery".to_string(),
+        names => names.join(", "),
+    }
+}
+
+//helper, a problem without a fix
+fn problem(span: Span, message: String) -> Problem {
+    Problem { span, message, fix: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_bodies_are_linted_whole() {
+        let sql = "CREATE TRIGGER t1_audit BEFORE INSERT ON t1 FOR EACH ROW BEGIN\n  SET NEW.a = 0;\nEND;\nSELECT a FROM t1;";
+        let findings = lint(sql, Dialect::MySql, &severities(&Config::default()).unwrap());
+        assert!(findings.iter().all(|finding| finding.rule != SYNTAX), "{:?}", findings);
+    }
+
+    #[test]
+    fn unknown_rules_in_suppressions_are_reported() {
+        let sql = "-- sqlparser-disable-next-line selct-star, null-comparison\nSELECT * FROM t1 WHERE a = NULL;";
+        let findings = lint(sql, Dialect::default(), &severities(&Config::default()).unwrap());
+        let rules: Vec<&str> = findings.iter().map(|finding| finding.rule).collect();
+        assert_eq!(rules, [SUPPRESSION, "select-star"]);
+        assert!(findings[0].message.contains("selct-star"), "{}", findings[0].message);
+    }
+}
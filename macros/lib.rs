@@ -0,0 +1,55 @@
+//the `sql!` macro, SQL checked by the parser when the program using it is compiled
+//a procedural macro crate of its own, built against the library (see "Compile-time checks" in the README):
+//it has no dependencies besides the library, so the tokens it gets are read by hand
+extern crate proc_macro;
+extern crate sqlparser;
+
+use proc_macro::{TokenStream, TokenTree, Span, Ident, Punct, Spacing, Group, Delimiter, Literal};
+use sqlparser::dialect::Dialect;
+use sqlparser::embedded::string_value;
+use sqlparser::parser::parse_embedded;
+use sqlparser::tokenizer::Origin;
+
+/// Checks a SQL script when the crate using it is compiled, and expands to the string literal unchanged. The script has to parse as a whole: `sql!("SELECT id FROM users;")` compiles, `sql!("SELECT id FROM;")` stops the build with the parser's error on the literal, with the line and column of the error in the SQL. A dialect can come first, as it is named to `--dialect`: `sql!(postgres, "SELECT data->>'name' FROM t;")`.
+#[proc_macro]
+pub fn sql(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let (dialect, literal) = match tokens.as_slice() {
+        [TokenTree::Literal(literal)] => (Dialect::default(), literal),
+        [TokenTree::Ident(name), TokenTree::Punct(comma), TokenTree::Literal(literal)] if comma.as_char() == ',' => {
+            match Dialect::from_name(&name.to_string()) {
+                Some(dialect) => (dialect, literal),
+                None => return compile_error(&format!("Unknown dialect: {:?}", name.to_string()), name.span()),
+            }
+        }
+        _ => return compile_error("Expected a string literal, optionally after a dialect: sql!(\"SELECT ...\") or sql!(postgres, \"SELECT ...\")", Span::call_site()),
+    };
+    let sql = match string_value(&literal.to_string()) {
+        Some(sql) => sql,
+        None => return compile_error("Expected a string literal", literal.span()),
+    };
+    //the span of a literal can not be narrowed down to a part of it, so the error points at the whole literal and
+    //locates itself in the SQL text
+    match parse_embedded(&sql, dialect, Origin::default()) {
+        Ok(_) => TokenTree::Literal(literal.clone()).into(),
+        Err(err) => compile_error(&format!("Invalid SQL: {}", err), literal.span()),
+    }
+}
+
+//helper, `compile_error!("message")` with every token at `span`, so the error is reported there
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(message).into());
+    args.set_span(span);
+    let tokens = vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct({
+            let mut bang = Punct::new('!', Spacing::Alone);
+            bang.set_span(span);
+            bang
+        }),
+        TokenTree::Group(args),
+    ];
+    tokens.into_iter().collect()
+}
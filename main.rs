@@ -1,16 +1,1010 @@
-mod token;
-mod tokenizer;
-mod parser;
-mod statement;
+//the command line interface needs the whole library: build it with every feature (see "Features" in the README)
+#[cfg(not(feature = "cli"))]
+compile_error!("the command line interface needs the cli feature");
 
-use std::io::{self, Write};
-use tokenizer::Tokenizer;
-use parser::Parser;
+extern crate sqlparser;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+use sqlparser::tokenizer::{Tokenizer, SpannedToken};
+use sqlparser::parser::{Parser, ParseMode};
+use sqlparser::token::Token;
+use sqlparser::conformance::{self, CaseOutcome};
+use sqlparser::batch;
+use sqlparser::formatter;
+use sqlparser::dialect::Dialect;
+use sqlparser::config::{Config, OutputFormat};
+use sqlparser::formatter::{Layout, CommentPolicy, CommentPlacement, CommentAttachment, KeywordCase};
+use sqlparser::json::{self, JsonValue, ToJson};
+use sqlparser::diff;
+use sqlparser::similarity;
+use sqlparser::analysis;
+use sqlparser::rewrite;
+use sqlparser::infer;
+use sqlparser::migration;
+use sqlparser::advisor::{self, Catalog};
+use sqlparser::workload;
+use sqlparser::session::Session;
+use sqlparser::engine::{Engine, Outcome};
+use sqlparser::table_render::{self, TableStyle, Border, ResultFormat};
+use sqlparser::semantic;
+use sqlparser::statement::Statement;
+use sqlparser::normalize::{self, ColumnOrder};
+use sqlparser::lint::{self, Severity};
+use sqlparser::embedded;
+
+//options accepted by every command, defaults come from the configuration file
+struct Options {
+    dialect: Dialect,
+    keep_hints: bool,
+    mode: ParseMode,
+    output: OutputFormat,
+    layout: Layout,
+    comments: CommentPolicy,
+    keyword_case: KeywordCase,
+    lint: Vec<(&'static str, Severity)>,
+    embedded_functions: Vec<String>,
+}
 
 fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let options = take_options(&mut args);
+    match args.first().map(|s| s.as_str()) {
+        Some("conformance") => run_conformance(&args[1..]),
+        Some("parse") => run_parse(&args[1..], &options),
+        Some("format") => run_format(&args[1..], &options),
+        Some("tokens") => run_tokens(&args[1..], &options),
+        Some("tables") => run_tables(&args[1..], &options),
+        Some("subqueries") => run_subqueries(&args[1..], &options),
+        Some("summary") => run_summary(&args[1..], &options),
+        Some("to-json") => run_to_json(&args[1..], &options),
+        Some("from-json") => run_from_json(&args[1..], &options),
+        Some("diff") => run_diff(&args[1..], &options),
+        Some("similar") => run_similar(&args[1..], &options),
+        Some("lint") => run_lint(&args[1..], &options),
+        Some("check-rust") => run_check_rust(&args[1..], &options),
+        Some("infer-schema") => run_infer_schema(&args[1..], &options),
+        Some("normalize") => run_normalize(&args[1..], &options),
+        Some("squash") => run_squash(&args[1..], &options),
+        Some("rename-table") => run_rename(&args[1..], 2, &options),
+        Some("rename-column") => run_rename(&args[1..], 3, &options),
+        Some("resolve") => run_resolve(&args[1..], &options),
+        Some("suggest-indexes") => run_suggest_indexes(&args[1..], &options),
+        Some("workload") => run_workload(&args[1..], &options),
+        #[cfg(feature = "arbitrary")]
+        Some("roundtrip") => run_roundtrip(&args[1..]),
+        Some("repl") => run_repl(&args[1..], &options),
+        Some("bench") => run_bench(&args[1..], &options),
+        Some(other) => {
+            eprintln!(" Unknown command: {}", other);
+            eprintln!(" Usage: main [--dialect <name>] [--keep-hints] [--permissive] [--output debug|json|summary] [--summary] [conformance <dir> [--bless] | parse [--recursive] [--jobs N] <path>... | format [--comments leading|trailing] [--attach-comments statement|clause] [--keyword-case upper|lower|preserve] [--source-map <file> | --lines <ranges>] [file] | tokens [file] | tables [file] | subqueries [file] | summary [file] | to-json [file] | from-json [file] | diff <old> <new> | similar [--threshold T] [file] | lint [--rules] [--fix] [file] | check-rust [--functions <names>] <path>... | infer-schema [file] | normalize [--columns as-written|name|keys-first] [file] | squash <path>... | rename-table <old> <new> [file] | rename-column <table> <old> <new> [file] | resolve [file] | suggest-indexes <schema> [file] | workload [--top N] [file] | repl [--catalog <file>] [--execute] [--unicode] [--max-width N] [--output-format table|csv|json] | bench [rows]]");
+            process::exit(2);
+        }
+        None => run_repl(&[], &options),
+    }
+}
+
+//parse a script (read from `source`) with the dialect and mode of the options, the clauses skipped in permissive mode are
+//reported as warnings
+fn parse_tokens(tokens: Vec<Token>, source: &[u8], options: &Options) -> Result<Vec<Statement>, String> {
+    let mut parser = Parser::with_dialect(tokens, options.dialect).mode(options.mode).source(source);
+    let stmts = parser.parse_statements()?;
+    for clause in parser.skipped() {
+        eprintln!(" Warning: statement {}: skipped {}", clause.statement + 1, formatter::format_tokens(&clause.tokens));
+    }
+    Ok(stmts)
+}
+
+//remove the global options from the argument list, they override the configuration file
+fn take_options(args: &mut Vec<String>) -> Options {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!(" Error in configuration file {}", err);
+            process::exit(2);
+        }
+    };
+    let lint = match lint::severities(&config) {
+        Ok(lint) => lint,
+        Err(err) => {
+            eprintln!(" Error in configuration: {}", err);
+            process::exit(2);
+        }
+    };
+    let embedded_functions = config.embedded_functions.clone().unwrap_or_else(|| embedded::DEFAULT_FUNCTIONS.iter().map(|name| name.to_string()).collect());
+    let mut options = Options { dialect: config.dialect, keep_hints: config.keep_hints, mode: config.mode, output: config.output, layout: config.layout, comments: config.comments, keyword_case: config.keyword_case, lint, embedded_functions };
+    if let Some(pos) = args.iter().position(|a| a == "--keep-hints") {
+        args.remove(pos);
+        options.keep_hints = true;
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--permissive") {
+        args.remove(pos);
+        options.mode = ParseMode::Permissive;
+    }
+    while let Some(pos) = args.iter().position(|a| a == "--dialect") {
+        let name = if pos + 1 < args.len() { args.remove(pos + 1) } else { String::new() };
+        args.remove(pos);
+        options.dialect = match Dialect::from_name(&name) {
+            Some(dialect) => dialect,
+            None => {
+                eprintln!(" Unknown dialect: {:?}", name);
+                process::exit(2);
+            }
+        };
+    }
+    //shorthand for --output summary
+    if let Some(pos) = args.iter().position(|a| a == "--summary") {
+        args.remove(pos);
+        options.output = OutputFormat::Summary;
+    }
+    while let Some(pos) = args.iter().position(|a| a == "--output") {
+        let name = if pos + 1 < args.len() { args.remove(pos + 1) } else { String::new() };
+        args.remove(pos);
+        options.output = match OutputFormat::from_name(&name) {
+            Some(output) => output,
+            None => {
+                eprintln!(" Unknown output format: {:?}", name);
+                process::exit(2);
+            }
+        };
+    }
+    options
+}
+
+//run the conformance corpus and report pass/fail per file
+fn run_conformance(args: &[String]) {
+    let bless = args.iter().any(|a| a == "--bless");
+    let dir = args.iter().find(|a| !a.starts_with("--")).map(|s| s.as_str()).unwrap_or("tests/conformance");
+
+    let report = match conformance::run_corpus(Path::new(dir), bless) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(2);
+        }
+    };
+
+    for result in &report.results {
+        match &result.outcome {
+            CaseOutcome::Passed => println!("PASS    {}", result.path.display()),
+            CaseOutcome::Blessed => println!("BLESSED {}", result.path.display()),
+            CaseOutcome::Failed(reason) => println!("FAIL    {}: {}", result.path.display(), reason),
+        }
+    }
+    println!("\n{} passed, {} failed, {} blessed", report.passed(), report.failed(), report.blessed());
+
+    if report.failed() > 0 {
+        process::exit(1);
+    }
+}
+
+//parse many files and print a summary per file and for the whole batch
+//usage: parse [--recursive] [--jobs N] <path>...
+fn run_parse(args: &[String], options: &Options) {
+    let mut recursive = false;
+    let mut jobs = 1;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--recursive" | "-r" => recursive = true,
+            "--jobs" | "-j" => {
+                jobs = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => n,
+                    _ => {
+                        eprintln!(" Error: --jobs needs a positive number");
+                        process::exit(2);
+                    }
+                }
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!(" Usage: main parse [--recursive] [--jobs N] <path>...");
+        process::exit(2);
+    }
+
+    let files = match batch::expand_paths(&paths, recursive) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(2);
+        }
+    };
+
+    let report = batch::run_batch(&files, options.dialect, options.mode, options.keep_hints, jobs);
+    for file in &report.files {
+        let ms = file.elapsed.as_secs_f64() * 1000.0;
+        match &file.outcome {
+            Ok(count) => println!("OK      {}: {} statements in {:.1} ms", file.path.display(), count, ms),
+            Err(err) => println!("ERROR   {}: {}", file.path.display(), err),
+        }
+    }
+    println!(
+        "\n{} files, {} statements parsed, {} errors in {:.1} ms",
+        report.files.len(),
+        report.statements(),
+        report.errors(),
+        report.elapsed.as_secs_f64() * 1000.0,
+    );
+
+    if report.errors() > 0 {
+        process::exit(1);
+    }
+}
+
+//helper, the script named by the first argument, or standard input
+//read as bytes, the tokenizer copes with text that is not valid UTF-8
+fn read_source(args: &[String]) -> Vec<u8> {
+    let source = match args.first() {
+        Some(path) => fs::read(path),
+        None => {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+        }
+    };
+    match source {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(2);
+        }
+    }
+}
+
+//parse a script (file argument or stdin) and print it back through the formatter, with its comments
+fn run_format(args: &[String], options: &Options) {
+    let mut policy = options.comments;
+    let mut keyword_case = options.keyword_case;
+    let mut source_map: Option<PathBuf> = None;
+    let mut lines: Option<Vec<(usize, usize)>> = None;
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--comments" => {
+                policy.placement = match args.next().and_then(|name| CommentPlacement::from_name(name)) {
+                    Some(placement) => placement,
+                    None => {
+                        eprintln!(" Error: --comments needs leading or trailing");
+                        process::exit(2);
+                    }
+                }
+            }
+            "--attach-comments" => {
+                policy.attachment = match args.next().and_then(|name| CommentAttachment::from_name(name)) {
+                    Some(attachment) => attachment,
+                    None => {
+                        eprintln!(" Error: --attach-comments needs statement or clause");
+                        process::exit(2);
+                    }
+                }
+            }
+            "--keyword-case" => {
+                keyword_case = match args.next().and_then(|name| KeywordCase::from_name(name)) {
+                    Some(case) => case,
+                    None => {
+                        eprintln!(" Error: --keyword-case needs upper, lower or preserve");
+                        process::exit(2);
+                    }
+                }
+            }
+            "--source-map" => match args.next() {
+                Some(path) => source_map = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!(" Error: --source-map needs a file");
+                    process::exit(2);
+                }
+            },
+            "--lines" => match args.next().and_then(|ranges| line_ranges(ranges)) {
+                Some(ranges) => lines = Some(ranges),
+                None => {
+                    eprintln!(" Error: --lines needs line ranges, e.g. 3-5,12");
+                    process::exit(2);
+                }
+            },
+            _ => rest.push(arg.clone()),
+        }
+    }
+    let source = read_source(&rest);
+    //only the statements on the changed lines are formatted, the rest of the text is printed as it is
+    if let Some(lines) = lines {
+        let sql = String::from_utf8_lossy(&source);
+        match formatter::format_changed(&sql, &lines, options.dialect, options.layout, policy, keyword_case) {
+            Ok(text) => print!("{}", text),
+            Err(err) => {
+                eprintln!(" Error: {}", err);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    //hints are kept whatever --keep-hints says, as format_changed does, so they are printed where they were written
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(true).keep_comments(true).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| formatter::format_commented(tokens, options.dialect, options.layout, policy)) {
+        Ok(text) => {
+            let text = formatter::recase_keywords(&text, &source, options.dialect, keyword_case);
+            println!("{}", text);
+            //an array of {"formatted": span, "source": span}, with the spans of the same token in the output and the input
+            if let Some(path) = source_map {
+                let map = formatter::source_map(&text, &source, options.dialect);
+                let mappings = map.mappings.iter().map(|(formatted, source)| JsonValue::object(vec![
+                    ("formatted", formatted.to_json()),
+                    ("source", source.to_json()),
+                ]));
+                if let Err(err) = fs::write(&path, JsonValue::Array(mappings.collect()).to_pretty_string()) {
+                    eprintln!(" Error: {}: {}", path.display(), err);
+                    process::exit(2);
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//helper, line ranges written as `3-5,12`, each a line or two lines with a dash between them
+fn line_ranges(text: &str) -> Option<Vec<(usize, usize)>> {
+    text.split(',')
+        .map(|range| match range.split_once('-') {
+            Some((from, to)) => Some((from.trim().parse().ok()?, to.trim().parse().ok()?)),
+            None => range.trim().parse().ok().map(|line| (line, line)),
+        })
+        .collect()
+}
+
+//print the tables each statement of a script (file argument or stdin) reads and writes
+fn run_tables(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(stmts) => {
+            for (i, stmt) in stmts.iter().enumerate() {
+                let read = analysis::read_tables(stmt).join(", ");
+                let written = analysis::written_tables(stmt).join(", ");
+                println!("{}\tread: {}\twrite: {}", i + 1, read, written);
+            }
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//print the subqueries of every statement of a script (file argument or stdin), whether they are correlated and on which columns
+fn run_subqueries(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(stmts) => {
+            for (i, stmt) in stmts.iter().enumerate() {
+                for subquery in analysis::subqueries(stmt) {
+                    let correlation = if subquery.is_correlated() {
+                        format!("correlated: {}", subquery.outer_columns.join(", "))
+                    } else {
+                        "uncorrelated".to_string()
+                    };
+                    println!("{}	{}	depth {}	{}	{}", i + 1, subquery.kind, subquery.depth, correlation, analysis::summary(&subquery.query));
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//suggest indexes for the queries of a workload (file argument or stdin), given the schema with the indexes it has
+fn run_suggest_indexes(args: &[String], options: &Options) {
+    let Some(schema_path) = args.first() else {
+        eprintln!(" Usage: main suggest-indexes <schema> [file]");
+        process::exit(2);
+    };
+    let parse = |source: Vec<u8>| -> Result<Vec<Statement>, String> {
+        let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize().map_err(|err| err.to_string())?;
+        parse_tokens(tokens, &source, options)
+    };
+    let schema = match fs::read(schema_path).map_err(|err| err.to_string()).and_then(parse) {
+        Ok(schema) => schema,
+        Err(err) => {
+            eprintln!(" Error: {}: {}", schema_path, err);
+            process::exit(1);
+        }
+    };
+    match parse(read_source(&args[1..])) {
+        Ok(workload) => {
+            for suggestion in advisor::suggest_indexes(&Catalog::from_statements(&schema), &workload) {
+                let queries = if suggestion.queries == 1 { "1 query".to_string() } else { format!("{} queries", suggestion.queries) };
+                println!("{} -- {}: {}", formatter::format_statement(&suggestion.to_statement()), queries, suggestion.reason);
+            }
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//report the most frequent query shapes of a query log (file argument or stdin), the tables they use and the statements that do not parse
+//usage: workload [--top N] [file]
+fn run_workload(args: &[String], options: &Options) {
+    let mut top = 10;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--top" => {
+                top = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => n,
+                    _ => {
+                        eprintln!(" Error: --top needs a positive number");
+                        process::exit(2);
+                    }
+                }
+            }
+            file => files.push(file.to_string()),
+        }
+    }
+    //logs are not always clean UTF-8, the bytes that are not only matter inside literals, which the fingerprints leave out
+    let source = String::from_utf8_lossy(&read_source(&files)).into_owned();
+    let report = workload::analyze_workload(&source, options.dialect, options.keep_hints);
+    println!("{} statements, {} shapes, {} failed to parse", report.statements, report.shapes.len(), report.failures.len());
+    if !report.shapes.is_empty() {
+        println!("\nTop query shapes:");
+        for shape in report.shapes.iter().take(top) {
+            let share = shape.count as f64 * 100.0 / report.statements as f64;
+            println!("{:>8} {:>5.1}%  {}  (first at line {})", shape.count, share, shape.fingerprint, shape.first_line);
+        }
+    }
+    if !report.tables.is_empty() {
+        println!("\nTables:");
+        for table in report.tables.iter().take(top) {
+            println!("{:>8}  {}  (read {}, written {})", table.reads + table.writes, table.name, table.reads, table.writes);
+        }
+    }
+    if !report.failures.is_empty() {
+        println!("\nFailed to parse:");
+        for failure in &report.failures {
+            println!("    line {}: {}", failure.line, failure.error);
+            println!("        {}", failure.text.lines().next().unwrap_or_default());
+        }
+    }
+}
+
+//print CREATE TABLE statements inferred from the INSERT statements of a dump (file argument or stdin)
+fn run_infer_schema(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| infer::infer_tables(&tokens)) {
+        Ok(stmts) => println!("{}", formatter::for_dialect(&formatter::format_statements_with(&stmts, options.layout), options.dialect)),
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//print a script (file argument or stdin) in canonical form, for structural diffs
+fn run_normalize(args: &[String], options: &Options) {
+    let mut order = ColumnOrder::default();
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--columns" => {
+                order = match args.next().and_then(|name| ColumnOrder::from_name(name)) {
+                    Some(order) => order,
+                    None => {
+                        eprintln!(" Error: --columns needs as-written, name or keys-first");
+                        process::exit(2);
+                    }
+                }
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    let source = read_source(&rest);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(mut stmts) => {
+            for stmt in stmts.iter_mut() {
+                normalize::normalize(stmt, order);
+            }
+            println!("{}", formatter::for_dialect(&formatter::format_statements_with(&stmts, options.layout), options.dialect));
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//squash migration scripts into the schema they leave behind, a directory stands for its .sql files in name order
+fn run_squash(args: &[String], options: &Options) {
+    if args.is_empty() {
+        eprintln!(" Usage: main squash <path>...");
+        process::exit(2);
+    }
+    let paths: Vec<PathBuf> = args.iter().map(PathBuf::from).collect();
+    let files = match batch::expand_paths(&paths, true) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(2);
+        }
+    };
+
+    let mut stmts = Vec::new();
+    for file in &files {
+        let parsed = fs::read(file).map_err(|err| err.to_string()).and_then(|source| {
+            let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize().map_err(|err| err.to_string())?;
+            parse_tokens(tokens, &source, options)
+        });
+        match parsed {
+            Ok(parsed) => stmts.extend(parsed),
+            Err(err) => {
+                eprintln!(" Error: {}: {}", file.display(), err);
+                process::exit(1);
+            }
+        }
+    }
+    match migration::squash(&stmts) {
+        Ok(schema) => println!("{}", formatter::for_dialect(&formatter::format_statements_with(&schema, options.layout), options.dialect)),
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//rename a table (`names` is [old, new]) or a column ([table, old, new]) across a script and print it formatted
+fn run_rename(args: &[String], count: usize, options: &Options) {
+    if args.len() < count {
+        eprintln!(" Usage: main rename-table <old> <new> [file] | rename-column <table> <old> <new> [file]");
+        process::exit(2);
+    }
+    let (names, rest) = args.split_at(count);
+    let source = read_source(rest);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(mut stmts) => {
+            match names {
+                [old, new] => rewrite::rename_table(&mut stmts, old, new),
+                [table, old, new] => rewrite::rename_column(&mut stmts, table, old, new),
+                _ => unreachable!(),
+            }
+            println!("{}", formatter::for_dialect(&formatter::format_statements_with(&stmts, options.layout), options.dialect));
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//print a script (file argument or stdin) with ORDER BY and GROUP BY positions and aliases replaced by the columns they refer to
+fn run_resolve(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    let resolved = tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)).and_then(|mut stmts| {
+        for stmt in stmts.iter_mut() {
+            rewrite::resolve_select_references(stmt)?;
+        }
+        Ok(stmts)
+    });
+    match resolved {
+        Ok(stmts) => println!("{}", formatter::for_dialect(&formatter::format_statements_with(&stmts, options.layout), options.dialect)),
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//print a one line summary of every statement of a script (file argument or stdin)
+fn run_summary(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(stmts) => {
+            for (i, stmt) in stmts.iter().enumerate() {
+                println!("{}\t{}", i + 1, analysis::summary(stmt));
+            }
+        }
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//print the statements of a script (file argument or stdin) as a versioned JSON document
+fn run_to_json(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(stmts) => println!("{}", json::to_document(&stmts).to_pretty_string()),
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//load a document written by to-json (file argument or stdin) and print its statements as SQL
+fn run_from_json(args: &[String], options: &Options) {
+    let source = read_source(args);
+    match String::from_utf8(source).map_err(|_| "The document is not valid UTF-8".to_string()).and_then(|text| json::load_document(&text)) {
+        Ok(stmts) => println!("{}", formatter::for_dialect(&formatter::format_statements_with(&stmts, options.layout), options.dialect)),
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+//compare two scripts statement by statement, the n-th statement of one with the n-th of the other, and print how the
+//syntax trees differ; like diff, the exit status is 1 when they do
+//usage: diff <old> <new>
+fn run_diff(args: &[String], options: &Options) {
+    let [old, new] = args else {
+        eprintln!(" Usage: main diff <old> <new>");
+        process::exit(2);
+    };
+    let parse = |path: &String| {
+        let parsed = fs::read(path).map_err(|err| err.to_string()).and_then(|source| {
+            let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize().map_err(|err| err.to_string())?;
+            parse_tokens(tokens, &source, options)
+        });
+        parsed.unwrap_or_else(|err| {
+            eprintln!(" Error: {}: {}", path, err);
+            process::exit(2);
+        })
+    };
+    let (old, new) = (parse(old), parse(new));
+    let mut differ = false;
+    for i in 0..old.len().max(new.len()) {
+        match (old.get(i), new.get(i)) {
+            (Some(old), Some(new)) => {
+                let edits = diff::diff(old, new);
+                if !edits.is_empty() {
+                    println!("statement {}:", i + 1);
+                    for edit in &edits {
+                        println!("  {}", edit);
+                    }
+                }
+                differ |= !edits.is_empty();
+            }
+            (Some(old), None) => println!("statement {}: deleted {}", i + 1, analysis::summary(old)),
+            (None, Some(new)) => println!("statement {}: inserted {}", i + 1, analysis::summary(new)),
+            (None, None) => {}
+        }
+    }
+    if differ || old.len() != new.len() {
+        process::exit(1);
+    }
+}
+
+//group the statements of a script (file argument or stdin) that are near duplicates of each other and print the groups
+//of more than one, each statement with its similarity to the first of its group
+fn run_similar(args: &[String], options: &Options) {
+    let mut threshold = 0.8;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threshold" => {
+                threshold = match args.next().and_then(|t| t.parse().ok()) {
+                    Some(t) if (0.0..=1.0).contains(&t) => t,
+                    _ => {
+                        eprintln!(" Error: --threshold needs a number from 0 to 1");
+                        process::exit(2);
+                    }
+                }
+            }
+            file => files.push(file.to_string()),
+        }
+    }
+    let source = read_source(&files);
+    let tokens = Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).tokenize();
+    let stmts = match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, &source, options)) {
+        Ok(stmts) => stmts,
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    };
+    let groups: Vec<Vec<usize>> = similarity::cluster(&stmts, threshold).into_iter().filter(|group| group.len() > 1).collect();
+    println!("{} statements, {} groups of near duplicates", stmts.len(), groups.len());
+    for group in &groups {
+        let first = &stmts[group[0]];
+        println!("\n{}\t     \t{}", group[0] + 1, analysis::summary(first));
+        for &i in &group[1..] {
+            println!("{}\t{:.3}\t{}", i + 1, similarity::similarity(first, &stmts[i]), analysis::summary(&stmts[i]));
+        }
+    }
+}
+
+//rounds of lint --fix before giving up on a script whose fixes keep changing it
+const MAX_FIX_ROUNDS: usize = 10;
+
+//check a script (file argument or stdin) with the lint rules, exits with an error status when a finding is an error
+//with --fix the fixable findings are fixed first: a file is rewritten in place, a script from stdin is printed fixed,
+//and what is left is reported as usual
+fn run_lint(args: &[String], options: &Options) {
+    if args.iter().any(|a| a == "--rules") {
+        for rule in lint::RULES {
+            let severity = options.lint.iter().find(|(name, _)| *name == rule.name).map_or(rule.severity, |(_, severity)| *severity);
+            println!("{:<24}{:<9}{}", rule.name, severity, rule.description);
+        }
+        return;
+    }
+    let fix = args.iter().any(|a| a == "--fix");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--fix").cloned().collect();
+    let source = read_source(&args);
+    let mut sql = String::from_utf8_lossy(&source).into_owned();
+    let mut findings = lint::lint(&sql, options.dialect, &options.lint);
+    let name = args.first().map_or("<stdin>", |path| path.as_str());
+
+    if fix {
+        //a fix may make room for another one (overlapping fixes wait for the next round), so lint again until nothing changes
+        let mut fixed = 0;
+        for _ in 0..MAX_FIX_ROUNDS {
+            let (next, applied) = lint::apply_fixes(&sql, &findings);
+            if applied == 0 {
+                break;
+            }
+            fixed += applied;
+            sql = next;
+            findings = lint::lint(&sql, options.dialect, &options.lint);
+        }
+        match args.first() {
+            Some(path) if fixed > 0 => {
+                if let Err(err) = fs::write(path, &sql) {
+                    eprintln!(" Error: {}", err);
+                    process::exit(2);
+                }
+            }
+            Some(_) => {}
+            None => print!("{}", sql),
+        }
+        eprintln!("{}: {} fixed", name, fixed);
+    }
+
+    for finding in &findings {
+        //stdout has the fixed script then, the findings go with it to stderr
+        if fix && args.is_empty() {
+            eprintln!("{}:{}", name, finding);
+        } else {
+            println!("{}:{}", name, finding);
+        }
+    }
+    if findings.iter().any(|finding| finding.severity == Severity::Error) {
+        process::exit(1);
+    }
+}
+
+//check the SQL in the string literals of Rust files, directories are walked for `.rs` files
+//usage: check-rust [--functions <names>] <path>..., names separated by commas replace the configured ones
+fn run_check_rust(args: &[String], options: &Options) {
+    let mut functions = options.embedded_functions.clone();
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--functions" => {
+                functions = args.next().map(|names| names.split(',').map(str::trim).filter(|name| !name.is_empty()).map(String::from).collect()).unwrap_or_default();
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!(" Usage: main check-rust [--functions <names>] <path>...");
+        process::exit(2);
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut found = Vec::new();
+            if let Err(err) = embedded::collect_rust_files(&path, &mut found) {
+                eprintln!(" Error: {}", err);
+                process::exit(2);
+            }
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path);
+        }
+    }
+
+    let mut errors = false;
+    for file in &files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!(" Error: {}: {}", file.display(), err);
+                process::exit(2);
+            }
+        };
+        for finding in embedded::check_rust(&source, &functions, options.dialect, &options.lint) {
+            errors |= finding.severity == Severity::Error;
+            println!("{}:{}", file.display(), finding);
+        }
+    }
+    if errors {
+        process::exit(1);
+    }
+}
+
+//print the tokens of a script (file argument or stdin) with their line and column
+fn run_tokens(args: &[String], options: &Options) {
+    let source = read_source(args);
+    let mut failed = false;
+    for result in Tokenizer::from_bytes(&source, options.dialect).keep_hints(options.keep_hints).spanned() {
+        match result {
+            Ok(spanned) => println!("{}:{}\t{:?}", spanned.span.line, spanned.span.column, spanned.token),
+            Err(err) => {
+                eprintln!(" Error: {}", err);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+//generate random statements and check that format -> parse gives back the same AST
+//usage: roundtrip [count] [seed]
+#[cfg(feature = "arbitrary")]
+fn run_roundtrip(args: &[String]) {
+    let count = args.first().and_then(|s| s.parse().ok()).unwrap_or(1000);
+    let seed = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(1);
+
+    let failures = sqlparser::arbitrary::check_roundtrips(seed, count);
+    for failure in &failures {
+        println!("FAIL seed {}: {}\n{}\n", failure.seed, failure.error, failure.sql);
+    }
+    println!("{} statements checked, {} failed", count, failures.len());
+
+    if !failures.is_empty() {
+        process::exit(1);
+    }
+}
+
+//time the engine on a generated table, evaluating a chunk of rows at a time and then row by row, and check both give the same rows
+//usage: bench [rows]
+fn run_bench(args: &[String], options: &Options) {
+    let rows: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(50000);
+    let mut script = String::from("CREATE TABLE bench (id INT, price INT, quantity INT, discount DECIMAL(4, 2), name VARCHAR(20));\nINSERT INTO bench VALUES ");
+    for id in 0..rows {
+        let separator = if id == 0 { "" } else { ", " };
+        script.push_str(&format!("{}({}, {}, {}, 0.{:02}, 'item {}')", separator, id, id * 37 % 1000, id % 50, id % 30, id % 100));
+    }
+    script.push_str(";\n");
+    let queries = [
+        "SELECT id, price * quantity FROM bench WHERE price > 500 AND quantity < 25",
+        "SELECT id, price * quantity * (1 - discount) FROM bench WHERE discount >= 0.10",
+        "SELECT id, name FROM bench WHERE name = 'item 42' OR price + quantity = 100",
+        "SELECT id FROM bench WHERE NOT (price >= 100 AND price <= 900) AND quantity != 7",
+    ];
+    for query in &queries {
+        script.push_str(query);
+        script.push_str(";\n");
+    }
+    let tokens = Tokenizer::from_bytes(script.as_bytes(), options.dialect).tokenize();
+    let stmts = match tokens.map_err(|err| err.to_string()).and_then(|tokens| parse_tokens(tokens, script.as_bytes(), options)) {
+        Ok(stmts) => stmts,
+        Err(err) => {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let mut vectorized = Engine::default();
+    let mut row_at_a_time = Engine::default().row_at_a_time(true);
+    for stmt in &stmts[..2] {
+        if let Err(err) = vectorized.execute(stmt).and_then(|_| row_at_a_time.execute(stmt)) {
+            eprintln!(" Error: {}", err);
+            process::exit(1);
+        }
+    }
+    println!("{} rows", rows);
+    for (query, stmt) in queries.iter().zip(&stmts[2..]) {
+        let (chunked, chunked_time) = time(&mut vectorized, stmt);
+        let (single, single_time) = time(&mut row_at_a_time, stmt);
+        if chunked != single {
+            eprintln!(" Error: the engines disagree on {}", query);
+            process::exit(1);
+        }
+        let speedup = single_time.as_secs_f64() / chunked_time.as_secs_f64().max(1e-9);
+        println!("{}\n  chunk at a time {:>9.2?}, row at a time {:>9.2?}, {:.1}x", query, chunked_time, single_time, speedup);
+    }
+}
+
+//helper, run a query once and return its outcome (or error) with how long it took
+fn time(engine: &mut Engine, stmt: &Statement) -> (Result<Outcome, String>, Duration) {
+    let start = Instant::now();
+    let outcome = engine.execute(stmt);
+    (outcome, start.elapsed())
+}
+
+//interactive multiline prompt
+//usage: repl [--catalog <file>] [--execute] [--unicode] [--max-width N] [--output-format table|csv|json], the tables created in the session are checked against and completed from,
+//and with a catalog file they are read from it at the start and written back whenever they change
+fn run_repl(args: &[String], options: &Options) {
+    let mut catalog = None;
+    let mut execute = false;
+    let mut style = TableStyle::default();
+    let mut format = ResultFormat::Table;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.len()) {
+            ("--catalog", 1..) => catalog = args.next().map(PathBuf::from),
+            ("--execute", _) => execute = true,
+            ("--unicode", _) => style.border = Border::Unicode,
+            ("--output-format", _) => {
+                format = match args.next().and_then(|name| ResultFormat::from_name(name)) {
+                    Some(format) => format,
+                    None => {
+                        eprintln!(" Error: --output-format needs table, csv or json");
+                        process::exit(2);
+                    }
+                }
+            }
+            ("--max-width", _) => {
+                style.max_width = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => Some(n),
+                    _ => {
+                        eprintln!(" Error: --max-width needs a positive number");
+                        process::exit(2);
+                    }
+                }
+            }
+            _ => {
+                eprintln!(" Usage: main repl [--catalog <file>] [--execute] [--unicode] [--max-width N] [--output-format table|csv|json]");
+                process::exit(2);
+            }
+        }
+    }
+    //a catalog file that is not there yet is made when the first table is created
+    let loaded = match &catalog {
+        Some(path) if path.exists() => fs::read_to_string(path).map_err(|err| err.to_string()).and_then(|sql| Session::from_sql(&sql, options.dialect)),
+        _ => Ok(Session::default()),
+    };
+    let mut session = match loaded {
+        Ok(session) => session,
+        Err(err) => {
+            eprintln!(" Error: {}: {}", catalog.unwrap_or_default().display(), err);
+            process::exit(1);
+        }
+    };
+    //the tables of the catalog start out empty
+    let mut engine = Engine::default();
+    //where `\o` sends the rows of queries, with the format its extension asks for
+    let mut output: Option<(PathBuf, ResultFormat)> = None;
+    if execute {
+        for stmt in session.schema() {
+            if let Err(err) = engine.execute(stmt) {
+                eprintln!(" Error: {}", err);
+            }
+        }
+    }
+
     //instructions on how to use the program
     println!("Simple SQL Parser CLI (multiline)");
-    println!("Enter SQL statements ending with `;`. Press Ctrl+Z to exit.\n");
+    println!("Enter SQL statements ending with `;`. Press Ctrl+Z to exit.");
+    println!("\\complete <text> lists what can follow the text, \\schema prints the tables created so far, \\reset drops them, \\o <file> writes query results to a file.\n");
 
     let stdin = io::stdin();
     let mut buffer = String::new(); //collect multiple lines until complete statement formed
@@ -31,16 +1025,79 @@ fn main() {
             break;
         }
 
+        //commands of the session itself, on a line of their own between statements
+        if buffer.trim().is_empty() && line.starts_with('\\') {
+            let line = line.trim_end_matches(['\n', '\r']);
+            match line.split_once(' ').unwrap_or((line, "")) {
+                ("\\complete", text) => println!("{}", session.complete(text, options.dialect).join(" ")),
+                ("\\schema", _) => print!("{}", session.to_sql()),
+                ("\\reset", _) => {
+                    engine.reset();
+                    session = Session::default();
+                    save_catalog(catalog.as_deref(), &session);
+                }
+                //an empty file first, so every result that follows can be added to it
+                ("\\o", "") => output = None,
+                ("\\o", path) => match fs::write(path, "") {
+                    Ok(()) => {
+                        let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+                        output = Some((PathBuf::from(path), ResultFormat::from_name(extension).unwrap_or(format)));
+                    }
+                    Err(err) => eprintln!(" Error: {}: {}", path, err),
+                },
+                (other, _) => eprintln!(" Unknown command: {}", other),
+            }
+            continue;
+        }
+
         buffer.push_str(&line); //add new line to input buffer
 
         //check if the sql statement complete or not
         if buffer.trim_end().ends_with(';') {
-            let tokens: Vec<_> = Tokenizer::new(&buffer).collect(); //tokenizing the entire sql statement
-            let mut parser = Parser::new(tokens); //new parser using list of tokens
+            let tokens = Tokenizer::with_dialect(&buffer, options.dialect).keep_hints(options.keep_hints).tokenize(); //tokenizing the entire sql statement
             
             //parse the sql statement, if it can print, if it cannot show error
-            match parser.parse_statement() {
-                Ok(stmt) => println!("{:#?}", stmt),
+            match tokens.map_err(|err| err.to_string()).and_then(|tokens| {
+                let mut parser = Parser::with_dialect(tokens, options.dialect).mode(options.mode).source(buffer.as_bytes());
+                let stmt = parser.parse_statement()?;
+                for clause in parser.skipped() {
+                    eprintln!(" Warning: skipped {}", formatter::format_tokens(&clause.tokens));
+                }
+                Ok(stmt)
+            }) {
+                //the result of running it takes the place of the statement, and the engine reports what the checks would
+                Ok(stmt) if execute => match engine.execute(&stmt) {
+                    Ok(outcome) => {
+                        print_outcome(&outcome, &style, format, output.as_ref());
+                        if let Ok(true) = session.apply(&stmt) {
+                            save_catalog(catalog.as_deref(), &session);
+                        }
+                    }
+                    Err(err) => eprintln!(" Error: {}", err),
+                },
+                //SHOW TABLES and DESCRIBE are answered from the tables of the session instead of printed
+                Ok(stmt @ (Statement::Show { .. } | Statement::Describe { .. })) => match session.introspect(&stmt) {
+                    Some(Ok(result)) => print_outcome(&Outcome::Rows(result), &style, format, output.as_ref()),
+                    Some(Err(err)) => eprintln!(" Error: {}", err),
+                    None => {}
+                },
+                Ok(stmt) => {
+                    match options.output {
+                        OutputFormat::Debug => println!("{:#?}", stmt),
+                        OutputFormat::Json => println!("{}", stmt.to_json().to_pretty_string()),
+                        OutputFormat::Summary => println!("{}", analysis::summary(&stmt)),
+                    }
+                    //checked against the tables as they were before the statement
+                    let tokens: Vec<SpannedToken> = Tokenizer::with_dialect(&buffer, options.dialect).spanned().filter_map(Result::ok).collect();
+                    for diagnostic in semantic::analyze_with_dialect(&stmt, &tokens, options.dialect).into_iter().chain(session.check(&stmt, &tokens)) {
+                        eprintln!(" Warning: {}", diagnostic);
+                    }
+                    match session.apply(&stmt) {
+                        Ok(true) => save_catalog(catalog.as_deref(), &session),
+                        Ok(false) => {}
+                        Err(err) => eprintln!(" Error: {}", err),
+                    }
+                }
                 Err(err) => eprintln!(" Error: {}", err),
             }
 
@@ -49,4 +1106,42 @@ fn main() {
     }
 
     println!("\n Goodbye!"); //sayonara
+}
+
+//helper, write the tables of the session to the catalog file, when there is one
+fn save_catalog(catalog: Option<&Path>, session: &Session) {
+    if let Some(path) = catalog {
+        if let Err(err) = fs::write(path, session.to_sql()) {
+            eprintln!(" Error: {}: {}", path.display(), err);
+        }
+    }
+}
+
+//print what running a statement did, the rows of a query in the chosen format, or added to the file given with `\o`
+fn print_outcome(outcome: &Outcome, style: &TableStyle, format: ResultFormat, output: Option<&(PathBuf, ResultFormat)>) {
+    let result = match outcome {
+        Outcome::Rows(result) => result,
+        Outcome::Affected(count) => return println!("{}", row_count(*count)),
+        Outcome::Done => return println!("OK"),
+    };
+    match output {
+        Some((path, format)) => {
+            let written = fs::OpenOptions::new().append(true).open(path).and_then(|mut file| file.write_all(table_render::render_result_as(result, *format, style).as_bytes()));
+            match written {
+                Ok(()) => println!("({} written to {})", row_count(result.rows.len()), path.display()),
+                Err(err) => eprintln!(" Error: {}: {}", path.display(), err),
+            }
+        }
+        None if format == ResultFormat::Table => {
+            print!("{}", table_render::render_result(result, style));
+            println!("({})", row_count(result.rows.len()));
+        }
+        //csv and json are left without the count, so they can be copied as they are
+        None => print!("{}", table_render::render_result_as(result, format, style)),
+    }
+}
+
+//helper, `1 row` or `n rows`
+fn row_count(count: usize) -> String {
+    format!("{} {}", count, if count == 1 { "row" } else { "rows" })
 }
\ No newline at end of file
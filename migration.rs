@@ -0,0 +1,167 @@
+//schema level operations over the DDL of migration scripts
+use crate::statement::{Statement, AlterAction, ObjectType};
+use crate::rewrite::{rename_table, rename_column};
+
+//squash the statements of a series of migrations, in the order they run, into the schema they leave behind:
+//one CREATE TABLE per table, sorted by table name (case-insensitive) so two squashes diff cleanly, each followed by
+//the CREATE INDEX statements of the table sorted by index name
+//statements that do not change the schema, like SELECT, are dropped, ALTER TABLE is folded into the CREATE TABLE
+//and RENAME TABLE renames it, the indexes going along. Dropping a table drops its indexes, and so does dropping
+//a column they use
+//a table created again with the very same definition is kept once, with a different one it is an error,
+//like running the migrations against a database would be, and so is dropping a table that does not exist without IF EXISTS
+//or altering one that does not exist, or a column that does not exist
+pub fn squash(stmts: &[Statement]) -> Result<Vec<Statement>, String> {
+    let mut tables: Vec<(String, Statement)> = Vec::new();
+    let mut indexes: Vec<Statement> = Vec::new();
+    for stmt in stmts {
+        match stmt {
+            //the schema is written without IF NOT EXISTS, which only says to leave an existing table alone
+            Statement::CreateTable { table_name, column_list, query, if_not_exists, temporary } => {
+                match tables.iter().find(|(name, _)| name.eq_ignore_ascii_case(table_name)) {
+                    Some(_) if *if_not_exists => {}
+                    Some((_, Statement::CreateTable { column_list: existing, query: existing_query, temporary: existing_temporary, .. }))
+                        if existing == column_list && existing_query == query && existing_temporary == temporary => {}
+                    Some(_) => return Err(format!("Table {} is created twice with different definitions", table_name)),
+                    None => tables.push((table_name.clone(), Statement::CreateTable {
+                        table_name: table_name.clone(),
+                        column_list: column_list.clone(),
+                        query: query.clone(),
+                        if_not_exists: false,
+                        temporary: *temporary,
+                    })),
+                }
+            }
+            //renames go through the rewrite functions, so CHECK constraints follow the new names
+            Statement::AlterTable { table_name, action } => {
+                let Some(index) = tables.iter().position(|(name, _)| name.eq_ignore_ascii_case(table_name)) else {
+                    return Err(format!("Table {} is altered but was never created", table_name));
+                };
+                if let AlterAction::RenameTo(new_name) = action {
+                    rename_squashed_table(&mut tables, index, table_name, new_name)?;
+                    rename_table(&mut indexes, table_name, new_name);
+                    continue;
+                }
+                let (_, stmt) = &mut tables[index];
+                let Statement::CreateTable { column_list, query, .. } = stmt else { unreachable!() };
+                //the columns of a table created from a query are not written down, so there is nothing to change them in
+                if query.is_some() {
+                    return Err(format!("Table {} is created from a query, its columns cannot be altered in the squashed schema", table_name));
+                }
+                let column = |name: &str| column_list.iter().position(|column| column.column_name.eq_ignore_ascii_case(name));
+                match action {
+                    AlterAction::AddColumn(added) => {
+                        if column(&added.column_name).is_some() {
+                            return Err(format!("Column {} is added to table {} but already exists", added.column_name, table_name));
+                        }
+                        column_list.push(added.clone());
+                    }
+                    AlterAction::DropColumn(column_name) => match column(column_name) {
+                        Some(position) => {
+                            column_list.remove(position);
+                            indexes.retain(|index| !indexes_column(index, table_name, column_name));
+                        }
+                        None => return Err(format!("Column {} is dropped from table {} but does not exist", column_name, table_name)),
+                    },
+                    AlterAction::RenameColumn { column_name, new_name } => {
+                        if column(column_name).is_none() {
+                            return Err(format!("Column {} of table {} is renamed but does not exist", column_name, table_name));
+                        }
+                        if column(new_name).is_some() && !new_name.eq_ignore_ascii_case(column_name) {
+                            return Err(format!("Column {} of table {} is renamed to {} but that column already exists", column_name, table_name, new_name));
+                        }
+                        rename_column(std::slice::from_mut(stmt), table_name, column_name, new_name);
+                        rename_column(&mut indexes, table_name, column_name, new_name);
+                    }
+                    AlterAction::RenameTo(_) => unreachable!(),
+                }
+            }
+            //one pair after the other, `a TO tmp, b TO a, tmp TO b` swaps two tables
+            Statement::RenameTable { renames } => {
+                for rename in renames {
+                    let Some(index) = tables.iter().position(|(name, _)| name.eq_ignore_ascii_case(&rename.old_name)) else {
+                        return Err(format!("Table {} is renamed but was never created", rename.old_name));
+                    };
+                    rename_squashed_table(&mut tables, index, &rename.old_name, &rename.new_name)?;
+                    rename_table(&mut indexes, &rename.old_name, &rename.new_name);
+                }
+            }
+            //a table dropped and created again is kept with its new definition
+            Statement::Drop { object_type: ObjectType::Table, names, if_exists } => {
+                for name in names {
+                    match tables.iter().position(|(table, _)| table.eq_ignore_ascii_case(name)) {
+                        Some(index) => {
+                            tables.remove(index);
+                            indexes.retain(|index| !matches!(index, Statement::CreateIndex { table_name, .. } if table_name.eq_ignore_ascii_case(name)));
+                        }
+                        None if *if_exists => {}
+                        None => return Err(format!("Table {} is dropped but was never created", name)),
+                    }
+                }
+            }
+            //an index name is unique within its table, like in MySQL
+            Statement::CreateIndex { name, table_name, columns, unique, if_not_exists } => {
+                let Some((_, Statement::CreateTable { column_list, query, .. })) = tables.iter().find(|(table, _)| table.eq_ignore_ascii_case(table_name)) else {
+                    return Err(format!("Index {} is created on table {} but that table was never created", name, table_name));
+                };
+                //a table created from a query has no column list to check the columns against
+                let missing = columns.iter().find(|column| !column_list.iter().any(|existing| existing.column_name.eq_ignore_ascii_case(&column.name)));
+                if let (None, Some(column)) = (query, missing) {
+                    return Err(format!("Index {} is created on column {} of table {} but that column does not exist", name, column.name, table_name));
+                }
+                let index = Statement::CreateIndex { name: name.clone(), table_name: table_name.clone(), columns: columns.clone(), unique: *unique, if_not_exists: false };
+                match indexes.iter().find(|existing| same_index(existing, name, table_name)) {
+                    Some(_) if *if_not_exists => {}
+                    Some(existing) if *existing == index => {}
+                    Some(_) => return Err(format!("Index {} on table {} is created twice with different definitions", name, table_name)),
+                    None => indexes.push(index),
+                }
+            }
+            //DROP INDEX names no table, so an index of that name on any table goes
+            Statement::Drop { object_type: ObjectType::Index, names, if_exists } => {
+                for name in names {
+                    let before = indexes.len();
+                    indexes.retain(|index| !matches!(index, Statement::CreateIndex { name: index_name, .. } if index_name.eq_ignore_ascii_case(name)));
+                    if indexes.len() == before && !*if_exists {
+                        return Err(format!("Index {} is dropped but was never created", name));
+                    }
+                }
+            }
+            //views, triggers, routines, databases and schemas are not part of the squashed schema
+            Statement::Drop { .. } | Statement::Select { .. } | Statement::SetOperation { .. } | Statement::Values { .. } | Statement::Insert { .. } | Statement::Delete { .. } | Statement::Merge { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Prepare { .. } | Statement::Execute { .. } | Statement::Deallocate { .. } => {}
+        }
+    }
+    tables.sort_by_key(|(name, _)| name.to_lowercase());
+    indexes.sort_by_key(|index| match index {
+        Statement::CreateIndex { name, .. } => name.to_lowercase(),
+        _ => unreachable!(),
+    });
+    let mut schema = Vec::new();
+    for (table_name, table) in tables {
+        schema.push(table);
+        schema.extend(indexes.iter().filter(|index| matches!(index, Statement::CreateIndex { table_name: indexed, .. } if indexed.eq_ignore_ascii_case(&table_name))).cloned());
+    }
+    Ok(schema)
+}
+
+//helper, whether a squashed CREATE INDEX has the name on the table
+fn same_index(index: &Statement, name: &str, table_name: &str) -> bool {
+    matches!(index, Statement::CreateIndex { name: index_name, table_name: indexed, .. } if index_name.eq_ignore_ascii_case(name) && indexed.eq_ignore_ascii_case(table_name))
+}
+
+//helper, whether a squashed CREATE INDEX is on the table and uses the column
+fn indexes_column(index: &Statement, table_name: &str, column_name: &str) -> bool {
+    matches!(index, Statement::CreateIndex { table_name: indexed, columns, .. }
+        if indexed.eq_ignore_ascii_case(table_name) && columns.iter().any(|column| column.name.eq_ignore_ascii_case(column_name)))
+}
+
+//helper, renames the table at `index` of a squash, `table_name` being its name as the renaming statement writes it
+fn rename_squashed_table(tables: &mut [(String, Statement)], index: usize, table_name: &str, new_name: &str) -> Result<(), String> {
+    if tables.iter().any(|(name, _)| name.eq_ignore_ascii_case(new_name)) {
+        return Err(format!("Table {} is renamed to {} but that table already exists", table_name, new_name));
+    }
+    let (name, stmt) = &mut tables[index];
+    rename_table(std::slice::from_mut(stmt), table_name, new_name);
+    *name = new_name.to_string();
+    Ok(())
+}
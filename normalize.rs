@@ -0,0 +1,259 @@
+//canonical forms of statements, so two versions that only differ in how they were written compare equal
+use std::cmp::Ordering;
+use crate::statement::{
+    Statement,
+    Expression,
+    BinaryOperator,
+    TableReference,
+    GroupingElement,
+    Constraint,
+    TableColumn,
+    AlterAction,
+};
+use crate::formatter::format_expression;
+use crate::rewrite::for_each_select_mut;
+
+/// Order of the columns of a `CREATE TABLE` after normalizing.
+/// 1. `AsWritten` – Keep the order of the statement, which is also the order of `SELECT *`.
+/// 2. `Name` – Sort by column name (case-insensitive).
+/// 3. `KeysFirst` – Primary key columns first, each group sorted by name.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColumnOrder {
+    #[default]
+    AsWritten,
+    Name,
+    KeysFirst,
+}
+
+impl ColumnOrder {
+    pub fn from_name(name: &str) -> Option<ColumnOrder> {
+        match name.to_ascii_lowercase().as_str() {
+            "as-written" => Some(ColumnOrder::AsWritten),
+            "name" => Some(ColumnOrder::Name),
+            "keys-first" => Some(ColumnOrder::KeysFirst),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrites a statement into a canonical form, so two schema dumps or queries can be compared structurally: equal after normalizing means equal up to the order of things that have no order.
+///
+/// Operands of `AND` and `OR` chains are sorted, as are the operands of `=`, `<>` and `IS [NOT] DISTINCT FROM`, and a comparison is turned around when that puts its operands in order (`1 < a` becomes `a > 1`). Operands are ordered by their SQL text, with constants (literals and placeholders) after everything else, so a column is compared to a value rather than the other way around. `CREATE TABLE` columns are ordered by `columns` and their constraints are put in one order (`PRIMARY KEY`, `NOT NULL`, `CHECK`s by their text) with duplicates dropped. Select lists, `ORDER BY`, `GROUP BY` and the columns and rows of `INSERT` and the arms of `MERGE` keep their order, it is part of what a statement means, and so does anything that would move one `?` parameter past another, as they are bound by position.
+pub fn normalize(stmt: &mut Statement, columns: ColumnOrder) {
+    match stmt {
+        //the query of CREATE TABLE ... AS is left to for_each_select_mut
+        Statement::CreateTable { column_list, query, .. } => {
+            normalize_columns(column_list, columns);
+            if query.is_none() {
+                return;
+            }
+        }
+        Statement::AlterTable { action, .. } => {
+            if let AlterAction::AddColumn(column) = action {
+                normalize_columns(std::slice::from_mut(column), ColumnOrder::AsWritten);
+            }
+            return;
+        }
+        //the values themselves, their subqueries are left to for_each_select_mut
+        Statement::Values { rows } => rows.iter_mut().flatten().for_each(normalize_expression),
+        //the query of INSERT ... SELECT is left to for_each_select_mut
+        Statement::Insert { source, on_conflict, .. } => {
+            for expr in source.rows_mut().iter_mut().flatten().chain(on_conflict.iter_mut().flat_map(|on_conflict| on_conflict.expressions_mut())) {
+                normalize_expression(expr);
+            }
+        }
+        Statement::Delete { where_clause, .. } => {
+            if let Some(expr) = where_clause {
+                normalize_expression(expr);
+            }
+        }
+        //the arms keep their order, a row takes the first one that applies to it
+        Statement::Merge { source, on, clauses, .. } => {
+            normalize_table(source);
+            for expr in std::iter::once(on).chain(clauses.iter_mut().flat_map(|clause| clause.expressions_mut())) {
+                normalize_expression(expr);
+            }
+        }
+        //the ORDER BY and LIMIT of the combined rows, both sides are left to for_each_select_mut
+        //like a select list, the sides keep their order: the left one names the columns
+        Statement::SetOperation { orderby, limit, .. } => {
+            for expr in orderby.iter_mut().chain(limit.iter_mut()) {
+                normalize_expression(expr);
+            }
+        }
+        //the prepared statement is normalized as a statement of its own, its queries included
+        Statement::Prepare { statement, .. } => {
+            normalize(statement, columns);
+            return;
+        }
+        Statement::Execute { args, .. } => args.iter_mut().for_each(normalize_expression),
+        Statement::Select { .. } | Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Deallocate { .. } => {}
+    }
+    for_each_select_mut(stmt, &mut |select| {
+        if let Statement::Select { columns, from, r#where, group_by, having, qualify, orderby, limit, .. } = select {
+            for table in from.iter_mut() {
+                normalize_table(table);
+            }
+            let clauses = r#where.iter_mut().chain(having.iter_mut()).chain(qualify.iter_mut()).chain(limit.iter_mut());
+            for expr in columns.iter_mut().chain(clauses).chain(orderby.iter_mut()) {
+                normalize_expression(expr);
+            }
+            for element in group_by.iter_mut() {
+                match element {
+                    GroupingElement::Expression(expr) => normalize_expression(expr),
+                    GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => {
+                        for expr in sets.iter_mut().flatten() {
+                            normalize_expression(expr);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn normalize_columns(column_list: &mut [TableColumn], order: ColumnOrder) {
+    for column in column_list.iter_mut() {
+        for constraint in column.constraints.iter_mut() {
+            if let Constraint::Check(expr) = constraint {
+                normalize_expression(expr);
+            }
+        }
+        column.constraints.sort_by_key(constraint_key);
+        column.constraints.dedup();
+    }
+    let name = |column: &TableColumn| column.column_name.to_lowercase();
+    match order {
+        ColumnOrder::AsWritten => {}
+        ColumnOrder::Name => column_list.sort_by_key(name),
+        ColumnOrder::KeysFirst => column_list.sort_by_key(|column| (!column.constraints.contains(&Constraint::PrimaryKey), name(column))),
+    }
+}
+
+//helper, sort key putting constraints in their canonical order
+fn constraint_key(constraint: &Constraint) -> (u8, String) {
+    match constraint {
+        Constraint::PrimaryKey => (0, String::new()),
+        Constraint::NotNull => (1, String::new()),
+        Constraint::Check(expr) => (2, format_expression(expr)),
+    }
+}
+
+//subqueries are left to for_each_select_mut, only the expressions of the FROM clause itself are normalized here
+fn normalize_table(table: &mut TableReference) {
+    match table {
+        TableReference::Table { .. } | TableReference::Derived { .. } => {}
+        TableReference::Function { args, .. } => {
+            for arg in args.iter_mut() {
+                normalize_expression(arg);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            normalize_table(table);
+            for expr in std::iter::once(&mut pivot.aggregate).chain(std::iter::once(&mut pivot.column)).chain(pivot.values.iter_mut()) {
+                normalize_expression(expr);
+            }
+        }
+        TableReference::Unpivot { table, .. } => normalize_table(table),
+        TableReference::Join { left, right, on, .. } => {
+            normalize_table(left);
+            normalize_table(right);
+            if let Some(on) = on {
+                normalize_expression(on);
+            }
+        }
+    }
+}
+
+//normalize the operands first, then the expression itself, so its operands are compared in their canonical form
+fn normalize_expression(expr: &mut Expression) {
+    match expr {
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            normalize_expression(left_operand);
+            normalize_expression(right_operand);
+        }
+        Expression::UnaryOperation { operand, .. } => normalize_expression(operand),
+        Expression::Alias { expr, .. } => normalize_expression(expr),
+        Expression::Function { args, over, .. } => {
+            for arg in args.iter_mut() {
+                normalize_expression(arg);
+            }
+            if let Some(window) = over {
+                for expr in window.partition_by.iter_mut().chain(window.order_by.iter_mut()) {
+                    normalize_expression(expr);
+                }
+            }
+        }
+        Expression::Subquery(_) | Expression::Exists { .. } | Expression::Wildcard | Expression::Literal(_)
+        | Expression::Placeholder(_) | Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {}
+    }
+
+    match expr {
+        Expression::BinaryOperation { operator: operator @ (BinaryOperator::And | BinaryOperator::Or), .. } => {
+            let operator = *operator;
+            let mut operands = Vec::new();
+            flatten(std::mem::replace(expr, Expression::Wildcard), operator, &mut operands);
+            if operands.iter().filter(|operand| has_positional_placeholder(operand)).count() <= 1 {
+                operands.sort_by(compare_operands);
+            }
+            *expr = operands.into_iter().reduce(|left, right| Expression::BinaryOperation {
+                left_operand: Box::new(left),
+                operator,
+                right_operand: Box::new(right),
+            }).unwrap();
+        }
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            if let Some(swapped) = swapped_operator(*operator) {
+                if compare_operands(left_operand, right_operand) == Ordering::Greater && !both_positional(left_operand, right_operand) {
+                    std::mem::swap(left_operand, right_operand);
+                    *operator = swapped;
+                }
+            }
+        }
+        Expression::IsDistinctFrom { left_operand, right_operand, .. }
+            if compare_operands(left_operand, right_operand) == Ordering::Greater && !both_positional(left_operand, right_operand) => {
+            std::mem::swap(left_operand, right_operand);
+        }
+        _ => {}
+    }
+}
+
+//helper, the operands of a chain of one operator, `a AND (b AND c)` and `(a AND b) AND c` both give a, b, c
+fn flatten(expr: Expression, operator: BinaryOperator, operands: &mut Vec<Expression>) {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator: op, right_operand } if op == operator => {
+            flatten(*left_operand, operator, operands);
+            flatten(*right_operand, operator, operands);
+        }
+        other => operands.push(other),
+    }
+}
+
+/// The operator with its operands the other way around, for the operators where that keeps the meaning: `a < b` is `b > a`.
+pub fn swapped_operator(operator: BinaryOperator) -> Option<BinaryOperator> {
+    match operator {
+        BinaryOperator::Equal => Some(BinaryOperator::Equal),
+        BinaryOperator::NotEqual => Some(BinaryOperator::NotEqual),
+        BinaryOperator::LessThan => Some(BinaryOperator::GreaterThan),
+        BinaryOperator::GreaterThan => Some(BinaryOperator::LessThan),
+        BinaryOperator::LessThanOrEqual => Some(BinaryOperator::GreaterThanOrEqual),
+        BinaryOperator::GreaterThanOrEqual => Some(BinaryOperator::LessThanOrEqual),
+        _ => None,
+    }
+}
+
+//constants go after everything else, otherwise the SQL text decides
+fn compare_operands(a: &Expression, b: &Expression) -> Ordering {
+    let key = |expr: &Expression| (matches!(expr, Expression::Literal(_) | Expression::Placeholder(_)), format_expression(expr));
+    key(a).cmp(&key(b))
+}
+
+//`?` parameters are numbered by position, operands that both have one must keep their order
+//(the check goes by the SQL text, so a `?` in a string literal counts too, which only means an operand is not moved)
+fn has_positional_placeholder(expr: &Expression) -> bool {
+    format_expression(expr).contains('?')
+}
+
+fn both_positional(a: &Expression, b: &Expression) -> bool {
+    has_positional_placeholder(a) && has_positional_placeholder(b)
+}
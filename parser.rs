@@ -1,329 +1,2289 @@
-use crate::token::{Token, Keyword};
-use crate::statement::{
-    Statement,
-    Expression,
-    BinaryOperator,
-    UnaryOperator,
-    TableColumn,
-    DBType,
-    Constraint,
-};
-
-//holds a list of tokens and a position index for parsing them
-pub struct Parser {
-    tokens: Vec<Token>,
-    pos: usize,
-}
-//make new parser with token list
-impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, pos: 0 }
-    }
-
-    //peek at current token without going forward
-    fn peek(&self) -> &Token {
-        &self.tokens[self.pos]
-    }
-
-    //get current token and move to next
-    fn next(&mut self) -> Token {
-        let tok = self.tokens[self.pos].clone();
-        if self.pos < self.tokens.len() - 1 {
-            self.pos += 1;
-        }
-        tok
-    }
-
-    //expect specific token, if it doesnt match, show error
-    fn expect(&mut self, expected: &Token) -> Result<(), String>
-    where
-        Token: PartialEq + std::fmt::Debug,
-    {
-        if self.peek() == expected {
-            self.next();
-            Ok(())
-        } else {
-            Err(format!("Expected {:?}, found {:?}", expected, self.peek()))
-        }
-    }
-
-    //main entry
-    //decide what kind of sql statement to parse
-    pub fn parse_statement(&mut self) -> Result<Statement, String> {
-        match self.peek() {
-            Token::Keyword(Keyword::Select) => {
-                self.next();
-                self.parse_select()
-            }
-            Token::Keyword(Keyword::Create) => {
-                self.next();
-                self.parse_create_table()
-            }
-            other => Err(format!("Expected SELECT or CREATE, found {:?}", other)),
-        }
-    }
-
-    //select parsing
-    fn parse_select(&mut self) -> Result<Statement, String> {
-        //start columns
-        let mut columns = Vec::new();
-        loop {
-            let expr = self.parse_expression(0)?;
-            columns.push(expr);
-            if let Token::Comma = self.peek() {
-                self.next();
-                continue;
-            }
-            break;
-        }
-
-        //make sure 'FROM' appears after the SELECT columns
-        self.expect(&Token::Keyword(Keyword::From))?;
-        let table_name = match self.next() {
-            Token::Identifier(s) => s,
-            other => return Err(format!("Expected table name, found {:?}", other)),
-        };
-
-        //optional WHERE exp
-        let where_clause = if let Token::Keyword(Keyword::Where) = self.peek() {
-            self.next();
-            Some(self.parse_expression(0)?)
-        } else {
-            None
-        };
-
-        //optional ORDER BY exp
-        let mut orderby = Vec::new();
-        if let Token::Keyword(Keyword::Order) = self.peek() {
-            self.next();
-            self.expect(&Token::Keyword(Keyword::By))?;
-            loop {
-                let expr = self.parse_expression(0)?;
-                orderby.push(expr);
-                if let Token::Comma = self.peek() {
-                    self.next();
-                    continue;
-                }
-                break;
-            }
-        }
-        
-        self.expect(&Token::Semicolon)?;
-
-        Ok(Statement::Select {
-            columns,
-            from: table_name,
-            r#where: where_clause,
-            orderby,
-        })
-    }
-
-    //create table parsing
-    fn parse_create_table(&mut self) -> Result<Statement, String> {
-        //confirm TABLE appears after CREATE
-        self.expect(&Token::Keyword(Keyword::Table))?;
-
-        //table name
-        let table_name = match self.next() {
-            Token::Identifier(s) => s,
-            other => return Err(format!("Expected table name, found {:?}", other)),
-        };
-        
-        self.expect(&Token::LeftParentheses)?;
-
-        let mut columns = Vec::new();
-        loop {
-            //end of list?
-            if let Token::RightParentheses = self.peek() {
-                self.next();
-                break;
-            }
-
-            //column name
-            let col_name = match self.next() {
-                Token::Identifier(s) => s,
-                other => return Err(format!("Expected column name, found {:?}", other)),
-            };
-
-            //column type
-            let col_type = match self.peek() {
-                Token::Keyword(Keyword::Int) => {
-                    self.next();
-                    DBType::Int
-                }
-                Token::Keyword(Keyword::Bool) => {
-                    self.next();
-                    DBType::Bool
-                }
-                Token::Keyword(Keyword::Varchar) => {
-                    self.next();
-                    self.expect(&Token::LeftParentheses)?;
-                    let len = match self.next() {
-                        Token::Number(n) => n as usize,
-                        other => return Err(format!("Expected VARCHAR length, found {:?}", other)),
-                    };
-                    self.expect(&Token::RightParentheses)?;
-                    DBType::Varchar(len)
-                }
-                other => return Err(format!("Expected type, found {:?}", other)),
-            };
-
-            //optional constraints
-            let mut constraints = Vec::new();
-            loop {
-                match self.peek() {
-                    Token::Keyword(Keyword::Primary) => {
-                        self.next();
-                        self.expect(&Token::Keyword(Keyword::Key))?;
-                        constraints.push(Constraint::PrimaryKey);
-                    }
-                    Token::Keyword(Keyword::Not) => {
-                        self.next();
-                        self.expect(&Token::Keyword(Keyword::Null))?;
-                        constraints.push(Constraint::NotNull);
-                    }
-                    Token::Keyword(Keyword::Check) => {
-                        self.next();
-                        self.expect(&Token::LeftParentheses)?;
-                        let expr = self.parse_expression(0)?;
-                        self.expect(&Token::RightParentheses)?;
-                        constraints.push(Constraint::Check(expr));
-                    }
-                    _ => break,
-                }
-            }
-
-            columns.push(TableColumn {
-                column_name: col_name,
-                column_type: col_type,
-                constraints,
-            });
-
-            //comma or end
-            match self.peek() {
-                Token::Comma => { self.next(); }
-                Token::RightParentheses => { self.next(); break; }
-                other => return Err(format!("Expected ',' or ')', found {:?}", other)),
-            }
-        }
-        
-        self.expect(&Token::Semicolon)?;
-
-        Ok(Statement::CreateTable {
-            table_name,
-            column_list: columns,
-        })
-    }
-
-    //pratt parsing for expressions
-    fn parse_expression(&mut self, min_prec: u8) -> Result<Expression, String> {
-        //parse prefix
-        let mut left = match self.next() {
-            Token::Number(n) => Expression::Number(n),
-            Token::Identifier(s) => Expression::Identifier(s),
-            Token::String(s) => Expression::String(s),
-            Token::Keyword(Keyword::True) => Expression::Bool(true),
-            Token::Keyword(Keyword::False) => Expression::Bool(false),
-            Token::LeftParentheses => {
-                let expr = self.parse_expression(0)?;
-                self.expect(&Token::RightParentheses)?;
-                expr
-            }
-            Token::Minus => {
-                let rhs = self.parse_expression(100)?;
-                Expression::UnaryOperation { operand: Box::new(rhs), operator: UnaryOperator::Minus }
-            }
-            Token::Plus => {
-                let rhs = self.parse_expression(100)?;
-                Expression::UnaryOperation { operand: Box::new(rhs), operator: UnaryOperator::Plus }
-            }
-            Token::Keyword(Keyword::Not) => {
-                let rhs = self.parse_expression(100)?;
-                Expression::UnaryOperation { operand: Box::new(rhs), operator: UnaryOperator::Not }
-            }
-            other => return Err(format!("Unexpected prefix token: {:?}", other)),
-        };
-
-        //infix/postfix loop
-        loop {
-            let prec = self.infix_precedence(self.peek());
-            if prec <= min_prec {
-                break;
-            }
-            let tok = self.next();
-            left = match tok {
-                Token::Plus => {
-                    let rhs = self.parse_expression(25)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::Plus, right_operand: Box::new(rhs) }
-                }
-                Token::Minus => {
-                    let rhs = self.parse_expression(25)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::Minus, right_operand: Box::new(rhs) }
-                }
-                Token::Star => {
-                    let rhs = self.parse_expression(30)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::Multiply, right_operand: Box::new(rhs) }
-                }
-                Token::Divide => {
-                    let rhs = self.parse_expression(30)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::Divide, right_operand: Box::new(rhs) }
-                }
-                Token::GreaterThan => {
-                    let rhs = self.parse_expression(20)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::GreaterThan, right_operand: Box::new(rhs) }
-                }
-                Token::Keyword(Keyword::And) => {
-                    let rhs = self.parse_expression(10)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::And, right_operand: Box::new(rhs) }
-                }
-                Token::Keyword(Keyword::Or) => {
-                    let rhs = self.parse_expression(15)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::Or, right_operand: Box::new(rhs) }
-                }
-                Token::Keyword(Keyword::Asc) => {
-                    Expression::UnaryOperation { operand: Box::new(left), operator: UnaryOperator::Asc }
-                }
-                Token::Keyword(Keyword::Desc) => {
-                    Expression::UnaryOperation { operand: Box::new(left), operator: UnaryOperator::Desc }
-                }
-                Token::Equal => {
-                    let rhs = self.parse_expression(20)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::Equal, right_operand: Box::new(rhs) }
-                }
-                Token::NotEqual => {
-                    let rhs = self.parse_expression(20)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::NotEqual, right_operand: Box::new(rhs) }
-                }
-                Token::LessThan => {
-                    let rhs = self.parse_expression(20)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::LessThan, right_operand: Box::new(rhs) }
-                }
-                Token::GreaterThanOrEqual => {
-                    let rhs = self.parse_expression(20)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::GreaterThanOrEqual, right_operand: Box::new(rhs) }
-                }
-                Token::LessThanOrEqual => {
-                    let rhs = self.parse_expression(20)?;
-                    Expression::BinaryOperation { left_operand: Box::new(left), operator: BinaryOperator::LessThanOrEqual, right_operand: Box::new(rhs) }
-                }
-                _ => break,
-            };
-        }
-
-        Ok(left)
-    }
-
-    //return precedence of infix or postfix tokens
-    fn infix_precedence(&self, tok: &Token) -> u8 {
-        match tok {
-            Token::Plus | Token::Minus => 25,
-            Token::Star | Token::Divide => 30,
-            Token::GreaterThan | Token::LessThan | Token::Equal | Token::NotEqual
-            | Token::GreaterThanOrEqual | Token::LessThanOrEqual => 20,
-            Token::Keyword(Keyword::Or) => 15,
-            Token::Keyword(Keyword::And) => 10,
-            Token::Keyword(Keyword::Asc) | Token::Keyword(Keyword::Desc) => 5,
-            _ => 0,
-        }
-    }
-}
\ No newline at end of file
+use std::convert::TryFrom;
+use crate::token::{Token, Keyword};
+use crate::tokenizer::{Tokenizer, Origin, Span};
+use crate::dialect::Dialect;
+use crate::statement::{
+    Statement,
+    Expression,
+    BinaryOperator,
+    UnaryOperator,
+    Associativity,
+    ValueType,
+    Literal,
+    TableColumn,
+    TableReference,
+    TableSample,
+    PivotClause,
+    UnpivotClause,
+    JoinKind,
+    GroupingElement,
+    WindowSpec,
+    Hint,
+    DBType,
+    Constraint,
+    AlterAction,
+    TableRename,
+    IndexColumn,
+    ObjectType,
+    ShowObject,
+    InsertSource,
+    OnConflict,
+    ConflictAction,
+    Assignment,
+    MergeClause,
+    MergeAction,
+    With,
+    TriggerTiming,
+    TriggerEvent,
+    FunctionArg,
+    CopyDirection,
+    CopyOption,
+    DuplicateHandling,
+    LoadFormat,
+    Cte,
+    SetOperator,
+    precedence,
+};
+
+/// Error returned by the parser entry points, a message describing what was expected and what was found.
+pub type ParseError = String;
+
+/// How the parser treats SQL it does not understand.
+/// 1. `Strict` – Anything it does not know is an error, the default.
+/// 2. `Permissive` – A statement of a kind it does not know (`GRANT ...`, `CREATE EXTENSION ...`) is kept as `Statement::Unparsed` when the parser has the text it reads (`Parser::source`), a clause it does not know, at the end of a statement or of an item in the column list of `CREATE TABLE`, is skipped up to the next `;` (or `,` and `)` in a column list) and kept as a `SkippedClause`, and a type it does not know is read as `DBType::Custom`. The statement around the clause is returned without it, so pipelines keep processing SQL that is only partly supported (`ENGINE = InnoDB` after a table, `RETURNING id` after an INSERT).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ParseMode {
+    #[default]
+    Strict,
+    Permissive,
+}
+
+impl ParseMode {
+    pub fn from_name(name: &str) -> Option<ParseMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "strict" => Some(ParseMode::Strict),
+            "permissive" => Some(ParseMode::Permissive),
+            _ => None,
+        }
+    }
+}
+
+/// A clause the parser skipped in permissive mode.
+/// 1. `statement` – Index of the statement it belongs to, among the ones `parse_statements` returns.
+/// 2. `tokens` – The tokens of the clause, from the first one the parser did not understand, as the tokenizer read them.
+/// 3. `error` – The error strict mode would have failed with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedClause {
+    pub statement: usize,
+    pub tokens: Vec<Token>,
+    pub error: String,
+}
+
+/// Parses a standalone expression such as a stored `WHERE` fragment (`age > 18 AND active`), without wrapping it in a `SELECT`. The whole input must be one expression, an optional trailing semicolon aside.
+pub fn parse_expression(sql: &str) -> Result<Expression, ParseError> {
+    parse_expression_with_dialect(sql, Dialect::default())
+}
+
+/// Like `parse_expression`, following the lexical and operator rules of the given dialect.
+pub fn parse_expression_with_dialect(sql: &str, dialect: Dialect) -> Result<Expression, ParseError> {
+    let tokens = Tokenizer::with_dialect(sql, dialect).tokenize().map_err(|err| err.to_string())?;
+    let mut parser = Parser::with_dialect(tokens, dialect);
+    let expr = parser.parse_expression(0)?;
+    parser.expect_end("expression")?;
+    Ok(expr)
+}
+
+/// Parses a column type on its own, e.g. `"DECIMAL(10,2)"` or `"VARCHAR(255)"`, for tools that validate type names kept outside of SQL scripts.
+pub fn parse_data_type(sql: &str) -> Result<DBType, ParseError> {
+    parse_data_type_with_dialect(sql, Dialect::default())
+}
+
+/// Like `parse_data_type`, following the lexical rules of the given dialect.
+pub fn parse_data_type_with_dialect(sql: &str, dialect: Dialect) -> Result<DBType, ParseError> {
+    let tokens = Tokenizer::with_dialect(sql, dialect).tokenize().map_err(|err| err.to_string())?;
+    let mut parser = Parser::with_dialect(tokens, dialect);
+    let ty = parser.parse_data_type()?;
+    parser.expect_end("data type")?;
+    Ok(ty)
+}
+
+/// Parses a script taken out of a larger file, such as the text of a string literal in a Rust source file, that starts at `origin` in it. Errors say where they are in the host file: a lexical error where the offending text is, a syntax error at the token the parser could not take (the last token of the script when it ends too early).
+pub fn parse_embedded(sql: &str, dialect: Dialect, origin: Origin) -> Result<Vec<Statement>, ParseError> {
+    let mut spans = Vec::new();
+    let mut tokens = Vec::new();
+    for result in Tokenizer::with_dialect(sql, dialect).origin(origin).spanned() {
+        let spanned = result.map_err(|err| err.to_string())?;
+        spans.push(spanned.span);
+        tokens.push(spanned.token);
+    }
+    //without hints and comments the parser keeps every token, so its positions are positions in `spans`
+    let mut parser = Parser::with_dialect(tokens, dialect);
+    let mut statements = Vec::new();
+    while parser.peek() != &Token::Eof {
+        let stmt = parser.parse_statement().map_err(|err| {
+            //a script that ends too early fails at the Eof after its last token
+            let at = spans[parser.pos.min(spans.len() - 1)];
+            format!("{} at line {}, column {}", err, at.line, at.column)
+        })?;
+        statements.push(stmt);
+    }
+    Ok(statements)
+}
+
+//holds a list of tokens and a position index for parsing them
+pub struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    dialect: Dialect,
+    //hint comments taken out of the token list, each with the position of the token that followed it
+    hints: Vec<(usize, String)>,
+    //whether a type name the parser does not know is read as DBType::Custom rather than rejected
+    custom_types: bool,
+    mode: ParseMode,
+    //clauses skipped in permissive mode, and the number of statements parse_statements has returned so far
+    skipped: Vec<SkippedClause>,
+    statements: usize,
+    //the text the tokens were read from, for the text of unparsed statements, and the span of every token once one is found
+    source: Option<Vec<u8>>,
+    spans: Vec<Span>,
+    //position of the statement being parsed
+    statement_start: usize,
+}
+//make new parser with token list
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Parser::with_dialect(tokens, Dialect::default())
+    }
+
+    //parser that accepts the clauses of the given dialect, the tokens should come from a tokenizer with the same dialect
+    pub fn with_dialect(tokens: Vec<Token>, dialect: Dialect) -> Self {
+        //hints and kept comments may appear between any two tokens, set hints aside and drop comments so the grammar never sees them
+        let mut hints = Vec::new();
+        let mut kept = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match token {
+                Token::Hint(text) => hints.push((kept.len(), text)),
+                Token::Comment { .. } => {}
+                other => kept.push(other),
+            }
+        }
+        //the tokenizer iterator stops before Eof, so add it back to always have a token to peek at
+        if kept.last() != Some(&Token::Eof) {
+            kept.push(Token::Eof);
+        }
+        Parser { tokens: kept, pos: 0, dialect, hints, custom_types: false, mode: ParseMode::Strict, skipped: Vec::new(), statements: 0, source: None, spans: Vec::new(), statement_start: 0 }
+    }
+
+    /// Reads a column type the parser does not know, `CITEXT` or `GEOGRAPHY(Point)`, as `DBType::Custom` with the expressions in parentheses after its name, instead of failing with "Expected type". Off by default, so a misspelled type is an error.
+    pub fn custom_types(mut self, custom_types: bool) -> Self {
+        self.custom_types = custom_types;
+        self
+    }
+
+    /// Sets how SQL the parser does not understand is handled, see `ParseMode`. Strict by default.
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Gives the parser the text its tokens were read from, `Parser::with_dialect(tokens, dialect).source(sql)`, so a statement kept unparsed in permissive mode comes with its text as written and where it is (`Statement::Unparsed`). Without it, or with tokens that were not read from `sql`, such a statement is an error as in strict mode.
+    pub fn source(mut self, sql: &[u8]) -> Self {
+        self.source = Some(sql.to_vec());
+        self
+    }
+
+    //helper, the span of every token, found by reading the source again the first time they are needed; None without a
+    //source or when the tokens were not read from it
+    fn token_spans(&mut self) -> Option<&[Span]> {
+        let sql = self.source.as_ref()?;
+        if self.spans.is_empty() {
+            //hints are read too, so every token of any tokenizer over `sql` is there, and set aside as in with_dialect
+            self.spans = Tokenizer::from_bytes(sql, self.dialect)
+                .keep_hints(true)
+                .spanned()
+                .filter_map(Result::ok)
+                .filter(|spanned| !matches!(spanned.token, Token::Hint(_)))
+                .map(|spanned| spanned.span)
+                .collect();
+        }
+        //the token list ends with Eof, which has no span
+        (self.spans.len() + 1 == self.tokens.len()).then_some(&self.spans[..])
+    }
+
+    /// The clauses skipped so far in permissive mode, in the order they were found.
+    pub fn skipped(&self) -> &[SkippedClause] {
+        &self.skipped
+    }
+
+    //peek at current token without going forward
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    //peek n tokens ahead, Eof once past the end
+    fn peek_nth(&self, n: usize) -> &Token {
+        &self.tokens[(self.pos + n).min(self.tokens.len() - 1)]
+    }
+
+    //helper, whether the token n ahead is the given word used as a contextual keyword
+    fn peek_word(&self, n: usize, word: &str) -> bool {
+        matches!(self.peek_nth(n), Token::Identifier(s) if s.eq_ignore_ascii_case(word))
+    }
+
+    //get current token and move to next
+    fn next(&mut self) -> Token {
+        let tok = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    //like next, but a non-reserved keyword comes back as an identifier, for positions that expect a name
+    fn next_name(&mut self) -> Token {
+        match self.next() {
+            Token::Keyword(keyword) if !keyword.is_reserved() => Token::Identifier(keyword.as_str().to_lowercase()),
+            other => other,
+        }
+    }
+
+    //error for a token found where a name was expected, a reserved keyword gets a hint on how to use it as a name anyway
+    fn expected_name(&self, what: &str, found: &Token) -> String {
+        match found {
+            Token::Keyword(keyword) => format!("Expected {}, found {}. {}", what, keyword.as_str(), self.reserved_hint(*keyword)),
+            other => format!("Expected {}, found {:?}", what, other),
+        }
+    }
+
+    //helper, how to use a reserved keyword as a name, `ORDER is a reserved keyword; quote it as `order``
+    fn reserved_hint(&self, keyword: Keyword) -> String {
+        let quote = self.dialect.identifier_quote();
+        format!("{} is a reserved keyword; quote it as {}{}{}", keyword.as_str(), quote, keyword.as_str().to_lowercase(), quote)
+    }
+
+    //expect specific token, if it doesnt match, show error
+    fn expect(&mut self, expected: &Token) -> Result<(), String>
+    where
+        Token: PartialEq + std::fmt::Debug,
+    {
+        if self.peek() == expected {
+            self.next();
+            Ok(())
+        } else {
+            Err(format!("Expected {:?}, found {:?}", expected, self.peek()))
+        }
+    }
+
+    //end of a statement, in permissive mode a clause the statement does not have is skipped up to the semicolon
+    fn expect_statement_end(&mut self) -> Result<(), String> {
+        if self.peek() != &Token::Semicolon {
+            let err = format!("Expected {:?}, found {:?}", Token::Semicolon, self.peek());
+            self.skip_clause(&[], err)?;
+        }
+        self.expect(&Token::Semicolon)
+    }
+
+    //helper, in permissive mode skip the tokens up to the end of the statement or one of `stop` outside of parentheses and
+    //keep them as a SkippedClause; in strict mode, or when there is nothing to skip, `err` is the error
+    fn skip_clause(&mut self, stop: &[Token], err: String) -> Result<(), String> {
+        if self.mode == ParseMode::Strict {
+            return Err(err);
+        }
+        let mut tokens = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                Token::Semicolon | Token::Eof => break,
+                tok if depth == 0 && stop.contains(tok) => break,
+                Token::LeftParentheses => depth += 1,
+                Token::RightParentheses => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            tokens.push(self.next());
+        }
+        if tokens.is_empty() {
+            return Err(err);
+        }
+        self.skipped.push(SkippedClause { statement: self.statements, tokens, error: err });
+        Ok(())
+    }
+
+    //main entry
+    //decide what kind of sql statement to parse
+    pub fn parse_statement(&mut self) -> Result<Statement, String> {
+        self.statement_start = self.pos;
+        match self.peek() {
+            Token::Keyword(Keyword::Select | Keyword::With | Keyword::Values) | Token::LeftParentheses => {
+                let query = self.parse_query()?;
+                self.expect_statement_end()?;
+                Ok(query)
+            }
+            Token::Keyword(Keyword::Create) => {
+                self.next();
+                self.parse_create()
+            }
+            Token::Keyword(Keyword::Insert) => {
+                self.next();
+                self.parse_insert()
+            }
+            Token::Keyword(Keyword::Delete) => {
+                self.next();
+                self.parse_delete()
+            }
+            Token::Keyword(Keyword::Merge) => {
+                self.next();
+                self.parse_merge()
+            }
+            Token::Keyword(Keyword::Drop) => {
+                self.next();
+                self.parse_drop()
+            }
+            Token::Keyword(Keyword::Alter) => {
+                self.next();
+                self.parse_alter_table()
+            }
+            Token::Keyword(Keyword::Rename) => {
+                self.next();
+                self.parse_rename_table()
+            }
+            Token::Keyword(Keyword::Truncate) => {
+                self.next();
+                self.parse_truncate()
+            }
+            Token::Keyword(Keyword::Analyze) => {
+                self.next();
+                let table_name = self.parse_maintenance_table()?;
+                Ok(Statement::Analyze { table_name })
+            }
+            Token::Keyword(Keyword::Vacuum) => {
+                self.next();
+                let full = self.peek() == &Token::Keyword(Keyword::Full);
+                if full {
+                    self.next();
+                }
+                let table_name = self.parse_maintenance_table()?;
+                Ok(Statement::Vacuum { full, table_name })
+            }
+            Token::Keyword(Keyword::Show) => {
+                self.next();
+                self.parse_show()
+            }
+            Token::Keyword(Keyword::Describe) | Token::Keyword(Keyword::Desc) => {
+                self.next();
+                self.parse_describe()
+            }
+            Token::Keyword(Keyword::Set) => {
+                self.next();
+                self.parse_set()
+            }
+            Token::Keyword(Keyword::Call) => {
+                self.next();
+                self.parse_call()
+            }
+            Token::Keyword(Keyword::Copy) => {
+                self.next();
+                self.parse_copy()
+            }
+            Token::Keyword(Keyword::Load) => {
+                self.next();
+                self.parse_load_data()
+            }
+            Token::Keyword(Keyword::Prepare) => {
+                self.next();
+                self.parse_prepare()
+            }
+            Token::Keyword(Keyword::Execute) => {
+                self.next();
+                self.parse_execute()
+            }
+            Token::Keyword(Keyword::Deallocate) => {
+                self.next();
+                self.parse_deallocate()
+            }
+            other => {
+                let err = format!("Expected SELECT, WITH, CREATE, INSERT, DELETE, MERGE, DROP, ALTER, RENAME, TRUNCATE, ANALYZE, VACUUM, SHOW, DESCRIBE, SET, CALL, COPY, LOAD, PREPARE, EXECUTE or DEALLOCATE, found {:?}", other);
+                self.unknown_statement(err)
+            }
+        }
+    }
+
+    //a statement of a kind the parser does not know, found at its first word or at the word after CREATE, DROP, ALTER
+    //or SHOW: in permissive mode it is kept as its text up to the semicolon, `err` is the error in strict mode or when
+    //the parser has no text to keep
+    fn unknown_statement(&mut self, err: String) -> Result<Statement, String> {
+        if self.mode == ParseMode::Strict {
+            return Err(err);
+        }
+        let start = self.statement_start;
+        self.pos = start;
+        while !matches!(self.peek(), Token::Semicolon | Token::Eof) {
+            self.next();
+        }
+        let end = self.pos;
+        let Some((first, last)) = self.token_spans().filter(|_| end > start).map(|spans| (spans[start], spans[end - 1])) else {
+            self.pos = start;
+            return Err(err);
+        };
+        let span = Span { start: first.start, end: last.end, line: first.line, column: first.column };
+        let raw = self.source.as_ref().map(|sql| String::from_utf8_lossy(&sql[span.start..span.end]).into_owned()).unwrap_or_default();
+        self.expect(&Token::Semicolon)?;
+        Ok(Statement::Unparsed { raw, span })
+    }
+
+    //parse every statement until the end of input
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>, String> {
+        let mut statements = Vec::new();
+        while self.peek() != &Token::Eof {
+            statements.push(self.parse_statement()?);
+            self.statements += 1;
+        }
+        Ok(statements)
+    }
+
+    //parenthesized SELECT used inside an expression, e.g. EXISTS (SELECT ...)
+    fn parse_subquery(&mut self) -> Result<Statement, String> {
+        self.expect(&Token::LeftParentheses)?;
+        let select = self.parse_query()?;
+        self.expect(&Token::RightParentheses)?;
+        Ok(select)
+    }
+
+    //a query without the closing semicolon: SELECTs combined with UNION, INTERSECT and EXCEPT, with an optional WITH
+    //clause in front and optional ORDER BY and LIMIT clauses after them
+    fn parse_query(&mut self) -> Result<Statement, String> {
+        let with = if let Token::Keyword(Keyword::With) = self.peek() {
+            self.next();
+            Some(self.parse_with()?)
+        } else {
+            None
+        };
+        let mut query = self.parse_set_expression(0)?;
+        let (orderby, limit) = self.parse_order_limit()?;
+        if let Statement::Select { with: query_with, orderby: query_orderby, limit: query_limit, .. }
+        | Statement::SetOperation { with: query_with, orderby: query_orderby, limit: query_limit, .. } = &mut query
+        {
+            //only a query in parentheses can have them already
+            if (with.is_some() && query_with.is_some()) || (!orderby.is_empty() && !query_orderby.is_empty()) || (limit.is_some() && query_limit.is_some()) {
+                return Err("A query in parentheses with its own WITH, ORDER BY or LIMIT clause can not have another one".to_string());
+            }
+            if with.is_some() {
+                *query_with = with;
+            }
+            if !orderby.is_empty() {
+                *query_orderby = orderby;
+            }
+            if limit.is_some() {
+                *query_limit = limit;
+            }
+        } else if with.is_some() || !orderby.is_empty() || limit.is_some() {
+            return Err("VALUES can not have a WITH, ORDER BY or LIMIT clause".to_string());
+        }
+        Ok(query)
+    }
+
+    //SELECTs and queries in parentheses combined with UNION, INTERSECT and EXCEPT, INTERSECT binds tighter than the others
+    //and operators that bind the same go from left to right
+    fn parse_set_expression(&mut self, min_precedence: u8) -> Result<Statement, String> {
+        let mut left = match self.next() {
+            Token::LeftParentheses => {
+                let query = self.parse_query()?;
+                self.expect(&Token::RightParentheses)?;
+                query
+            }
+            Token::Keyword(Keyword::Select) => self.parse_select_body()?,
+            Token::Keyword(Keyword::Values) => Statement::Values { rows: self.parse_value_rows(None, "VALUES")? },
+            other => return Err(format!("Expected SELECT, VALUES or a query in parentheses, found {:?}", other)),
+        };
+        loop {
+            let operator = match self.peek() {
+                Token::Keyword(Keyword::Union) => SetOperator::Union,
+                Token::Keyword(Keyword::Intersect) => SetOperator::Intersect,
+                Token::Keyword(Keyword::Except) => SetOperator::Except,
+                _ => break,
+            };
+            if operator.precedence() < min_precedence {
+                break;
+            }
+            self.next();
+            //DISTINCT is what happens without ALL
+            let all = match self.peek() {
+                Token::Keyword(Keyword::All) => {
+                    self.next();
+                    true
+                }
+                Token::Keyword(Keyword::Distinct) => {
+                    self.next();
+                    false
+                }
+                _ => false,
+            };
+            let right = self.parse_set_expression(operator.precedence() + 1)?;
+            left = Statement::SetOperation { with: None, left: Box::new(left), operator, all, right: Box::new(right), orderby: Vec::new(), limit: None };
+        }
+        Ok(left)
+    }
+
+    //a query in parentheses where parentheses could also group an expression or joins, `(` is already consumed and
+    //the next token is another `(`: the query when the tokens up to the closing parenthesis form one, otherwise None
+    //with nothing consumed
+    fn parse_parenthesized_query(&mut self) -> Option<Statement> {
+        let start = self.pos;
+        match self.parse_query().and_then(|query| self.expect(&Token::RightParentheses).map(|_| query)) {
+            Ok(query) => Some(query),
+            Err(_) => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    //the common table expressions of a WITH clause, WITH is already consumed
+    fn parse_with(&mut self) -> Result<With, String> {
+        let recursive = if let Token::Keyword(Keyword::Recursive) = self.peek() {
+            self.next();
+            true
+        } else {
+            false
+        };
+        let mut ctes = Vec::new();
+        loop {
+            let name = self.parse_identifier("common table expression name")?;
+            let mut columns = Vec::new();
+            if let Token::LeftParentheses = self.peek() {
+                self.next();
+                loop {
+                    columns.push(self.parse_identifier("column name")?);
+                    match self.next() {
+                        Token::Comma => continue,
+                        Token::RightParentheses => break,
+                        other => return Err(format!("Expected ',' or ')' in the column list of {}, found {:?}", name, other)),
+                    }
+                }
+            }
+            self.expect(&Token::Keyword(Keyword::As))?;
+            let query = self.parse_subquery()?;
+            if ctes.iter().any(|cte: &Cte| cte.name.eq_ignore_ascii_case(&name)) {
+                return Err(format!("Common table expression {} is defined more than once", name));
+            }
+            ctes.push(Cte { name, columns, query: Box::new(query) });
+            if let Token::Comma = self.peek() {
+                self.next();
+                continue;
+            }
+            break;
+        }
+        Ok(With { recursive, ctes })
+    }
+
+    //optional `AS alias` (or just `alias`) after a projected column
+    fn parse_alias(&mut self, expr: Expression) -> Result<Expression, String> {
+        Ok(match self.parse_optional_alias()? {
+            Some(alias) => Expression::Alias { expr: Box::new(expr), alias },
+            None => expr,
+        })
+    }
+
+    //helper, `AS name` or a bare name, None when neither follows
+    fn parse_optional_alias(&mut self) -> Result<Option<String>, String> {
+        let explicit = if let Token::Keyword(Keyword::As) = self.peek() {
+            self.next();
+            true
+        } else {
+            false
+        };
+        match self.peek() {
+            tok if is_name(tok) => match self.next_name() {
+                Token::Identifier(s) => Ok(Some(s)),
+                _ => unreachable!(),
+            },
+            other if explicit => Err(self.expected_name("alias after AS", other)),
+            _ => Ok(None),
+        }
+    }
+
+    //one comma separated item of the FROM clause, a table reference followed by any number of joins
+    fn parse_table_with_joins(&mut self) -> Result<TableReference, String> {
+        let mut left = self.parse_table_factor()?;
+        loop {
+            let kind = match self.peek() {
+                Token::Keyword(Keyword::Join) => JoinKind::Inner,
+                Token::Keyword(Keyword::Inner) => {
+                    self.next();
+                    JoinKind::Inner
+                }
+                Token::Keyword(Keyword::Left) => {
+                    self.next();
+                    self.consume_outer();
+                    JoinKind::Left
+                }
+                Token::Keyword(Keyword::Right) => {
+                    self.next();
+                    self.consume_outer();
+                    JoinKind::Right
+                }
+                Token::Keyword(Keyword::Full) => {
+                    self.next();
+                    self.consume_outer();
+                    JoinKind::Full
+                }
+                Token::Keyword(Keyword::Cross) => {
+                    self.next();
+                    JoinKind::Cross
+                }
+                _ => break,
+            };
+            self.expect(&Token::Keyword(Keyword::Join))?;
+            let right = self.parse_table_factor()?;
+
+            //a missing condition is allowed here and left for analysis to report
+            let on = if kind != JoinKind::Cross && self.peek() == &Token::Keyword(Keyword::On) {
+                self.next();
+                Some(self.parse_condition("ON")?)
+            } else {
+                None
+            };
+            left = TableReference::Join { left: Box::new(left), right: Box::new(right), kind, on };
+        }
+        Ok(left)
+    }
+
+    //optional TABLESAMPLE method (percentage) [REPEATABLE (seed)] after a table name and its alias
+    fn parse_table_sample(&mut self) -> Result<Option<TableSample>, String> {
+        if self.peek() != &Token::Keyword(Keyword::Tablesample) {
+            return Ok(None);
+        }
+        self.next();
+        let method = match self.next_name() {
+            Token::Identifier(method) => method,
+            other => return Err(self.expected_name("sampling method after TABLESAMPLE", &other)),
+        };
+        self.expect(&Token::LeftParentheses)?;
+        let percentage = self.parse_expression(0)?;
+        self.expect(&Token::RightParentheses)?;
+
+        let repeatable = if let Token::Keyword(Keyword::Repeatable) = self.peek() {
+            self.next();
+            self.expect(&Token::LeftParentheses)?;
+            let seed = self.parse_expression(0)?;
+            self.expect(&Token::RightParentheses)?;
+            Some(seed)
+        } else {
+            None
+        };
+        Ok(Some(TableSample { method, percentage, repeatable }))
+    }
+
+    //helper, the optional OUTER of LEFT/RIGHT/FULL OUTER JOIN
+    fn consume_outer(&mut self) {
+        if let Token::Keyword(Keyword::Outer) = self.peek() {
+            self.next();
+        }
+    }
+
+    //a single table reference: table name, table function or subquery, each with an optional alias
+    fn parse_table_factor(&mut self) -> Result<TableReference, String> {
+        let lateral = if let Token::Keyword(Keyword::Lateral) = self.peek() {
+            self.next();
+            true
+        } else {
+            false
+        };
+
+        let table = match self.next_name() {
+            Token::Identifier(name) => {
+                if let Token::LeftParentheses = self.peek() {
+                    self.next();
+                    let args = self.parse_function_args()?;
+                    TableReference::Function { lateral, name, args, alias: self.parse_optional_alias()? }
+                } else if lateral {
+                    return Err(format!("Expected subquery or table function after LATERAL, found {:?}", name));
+                } else {
+                    let alias = self.parse_optional_alias()?;
+                    TableReference::Table { name, alias, sample: self.parse_table_sample()? }
+                }
+            }
+            //parentheses around joins only group them, like in expressions
+            //unless they hold a query that starts with a query in parentheses, ((SELECT ...) UNION ...)
+            Token::LeftParentheses if !lateral && !matches!(self.peek(), Token::Keyword(Keyword::Select | Keyword::With | Keyword::Values)) => {
+                let subquery = if self.peek() == &Token::LeftParentheses { self.parse_parenthesized_query() } else { None };
+                match subquery {
+                    Some(subquery) => TableReference::Derived { lateral, subquery: Box::new(subquery), alias: self.parse_optional_alias()? },
+                    None => {
+                        let nested = self.parse_table_with_joins()?;
+                        self.expect(&Token::RightParentheses)?;
+                        nested
+                    }
+                }
+            }
+            Token::LeftParentheses => {
+                let subquery = self.parse_query()?;
+                self.expect(&Token::RightParentheses)?;
+                TableReference::Derived { lateral, subquery: Box::new(subquery), alias: self.parse_optional_alias()? }
+            }
+            other => return Err(self.expected_name("table name", &other)),
+        };
+        self.parse_pivots(table)
+    }
+
+    //any number of PIVOT (...) / UNPIVOT (...) operators applied to a table reference, each with an optional alias
+    fn parse_pivots(&mut self, mut table: TableReference) -> Result<TableReference, String> {
+        loop {
+            table = match self.peek() {
+                Token::Keyword(Keyword::Pivot) => {
+                    self.next();
+                    self.expect(&Token::LeftParentheses)?;
+                    let aggregate = self.parse_expression(0)?;
+                    self.expect(&Token::Keyword(Keyword::For))?;
+                    let column = self.parse_expression(0)?;
+                    self.expect(&Token::Keyword(Keyword::In))?;
+                    self.expect(&Token::LeftParentheses)?;
+                    let values = self.parse_function_args()?;
+                    self.expect(&Token::RightParentheses)?;
+                    let pivot = PivotClause { aggregate, column, values };
+                    TableReference::Pivot { table: Box::new(table), pivot, alias: self.parse_optional_alias()? }
+                }
+                Token::Keyword(Keyword::Unpivot) => {
+                    self.next();
+                    self.expect(&Token::LeftParentheses)?;
+                    let value_column = self.parse_identifier("value column")?;
+                    self.expect(&Token::Keyword(Keyword::For))?;
+                    let name_column = self.parse_identifier("name column")?;
+                    self.expect(&Token::Keyword(Keyword::In))?;
+                    self.expect(&Token::LeftParentheses)?;
+                    let mut columns = vec![self.parse_identifier("column")?];
+                    while let Token::Comma = self.peek() {
+                        self.next();
+                        columns.push(self.parse_identifier("column")?);
+                    }
+                    self.expect(&Token::RightParentheses)?;
+                    self.expect(&Token::RightParentheses)?;
+                    let unpivot = UnpivotClause { value_column, name_column, columns };
+                    TableReference::Unpivot { table: Box::new(table), unpivot, alias: self.parse_optional_alias()? }
+                }
+                _ => return Ok(table),
+            };
+        }
+    }
+
+    //helper, a plain identifier, `what` names it in the error message
+    fn parse_identifier(&mut self, what: &str) -> Result<String, String> {
+        match self.next_name() {
+            Token::Identifier(name) => Ok(name),
+            other => Err(self.expected_name(what, &other)),
+        }
+    }
+
+    //helper, consume a word used as a contextual keyword, `after` names what it follows in the error message
+    fn expect_word(&mut self, word: &str, after: &str) -> Result<(), String> {
+        if self.peek_word(0, word) {
+            self.next();
+            Ok(())
+        } else {
+            Err(format!("Expected {} after {}, found {:?}", word, after, self.peek()))
+        }
+    }
+
+    //helper, a string literal, `what` names it in the error message
+    fn parse_string(&mut self, what: &str) -> Result<String, String> {
+        match self.next() {
+            Token::String { value, .. } => Ok(value),
+            other => Err(format!("Expected {} as a string, found {:?}", what, other)),
+        }
+    }
+
+    //column names in parentheses, the opening parenthesis is already consumed
+    fn parse_column_names(&mut self) -> Result<Vec<String>, String> {
+        let mut columns = Vec::new();
+        loop {
+            columns.push(self.parse_identifier("column name")?);
+            match self.next() {
+                Token::Comma => continue,
+                Token::RightParentheses => break,
+                other => return Err(format!("Expected ',' or ')' in the column list, found {:?}", other)),
+            }
+        }
+        Ok(columns)
+    }
+
+    //argument list of a function call, the opening parenthesis is already consumed
+    fn parse_function_args(&mut self) -> Result<Vec<Expression>, String> {
+        let mut args = Vec::new();
+        if let Token::RightParentheses = self.peek() {
+            self.next();
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_expression(0)?);
+            match self.next() {
+                Token::Comma => continue,
+                Token::RightParentheses => break,
+                other => return Err(format!("Expected ',' or ')' in function arguments, found {:?}", other)),
+            }
+        }
+        Ok(args)
+    }
+
+    //window after OVER, (PARTITION BY ... ORDER BY ...) with both parts optional
+    fn parse_window_spec(&mut self) -> Result<WindowSpec, String> {
+        self.expect(&Token::LeftParentheses)?;
+        let mut partition_by = Vec::new();
+        if let Token::Keyword(Keyword::Partition) = self.peek() {
+            self.next();
+            self.expect(&Token::Keyword(Keyword::By))?;
+            loop {
+                partition_by.push(self.parse_expression(0)?);
+                if let Token::Comma = self.peek() {
+                    self.next();
+                    continue;
+                }
+                break;
+            }
+        }
+        let mut order_by = Vec::new();
+        if let Token::Keyword(Keyword::Order) = self.peek() {
+            self.next();
+            self.expect(&Token::Keyword(Keyword::By))?;
+            loop {
+                order_by.push(self.parse_expression(0)?);
+                if let Token::Comma = self.peek() {
+                    self.next();
+                    continue;
+                }
+                break;
+            }
+        }
+        self.expect(&Token::RightParentheses)?;
+        Ok(WindowSpec { partition_by, order_by })
+    }
+
+    //everything after the SELECT keyword, without the closing semicolon
+    fn parse_select_body(&mut self) -> Result<Statement, String> {
+        //hint comments directly before or after the SELECT keyword that was just consumed
+        let hints = self.hints.iter()
+            .filter(|(at, _)| *at + 1 == self.pos || *at == self.pos)
+            .flat_map(|(_, text)| parse_hints(text))
+            .collect();
+
+        //start columns
+        let mut columns = Vec::new();
+        loop {
+            let expr = self.parse_expression(0)?;
+            columns.push(self.parse_alias(expr)?);
+            if let Token::Comma = self.peek() {
+                self.next();
+                continue;
+            }
+            break;
+        }
+
+        //make sure 'FROM' appears after the SELECT columns
+        self.expect(&Token::Keyword(Keyword::From))?;
+        let mut from = Vec::new();
+        loop {
+            from.push(self.parse_table_with_joins()?);
+            if let Token::Comma = self.peek() {
+                self.next();
+                continue;
+            }
+            break;
+        }
+
+        //optional WHERE exp
+        let where_clause = if let Token::Keyword(Keyword::Where) = self.peek() {
+            self.next();
+            Some(self.parse_condition("WHERE")?)
+        } else {
+            None
+        };
+
+        //optional GROUP BY list and HAVING exp
+        let mut group_by = Vec::new();
+        if let Token::Keyword(Keyword::Group) = self.peek() {
+            self.next();
+            self.expect(&Token::Keyword(Keyword::By))?;
+            loop {
+                group_by.push(self.parse_grouping_element()?);
+                if let Token::Comma = self.peek() {
+                    self.next();
+                    continue;
+                }
+                break;
+            }
+        }
+        let having = if let Token::Keyword(Keyword::Having) = self.peek() {
+            self.next();
+            Some(self.parse_condition("HAVING")?)
+        } else {
+            None
+        };
+
+        //optional QUALIFY exp, only in dialects that have it
+        let qualify = if let Token::Keyword(Keyword::Qualify) = self.peek() {
+            if !self.dialect.supports_qualify() {
+                return Err(format!("QUALIFY is not supported in the {} dialect", self.dialect));
+            }
+            self.next();
+            Some(self.parse_condition("QUALIFY")?)
+        } else {
+            None
+        };
+
+        //ORDER BY and LIMIT belong to the query, which may combine this SELECT with others, see parse_query
+        Ok(Statement::Select {
+            with: None,
+            hints,
+            columns,
+            from,
+            r#where: where_clause,
+            group_by,
+            having,
+            qualify,
+            orderby: Vec::new(),
+            limit: None,
+        })
+    }
+
+    //the optional ORDER BY and LIMIT clauses at the end of a query
+    fn parse_order_limit(&mut self) -> Result<(Vec<Expression>, Option<Expression>), String> {
+        let mut orderby = Vec::new();
+        if let Token::Keyword(Keyword::Order) = self.peek() {
+            self.next();
+            self.expect(&Token::Keyword(Keyword::By))?;
+            loop {
+                let expr = self.parse_expression(0)?;
+                orderby.push(expr);
+                if let Token::Comma = self.peek() {
+                    self.next();
+                    continue;
+                }
+                break;
+            }
+        }
+
+        let limit = if let Token::Keyword(Keyword::Limit) = self.peek() {
+            self.next();
+            Some(self.parse_expression(0)?)
+        } else {
+            None
+        };
+        Ok((orderby, limit))
+    }
+
+    //one GROUP BY item: an expression, ROLLUP (...), CUBE (...) or GROUPING SETS (...)
+    //the construct names are only special here, elsewhere they stay ordinary identifiers
+    fn parse_grouping_element(&mut self) -> Result<GroupingElement, String> {
+        if self.peek_word(0, "ROLLUP") && self.peek_nth(1) == &Token::LeftParentheses {
+            self.next();
+            return Ok(GroupingElement::Rollup(self.parse_grouping_sets()?));
+        }
+        if self.peek_word(0, "CUBE") && self.peek_nth(1) == &Token::LeftParentheses {
+            self.next();
+            return Ok(GroupingElement::Cube(self.parse_grouping_sets()?));
+        }
+        if self.peek_word(0, "GROUPING") && self.peek_word(1, "SETS") {
+            self.next();
+            self.next();
+            return Ok(GroupingElement::GroupingSets(self.parse_grouping_sets()?));
+        }
+        Ok(GroupingElement::Expression(self.parse_expression(0)?))
+    }
+
+    //parenthesized list of grouping sets, each one either a single expression or a parenthesized (possibly empty) list
+    fn parse_grouping_sets(&mut self) -> Result<Vec<Vec<Expression>>, String> {
+        self.expect(&Token::LeftParentheses)?;
+        let mut sets = Vec::new();
+        loop {
+            //a parenthesized set, unless the parentheses only open a longer expression like (a + b) * 2
+            let start = self.pos;
+            let mut set = None;
+            if let Token::LeftParentheses = self.peek() {
+                self.next();
+                match (self.parse_function_args(), self.peek()) {
+                    (Ok(exprs), Token::Comma | Token::RightParentheses) => set = Some(exprs),
+                    _ => self.pos = start,
+                }
+            }
+            match set {
+                Some(set) => sets.push(set),
+                None => sets.push(vec![self.parse_expression(0)?]),
+            }
+            match self.next() {
+                Token::Comma => continue,
+                Token::RightParentheses => break,
+                other => return Err(format!("Expected ',' or ')' in grouping sets, found {:?}", other)),
+            }
+        }
+        Ok(sets)
+    }
+
+    //create parsing, CREATE is already consumed, the keyword after it tells what is created
+    fn parse_create(&mut self) -> Result<Statement, String> {
+        let or_replace = self.peek() == &Token::Keyword(Keyword::Or) && self.peek_nth(1) == &Token::Keyword(Keyword::Replace);
+        if or_replace {
+            self.next();
+            self.next();
+        }
+        //TEMP and TEMPORARY are not reserved, they are only taken for the modifier right before TABLE
+        let temporary = (self.peek_word(0, "temp") || self.peek_word(0, "temporary")) && self.peek_nth(1) == &Token::Keyword(Keyword::Table);
+        if temporary {
+            self.next();
+        }
+        match self.next() {
+            Token::Keyword(Keyword::Function) => self.parse_create_routine(or_replace, true),
+            Token::Keyword(Keyword::Procedure) => self.parse_create_routine(or_replace, false),
+            other if or_replace => self.unknown_statement(format!("Expected FUNCTION or PROCEDURE after CREATE OR REPLACE, found {:?}", other)),
+            Token::Keyword(Keyword::Table) => self.parse_create_table(temporary),
+            Token::Keyword(Keyword::Index) => self.parse_create_index(false),
+            Token::Keyword(Keyword::Trigger) => self.parse_create_trigger(),
+            Token::Keyword(Keyword::Unique) => {
+                self.expect(&Token::Keyword(Keyword::Index))?;
+                self.parse_create_index(true)
+            }
+            Token::Keyword(Keyword::Database) => {
+                let if_not_exists = self.parse_if_not_exists();
+                let name = self.parse_identifier("database name")?;
+                self.expect_statement_end()?;
+                Ok(Statement::CreateDatabase { name, if_not_exists })
+            }
+            Token::Keyword(Keyword::Schema) => {
+                let if_not_exists = self.parse_if_not_exists();
+                let name = self.parse_identifier("schema name")?;
+                self.expect_statement_end()?;
+                Ok(Statement::CreateSchema { name, if_not_exists })
+            }
+            other => self.unknown_statement(format!("Expected TABLE, INDEX, TRIGGER, FUNCTION, PROCEDURE, DATABASE or SCHEMA after CREATE, found {:?}", other)),
+        }
+    }
+
+    //an optional IF NOT EXISTS, IF is not reserved so `CREATE SCHEMA if;` creates the schema named if
+    fn parse_if_not_exists(&mut self) -> bool {
+        let if_not_exists = self.peek() == &Token::Keyword(Keyword::If)
+            && self.peek_nth(1) == &Token::Keyword(Keyword::Not)
+            && self.peek_nth(2) == &Token::Keyword(Keyword::Exists);
+        if if_not_exists {
+            self.next();
+            self.next();
+            self.next();
+        }
+        if_not_exists
+    }
+
+    //create function and create procedure parsing, CREATE [OR REPLACE] FUNCTION or PROCEDURE is already consumed
+    //a function has RETURNS, then AS with the body as a string and LANGUAGE come in either order
+    fn parse_create_routine(&mut self, or_replace: bool, function: bool) -> Result<Statement, String> {
+        let name = self.parse_identifier(if function { "function name" } else { "procedure name" })?;
+        self.expect(&Token::LeftParentheses)?;
+        let mut args = Vec::new();
+        while self.peek() != &Token::RightParentheses {
+            if !args.is_empty() {
+                self.expect(&Token::Comma)?;
+            }
+            //a type right away, `(INT, VARCHAR(10), DOUBLE PRECISION)`, is an argument without a name
+            let words = self.peek_type_name(0).map_or(1, |(words, _)| words.len());
+            let name = match self.peek_nth(words) {
+                Token::Comma | Token::RightParentheses | Token::LeftParentheses => None,
+                _ => Some(self.parse_identifier("argument name")?),
+            };
+            args.push(FunctionArg { name, data_type: self.parse_data_type()? });
+        }
+        self.next();
+        let returns = if function {
+            self.expect(&Token::Keyword(Keyword::Returns))?;
+            Some(self.parse_data_type()?)
+        } else {
+            None
+        };
+        let (mut body, mut language) = (None, None);
+        loop {
+            match self.peek() {
+                Token::Keyword(Keyword::As) if body.is_none() => {
+                    self.next();
+                    body = match self.next() {
+                        Token::String { value, .. } => Some(value),
+                        other => return Err(format!("Expected the body of the routine as a string, found {:?}", other)),
+                    };
+                }
+                Token::Keyword(Keyword::Language) if language.is_none() => {
+                    self.next();
+                    language = match self.next_name() {
+                        Token::Identifier(name) | Token::String { value: name, .. } => Some(name),
+                        other => return Err(self.expected_name("language name", &other)),
+                    };
+                }
+                _ => break,
+            }
+        }
+        let body = body.ok_or_else(|| format!("Expected AS and the body of the routine, found {:?}", self.peek()))?;
+        self.expect_statement_end()?;
+        Ok(match returns {
+            Some(returns) => Statement::CreateFunction { or_replace, name, args, returns, body, language },
+            None => Statement::CreateProcedure { or_replace, name, args, body, language },
+        })
+    }
+
+    //create trigger parsing, CREATE TRIGGER is already consumed
+    //the body is not parsed, its tokens are taken up to the semicolon outside of any BEGIN ... END block
+    fn parse_create_trigger(&mut self) -> Result<Statement, String> {
+        let name = self.parse_identifier("trigger name")?;
+        let timing = match self.next() {
+            Token::Keyword(Keyword::Before) => TriggerTiming::Before,
+            Token::Keyword(Keyword::After) => TriggerTiming::After,
+            other => return Err(format!("Expected BEFORE or AFTER, found {:?}", other)),
+        };
+        let event = match self.next() {
+            Token::Keyword(Keyword::Insert) => TriggerEvent::Insert,
+            Token::Keyword(Keyword::Update) => TriggerEvent::Update,
+            Token::Keyword(Keyword::Delete) => TriggerEvent::Delete,
+            other => return Err(format!("Expected INSERT, UPDATE or DELETE, found {:?}", other)),
+        };
+        self.expect(&Token::Keyword(Keyword::On))?;
+        let table_name = self.parse_identifier("table name")?;
+        for keyword in [Keyword::For, Keyword::Each, Keyword::Row] {
+            self.expect(&Token::Keyword(keyword))?;
+        }
+        let mut body = Vec::new();
+        let mut depth = 0;
+        while !(depth == 0 && self.peek() == &Token::Semicolon) && self.peek() != &Token::Eof {
+            depth = self.peek().block_depth(depth, self.peek_nth(1));
+            body.push(self.next());
+        }
+        if body.is_empty() {
+            return Err(format!("Expected the body of the trigger, found {:?}", self.peek()));
+        }
+        if depth > 0 {
+            return Err("Expected END, found Eof".to_string());
+        }
+        self.expect_statement_end()?;
+        Ok(Statement::CreateTrigger { name, timing, event, table_name, body })
+    }
+
+    //create index parsing, CREATE [UNIQUE] INDEX is already consumed
+    fn parse_create_index(&mut self, unique: bool) -> Result<Statement, String> {
+        let if_not_exists = self.parse_if_not_exists();
+        let name = self.parse_identifier("index name")?;
+        self.expect(&Token::Keyword(Keyword::On))?;
+        let table_name = self.parse_identifier("table name")?;
+        self.expect(&Token::LeftParentheses)?;
+        let mut columns = vec![self.parse_index_column()?];
+        while let Token::Comma = self.peek() {
+            self.next();
+            columns.push(self.parse_index_column()?);
+        }
+        self.expect(&Token::RightParentheses)?;
+        self.expect_statement_end()?;
+        Ok(Statement::CreateIndex { name, table_name, columns, unique, if_not_exists })
+    }
+
+    //a column of an index with its optional ASC or DESC
+    fn parse_index_column(&mut self) -> Result<IndexColumn, String> {
+        let name = self.parse_identifier("column name")?;
+        let descending = match self.peek() {
+            Token::Keyword(Keyword::Asc) => {
+                self.next();
+                false
+            }
+            Token::Keyword(Keyword::Desc) => {
+                self.next();
+                true
+            }
+            _ => false,
+        };
+        Ok(IndexColumn { name, descending })
+    }
+
+    //create table parsing, CREATE TABLE is already consumed
+    fn parse_create_table(&mut self, temporary: bool) -> Result<Statement, String> {
+        let if_not_exists = self.parse_if_not_exists();
+
+        //table name
+        let table_name = match self.next_name() {
+            Token::Identifier(s) => s,
+            other => return Err(self.expected_name("table name", &other)),
+        };
+
+        //CREATE TABLE ... AS SELECT, the columns come from the query
+        if let Token::Keyword(Keyword::As) = self.peek() {
+            self.next();
+            let query = self.parse_query()?;
+            self.expect_statement_end()?;
+            return Ok(Statement::CreateTable {
+                table_name,
+                column_list: Vec::new(),
+                query: Some(Box::new(query)),
+                if_not_exists,
+                temporary,
+            });
+        }
+
+        self.expect(&Token::LeftParentheses)?;
+
+        let mut columns = Vec::new();
+        loop {
+            //end of list?
+            if let Token::RightParentheses = self.peek() {
+                self.next();
+                break;
+            }
+
+            //in permissive mode an item that is not a column definition (a table constraint) is skipped whole
+            let start = self.pos;
+            match self.parse_column_definition() {
+                Ok(column) => columns.push(column),
+                Err(err) => {
+                    self.pos = start;
+                    self.skip_clause(&[Token::Comma, Token::RightParentheses], err)?;
+                }
+            }
+
+            //comma or end, in permissive mode after skipping what is left of the column definition
+            if !matches!(self.peek(), Token::Comma | Token::RightParentheses) {
+                let err = format!("Expected ',' or ')', found {:?}", self.peek());
+                self.skip_clause(&[Token::Comma, Token::RightParentheses], err)?;
+            }
+            match self.peek() {
+                Token::Comma => { self.next(); }
+                _ => { self.expect(&Token::RightParentheses)?; break; }
+            }
+        }
+        
+        self.expect_statement_end()?;
+
+        Ok(Statement::CreateTable {
+            table_name,
+            column_list: columns,
+            query: None,
+            if_not_exists,
+            temporary,
+        })
+    }
+
+    //one column of a table definition: name, type and constraints
+    fn parse_column_definition(&mut self) -> Result<TableColumn, String> {
+        //column name
+        let col_name = match self.next_name() {
+            Token::Identifier(s) => s,
+            other => return Err(self.expected_name("column name", &other)),
+        };
+
+        //column type
+        let col_type = self.parse_data_type()?;
+
+        //optional constraints
+        let mut constraints = Vec::new();
+        loop {
+            match self.peek() {
+                Token::Keyword(Keyword::Primary) => {
+                    self.next();
+                    self.expect(&Token::Keyword(Keyword::Key))?;
+                    constraints.push(Constraint::PrimaryKey);
+                }
+                Token::Keyword(Keyword::Not) => {
+                    self.next();
+                    self.expect(&Token::Keyword(Keyword::Null))?;
+                    constraints.push(Constraint::NotNull);
+                }
+                Token::Keyword(Keyword::Check) => {
+                    self.next();
+                    self.expect(&Token::LeftParentheses)?;
+                    let expr = self.parse_condition("CHECK")?;
+                    self.expect(&Token::RightParentheses)?;
+                    constraints.push(Constraint::Check(expr));
+                }
+                _ => break,
+            }
+        }
+
+        Ok(TableColumn {
+            column_name: col_name,
+            column_type: col_type,
+            constraints,
+        })
+    }
+
+    //alter table parsing, ALTER is already consumed
+    fn parse_alter_table(&mut self) -> Result<Statement, String> {
+        if self.peek() != &Token::Keyword(Keyword::Table) {
+            return self.unknown_statement(format!("Expected {:?}, found {:?}", Token::Keyword(Keyword::Table), self.peek()));
+        }
+        self.next();
+        let table_name = self.parse_identifier("table name")?;
+        let action = match self.next() {
+            Token::Keyword(Keyword::Add) => {
+                //COLUMN is optional, `ADD email VARCHAR(255)` adds a column too
+                if let Token::Keyword(Keyword::Column) = self.peek() {
+                    self.next();
+                }
+                AlterAction::AddColumn(self.parse_column_definition()?)
+            }
+            Token::Keyword(Keyword::Drop) => {
+                if let Token::Keyword(Keyword::Column) = self.peek() {
+                    self.next();
+                }
+                AlterAction::DropColumn(self.parse_identifier("column name")?)
+            }
+            //RENAME TO b renames the table, RENAME [COLUMN] a TO b a column, told apart by where the statement ends
+            //as both names may be `to`
+            Token::Keyword(Keyword::Rename) => {
+                let to = Token::Keyword(Keyword::To);
+                if self.peek() == &to && self.peek_nth(2) == &Token::Semicolon {
+                    self.next();
+                    AlterAction::RenameTo(self.parse_identifier("table name")?)
+                } else {
+                    if let Token::Keyword(Keyword::Column) = self.peek() {
+                        self.next();
+                    }
+                    let column_name = self.parse_identifier("column name")?;
+                    self.expect(&to)?;
+                    let new_name = self.parse_identifier("column name")?;
+                    AlterAction::RenameColumn { column_name, new_name }
+                }
+            }
+            other => return Err(format!("Expected ADD, DROP or RENAME after ALTER TABLE {}, found {:?}", table_name, other)),
+        };
+        self.expect_statement_end()?;
+        Ok(Statement::AlterTable { table_name, action })
+    }
+
+    //insert parsing, INSERT is already consumed
+    fn parse_insert(&mut self) -> Result<Statement, String> {
+        self.expect(&Token::Keyword(Keyword::Into))?;
+        let table_name = self.parse_identifier("table name")?;
+
+        //optional column list; a parenthesis opening a query, `INSERT INTO t (SELECT ...)`, is the source instead
+        let mut columns = None;
+        let query = matches!(self.peek_nth(1), Token::Keyword(Keyword::Select | Keyword::With | Keyword::Values) | Token::LeftParentheses);
+        if self.peek() == &Token::LeftParentheses && !query {
+            self.next();
+            let mut names = Vec::new();
+            loop {
+                names.push(self.parse_identifier("column name")?);
+                match self.next() {
+                    Token::Comma => continue,
+                    Token::RightParentheses => break,
+                    other => return Err(format!("Expected ',' or ')' in INSERT column list, found {:?}", other)),
+                }
+            }
+            columns = Some(names);
+        }
+
+        let source = match self.peek() {
+            Token::Keyword(Keyword::Values) => {
+                self.next();
+                InsertSource::Values(self.parse_value_rows(columns.as_ref().map(|columns| columns.len()), &format!("INSERT into {}", table_name))?)
+            }
+            Token::Keyword(Keyword::Select | Keyword::With) | Token::LeftParentheses => InsertSource::Query(Box::new(self.parse_query()?)),
+            other => return Err(format!("Expected VALUES or a query after INSERT INTO {}, found {:?}", table_name, other)),
+        };
+
+        let on_conflict = if let Token::Keyword(Keyword::On) = self.peek() {
+            self.next();
+            Some(self.parse_on_conflict()?)
+        } else {
+            None
+        };
+
+        self.expect_statement_end()?;
+        Ok(Statement::Insert { table_name, columns, source, on_conflict })
+    }
+
+    //the rows after VALUES, which is already consumed; every row has as many values as the first one, and as `width` when
+    //given, `what` names the statement in the error message
+    fn parse_value_rows(&mut self, width: Option<usize>, what: &str) -> Result<Vec<Vec<Expression>>, String> {
+        let mut rows: Vec<Vec<Expression>> = Vec::new();
+        loop {
+            self.expect(&Token::LeftParentheses)?;
+            let mut row = Vec::new();
+            loop {
+                row.push(self.parse_expression(0)?);
+                match self.next() {
+                    Token::Comma => continue,
+                    Token::RightParentheses => break,
+                    other => return Err(format!("Expected ',' or ')' in VALUES, found {:?}", other)),
+                }
+            }
+            let width = width.unwrap_or(rows.first().map_or(row.len(), |first| first.len()));
+            if row.len() != width {
+                return Err(format!("Row {} of {} has {} values, expected {}", rows.len() + 1, what, row.len(), width));
+            }
+            rows.push(row);
+            match self.peek() {
+                Token::Comma => { self.next(); }
+                _ => break,
+            }
+        }
+        Ok(rows)
+    }
+
+    //ON CONFLICT [(column, ...)] DO NOTHING | DO UPDATE SET column = value, ... [WHERE condition], ON is already consumed
+    fn parse_on_conflict(&mut self) -> Result<OnConflict, String> {
+        self.expect(&Token::Keyword(Keyword::Conflict))?;
+        let mut target = Vec::new();
+        if let Token::LeftParentheses = self.peek() {
+            self.next();
+            loop {
+                target.push(self.parse_identifier("column name")?);
+                match self.next() {
+                    Token::Comma => continue,
+                    Token::RightParentheses => break,
+                    other => return Err(format!("Expected ',' or ')' in ON CONFLICT target, found {:?}", other)),
+                }
+            }
+        }
+        self.expect(&Token::Keyword(Keyword::Do))?;
+        let action = match self.next() {
+            Token::Keyword(Keyword::Nothing) => ConflictAction::Nothing,
+            Token::Keyword(Keyword::Update) => {
+                //the database could not tell which constraint's conflicts to update on
+                if target.is_empty() {
+                    return Err("ON CONFLICT DO UPDATE needs the conflicting columns, ON CONFLICT (column) DO UPDATE".to_string());
+                }
+                self.expect(&Token::Keyword(Keyword::Set))?;
+                let mut assignments = Vec::new();
+                loop {
+                    let column = self.parse_identifier("column name")?;
+                    self.expect(&Token::Equal)?;
+                    assignments.push(Assignment { column, value: self.parse_expression(0)? });
+                    match self.peek() {
+                        Token::Comma => { self.next(); }
+                        _ => break,
+                    }
+                }
+                let where_clause = if let Token::Keyword(Keyword::Where) = self.peek() {
+                    self.next();
+                    Some(self.parse_condition("WHERE")?)
+                } else {
+                    None
+                };
+                ConflictAction::Update { assignments, where_clause }
+            }
+            other => return Err(format!("Expected NOTHING or UPDATE after DO, found {:?}", other)),
+        };
+        Ok(OnConflict { target, action })
+    }
+
+    //delete parsing, DELETE is already consumed
+    fn parse_delete(&mut self) -> Result<Statement, String> {
+        self.expect(&Token::Keyword(Keyword::From))?;
+        let table = self.parse_identifier("table name")?;
+        let where_clause = if let Token::Keyword(Keyword::Where) = self.peek() {
+            self.next();
+            Some(self.parse_condition("WHERE")?)
+        } else {
+            None
+        };
+        self.expect_statement_end()?;
+        Ok(Statement::Delete { table, where_clause })
+    }
+
+    //MERGE INTO target [[AS] alias] USING source ON condition WHEN [NOT] MATCHED [AND condition] THEN action ..., MERGE is
+    //already consumed; WHEN, MATCHED and THEN are only keywords here, so they are read as words
+    fn parse_merge(&mut self) -> Result<Statement, String> {
+        self.expect(&Token::Keyword(Keyword::Into))?;
+        let table_name = self.parse_identifier("table name")?;
+        let alias = self.parse_optional_alias()?;
+        self.expect(&Token::Keyword(Keyword::Using))?;
+        let source = self.parse_table_factor()?;
+        self.expect(&Token::Keyword(Keyword::On))?;
+        let on = self.parse_condition("ON")?;
+        let mut clauses = Vec::new();
+        while self.peek_word(0, "when") {
+            self.next();
+            let matched = self.peek() != &Token::Keyword(Keyword::Not);
+            if !matched {
+                self.next();
+            }
+            self.expect_word("matched", if matched { "WHEN" } else { "WHEN NOT" })?;
+            let condition = if let Token::Keyword(Keyword::And) = self.peek() {
+                self.next();
+                Some(self.parse_condition("WHEN")?)
+            } else {
+                None
+            };
+            self.expect_word("then", "MATCHED")?;
+            let action = self.parse_merge_action(matched)?;
+            clauses.push(MergeClause { matched, condition, action });
+        }
+        if clauses.is_empty() {
+            return Err(format!("Expected WHEN MATCHED or WHEN NOT MATCHED after the condition of MERGE INTO {}, found {:?}", table_name, self.peek()));
+        }
+        self.expect_statement_end()?;
+        Ok(Statement::Merge { table_name, alias, source, on, clauses })
+    }
+
+    //the action of a WHEN arm of MERGE, THEN is already consumed; a matched row can be updated or deleted, a row without
+    //a match can only be inserted
+    fn parse_merge_action(&mut self, matched: bool) -> Result<MergeAction, String> {
+        let action = match self.next() {
+            Token::Keyword(Keyword::Update) if matched => {
+                self.expect(&Token::Keyword(Keyword::Set))?;
+                let mut assignments = Vec::new();
+                loop {
+                    let column = self.parse_identifier("column name")?;
+                    self.expect(&Token::Equal)?;
+                    assignments.push(Assignment { column, value: self.parse_expression(0)? });
+                    match self.peek() {
+                        Token::Comma => { self.next(); }
+                        _ => break,
+                    }
+                }
+                MergeAction::Update { assignments }
+            }
+            Token::Keyword(Keyword::Delete) if matched => MergeAction::Delete,
+            Token::Keyword(Keyword::Insert) if !matched => {
+                let columns = if let Token::LeftParentheses = self.peek() {
+                    self.next();
+                    Some(self.parse_column_names()?)
+                } else {
+                    None
+                };
+                self.expect(&Token::Keyword(Keyword::Values))?;
+                let mut rows = self.parse_value_rows(columns.as_ref().map(|columns| columns.len()), "MERGE INSERT")?;
+                if rows.len() > 1 {
+                    return Err("The INSERT of a MERGE inserts one row, found more after VALUES".to_string());
+                }
+                MergeAction::Insert { columns, values: rows.remove(0) }
+            }
+            Token::Keyword(Keyword::Do) => {
+                self.expect(&Token::Keyword(Keyword::Nothing))?;
+                MergeAction::Nothing
+            }
+            other if matched => return Err(format!("Expected UPDATE, DELETE or DO NOTHING after WHEN MATCHED THEN, found {:?}", other)),
+            other => return Err(format!("Expected INSERT or DO NOTHING after WHEN NOT MATCHED THEN, found {:?}", other)),
+        };
+        Ok(action)
+    }
+
+    //drop parsing, DROP is already consumed
+    fn parse_drop(&mut self) -> Result<Statement, String> {
+        let object_type = match self.next() {
+            Token::Keyword(Keyword::Table) => ObjectType::Table,
+            Token::Keyword(Keyword::Index) => ObjectType::Index,
+            Token::Keyword(Keyword::View) => ObjectType::View,
+            other => return self.unknown_statement(format!("Expected TABLE, INDEX or VIEW after DROP, found {:?}", other)),
+        };
+        let what = match object_type {
+            ObjectType::Table => "table name",
+            ObjectType::Index => "index name",
+            ObjectType::View => "view name",
+        };
+        //IF is not reserved, `DROP TABLE if;` drops the table named if
+        let if_exists = self.peek() == &Token::Keyword(Keyword::If) && self.peek_nth(1) == &Token::Keyword(Keyword::Exists);
+        if if_exists {
+            self.next();
+            self.next();
+        }
+        let mut names = vec![self.parse_identifier(what)?];
+        while let Token::Comma = self.peek() {
+            self.next();
+            names.push(self.parse_identifier(what)?);
+        }
+        self.expect_statement_end()?;
+        Ok(Statement::Drop { object_type, names, if_exists })
+    }
+
+    //rename table parsing, RENAME is already consumed, one or more `old TO new` separated by commas
+    fn parse_rename_table(&mut self) -> Result<Statement, String> {
+        self.expect(&Token::Keyword(Keyword::Table))?;
+        let mut renames = Vec::new();
+        loop {
+            let old_name = self.parse_identifier("table name")?;
+            self.expect(&Token::Keyword(Keyword::To))?;
+            let new_name = self.parse_identifier("table name")?;
+            renames.push(TableRename { old_name, new_name });
+            if self.peek() != &Token::Comma {
+                break;
+            }
+            self.next();
+        }
+        self.expect_statement_end()?;
+        Ok(Statement::RenameTable { renames })
+    }
+
+    //truncate parsing, TRUNCATE is already consumed, TABLE is optional
+    fn parse_truncate(&mut self) -> Result<Statement, String> {
+        if let Token::Keyword(Keyword::Table) = self.peek() {
+            self.next();
+        }
+        let table_name = self.parse_identifier("table name")?;
+        self.expect_statement_end()?;
+        Ok(Statement::Truncate { table_name })
+    }
+
+    //the optional table of ANALYZE and VACUUM and the end of the statement, no table means the whole database
+    fn parse_maintenance_table(&mut self) -> Result<Option<String>, String> {
+        let table_name = if self.peek() == &Token::Semicolon { None } else { Some(self.parse_identifier("table name")?) };
+        self.expect_statement_end()?;
+        Ok(table_name)
+    }
+
+    //show parsing, SHOW is already consumed
+    fn parse_show(&mut self) -> Result<Statement, String> {
+        let object = match self.peek() {
+            Token::Keyword(Keyword::Tables) => ShowObject::Tables,
+            Token::Keyword(Keyword::Databases) => ShowObject::Databases,
+            other => return self.unknown_statement(format!("Expected TABLES or DATABASES after SHOW, found {:?}", other)),
+        };
+        self.next();
+        self.expect_statement_end()?;
+        Ok(Statement::Show { object })
+    }
+
+    //describe parsing, DESCRIBE (or DESC) is already consumed
+    fn parse_describe(&mut self) -> Result<Statement, String> {
+        let table_name = self.parse_identifier("table name")?;
+        self.expect_statement_end()?;
+        Ok(Statement::Describe { table_name })
+    }
+
+    //set parsing, SET is already consumed: `SET name = value` or `SET name TO value`
+    fn parse_set(&mut self) -> Result<Statement, String> {
+        //`SET TIME ZONE 'UTC'` is how PostgreSQL spells `SET timezone TO 'UTC'`
+        let name = if self.peek_word(0, "TIME") && self.peek_word(1, "ZONE") {
+            self.next();
+            self.next();
+            "timezone".to_string()
+        } else {
+            let name = self.parse_identifier("variable name")?;
+            match self.next() {
+                Token::Equal | Token::Keyword(Keyword::To) => {}
+                other => return Err(format!("Expected = or TO after the variable name, found {:?}", other)),
+            }
+            name
+        };
+        //`SET search_path TO public, app` gives a list
+        let value = self.parse_set_value()?;
+        let mut rest = Vec::new();
+        while let Token::Comma = self.peek() {
+            self.next();
+            rest.push(self.parse_set_value()?);
+        }
+        self.expect_statement_end()?;
+        Ok(Statement::SetVariable { name, value, rest })
+    }
+
+    //`SET standard_conforming_strings = on`, the keyword is a value here
+    fn parse_set_value(&mut self) -> Result<Expression, String> {
+        if let Token::Keyword(Keyword::On) = self.peek() {
+            self.next();
+            Ok(Expression::Identifier("on".to_string()))
+        } else {
+            self.parse_expression(0)
+        }
+    }
+
+    //CALL name(args), CALL is already consumed; MySQL also accepts the name alone
+    fn parse_call(&mut self) -> Result<Statement, String> {
+        let name = self.parse_identifier("procedure name")?;
+        let args = if let Token::LeftParentheses = self.peek() {
+            self.next();
+            self.parse_function_args()?
+        } else {
+            Vec::new()
+        };
+        self.expect_statement_end()?;
+        Ok(Statement::Call { name, args })
+    }
+
+    //COPY table [(columns)] FROM|TO 'file'|STDIN|STDOUT [[WITH] (option [value], ...)], COPY is already consumed
+    fn parse_copy(&mut self) -> Result<Statement, String> {
+        let table_name = self.parse_identifier("table name")?;
+        let columns = if let Token::LeftParentheses = self.peek() {
+            self.next();
+            self.parse_column_names()?
+        } else {
+            Vec::new()
+        };
+        let direction = match self.next() {
+            Token::Keyword(Keyword::From) => CopyDirection::From,
+            Token::Keyword(Keyword::To) => CopyDirection::To,
+            other => return Err(format!("Expected FROM or TO in COPY, found {:?}", other)),
+        };
+        let stream = if direction == CopyDirection::From { "STDIN" } else { "STDOUT" };
+        let file = match self.next() {
+            Token::String { value, .. } => Some(value),
+            Token::Identifier(word) if word.eq_ignore_ascii_case(stream) => None,
+            other => return Err(format!("Expected a file name or {} in COPY, found {:?}", stream, other)),
+        };
+        let mut options = Vec::new();
+        if let Token::Keyword(Keyword::With) = self.peek() {
+            self.next();
+            if self.peek() != &Token::LeftParentheses {
+                return Err(format!("Expected '(' and the options of COPY after WITH, found {:?}", self.peek()));
+            }
+        }
+        if let Token::LeftParentheses = self.peek() {
+            self.next();
+            loop {
+                let name = match self.next() {
+                    Token::Identifier(name) => name.to_ascii_uppercase(),
+                    Token::Keyword(keyword) => keyword.as_str().to_string(),
+                    other => return Err(format!("Expected a COPY option, found {:?}", other)),
+                };
+                let value = match self.peek() {
+                    Token::Comma | Token::RightParentheses => None,
+                    _ => Some(match self.next() {
+                        Token::Identifier(word) => word,
+                        Token::Keyword(keyword) => keyword.as_str().to_lowercase(),
+                        Token::String { value, .. } => value,
+                        Token::Number(n) => n.to_string(),
+                        other => return Err(format!("Expected the value of COPY option {}, found {:?}", name, other)),
+                    }),
+                };
+                options.push(CopyOption { name, value });
+                match self.next() {
+                    Token::Comma => continue,
+                    Token::RightParentheses => break,
+                    other => return Err(format!("Expected ',' or ')' in COPY options, found {:?}", other)),
+                }
+            }
+        }
+        self.expect_statement_end()?;
+        Ok(Statement::Copy { table_name, columns, direction, file, options })
+    }
+
+    //LOAD DATA [LOCAL] INFILE 'file' [REPLACE|IGNORE] INTO TABLE table [FIELDS ...] [LINES ...] [IGNORE n LINES] [(columns)],
+    //LOAD is already consumed
+    fn parse_load_data(&mut self) -> Result<Statement, String> {
+        self.expect_word("DATA", "LOAD")?;
+        let local = self.peek_word(0, "LOCAL");
+        if local {
+            self.next();
+        }
+        self.expect_word("INFILE", "LOAD DATA")?;
+        let file = self.parse_string("the file of LOAD DATA")?;
+        let duplicates = match self.peek() {
+            Token::Keyword(Keyword::Replace) => Some(DuplicateHandling::Replace),
+            _ if self.peek_word(0, "IGNORE") => Some(DuplicateHandling::Ignore),
+            _ => None,
+        };
+        if duplicates.is_some() {
+            self.next();
+        }
+        self.expect(&Token::Keyword(Keyword::Into))?;
+        self.expect(&Token::Keyword(Keyword::Table))?;
+        let table_name = self.parse_identifier("table name")?;
+
+        let mut format = LoadFormat::default();
+        if self.peek_word(0, "FIELDS") || self.peek_word(0, "COLUMNS") {
+            self.next();
+            let mut any = false;
+            loop {
+                let target = if self.peek_word(0, "TERMINATED") {
+                    &mut format.fields_terminated_by
+                } else if self.peek_word(0, "ENCLOSED") || self.peek_word(0, "OPTIONALLY") {
+                    format.optionally_enclosed = self.peek_word(0, "OPTIONALLY");
+                    if format.optionally_enclosed {
+                        self.next();
+                    }
+                    &mut format.fields_enclosed_by
+                } else if self.peek_word(0, "ESCAPED") {
+                    &mut format.fields_escaped_by
+                } else {
+                    break;
+                };
+                self.next();
+                self.expect(&Token::Keyword(Keyword::By))?;
+                *target = Some(self.parse_string("the characters of FIELDS")?);
+                any = true;
+            }
+            if !any {
+                return Err(format!("Expected TERMINATED, ENCLOSED or ESCAPED after FIELDS, found {:?}", self.peek()));
+            }
+        }
+        if self.peek_word(0, "LINES") {
+            self.next();
+            let mut any = false;
+            loop {
+                let target = if self.peek_word(0, "STARTING") {
+                    &mut format.lines_starting_by
+                } else if self.peek_word(0, "TERMINATED") {
+                    &mut format.lines_terminated_by
+                } else {
+                    break;
+                };
+                self.next();
+                self.expect(&Token::Keyword(Keyword::By))?;
+                *target = Some(self.parse_string("the characters of LINES")?);
+                any = true;
+            }
+            if !any {
+                return Err(format!("Expected STARTING or TERMINATED after LINES, found {:?}", self.peek()));
+            }
+        }
+        let ignore_lines = if self.peek_word(0, "IGNORE") {
+            self.next();
+            let count = match self.next() {
+                Token::Number(n) => n,
+                other => return Err(format!("Expected the number of lines to IGNORE, found {:?}", other)),
+            };
+            if !self.peek_word(0, "LINES") && !self.peek_word(0, "ROWS") {
+                return Err(format!("Expected LINES or ROWS after IGNORE {}, found {:?}", count, self.peek()));
+            }
+            self.next();
+            Some(count)
+        } else {
+            None
+        };
+        let columns = if let Token::LeftParentheses = self.peek() {
+            self.next();
+            self.parse_column_names()?
+        } else {
+            Vec::new()
+        };
+        self.expect_statement_end()?;
+        Ok(Statement::LoadData { local, file, duplicates, table_name, format, ignore_lines, columns })
+    }
+
+    //PREPARE name [(types)] AS statement, PREPARE is already consumed; the statement ends with the semicolon of both
+    fn parse_prepare(&mut self) -> Result<Statement, String> {
+        let name = self.parse_identifier("prepared statement name")?;
+        let mut param_types = Vec::new();
+        if let Token::LeftParentheses = self.peek() {
+            self.next();
+            loop {
+                param_types.push(self.parse_data_type()?);
+                match self.next() {
+                    Token::Comma => continue,
+                    Token::RightParentheses => break,
+                    other => return Err(format!("Expected ',' or ')' in parameter types, found {:?}", other)),
+                }
+            }
+        }
+        self.expect(&Token::Keyword(Keyword::As))?;
+        let statement = self.parse_statement()?;
+        match statement {
+            Statement::Select { .. } | Statement::SetOperation { .. } | Statement::Values { .. } | Statement::Insert { .. } | Statement::Delete { .. } => {
+                Ok(Statement::Prepare { name, param_types, statement: Box::new(statement) })
+            }
+            _ => Err("Only queries, INSERT and DELETE statements can be prepared".to_string()),
+        }
+    }
+
+    //EXECUTE name [(args)], EXECUTE is already consumed
+    fn parse_execute(&mut self) -> Result<Statement, String> {
+        let name = self.parse_identifier("prepared statement name")?;
+        let args = if let Token::LeftParentheses = self.peek() {
+            self.next();
+            self.parse_function_args()?
+        } else {
+            Vec::new()
+        };
+        self.expect_statement_end()?;
+        Ok(Statement::Execute { name, args })
+    }
+
+    //DEALLOCATE [PREPARE] name | ALL, DEALLOCATE is already consumed
+    fn parse_deallocate(&mut self) -> Result<Statement, String> {
+        if let Token::Keyword(Keyword::Prepare) = self.peek() {
+            self.next();
+        }
+        let name = match self.peek() {
+            Token::Keyword(Keyword::All) => {
+                self.next();
+                None
+            }
+            _ => Some(self.parse_identifier("prepared statement name")?),
+        };
+        self.expect_statement_end()?;
+        Ok(Statement::Deallocate { name })
+    }
+
+    //column type, lengths and precisions in parentheses
+    //a type is the longest of the names in `TYPE_NAMES` that the words ahead spell out, then the numbers in parentheses
+    //after it, if any, which the entry turns into the type
+    fn parse_data_type(&mut self) -> Result<DBType, String> {
+        let (words, build) = match self.peek_type_name(0) {
+            Some(found) => found,
+            None if (self.custom_types || self.mode == ParseMode::Permissive) && is_name(self.peek()) => {
+                let name = self.parse_identifier("type name")?;
+                let args = if let Token::LeftParentheses = self.peek() {
+                    self.next();
+                    self.parse_function_args()?
+                } else {
+                    Vec::new()
+                };
+                return Ok(DBType::Custom(name, args));
+            }
+            None => return Err(format!("Expected type, found {:?}", self.next())),
+        };
+        self.pos += words.len();
+        let name = words.join(" ");
+        let mut args = Vec::new();
+        if let Token::LeftParentheses = self.peek() {
+            self.next();
+            loop {
+                args.push(self.parse_type_argument(&format!("a number in the arguments of {}", name))?);
+                match self.next() {
+                    Token::Comma => {}
+                    Token::RightParentheses => break,
+                    other => return Err(format!("Expected ',' or ')' in the arguments of {}, found {:?}", name, other)),
+                }
+            }
+        }
+        build(&name, &args)
+    }
+
+    //helper, the entry of `TYPE_NAMES` whose name is written from n tokens ahead
+    fn peek_type_name(&self, n: usize) -> Option<&'static (&'static [&'static str], TypeBuilder)> {
+        TYPE_NAMES.iter()
+            .filter(|(words, _)| words.iter().enumerate().all(|(i, word)| type_word(self.peek_nth(n + i)).as_deref() == Some(*word)))
+            .max_by_key(|(words, _)| words.len())
+    }
+
+    //helper, a number inside a type's parentheses
+    fn parse_type_argument(&mut self, what: &str) -> Result<usize, String> {
+        match self.next() {
+            Token::Number(n) => Ok(n as usize),
+            other => Err(format!("Expected {}, found {:?}", what, other)),
+        }
+    }
+
+    //helper, nothing but an optional semicolon may follow a standalone expression or type
+    fn expect_end(&mut self, what: &str) -> Result<(), String> {
+        if let Token::Semicolon = self.peek() {
+            self.next();
+        }
+        match self.peek() {
+            Token::Eof => Ok(()),
+            other => Err(format!("Expected end of {}, found {:?}", what, other)),
+        }
+    }
+
+    //pratt parsing for expressions
+    fn parse_expression(&mut self, min_prec: u8) -> Result<Expression, String> {
+        //parse prefix
+        let mut left = match self.next_name() {
+            Token::Number(n) => Expression::Literal(integer_literal(n, false)),
+            Token::Decimal(text) => Expression::Literal(Literal::Decimal(text)),
+            Token::Placeholder(text) => Expression::Placeholder(text),
+            Token::Identifier(s) => {
+                //an identifier directly followed by '(' is a function call
+                if let Token::LeftParentheses = self.peek() {
+                    self.next();
+                    let args = self.parse_function_args()?;
+                    let over = if let Token::Keyword(Keyword::Over) = self.peek() {
+                        self.next();
+                        Some(self.parse_window_spec()?)
+                    } else {
+                        None
+                    };
+                    Expression::Function { name: s, args, over }
+                } else if let Token::Period = self.peek() {
+                    //qualified name, table.column
+                    let mut parts = vec![s];
+                    while let Token::Period = self.peek() {
+                        self.next();
+                        match self.next_name() {
+                            Token::Identifier(part) => parts.push(part),
+                            other => return Err(self.expected_name("identifier after '.'", &other)),
+                        }
+                    }
+                    Expression::CompoundIdentifier(parts)
+                } else {
+                    Expression::Identifier(s)
+                }
+            }
+            Token::Star => Expression::Wildcard,
+            Token::String { value, .. } => Expression::Literal(Literal::String(value)),
+            Token::EscapeString { value, .. } => Expression::Literal(Literal::String(value)),
+            Token::NationalString { value, .. } => Expression::Literal(Literal::NationalString(value)),
+            Token::BitString { value, .. } => Expression::Literal(Literal::BitString(value)),
+            Token::HexString { value, .. } => Expression::Literal(Literal::HexString(value)),
+            Token::Keyword(Keyword::True) => Expression::Literal(Literal::Boolean(true)),
+            Token::Keyword(Keyword::False) => Expression::Literal(Literal::Boolean(false)),
+            Token::Keyword(Keyword::Null) => Expression::Literal(Literal::Null),
+            Token::LeftParentheses => {
+                //(SELECT ...) and (VALUES ...) are scalar subqueries, anything else is just grouping
+                let subquery = match self.peek() {
+                    Token::Keyword(Keyword::Select | Keyword::With | Keyword::Values) => {
+                        let subquery = self.parse_query()?;
+                        self.expect(&Token::RightParentheses)?;
+                        Some(subquery)
+                    }
+                    //unless the inner parentheses start a query, ((SELECT ...) UNION ...)
+                    Token::LeftParentheses => self.parse_parenthesized_query(),
+                    _ => None,
+                };
+                match subquery {
+                    Some(subquery) => Expression::Subquery(Box::new(subquery)),
+                    None => {
+                        let expr = self.parse_expression(0)?;
+                        self.expect(&Token::RightParentheses)?;
+                        expr
+                    }
+                }
+            }
+            //a minus sign directly in front of a number makes a negative literal
+            Token::Minus if matches!(self.peek(), Token::Number(_) | Token::Decimal(_)) => match self.next() {
+                Token::Number(n) => Expression::Literal(integer_literal(n, true)),
+                Token::Decimal(text) => Expression::Literal(Literal::Decimal(format!("-{}", text))),
+                _ => unreachable!(),
+            },
+            Token::Minus => {
+                let rhs = self.parse_expression(UnaryOperator::Minus.precedence())?;
+                Expression::UnaryOperation { operand: Box::new(rhs), operator: UnaryOperator::Minus }
+            }
+            Token::Plus => {
+                let rhs = self.parse_expression(UnaryOperator::Plus.precedence())?;
+                Expression::UnaryOperation { operand: Box::new(rhs), operator: UnaryOperator::Plus }
+            }
+            Token::Keyword(Keyword::Not) => {
+                //NOT EXISTS is one predicate rather than a negated EXISTS
+                if let Token::Keyword(Keyword::Exists) = self.peek() {
+                    self.next();
+                    let subquery = self.parse_subquery()?;
+                    Expression::Exists { subquery: Box::new(subquery), negated: true }
+                } else {
+                    let rhs = self.parse_expression(UnaryOperator::Not.precedence())?;
+                    Expression::UnaryOperation { operand: Box::new(rhs), operator: UnaryOperator::Not }
+                }
+            }
+            Token::Keyword(Keyword::Exists) => {
+                let subquery = self.parse_subquery()?;
+                Expression::Exists { subquery: Box::new(subquery), negated: false }
+            }
+            //next_name turned the other keywords into names, this one is reserved and may have been meant as a column
+            Token::Keyword(keyword) => return Err(format!("Unexpected prefix token: {}. {}", keyword.as_str(), self.reserved_hint(keyword))),
+            other => return Err(format!("Unexpected prefix token: {:?}", other)),
+        };
+
+        //infix/postfix loop
+        loop {
+            let prec = self.infix_precedence(self.peek());
+            if prec <= min_prec {
+                break;
+            }
+            let tok = self.next();
+            if let Some(operator) = self.dialect.binary_operator(&tok) {
+                //a right associative operator lets an operator of the same precedence take its right operand
+                let rhs_prec = match self.dialect.associativity(operator) {
+                    Associativity::Left => prec,
+                    Associativity::Right => prec - 1,
+                };
+                let rhs = self.parse_expression(rhs_prec)?;
+                left = Expression::BinaryOperation { left_operand: Box::new(left), operator, right_operand: Box::new(rhs) };
+                continue;
+            }
+            left = match tok {
+                Token::Keyword(Keyword::Is) => {
+                    let negated = if let Token::Keyword(Keyword::Not) = self.peek() {
+                        self.next();
+                        true
+                    } else {
+                        false
+                    };
+                    if let Token::Keyword(Keyword::Null) = self.peek() {
+                        self.next();
+                        let operator = if negated { UnaryOperator::IsNotNull } else { UnaryOperator::IsNull };
+                        left = Expression::UnaryOperation { operand: Box::new(left), operator };
+                        continue;
+                    }
+                    self.expect(&Token::Keyword(Keyword::Distinct))?;
+                    self.expect(&Token::Keyword(Keyword::From))?;
+                    let rhs = self.parse_expression(prec)?;
+                    Expression::IsDistinctFrom { left_operand: Box::new(left), right_operand: Box::new(rhs), negated }
+                }
+                //NOT REGEXP and NOT RLIKE, infix_precedence only lets a NOT through before one of them
+                Token::Keyword(Keyword::Not) => {
+                    let operator = match self.next() {
+                        Token::Keyword(Keyword::Regexp) => BinaryOperator::NotRegexp,
+                        _ => BinaryOperator::NotRlike,
+                    };
+                    let rhs = self.parse_expression(prec)?;
+                    Expression::BinaryOperation { left_operand: Box::new(left), operator, right_operand: Box::new(rhs) }
+                }
+                Token::Keyword(Keyword::Asc) => {
+                    Expression::UnaryOperation { operand: Box::new(left), operator: UnaryOperator::Asc }
+                }
+                Token::Keyword(Keyword::Desc) => {
+                    Expression::UnaryOperation { operand: Box::new(left), operator: UnaryOperator::Desc }
+                }
+                _ => break,
+            };
+        }
+
+        Ok(left)
+    }
+
+    //expression in a boolean context (WHERE, HAVING, ON, CHECK, ...), `clause` names it in the error message
+    fn parse_condition(&mut self, clause: &str) -> Result<Expression, String> {
+        let expr = self.parse_expression(0)?;
+        check_boolean(&expr, clause)?;
+        Ok(expr)
+    }
+
+    //return precedence of infix or postfix tokens, `tok` being the next one
+    fn infix_precedence(&self, tok: &Token) -> u8 {
+        match tok {
+            Token::Keyword(Keyword::Is) => precedence::COMPARISON,
+            Token::Keyword(Keyword::Not) => match self.peek_nth(1) {
+                Token::Keyword(Keyword::Regexp) => self.dialect.precedence(BinaryOperator::NotRegexp),
+                Token::Keyword(Keyword::Rlike) => self.dialect.precedence(BinaryOperator::NotRlike),
+                _ => 0,
+            },
+            Token::Keyword(Keyword::Asc) => UnaryOperator::Asc.precedence(),
+            Token::Keyword(Keyword::Desc) => UnaryOperator::Desc.precedence(),
+            _ => self.dialect.binary_operator(tok).map_or(0, |operator| self.dialect.precedence(operator)),
+        }
+    }
+}
+
+//helper, the literal for an integer token, negated when a minus sign came before it
+//integers outside of the i64 range are kept as decimal text
+fn integer_literal(n: u64, negative: bool) -> Literal {
+    let value = if negative { 0i128 - n as i128 } else { n as i128 };
+    match i64::try_from(value) {
+        Ok(value) => Literal::Integer(value),
+        Err(_) => Literal::Decimal(value.to_string()),
+    }
+}
+
+//reject a condition that is certainly not boolean, like WHERE 1 or CHECK (a AND 'yes')
+//operands of AND, OR and NOT are boolean contexts as well, values of unknown type are let through
+fn check_boolean(expr: &Expression, clause: &str) -> Result<(), String> {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And | BinaryOperator::Or, right_operand } => {
+            check_boolean(left_operand, clause)?;
+            check_boolean(right_operand, clause)
+        }
+        Expression::UnaryOperation { operand, operator: UnaryOperator::Not } => check_boolean(operand, clause),
+        _ => match expr.value_type() {
+            ValueType::Boolean | ValueType::Unknown => Ok(()),
+            other => Err(format!("Expected a boolean condition in {}, found a {:?} expression", clause, other)),
+        },
+    }
+}
+
+//helper, whether a token can be read as a name, see next_name
+fn is_name(tok: &Token) -> bool {
+    match tok {
+        Token::Identifier(_) => true,
+        Token::Keyword(keyword) => !keyword.is_reserved(),
+        _ => false,
+    }
+}
+
+//the names types are written under, word by word in upper case, each with what makes the type of its name and the numbers
+//in parentheses after it (none when there are no parentheses); a name may start a longer one, the longer one is read
+//when it is written in full, so `CHARACTER VARYING(20)` is not `CHARACTER` followed by `VARYING`
+type TypeBuilder = fn(&str, &[usize]) -> Result<DBType, String>;
+const TYPE_NAMES: &[(&[&str], TypeBuilder)] = &[
+    (&["INT"], |name, args| no_arguments(name, args, DBType::Int)),
+    (&["INTEGER"], |name, args| no_arguments(name, args, DBType::Int)),
+    (&["BOOL"], |name, args| no_arguments(name, args, DBType::Bool)),
+    (&["BOOLEAN"], |name, args| no_arguments(name, args, DBType::Bool)),
+    (&["VARCHAR"], varchar_type),
+    (&["CHARACTER", "VARYING"], varchar_type),
+    (&["CHAR", "VARYING"], varchar_type),
+    (&["CHAR"], char_type),
+    (&["CHARACTER"], char_type),
+    (&["DECIMAL"], decimal_type),
+    (&["NUMERIC"], decimal_type),
+    (&["DOUBLE"], |name, args| no_arguments(name, args, DBType::Double)),
+    (&["DOUBLE", "PRECISION"], |name, args| no_arguments(name, args, DBType::Double)),
+    (&["FLOAT8"], |name, args| no_arguments(name, args, DBType::Double)),
+    (&["TIMESTAMP"], |name, args| no_arguments(name, args, DBType::Timestamp { with_time_zone: false })),
+    (&["TIMESTAMP", "WITHOUT", "TIME", "ZONE"], |name, args| no_arguments(name, args, DBType::Timestamp { with_time_zone: false })),
+    (&["TIMESTAMP", "WITH", "TIME", "ZONE"], |name, args| no_arguments(name, args, DBType::Timestamp { with_time_zone: true })),
+    (&["TIMESTAMPTZ"], |name, args| no_arguments(name, args, DBType::Timestamp { with_time_zone: true })),
+];
+
+//helper, a token as a word of a type name: keywords (INT, WITH) and identifiers (PRECISION, VARYING) alike
+fn type_word(tok: &Token) -> Option<String> {
+    match tok {
+        Token::Keyword(keyword) => Some(keyword.as_str().to_string()),
+        Token::Identifier(name) => Some(name.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+//helper, a type written without parentheses
+fn no_arguments(name: &str, args: &[usize], ty: DBType) -> Result<DBType, String> {
+    match args {
+        [] => Ok(ty),
+        _ => Err(format!("{} takes no arguments, found {}", name, args.len())),
+    }
+}
+
+fn varchar_type(name: &str, args: &[usize]) -> Result<DBType, String> {
+    match *args {
+        [length] => Ok(DBType::Varchar(length)),
+        _ => Err(format!("Expected the length of {} in parentheses, found {} arguments", name, args.len())),
+    }
+}
+
+//a length of 1 unless one is given, as in CHAR
+fn char_type(name: &str, args: &[usize]) -> Result<DBType, String> {
+    match *args {
+        [] => Ok(DBType::Char(1)),
+        [length] => Ok(DBType::Char(length)),
+        _ => Err(format!("{} takes one length, found {} arguments", name, args.len())),
+    }
+}
+
+//scale defaults to 0, as in DECIMAL(10)
+fn decimal_type(name: &str, args: &[usize]) -> Result<DBType, String> {
+    let (precision, scale) = match *args {
+        [precision] => (precision, 0),
+        [precision, scale] => (precision, scale),
+        _ => return Err(format!("Expected the precision and optional scale of {} in parentheses, found {} arguments", name, args.len())),
+    };
+    if scale > precision {
+        return Err(format!("{} scale {} is larger than its precision {}", name, scale, precision));
+    }
+    Ok(DBType::Decimal(precision, scale))
+}
+
+//split the text of a hint comment into hints, `INDEX(t idx) NO_MERGE` gives INDEX with two arguments and NO_MERGE without any
+fn parse_hints(text: &str) -> Vec<Hint> {
+    let mut hints = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() || ch == ',' {
+            chars.next();
+            continue;
+        }
+        let mut name = String::new();
+        while let Some(ch) = chars.next_if(|&c| !c.is_whitespace() && c != ',' && c != '(') {
+            name.push(ch);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut args = Vec::new();
+        if chars.next_if_eq(&'(').is_some() {
+            let mut arg = String::new();
+            for ch in chars.by_ref() {
+                match ch {
+                    ')' => break,
+                    c if c.is_whitespace() || c == ',' => {
+                        if !arg.is_empty() {
+                            args.push(std::mem::take(&mut arg));
+                        }
+                    }
+                    c => arg.push(c),
+                }
+            }
+            if !arg.is_empty() {
+                args.push(arg);
+            }
+        }
+        if !name.is_empty() {
+            hints.push(Hint { name, args });
+        }
+    }
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_errors_point_at_the_failing_token() {
+        let origin = Origin { offset: 120, line: 12, column: 19 };
+        let err = parse_embedded("SELECT a FROM t1;\nSELECT b FROM;", Dialect::default(), origin).unwrap_err();
+        assert_eq!(err, "Expected table name, found Semicolon at line 13, column 14");
+        let err = parse_embedded("SELECT a FROM t1 WHERE", Dialect::default(), origin).unwrap_err();
+        assert!(err.ends_with("at line 12, column 36"), "{}", err);
+    }
+}
@@ -0,0 +1,185 @@
+//statements resolved against a schema once and then run many times with different parameter values
+use crate::statement::{Statement, Expression, Literal, TableColumn, ConflictAction};
+use crate::rewrite::{for_each_name_mut, for_each_select_mut, Source};
+use crate::formatter::format_expression;
+use crate::analysis::table_uses;
+
+/// A column reference of a prepared statement and the column it stands for: `table` is the position of the table's `CREATE TABLE` in the schema and `column` the position of the column in it. `name` is the reference as written, `o.id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedColumn {
+    pub name: String,
+    pub table: usize,
+    pub column: usize,
+}
+
+/// A `SELECT`, `INSERT` or `DELETE` checked against a schema once, for running the same statement many times with different parameters. Preparing finds the column every column reference stands for, and numbers the parameters: each `?` is the next one, `$n` is the n-th. `bind` then only puts the values in place of the parameters, the names are not looked up again.
+///
+/// Column references that can not be told without running the query are left unresolved rather than rejected: a column of a derived table or a table function, and a name that is the alias of a select list column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedStatement {
+    statement: Statement,
+    columns: Vec<ResolvedColumn>,
+    //the parameter of each placeholder, in the order `for_each_name_mut` reaches them
+    slots: Vec<usize>,
+    parameters: usize,
+}
+
+impl PreparedStatement {
+    /// Prepares a statement against a schema, the `CREATE TABLE` statements of the tables that exist (what `migration::squash` makes of a script). Fails for a table that is not in the schema, a column that is not in the table it belongs to or in any table it can belong to, a column more than one table of a query has, a statement that mixes `?` and `$n` parameters, and a statement other than a query (`SELECT`, or `UNION`, `INTERSECT` and `EXCEPT` of them), `INSERT` and `DELETE`.
+    pub fn prepare(stmt: &Statement, schema: &[Statement]) -> Result<PreparedStatement, String> {
+        let mut statement = stmt.clone();
+        let mut columns = Vec::new();
+        match &statement {
+            Statement::Select { .. } | Statement::SetOperation { .. } | Statement::Values { .. } => {}
+            Statement::Insert { table_name, columns: names, on_conflict, .. } => {
+                let (table, table_columns) = schema_table(schema, table_name)?;
+                let mut names: Vec<&String> = names.iter().flatten().collect();
+                if let Some(on_conflict) = on_conflict {
+                    names.extend(&on_conflict.target);
+                    if let ConflictAction::Update { assignments, .. } = &on_conflict.action {
+                        names.extend(assignments.iter().map(|assignment| &assignment.column));
+                    }
+                }
+                //a table created from a query has no known columns to check the names against
+                for name in names.into_iter().filter(|_| !table_columns.is_empty()) {
+                    let column = column_position(table_columns, name).ok_or_else(|| format!("Column {} is not a column of table {}", name, table_name))?;
+                    columns.push(ResolvedColumn { name: name.clone(), table, column });
+                }
+            }
+            Statement::Delete { .. } => {}
+            _ => return Err("Only queries, INSERT and DELETE statements can be prepared".to_string()),
+        }
+        //every table the statement uses has to be in the schema, whether or not its columns are named
+        for table in table_uses(&statement) {
+            schema_table(schema, &table.name)?;
+        }
+
+        let mut aliases = Vec::new();
+        for_each_select_mut(&mut statement, &mut |select| {
+            if let Statement::Select { columns, .. } = select {
+                aliases.extend(columns.iter().filter_map(|column| match column {
+                    Expression::Alias { alias, .. } => Some(alias.clone()),
+                    _ => None,
+                }));
+            }
+        });
+        let mut placeholders = Vec::new();
+        let mut error = None;
+        for_each_name_mut(&mut statement, &mut Vec::new(), &mut |expr, scopes| match expr {
+            Expression::Placeholder(text) => placeholders.push(text.clone()),
+            _ => match resolve_column(expr, scopes, schema, &aliases) {
+                Ok(Some(column)) => columns.push(column),
+                Ok(None) => {}
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            },
+        });
+        if let Some(err) = error {
+            return Err(err);
+        }
+
+        let positional = placeholders.iter().filter(|text| *text == "?").count();
+        if positional > 0 && positional < placeholders.len() {
+            return Err("A statement can not mix ? and $n parameters".to_string());
+        }
+        let mut slots = Vec::new();
+        for (i, text) in placeholders.iter().enumerate() {
+            slots.push(match text[1..].parse::<usize>() {
+                Ok(n) if n > 0 => n - 1,
+                Ok(_) => return Err("Parameters are numbered from $1".to_string()),
+                Err(_) => i,
+            });
+        }
+        let parameters = slots.iter().max().map_or(0, |slot| slot + 1);
+        Ok(PreparedStatement { statement, columns, slots, parameters })
+    }
+
+    /// The statement as prepared, with its parameters.
+    pub fn statement(&self) -> &Statement {
+        &self.statement
+    }
+
+    /// The column references that were resolved, in the order they are written; the column list of an `INSERT` and the columns of its `ON CONFLICT` clause come first.
+    pub fn columns(&self) -> &[ResolvedColumn] {
+        &self.columns
+    }
+
+    /// How many values `bind` needs: the number of `?` parameters, or the highest `$n`.
+    pub fn parameter_count(&self) -> usize {
+        self.parameters
+    }
+
+    /// The statement with every parameter replaced by its value, `values[0]` for the first `?` or for `$1`. Fails when the number of values is not `parameter_count`.
+    pub fn bind(&self, values: &[Literal]) -> Result<Statement, String> {
+        if values.len() != self.parameters {
+            return Err(format!("Expected {} parameter values, found {}", self.parameters, values.len()));
+        }
+        let mut stmt = self.statement.clone();
+        let mut slots = self.slots.iter();
+        for_each_name_mut(&mut stmt, &mut Vec::new(), &mut |expr, _| {
+            if let Expression::Placeholder(_) = expr {
+                if let Some(slot) = slots.next() {
+                    *expr = Expression::Literal(values[*slot].clone());
+                }
+            }
+        });
+        Ok(stmt)
+    }
+}
+
+//helper, the position of a table in the schema with its columns; a table created from a query has none that are known
+fn schema_table<'a>(schema: &'a [Statement], name: &str) -> Result<(usize, &'a [TableColumn]), String> {
+    schema.iter().enumerate()
+        .find_map(|(i, stmt)| match stmt {
+            Statement::CreateTable { table_name, column_list, .. } if table_name.eq_ignore_ascii_case(name) => Some((i, column_list.as_slice())),
+            _ => None,
+        })
+        .ok_or_else(|| format!("Table {} is not in the schema", name))
+}
+
+//helper, case-insensitive lookup of a column
+fn column_position(columns: &[TableColumn], name: &str) -> Option<usize> {
+    columns.iter().position(|column| column.column_name.eq_ignore_ascii_case(name))
+}
+
+//one column reference, looked up from the innermost query outwards. `Ok(None)` is a reference whose column can not be
+//told from the schema: one of a source without known columns, or a select list alias
+fn resolve_column(expr: &Expression, scopes: &[Vec<Source>], schema: &[Statement], aliases: &[String]) -> Result<Option<ResolvedColumn>, String> {
+    let (qualifier, name) = match expr {
+        Expression::Identifier(name) => (None, name),
+        Expression::CompoundIdentifier(parts) => (Some(&parts[parts.len() - 2]), &parts[parts.len() - 1]),
+        _ => return Ok(None),
+    };
+    for scope in scopes.iter().rev() {
+        //`excluded` of ON CONFLICT is only reached through its qualifier, an unqualified name is the table's
+        let candidates = scope.iter().filter(|source| match qualifier {
+            Some(qualifier) => source.qualifier.eq_ignore_ascii_case(qualifier),
+            None => !source.qualifier.eq_ignore_ascii_case("excluded"),
+        });
+        let mut found = Vec::new();
+        let mut searched = None;
+        for source in candidates {
+            let Some(table_name) = &source.table else { return Ok(None) };
+            let (table, columns) = schema_table(schema, table_name)?;
+            if columns.is_empty() {
+                return Ok(None);
+            }
+            if let Some(column) = column_position(columns, name) {
+                found.push(ResolvedColumn { name: format_expression(expr), table, column });
+            }
+            searched = Some(table_name);
+        }
+        match (found.len(), searched) {
+            (1, _) => return Ok(found.pop()),
+            (0, Some(table_name)) if qualifier.is_some() => return Err(format!("Column {} is not a column of table {}", name, table_name)),
+            (0, _) => {}
+            _ => return Err(format!("Column {} is ambiguous, more than one table of the query has it", name)),
+        }
+    }
+    match qualifier {
+        None if aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name)) => Ok(None),
+        None => Err(format!("Column {} is not a column of any table it can belong to", name)),
+        Some(qualifier) => Err(format!("{} is not a table or alias of the query", qualifier)),
+    }
+}
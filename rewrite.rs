@@ -0,0 +1,834 @@
+//transformations of parsed statements, the result can be printed again with the formatter
+use std::convert::TryFrom;
+use crate::statement::{
+    Statement,
+    InsertSource,
+    Expression,
+    Literal,
+    BinaryOperator,
+    UnaryOperator,
+    TableReference,
+    GroupingElement,
+    JoinKind,
+    Constraint,
+    AlterAction,
+    ObjectType,
+    ConflictAction,
+    MergeAction,
+    With,
+};
+
+/// Adds a row filter, such as `tenant_id = ?`, to every `SELECT` that reads one of `tables`, subqueries included, so a multi-tenant proxy can confine every query to one tenant. The filter is `AND`-ed with the existing `WHERE` clause (which keeps its meaning, the operands of `AND` are separate subtrees). Column names in the filter are qualified with the alias (or name) the table has in the query, `o.tenant_id = ?` for `FROM orders AS o`.
+///
+/// A table on the optional side of an outer join (the right side of a `LEFT JOIN`, the left side of a `RIGHT JOIN`) gets the filter in the join's `ON` condition instead, so rows of the other table are still returned when nothing matches. Both sides of a `FULL JOIN` are optional, so a table on either is replaced by a filtered subquery, `(SELECT * FROM orders WHERE tenant_id = ?) AS o`. A table under `PIVOT` or `UNPIVOT` is replaced by a filtered subquery, `(SELECT * FROM orders WHERE tenant_id = ?) AS orders`, as its columns are not visible after the operator. A reference to a common table expression that has the name of one of the tables is not filtered, the query of the common table expression is. A `DELETE` from one of the tables gets the filter in its `WHERE` clause as well, so it only deletes rows the tenant can see. A `MERGE` into one of the tables gets it in its `ON` condition, so the rows of other tenants are never matched, and a source table that is one of them is replaced by a filtered subquery under the source's alias, so only the tenant's rows are merged. Table names are compared case-insensitively.
+pub fn add_row_filter(stmt: &mut Statement, tables: &[&str], filter: &Expression) {
+    selects_mut(stmt, &mut Vec::new(), &mut |select, ctes| filter_select(select, tables, filter, ctes));
+    match stmt {
+        Statement::Delete { table, where_clause } if is_filtered(table, tables) => {
+            *where_clause = Some(and(where_clause.take(), qualify(filter.clone(), table)));
+        }
+        Statement::Merge { table_name, alias, source, on, .. } => {
+            if is_filtered(table_name, tables) {
+                *on = and(Some(on.clone()), qualify(filter.clone(), alias.as_ref().unwrap_or(table_name)));
+            }
+            filter_in_subquery(source, tables, filter, &[]);
+        }
+        _ => {}
+    }
+}
+
+/// Makes sure a `SELECT` returns at most `max` rows, as a query gateway would: a missing `LIMIT` becomes `LIMIT max`, a constant one above `max` is lowered to it, and one that is not a constant (`LIMIT ?`) becomes `LIMIT least(?, max)`. Returns whether the statement was changed. Only the statement itself is limited, a `LIMIT` in a subquery changes what the query means rather than how many rows it returns.
+pub fn enforce_limit(stmt: &mut Statement, max: u64) -> bool {
+    let limit = match stmt {
+        Statement::Select { limit, .. } | Statement::SetOperation { limit, .. } => limit,
+        Statement::Prepare { statement, .. } => return enforce_limit(statement, max),
+        Statement::Values { .. } | Statement::CreateTable { .. } | Statement::Insert { .. } | Statement::Delete { .. } | Statement::Merge { .. } | Statement::AlterTable { .. } | Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Execute { .. } | Statement::Deallocate { .. } => return false,
+    };
+    let max_literal = || Expression::Literal(integer(max));
+    match limit.take() {
+        None => {
+            *limit = Some(max_literal());
+            true
+        }
+        Some(Expression::Literal(Literal::Integer(n))) if n >= 0 && n as u64 <= max => {
+            *limit = Some(Expression::Literal(Literal::Integer(n)));
+            false
+        }
+        Some(Expression::Literal(Literal::Integer(_) | Literal::Decimal(_))) => {
+            *limit = Some(max_literal());
+            true
+        }
+        Some(other) => {
+            *limit = Some(Expression::Function { name: "least".to_string(), args: vec![other, max_literal()], over: None });
+            true
+        }
+    }
+}
+
+//helper, the literal for a row count, decimal text when it does not fit an Integer
+fn integer(n: u64) -> Literal {
+    match i64::try_from(n) {
+        Ok(n) => Literal::Integer(n),
+        Err(_) => Literal::Decimal(n.to_string()),
+    }
+}
+
+/// Replaces `ORDER BY` and `GROUP BY` items that refer to the select list with the expressions they refer to, in every `SELECT` of the statement: a position (`ORDER BY 2`, counted from 1) stands for that column, and a name that is the alias of a column (`ORDER BY total` for `sum(price) AS total`) for its expression. `ASC` and `DESC` stay on the resolved item. Only whole items are resolved, `ORDER BY total + 1` is left as it is, and so are the sets of `ROLLUP`, `CUBE` and `GROUPING SETS`.
+///
+/// An alias in `GROUP BY` is taken to mean the select list column, as in MySQL and Snowflake. PostgreSQL prefers a column of the `FROM` tables with the same name, which can not be told without a schema. Aliases are compared case-insensitively.
+///
+/// Returns an error, and leaves the statement partly resolved, for a position that is not in the select list, a position at or after a `*` (its columns are not known), and an alias used by more than one column.
+pub fn resolve_select_references(stmt: &mut Statement) -> Result<(), String> {
+    let mut result = Ok(());
+    for_each_select_mut(stmt, &mut |select| {
+        if let Statement::Select { columns, group_by, orderby, .. } = select {
+            let resolved = orderby.iter_mut().try_for_each(|item| resolve_item(item, columns, "ORDER BY"));
+            let resolved = resolved.and_then(|_| group_by.iter_mut().try_for_each(|element| match element {
+                GroupingElement::Expression(expr) => resolve_item(expr, columns, "GROUP BY"),
+                _ => Ok(()),
+            }));
+            if result.is_ok() {
+                result = resolved;
+            }
+        }
+    });
+    result
+}
+
+//helper, replace one item with the select list column it refers to, looking through ASC and DESC
+fn resolve_item(item: &mut Expression, columns: &[Expression], clause: &str) -> Result<(), String> {
+    let target = match item {
+        Expression::UnaryOperation { operand, operator: UnaryOperator::Asc | UnaryOperator::Desc } => operand.as_mut(),
+        other => other,
+    };
+    let column = match target {
+        Expression::Literal(Literal::Integer(position)) => {
+            let position = *position;
+            let index = usize::try_from(position).ok().filter(|index| (1..=columns.len()).contains(index)).ok_or_else(|| {
+                format!("{} position {} is not in the select list, it has {} columns", clause, position, columns.len())
+            })?;
+            if columns[..index].contains(&Expression::Wildcard) {
+                return Err(format!("{} position {} can not be resolved, the select list has * before it", clause, position));
+            }
+            &columns[index - 1]
+        }
+        Expression::Identifier(name) => {
+            let mut aliased = columns.iter().filter(|column| matches!(column, Expression::Alias { alias, .. } if alias.eq_ignore_ascii_case(name)));
+            match (aliased.next(), aliased.next()) {
+                (Some(column), None) => column,
+                (Some(_), Some(_)) => return Err(format!("{} {} is ambiguous, more than one column of the select list is named {}", clause, name, name)),
+                (None, _) => return Ok(()),
+            }
+        }
+        _ => return Ok(()),
+    };
+    *target = match column {
+        Expression::Alias { expr, .. } => (**expr).clone(),
+        other => other.clone(),
+    };
+    Ok(())
+}
+
+//calls `f` on every SELECT in the statement, the statement itself included, innermost first
+//a UNION, INTERSECT or EXCEPT is a query as well, `f` gets it after the queries it combines
+//subqueries are done before the statement containing them, so `f` may add new subqueries without seeing them
+pub fn for_each_select_mut(stmt: &mut Statement, f: &mut dyn FnMut(&mut Statement)) {
+    selects_mut(stmt, &mut Vec::new(), &mut |select, _| f(select));
+}
+
+//for_each_select_mut, `f` given the names of the common table expressions the query can read as well, a table
+//reference with one of those names reads the common table expression rather than the table
+fn selects_mut(stmt: &mut Statement, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Statement, &[String])) {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            let visible = ctes.len();
+            with_selects_mut(with, ctes, f);
+            for table in from.iter_mut() {
+                table_selects_mut(table, ctes, f);
+            }
+            let clauses = r#where.iter_mut().chain(having.iter_mut()).chain(qualify.iter_mut()).chain(limit.iter_mut());
+            for expr in columns.iter_mut().chain(clauses).chain(orderby.iter_mut()) {
+                expression_selects_mut(expr, ctes, f);
+            }
+            for element in group_by.iter_mut() {
+                match element {
+                    GroupingElement::Expression(expr) => expression_selects_mut(expr, ctes, f),
+                    GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => {
+                        for expr in sets.iter_mut().flatten() {
+                            expression_selects_mut(expr, ctes, f);
+                        }
+                    }
+                }
+            }
+            f(stmt, ctes);
+            ctes.truncate(visible);
+        }
+        Statement::SetOperation { with, left, right, orderby, limit, .. } => {
+            let visible = ctes.len();
+            with_selects_mut(with, ctes, f);
+            selects_mut(left, ctes, f);
+            selects_mut(right, ctes, f);
+            for expr in orderby.iter_mut().chain(limit.iter_mut()) {
+                expression_selects_mut(expr, ctes, f);
+            }
+            f(stmt, ctes);
+            ctes.truncate(visible);
+        }
+        Statement::CreateTable { query, .. } => {
+            if let Some(query) = query {
+                selects_mut(query, ctes, f);
+            }
+        }
+        Statement::Values { rows } => {
+            for expr in rows.iter_mut().flatten() {
+                expression_selects_mut(expr, ctes, f);
+            }
+        }
+        Statement::Insert { source, on_conflict, .. } => {
+            if let InsertSource::Query(query) = source {
+                selects_mut(query, ctes, f);
+            }
+            for expr in source.rows_mut().iter_mut().flatten().chain(on_conflict.iter_mut().flat_map(|on_conflict| on_conflict.expressions_mut())) {
+                expression_selects_mut(expr, ctes, f);
+            }
+        }
+        Statement::Delete { where_clause, .. } => {
+            if let Some(expr) = where_clause {
+                expression_selects_mut(expr, ctes, f);
+            }
+        }
+        Statement::Merge { source, on, clauses, .. } => {
+            table_selects_mut(source, ctes, f);
+            for expr in std::iter::once(on).chain(clauses.iter_mut().flat_map(|clause| clause.expressions_mut())) {
+                expression_selects_mut(expr, ctes, f);
+            }
+        }
+        Statement::SetVariable { value, rest, .. } => std::iter::once(value).chain(rest).for_each(|expr| expression_selects_mut(expr, ctes, f)),
+        Statement::Call { args, .. } | Statement::Execute { args, .. } => args.iter_mut().for_each(|arg| expression_selects_mut(arg, ctes, f)),
+        Statement::Prepare { statement, .. } => selects_mut(statement, ctes, f),
+        Statement::AlterTable { .. } | Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Deallocate { .. } => {}
+    }
+}
+
+//helper, walks the queries of a WITH clause and adds the names of its common table expressions to `ctes`, the way
+//with_names_mut does
+fn with_selects_mut(with: &mut Option<With>, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Statement, &[String])) {
+    if let Some(with) = with {
+        for cte in with.ctes.iter_mut() {
+            if with.recursive {
+                ctes.push(cte.name.clone());
+            }
+            selects_mut(&mut cte.query, ctes, f);
+            if !with.recursive {
+                ctes.push(cte.name.clone());
+            }
+        }
+    }
+}
+
+fn table_selects_mut(table: &mut TableReference, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Statement, &[String])) {
+    match table {
+        TableReference::Table { .. } => {}
+        TableReference::Derived { subquery, .. } => selects_mut(subquery, ctes, f),
+        TableReference::Function { args, .. } => {
+            for arg in args.iter_mut() {
+                expression_selects_mut(arg, ctes, f);
+            }
+        }
+        TableReference::Pivot { table, .. } | TableReference::Unpivot { table, .. } => table_selects_mut(table, ctes, f),
+        TableReference::Join { left, right, on, .. } => {
+            table_selects_mut(left, ctes, f);
+            table_selects_mut(right, ctes, f);
+            if let Some(on) = on {
+                expression_selects_mut(on, ctes, f);
+            }
+        }
+    }
+}
+
+fn expression_selects_mut(expr: &mut Expression, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Statement, &[String])) {
+    match expr {
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => selects_mut(subquery, ctes, f),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_selects_mut(left_operand, ctes, f);
+            expression_selects_mut(right_operand, ctes, f);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_selects_mut(operand, ctes, f),
+        Expression::Alias { expr, .. } => expression_selects_mut(expr, ctes, f),
+        Expression::Function { args, over, .. } => {
+            for arg in args.iter_mut() {
+                expression_selects_mut(arg, ctes, f);
+            }
+            if let Some(window) = over {
+                for expr in window.partition_by.iter_mut().chain(window.order_by.iter_mut()) {
+                    expression_selects_mut(expr, ctes, f);
+                }
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) | Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {}
+    }
+}
+
+//helper, `left AND right`, or just `right` when there is nothing to add it to
+fn and(left: Option<Expression>, right: Expression) -> Expression {
+    match left {
+        Some(left) => Expression::BinaryOperation {
+            left_operand: Box::new(left),
+            operator: BinaryOperator::And,
+            right_operand: Box::new(right),
+        },
+        None => right,
+    }
+}
+
+//the filter for the matching tables of one SELECT's FROM clause (not its subqueries, selects_mut reaches those); a
+//reference to one of `ctes`, the common table expressions the query can read, is not the table
+fn filter_select(select: &mut Statement, tables: &[&str], filter: &Expression, ctes: &[String]) {
+    if let Statement::Select { from, r#where, .. } = select {
+        let mut filters = Vec::new();
+        for table in from.iter_mut() {
+            filters.extend(filter_table(table, tables, filter, ctes));
+        }
+        for extra in filters {
+            *r#where = Some(and(r#where.take(), extra));
+        }
+    }
+}
+
+//filters the tables of one table reference need in WHERE, the ones that belong in an ON condition are already placed
+fn filter_table(table: &mut TableReference, tables: &[&str], filter: &Expression, ctes: &[String]) -> Vec<Expression> {
+    match table {
+        TableReference::Table { name, alias, .. } if is_filtered(name, tables) && !is_cte(name, ctes) => {
+            vec![qualify(filter.clone(), alias.as_ref().unwrap_or(name))]
+        }
+        TableReference::Pivot { table: input, .. } | TableReference::Unpivot { table: input, .. } => {
+            filter_in_subquery(input, tables, filter, ctes);
+            Vec::new()
+        }
+        //either side of a FULL JOIN can be NULL-extended, a filter in WHERE would drop those rows and one in ON keep the
+        //rows it does not match, so the filtered tables of both sides become filtered subqueries
+        TableReference::Join { left, right, kind: JoinKind::Full, .. } => {
+            filter_in_subquery(left, tables, filter, ctes);
+            filter_in_subquery(right, tables, filter, ctes);
+            Vec::new()
+        }
+        TableReference::Join { left, right, kind, on } => {
+            let left_filters = filter_table(left, tables, filter, ctes);
+            let right_filters = filter_table(right, tables, filter, ctes);
+            let (kept, in_on) = match kind {
+                JoinKind::Left => (left_filters, right_filters),
+                JoinKind::Right => (right_filters, left_filters),
+                _ => (left_filters.into_iter().chain(right_filters).collect(), Vec::new()),
+            };
+            for extra in in_on {
+                *on = Some(and(on.take(), extra));
+            }
+            kept
+        }
+        _ => Vec::new(),
+    }
+}
+
+//helper, a filtered table with no WHERE of its own to take the filter (under PIVOT/UNPIVOT, the source of a MERGE, a side
+//of a FULL JOIN) becomes (SELECT * FROM table WHERE filter) AS table, the table's alias if it has one
+fn filter_in_subquery(input: &mut TableReference, tables: &[&str], filter: &Expression, ctes: &[String]) {
+    match input {
+        TableReference::Table { name, alias, sample } if is_filtered(name, tables) && !is_cte(name, ctes) => {
+            let alias = alias.take().unwrap_or_else(|| name.clone());
+            let subquery = Statement::Select {
+                with: None,
+                hints: Vec::new(),
+                columns: vec![Expression::Wildcard],
+                from: vec![TableReference::Table { name: name.clone(), alias: None, sample: sample.take() }],
+                r#where: Some(filter.clone()),
+                group_by: Vec::new(),
+                having: None,
+                qualify: None,
+                orderby: Vec::new(),
+                limit: None,
+            };
+            *input = TableReference::Derived { lateral: false, subquery: Box::new(subquery), alias: Some(alias) };
+        }
+        TableReference::Pivot { table, .. } | TableReference::Unpivot { table, .. } => filter_in_subquery(table, tables, filter, ctes),
+        TableReference::Join { left, right, .. } => {
+            filter_in_subquery(left, tables, filter, ctes);
+            filter_in_subquery(right, tables, filter, ctes);
+        }
+        _ => {}
+    }
+}
+
+fn is_filtered(name: &str, tables: &[&str]) -> bool {
+    tables.iter().any(|table| table.eq_ignore_ascii_case(name))
+}
+
+//helper, whether a table reference names one of the common table expressions a query can read
+fn is_cte(name: &str, ctes: &[String]) -> bool {
+    ctes.iter().any(|cte| cte.eq_ignore_ascii_case(name))
+}
+
+//helper, prefixes the bare column names of an expression with a table alias, `tenant_id` becomes `o.tenant_id`
+//names inside subqueries belong to the subquery and are left alone
+fn qualify(expr: Expression, alias: &str) -> Expression {
+    let qualify_box = |expr: Box<Expression>| Box::new(qualify(*expr, alias));
+    match expr {
+        Expression::Identifier(name) => Expression::CompoundIdentifier(vec![alias.to_string(), name]),
+        Expression::BinaryOperation { left_operand, operator, right_operand } => Expression::BinaryOperation {
+            left_operand: qualify_box(left_operand),
+            operator,
+            right_operand: qualify_box(right_operand),
+        },
+        Expression::IsDistinctFrom { left_operand, right_operand, negated } => Expression::IsDistinctFrom {
+            left_operand: qualify_box(left_operand),
+            right_operand: qualify_box(right_operand),
+            negated,
+        },
+        Expression::UnaryOperation { operand, operator } => Expression::UnaryOperation { operand: qualify_box(operand), operator },
+        Expression::Function { name, args, over } => Expression::Function {
+            name,
+            args: args.into_iter().map(|arg| qualify(arg, alias)).collect(),
+            over,
+        },
+        other => other,
+    }
+}
+
+/// Renames a table in every statement of a script: `CREATE TABLE`, `INSERT INTO`, `DELETE FROM`, the target and source of `MERGE`, `ALTER TABLE` (the new name of `RENAME TO` too), both names of a `RENAME TABLE` pair, `TRUNCATE`, `CREATE INDEX`, `CREATE TRIGGER` (not its body) and `DROP TABLE` names, table references in `FROM` clauses and joins (subqueries included), and the qualifier of column references that use the table name, `orders.id` becoming `purchases.id`. A qualifier that is an alias of the table, or a different table that happens to be aliased with the old name, is left alone, and so is a reference to a common table expression of the same name, with the column references qualified with it. Names are compared case-insensitively.
+pub fn rename_table(stmts: &mut [Statement], old: &str, new: &str) {
+    for stmt in stmts.iter_mut() {
+        //qualifiers first, while the table references still tell which names mean the table
+        for_each_name_mut(stmt, &mut Vec::new(), &mut |expr, scopes| {
+            if let Expression::CompoundIdentifier(parts) = expr {
+                let Some(index) = parts.len().checked_sub(2) else { return };
+                if resolve(scopes, &parts[index]).is_some_and(|source| source.is_unaliased(old)) {
+                    parts[index] = new.to_string();
+                }
+            }
+        });
+        if let Statement::CreateTable { table_name, .. } | Statement::Insert { table_name, .. } | Statement::Delete { table: table_name, .. } | Statement::Merge { table_name, .. } | Statement::AlterTable { table_name, .. } | Statement::Truncate { table_name } | Statement::Analyze { table_name: Some(table_name) } | Statement::Vacuum { table_name: Some(table_name), .. } | Statement::CreateIndex { table_name, .. } | Statement::CreateTrigger { table_name, .. } | Statement::Describe { table_name } = stmt {
+            if table_name.eq_ignore_ascii_case(old) {
+                *table_name = new.to_string();
+            }
+        }
+        if let Statement::Drop { object_type: ObjectType::Table, names, .. } = stmt {
+            for name in names.iter_mut().filter(|name| name.eq_ignore_ascii_case(old)) {
+                *name = new.to_string();
+            }
+        }
+        if let Statement::AlterTable { action: AlterAction::RenameTo(new_name), .. } = stmt {
+            if new_name.eq_ignore_ascii_case(old) {
+                *new_name = new.to_string();
+            }
+        }
+        if let Statement::RenameTable { renames } = stmt {
+            for name in renames.iter_mut().flat_map(|rename| [&mut rename.old_name, &mut rename.new_name]).filter(|name| name.eq_ignore_ascii_case(old)) {
+                *name = new.to_string();
+            }
+        }
+        if let Statement::Merge { source, .. } = stmt {
+            rename_table_references(source, old, new, &[]);
+        }
+        //the FROM clauses, of subqueries in INSERT values too, but not the references to a common table expression of
+        //the same name
+        selects_mut(stmt, &mut Vec::new(), &mut |select, ctes| {
+            if let Statement::Select { from, .. } = select {
+                for table in from.iter_mut() {
+                    rename_table_references(table, old, new, ctes);
+                }
+            }
+        });
+    }
+}
+
+/// Renames a column of `table` in every statement of a script: its definition in `CREATE TABLE` and `CHECK` constraints, the columns `ALTER TABLE` adds, drops or renames, the column lists of `INSERT` (its `ON CONFLICT` target and `SET` list included), of the arms of a `MERGE` into the table and of `CREATE INDEX`, column references qualified with the table's name or alias, and unqualified references in a `SELECT` whose `FROM` clause has the table or in the `WHERE` clause of a `DELETE` from it. Without a schema, an unqualified name is taken to belong to the table whenever the table is in the same `FROM` clause, so a column of the same name in a joined table is renamed as well. Names are compared case-insensitively.
+pub fn rename_column(stmts: &mut [Statement], table: &str, old: &str, new: &str) {
+    for stmt in stmts.iter_mut() {
+        for_each_name_mut(stmt, &mut Vec::new(), &mut |expr, scopes| match expr {
+            Expression::Identifier(name) if name.eq_ignore_ascii_case(old) => {
+                let in_from = scopes.last().is_some_and(|scope| scope.iter().any(|source| source.is_table(table)));
+                if in_from {
+                    *name = new.to_string();
+                }
+            }
+            Expression::CompoundIdentifier(parts) if parts.len() >= 2 && parts[parts.len() - 1].eq_ignore_ascii_case(old) => {
+                let qualifier = &parts[parts.len() - 2];
+                if resolve(scopes, qualifier).is_some_and(|source| source.is_table(table)) {
+                    *parts.last_mut().unwrap() = new.to_string();
+                }
+            }
+            _ => {}
+        });
+        match stmt {
+            Statement::CreateTable { table_name, column_list, .. } if table_name.eq_ignore_ascii_case(table) => {
+                for column in column_list.iter_mut().filter(|column| column.column_name.eq_ignore_ascii_case(old)) {
+                    column.column_name = new.to_string();
+                }
+            }
+            Statement::Insert { table_name, columns, on_conflict, .. } if table_name.eq_ignore_ascii_case(table) => {
+                let mut names: Vec<&mut String> = columns.iter_mut().flatten().collect();
+                if let Some(on_conflict) = on_conflict {
+                    names.extend(&mut on_conflict.target);
+                    if let ConflictAction::Update { assignments, .. } = &mut on_conflict.action {
+                        names.extend(assignments.iter_mut().map(|assignment| &mut assignment.column));
+                    }
+                }
+                for name in names.into_iter().filter(|name| name.eq_ignore_ascii_case(old)) {
+                    *name = new.to_string();
+                }
+            }
+            Statement::Merge { table_name, clauses, .. } if table_name.eq_ignore_ascii_case(table) => {
+                let names = clauses.iter_mut().flat_map(|clause| match &mut clause.action {
+                    MergeAction::Update { assignments } => assignments.iter_mut().map(|assignment| &mut assignment.column).collect(),
+                    MergeAction::Insert { columns, .. } => columns.iter_mut().flatten().collect(),
+                    MergeAction::Delete | MergeAction::Nothing => Vec::new(),
+                });
+                for name in names.filter(|name| name.eq_ignore_ascii_case(old)) {
+                    *name = new.to_string();
+                }
+            }
+            Statement::CreateIndex { table_name, columns, .. } if table_name.eq_ignore_ascii_case(table) => {
+                for name in columns.iter_mut().map(|column| &mut column.name).filter(|name| name.eq_ignore_ascii_case(old)) {
+                    *name = new.to_string();
+                }
+            }
+            Statement::AlterTable { table_name, action } if table_name.eq_ignore_ascii_case(table) => {
+                let names = match action {
+                    AlterAction::AddColumn(column) => vec![&mut column.column_name],
+                    AlterAction::DropColumn(column_name) => vec![column_name],
+                    AlterAction::RenameColumn { column_name, new_name } => vec![column_name, new_name],
+                    AlterAction::RenameTo(_) => Vec::new(),
+                };
+                for name in names.into_iter().filter(|name| name.eq_ignore_ascii_case(old)) {
+                    *name = new.to_string();
+                }
+            }
+            _ => {}
+        }
+        for_each_select_mut(stmt, &mut |select| {
+            if let Statement::Select { from, .. } = select {
+                for item in from.iter_mut() {
+                    rename_unpivot_columns(item, table, old, new);
+                }
+            }
+        });
+    }
+}
+
+//helper, renames the plain table references of one FROM item that are not one of `ctes`, subqueries are reached
+//through selects_mut
+fn rename_table_references(table: &mut TableReference, old: &str, new: &str, ctes: &[String]) {
+    match table {
+        TableReference::Table { name, .. } if name.eq_ignore_ascii_case(old) && !is_cte(name, ctes) => *name = new.to_string(),
+        TableReference::Pivot { table, .. } | TableReference::Unpivot { table, .. } => rename_table_references(table, old, new, ctes),
+        TableReference::Join { left, right, .. } => {
+            rename_table_references(left, old, new, ctes);
+            rename_table_references(right, old, new, ctes);
+        }
+        _ => {}
+    }
+}
+
+//helper, the column list of UNPIVOT names columns of the table underneath
+fn rename_unpivot_columns(item: &mut TableReference, table: &str, old: &str, new: &str) {
+    match item {
+        TableReference::Unpivot { table: input, unpivot, .. } => {
+            if let TableReference::Table { name, .. } = &**input {
+                if name.eq_ignore_ascii_case(table) {
+                    for column in unpivot.columns.iter_mut().filter(|column| column.eq_ignore_ascii_case(old)) {
+                        *column = new.to_string();
+                    }
+                }
+            }
+            rename_unpivot_columns(input, table, old, new);
+        }
+        TableReference::Pivot { table: input, .. } => rename_unpivot_columns(input, table, old, new),
+        TableReference::Join { left, right, .. } => {
+            rename_unpivot_columns(left, table, old, new);
+            rename_unpivot_columns(right, table, old, new);
+        }
+        _ => {}
+    }
+}
+
+/// One name a `FROM` clause makes visible to column references: the alias or table name used as qualifier, and the table behind it when it is a plain table (`None` for a derived table, a table function or the result of `PIVOT` and `UNPIVOT`).
+pub struct Source {
+    pub qualifier: String,
+    pub table: Option<String>,
+}
+
+impl Source {
+    //true when the source is the given table, under any qualifier
+    fn is_table(&self, table: &str) -> bool {
+        self.table.as_ref().is_some_and(|name| name.eq_ignore_ascii_case(table))
+    }
+
+    //true when the source is the given table without an alias, so the table name itself qualifies columns
+    fn is_unaliased(&self, table: &str) -> bool {
+        self.is_table(table) && self.qualifier.eq_ignore_ascii_case(table)
+    }
+}
+
+//helper, the sources of one FROM item
+fn sources(table: &TableReference, out: &mut Vec<Source>) {
+    match table {
+        TableReference::Table { name, alias, .. } => out.push(Source {
+            qualifier: alias.as_ref().unwrap_or(name).clone(),
+            table: Some(name.clone()),
+        }),
+        TableReference::Derived { alias, .. } => {
+            if let Some(alias) = alias {
+                out.push(Source { qualifier: alias.clone(), table: None });
+            }
+        }
+        TableReference::Function { name, alias, .. } => out.push(Source { qualifier: alias.as_ref().unwrap_or(name).clone(), table: None }),
+        //the input stays visible for the expressions inside the PIVOT/UNPIVOT clause
+        TableReference::Pivot { table, alias, .. } | TableReference::Unpivot { table, alias, .. } => {
+            sources(table, out);
+            if let Some(alias) = alias {
+                out.push(Source { qualifier: alias.clone(), table: None });
+            }
+        }
+        TableReference::Join { left, right, .. } => {
+            sources(left, out);
+            sources(right, out);
+        }
+    }
+}
+
+//helper, what a qualifier refers to, looked up from the innermost query outwards
+fn resolve<'a>(scopes: &'a [Vec<Source>], qualifier: &str) -> Option<&'a Source> {
+    scopes.iter().rev().find_map(|scope| scope.iter().find(|source| source.qualifier.eq_ignore_ascii_case(qualifier)))
+}
+
+/// Calls `f` on every column reference (`Identifier` and `CompoundIdentifier`) and every parameter (`Placeholder`) of the statement, subqueries included, in the order they are written, together with the sources of the queries around it, innermost last. `scopes` holds the sources of the queries the statement is part of, empty for a statement of its own. A common table expression read in a `FROM` clause is a source without a table, like a derived table.
+pub fn for_each_name_mut(stmt: &mut Statement, scopes: &mut Vec<Vec<Source>>, f: &mut dyn FnMut(&mut Expression, &[Vec<Source>])) {
+    statement_names_mut(stmt, scopes, &mut Vec::new(), f);
+}
+
+//helper, walks the queries of a WITH clause and adds the names of its common table expressions to `ctes`
+//a common table expression can use the ones before it, and itself as well in WITH RECURSIVE
+fn with_names_mut(with: &mut Option<With>, scopes: &mut Vec<Vec<Source>>, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Expression, &[Vec<Source>])) {
+    if let Some(with) = with {
+        for cte in with.ctes.iter_mut() {
+            if with.recursive {
+                ctes.push(cte.name.clone());
+            }
+            statement_names_mut(&mut cte.query, scopes, ctes, f);
+            if !with.recursive {
+                ctes.push(cte.name.clone());
+            }
+        }
+    }
+}
+
+//for_each_name_mut, with the names of the common table expressions the statement can read
+fn statement_names_mut(stmt: &mut Statement, scopes: &mut Vec<Vec<Source>>, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Expression, &[Vec<Source>])) {
+    match stmt {
+        Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } => {
+            let visible = ctes.len();
+            with_names_mut(with, scopes, ctes, f);
+            let mut scope = Vec::new();
+            for table in from.iter() {
+                sources(table, &mut scope);
+            }
+            for source in scope.iter_mut() {
+                if source.table.as_ref().is_some_and(|table| ctes.iter().any(|cte| cte.eq_ignore_ascii_case(table))) {
+                    source.table = None;
+                }
+            }
+            scopes.push(scope);
+            for expr in columns.iter_mut() {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+            for table in from.iter_mut() {
+                table_names_mut(table, scopes, ctes, f);
+            }
+            for expr in r#where.iter_mut() {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+            for element in group_by.iter_mut() {
+                match element {
+                    GroupingElement::Expression(expr) => expression_names_mut(expr, scopes, ctes, f),
+                    GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => {
+                        for expr in sets.iter_mut().flatten() {
+                            expression_names_mut(expr, scopes, ctes, f);
+                        }
+                    }
+                }
+            }
+            for expr in having.iter_mut().chain(qualify.iter_mut()).chain(orderby.iter_mut()).chain(limit.iter_mut()) {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+            scopes.pop();
+            ctes.truncate(visible);
+        }
+        Statement::SetOperation { with, left, right, orderby, limit, .. } => {
+            let visible = ctes.len();
+            with_names_mut(with, scopes, ctes, f);
+            statement_names_mut(left, scopes, ctes, f);
+            statement_names_mut(right, scopes, ctes, f);
+            //ORDER BY names columns of the combined rows, which come from no table
+            scopes.push(vec![Source { qualifier: String::new(), table: None }]);
+            for expr in orderby.iter_mut().chain(limit.iter_mut()) {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+            scopes.pop();
+            ctes.truncate(visible);
+        }
+        Statement::CreateTable { table_name, column_list, query, .. } => {
+            scopes.push(vec![Source { qualifier: table_name.clone(), table: Some(table_name.clone()) }]);
+            for constraint in column_list.iter_mut().flat_map(|column| column.constraints.iter_mut()) {
+                if let Constraint::Check(expr) = constraint {
+                    expression_names_mut(expr, scopes, ctes, f);
+                }
+            }
+            scopes.pop();
+            if let Some(query) = query {
+                statement_names_mut(query, scopes, ctes, f);
+            }
+        }
+        //VALUES has no FROM clause, names in it only mean something in its subqueries
+        Statement::Values { rows } => {
+            for expr in rows.iter_mut().flatten() {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+        }
+        Statement::Insert { table_name, source, on_conflict, .. } => {
+            match source {
+                InsertSource::Values(rows) => {
+                    for expr in rows.iter_mut().flatten() {
+                        expression_names_mut(expr, scopes, ctes, f);
+                    }
+                }
+                InsertSource::Query(query) => statement_names_mut(query, scopes, ctes, f),
+            }
+            //DO UPDATE sees the row in the table, and as `excluded` the row that was to be inserted into it
+            if let Some(on_conflict) = on_conflict {
+                scopes.push(vec![
+                    Source { qualifier: table_name.clone(), table: Some(table_name.clone()) },
+                    Source { qualifier: "excluded".to_string(), table: Some(table_name.clone()) },
+                ]);
+                for expr in on_conflict.expressions_mut() {
+                    expression_names_mut(expr, scopes, ctes, f);
+                }
+                scopes.pop();
+            }
+        }
+        Statement::Delete { table, where_clause } => {
+            scopes.push(vec![Source { qualifier: table.clone(), table: Some(table.clone()) }]);
+            if let Some(expr) = where_clause {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+            scopes.pop();
+        }
+        //the condition and the arms see the target, under its alias when it has one, and the source
+        Statement::Merge { table_name, alias, source, on, clauses } => {
+            let mut scope = vec![Source { qualifier: alias.as_ref().unwrap_or(table_name).clone(), table: Some(table_name.clone()) }];
+            sources(source, &mut scope);
+            for source in scope.iter_mut().skip(1) {
+                if source.table.as_ref().is_some_and(|table| ctes.iter().any(|cte| cte.eq_ignore_ascii_case(table))) {
+                    source.table = None;
+                }
+            }
+            table_names_mut(source, scopes, ctes, f);
+            scopes.push(scope);
+            for expr in std::iter::once(on).chain(clauses.iter_mut().flat_map(|clause| clause.expressions_mut())) {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+            scopes.pop();
+        }
+        Statement::AlterTable { table_name, action } => {
+            scopes.push(vec![Source { qualifier: table_name.clone(), table: Some(table_name.clone()) }]);
+            if let AlterAction::AddColumn(column) = action {
+                for constraint in column.constraints.iter_mut() {
+                    if let Constraint::Check(expr) = constraint {
+                        expression_names_mut(expr, scopes, ctes, f);
+                    }
+                }
+            }
+            scopes.pop();
+        }
+        Statement::Prepare { statement, .. } => statement_names_mut(statement, scopes, ctes, f),
+        //the names in the value of SET are words such as `warning`, not columns
+        Statement::RenameTable { .. } | Statement::Truncate { .. } | Statement::Analyze { .. } | Statement::Vacuum { .. } | Statement::Unparsed { .. } | Statement::Drop { .. } | Statement::CreateDatabase { .. } | Statement::CreateSchema { .. } | Statement::CreateIndex { .. } | Statement::CreateTrigger { .. } | Statement::CreateFunction { .. } | Statement::CreateProcedure { .. } | Statement::Show { .. } | Statement::Describe { .. } | Statement::SetVariable { .. } | Statement::Call { .. } | Statement::Copy { .. } | Statement::LoadData { .. } | Statement::Execute { .. } | Statement::Deallocate { .. } => {}
+    }
+}
+
+fn table_names_mut(table: &mut TableReference, scopes: &mut Vec<Vec<Source>>, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Expression, &[Vec<Source>])) {
+    match table {
+        TableReference::Table { sample, .. } => {
+            if let Some(sample) = sample {
+                expression_names_mut(&mut sample.percentage, scopes, ctes, f);
+                if let Some(seed) = &mut sample.repeatable {
+                    expression_names_mut(seed, scopes, ctes, f);
+                }
+            }
+        }
+        TableReference::Derived { subquery, .. } => statement_names_mut(subquery, scopes, ctes, f),
+        TableReference::Function { args, .. } => {
+            for arg in args.iter_mut() {
+                expression_names_mut(arg, scopes, ctes, f);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            table_names_mut(table, scopes, ctes, f);
+            for expr in std::iter::once(&mut pivot.aggregate).chain(std::iter::once(&mut pivot.column)).chain(pivot.values.iter_mut()) {
+                expression_names_mut(expr, scopes, ctes, f);
+            }
+        }
+        TableReference::Unpivot { table, .. } => table_names_mut(table, scopes, ctes, f),
+        TableReference::Join { left, right, on, .. } => {
+            table_names_mut(left, scopes, ctes, f);
+            table_names_mut(right, scopes, ctes, f);
+            if let Some(on) = on {
+                expression_names_mut(on, scopes, ctes, f);
+            }
+        }
+    }
+}
+
+fn expression_names_mut(expr: &mut Expression, scopes: &mut Vec<Vec<Source>>, ctes: &mut Vec<String>, f: &mut dyn FnMut(&mut Expression, &[Vec<Source>])) {
+    match expr {
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) | Expression::Placeholder(_) => f(expr, scopes),
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => statement_names_mut(subquery, scopes, ctes, f),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            expression_names_mut(left_operand, scopes, ctes, f);
+            expression_names_mut(right_operand, scopes, ctes, f);
+        }
+        Expression::UnaryOperation { operand, .. } => expression_names_mut(operand, scopes, ctes, f),
+        Expression::Alias { expr, .. } => expression_names_mut(expr, scopes, ctes, f),
+        Expression::Function { args, over, .. } => {
+            for arg in args.iter_mut() {
+                expression_names_mut(arg, scopes, ctes, f);
+            }
+            if let Some(window) = over {
+                for expr in window.partition_by.iter_mut().chain(window.order_by.iter_mut()) {
+                    expression_names_mut(expr, scopes, ctes, f);
+                }
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Tokenizer;
+    use crate::parser::Parser;
+    use crate::formatter::format_statements;
+
+    fn renamed(sql: &str, old: &str, new: &str) -> String {
+        let mut stmts = Parser::new(Tokenizer::new(sql).tokenize().unwrap()).parse_statements().unwrap();
+        rename_table(&mut stmts, old, new);
+        format_statements(&stmts)
+    }
+
+    #[test]
+    fn rename_table_skips_a_cte_of_the_same_name() {
+        let sql = "WITH orders AS (SELECT id FROM raw) SELECT orders.id FROM orders;";
+        assert_eq!(renamed(sql, "orders", "purchases"), "WITH orders AS (SELECT id FROM raw)\nSELECT orders.id\nFROM orders;");
+        let sql = "WITH recent AS (SELECT id FROM orders) SELECT orders.id FROM orders JOIN recent ON recent.id = orders.id;";
+        assert_eq!(renamed(sql, "orders", "purchases"), "WITH recent AS (SELECT id FROM purchases)\nSELECT purchases.id\nFROM purchases JOIN recent ON recent.id = purchases.id;");
+    }
+}
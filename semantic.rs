@@ -0,0 +1,671 @@
+//checks of parsed statements that need more than the grammar, like names that have to refer to something
+use std::fmt;
+use crate::token::{Token, Keyword};
+use crate::tokenizer::{SpannedToken, Span};
+use crate::dialect::Dialect;
+use crate::statement::{Statement, InsertSource, Expression, TableColumn, TableReference, GroupingElement, Constraint, DBType, BinaryOperator, UnaryOperator, ObjectType, ConflictAction, MergeAction};
+use crate::formatter::{format_type, format_expression};
+use crate::rewrite::{for_each_select_mut, resolve_select_references};
+use crate::analysis::{from_names, table_uses};
+
+//the longest VARCHAR and the most DECIMAL digits any of the dialects accepts (PostgreSQL for both)
+const MAX_VARCHAR_LENGTH: usize = 10_485_760;
+const MAX_DECIMAL_PRECISION: usize = 1000;
+
+//functions that compute one value from the rows of a group, names in lower case
+const AGGREGATE_FUNCTIONS: &[&str] = &[
+    "count", "sum", "avg", "min", "max", "any_value", "array_agg", "string_agg", "group_concat", "listagg",
+    "bool_and", "bool_or", "every", "json_agg", "jsonb_agg", "stddev", "stddev_pop", "stddev_samp",
+    "variance", "var_pop", "var_samp", "median", "count_if", "countif", "grouping",
+];
+
+/// What kind of problem a diagnostic is about, for tools that handle some of them differently.
+/// 1. `UnknownColumn` – A name in a `CHECK` that is not a column of the table, or, checked against a schema, a column that none of the tables it can belong to has.
+/// 2. `ForeignColumn` – A `CHECK` using a column qualified with the name of another table.
+/// 3. `NonBooleanCheck` – A column used as a `CHECK` condition that is not a `BOOL`.
+/// 4. `DuplicateColumn` – A column defined twice in one `CREATE TABLE` (names compared case-insensitively).
+/// 5. `MultiplePrimaryKeys` – A `PRIMARY KEY` after the first one of a table.
+/// 6. `InvalidTypeLength` – `VARCHAR(0)`, `DECIMAL(0)`, or a length or precision beyond what any dialect accepts.
+/// 7. `UngroupedColumn` – A column in the select list, `HAVING` or `ORDER BY` of a grouped query that is neither grouped by nor inside an aggregate.
+/// 8. `UnknownTable` – A table that is not in the schema the statement is checked against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticKind {
+    UnknownColumn,
+    ForeignColumn,
+    NonBooleanCheck,
+    DuplicateColumn,
+    MultiplePrimaryKeys,
+    InvalidTypeLength,
+    UngroupedColumn,
+    UnknownTable,
+}
+
+/// A problem the semantic analyzer found in a statement that parses, with the location of the offending part (an identifier or keyword where there is one).
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.span.line, self.span.column, self.message)
+    }
+}
+
+//check one parsed statement with the rules of the default dialect, see `analyze_with_dialect`
+pub fn analyze(stmt: &Statement, tokens: &[SpannedToken]) -> Vec<Diagnostic> {
+    analyze_with_dialect(stmt, tokens, Dialect::default())
+}
+
+/// Checks one parsed statement. `tokens` are the tokens the statement was parsed from, with their locations and without comments, the spans of the diagnostics come from them.
+///
+/// For a `CREATE TABLE`: column names have to be unique, there can be one `PRIMARY KEY`, and `VARCHAR` lengths and `DECIMAL` precisions have to be at least 1 and within what databases accept. `CHECK` constraints may only refer to columns of the table being created, by name or qualified with the table's name, and have to be boolean now that the types of those columns are known: `CHECK (age)` passes the parser but not this check when `age` is an `INT`.
+///
+/// For every `SELECT`, subqueries included, that is grouped (it has a `GROUP BY` or a `HAVING`, or uses an aggregate like `count(*)`): the select list, `HAVING` and `ORDER BY` may only use columns outside of aggregates when they are grouped by, `SELECT name, count(*) FROM users GROUP BY id` names `name`. Positions and aliases in `GROUP BY` and `ORDER BY` count as the columns they refer to. Without a schema a column that depends on a grouped primary key, which PostgreSQL accepts, is reported as well. Dialects that let a grouped query pick a value for any column (MySQL) skip this check.
+pub fn analyze_with_dialect(stmt: &Statement, tokens: &[SpannedToken], dialect: Dialect) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if let Statement::CreateTable { table_name, column_list, .. } = stmt {
+        check_create_table(table_name, column_list, tokens, &mut diagnostics);
+    }
+    if dialect.requires_grouped_columns() {
+        //a copy with positions and aliases replaced by what they stand for, a reference that does not resolve is left as written
+        let mut resolved = stmt.clone();
+        let _ = resolve_select_references(&mut resolved);
+        for_each_select_mut(&mut resolved, &mut |select| check_grouping(select, tokens, &mut diagnostics));
+    }
+    diagnostics
+}
+
+//the columns of the AST and the comma separated definitions in the tokens come in the same order
+fn check_create_table(table_name: &str, column_list: &[TableColumn], tokens: &[SpannedToken], diagnostics: &mut Vec<Diagnostic>) {
+    let mut primary_key: Option<&str> = None;
+    for (i, (column, column_tokens)) in column_list.iter().zip(column_token_ranges(tokens)).enumerate() {
+        let name_span = column_tokens[0].span;
+        if column_list[..i].iter().any(|earlier| earlier.column_name.eq_ignore_ascii_case(&column.column_name)) {
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::DuplicateColumn,
+                message: format!("Column {} is defined twice in table {}", column.column_name, table_name),
+                span: name_span,
+            });
+        }
+
+        let invalid = match column.column_type {
+            DBType::Varchar(length) if length == 0 || length > MAX_VARCHAR_LENGTH => Some(("VARCHAR length", length, MAX_VARCHAR_LENGTH)),
+            DBType::Char(length) if length == 0 || length > MAX_VARCHAR_LENGTH => Some(("CHAR length", length, MAX_VARCHAR_LENGTH)),
+            DBType::Decimal(precision, _) if precision == 0 || precision > MAX_DECIMAL_PRECISION => Some(("DECIMAL precision", precision, MAX_DECIMAL_PRECISION)),
+            _ => None,
+        };
+        if let Some((what, length, max)) = invalid {
+            //the type comes right after the column name
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::InvalidTypeLength,
+                message: format!("{} of column {} is {}, it has to be between 1 and {}", what, column.column_name, length, max),
+                span: column_tokens.get(1).map_or(name_span, |spanned| spanned.span),
+            });
+        }
+
+        //one PRIMARY token for every PRIMARY KEY constraint of the column
+        for primary in column_tokens.iter().filter(|spanned| spanned.token == Token::Keyword(Keyword::Primary)) {
+            match primary_key {
+                Some(first) => diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::MultiplePrimaryKeys,
+                    message: format!("Table {} has more than one PRIMARY KEY, the first one is on column {}", table_name, first),
+                    span: primary.span,
+                }),
+                None => primary_key = Some(&column.column_name),
+            }
+        }
+
+        let checks = column.constraints.iter().filter_map(|constraint| match constraint {
+            Constraint::Check(expr) => Some(expr),
+            _ => None,
+        });
+        for (expr, check_tokens) in checks.zip(check_token_ranges(column_tokens)) {
+            check_condition(table_name, column_list, expr, check_tokens, diagnostics);
+        }
+    }
+}
+
+//a CHECK may only use the columns of its table, and a column as a condition has to be a BOOL
+fn check_condition(table_name: &str, column_list: &[TableColumn], expr: &Expression, check_tokens: &[SpannedToken], diagnostics: &mut Vec<Diagnostic>) {
+    let column_type = |name: &str| column_list.iter().find(|column| column.column_name.eq_ignore_ascii_case(name)).map(|column| &column.column_type);
+
+    let mut references = Vec::new();
+    column_references(expr, &mut references);
+    for reference in references {
+        let Some(name) = column_name(reference) else { continue };
+        let qualifier = match reference {
+            Expression::CompoundIdentifier(parts) => Some(&parts[parts.len() - 2]),
+            _ => None,
+        };
+        match qualifier {
+            Some(qualifier) if !qualifier.eq_ignore_ascii_case(table_name) => diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::ForeignColumn,
+                message: format!("CHECK of table {} refers to {}.{}, a CHECK can only use columns of its own table", table_name, qualifier, name),
+                span: name_span(check_tokens, qualifier),
+            }),
+            _ if column_type(name).is_none() => diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::UnknownColumn,
+                message: format!("Column {} in CHECK is not a column of table {}", name, table_name),
+                span: name_span(check_tokens, name),
+            }),
+            _ => {}
+        }
+    }
+
+    if let Some((name, column_type)) = non_boolean_column(expr, &column_type) {
+        diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::NonBooleanCheck,
+            message: format!("Expected a boolean condition in CHECK, found column {} of type {}", name, format_type(column_type)),
+            span: name_span(check_tokens, name),
+        });
+    }
+}
+
+//the columns a grouped query uses outside of aggregates have to be grouped by
+fn check_grouping(select: &Statement, tokens: &[SpannedToken], diagnostics: &mut Vec<Diagnostic>) {
+    let Statement::Select { columns, from, group_by, having, orderby, .. } = select else { return };
+    let used = || columns.iter().chain(having).chain(orderby);
+    if group_by.is_empty() && having.is_none() && !used().any(has_aggregate) {
+        return;
+    }
+
+    //aliases of the select list stand for grouped values in HAVING where dialects accept them there
+    let aliases: Vec<Expression> = columns.iter().filter_map(|column| match column {
+        Expression::Alias { alias, .. } => Some(Expression::Identifier(alias.clone())),
+        _ => None,
+    }).collect();
+    let mut grouped: Vec<&Expression> = aliases.iter().collect();
+    for element in group_by {
+        match element {
+            GroupingElement::Expression(expr) => grouped.push(expr),
+            GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => grouped.extend(sets.iter().flatten()),
+        }
+    }
+    let mut qualifiers = Vec::new();
+    for table in from {
+        from_names(table, &mut qualifiers);
+    }
+
+    let mut ungrouped = Vec::new();
+    for expr in used() {
+        ungrouped_columns(expr, &grouped, &mut ungrouped);
+    }
+    for expr in ungrouped {
+        //a column of an outer query is a constant for this one
+        if let Expression::CompoundIdentifier(parts) = expr {
+            if !qualifiers.iter().any(|qualifier| qualifier.eq_ignore_ascii_case(&parts[parts.len() - 2])) {
+                continue;
+            }
+        }
+        let (message, span) = match column_name(expr) {
+            Some(name) => (format!("Column {} must appear in GROUP BY or be used in an aggregate function", format_expression(expr)), name_span(tokens, name)),
+            None => {
+                let star = tokens.iter().find(|spanned| spanned.token == Token::Star).unwrap_or(&tokens[0]);
+                ("* in a grouped query selects columns that are not in GROUP BY, name the grouped columns instead".to_string(), star.span)
+            }
+        };
+        let diagnostic = Diagnostic { kind: DiagnosticKind::UngroupedColumn, message, span };
+        //once per column, a column used twice is one mistake
+        if !diagnostics.contains(&diagnostic) {
+            diagnostics.push(diagnostic);
+        }
+    }
+}
+
+//column references (and `*`) of an expression that are neither inside an aggregate nor part of a grouped expression,
+//subqueries left out as they are checked on their own
+fn ungrouped_columns<'a>(expr: &'a Expression, grouped: &[&Expression], out: &mut Vec<&'a Expression>) {
+    if grouped.iter().any(|grouped| same_column(grouped, expr)) {
+        return;
+    }
+    match expr {
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) | Expression::Wildcard => out.push(expr),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            ungrouped_columns(left_operand, grouped, out);
+            ungrouped_columns(right_operand, grouped, out);
+        }
+        Expression::UnaryOperation { operand, .. } => ungrouped_columns(operand, grouped, out),
+        Expression::Alias { expr, .. } => ungrouped_columns(expr, grouped, out),
+        Expression::Function { over: None, name, .. } if is_aggregate(name) => {}
+        //window functions run after grouping, their arguments and windows are values of the groups
+        Expression::Function { args, over, .. } => {
+            for arg in args {
+                ungrouped_columns(arg, grouped, out);
+            }
+            if let Some(window) = over {
+                for expr in window.partition_by.iter().chain(&window.order_by) {
+                    ungrouped_columns(expr, grouped, out);
+                }
+            }
+        }
+        Expression::Subquery(_) | Expression::Exists { .. } | Expression::Literal(_) | Expression::Placeholder(_) => {}
+    }
+}
+
+//whether an expression calls an aggregate function outside of a window and of subqueries
+fn has_aggregate(expr: &Expression) -> bool {
+    match expr {
+        Expression::Function { name, args, over } => {
+            (over.is_none() && is_aggregate(name))
+                || args.iter().any(has_aggregate)
+                || over.as_ref().is_some_and(|window| window.partition_by.iter().chain(&window.order_by).any(has_aggregate))
+        }
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            has_aggregate(left_operand) || has_aggregate(right_operand)
+        }
+        Expression::UnaryOperation { operand, .. } => has_aggregate(operand),
+        Expression::Alias { expr, .. } => has_aggregate(expr),
+        _ => false,
+    }
+}
+
+//helper, case-insensitive lookup in AGGREGATE_FUNCTIONS
+fn is_aggregate(name: &str) -> bool {
+    AGGREGATE_FUNCTIONS.iter().any(|aggregate| aggregate.eq_ignore_ascii_case(name))
+}
+
+//helper, whether a grouped expression covers an expression, `u.id` and `id` are taken to be the same column
+fn same_column(grouped: &Expression, expr: &Expression) -> bool {
+    match (grouped, expr) {
+        (Expression::Identifier(_) | Expression::CompoundIdentifier(_), Expression::Identifier(_) | Expression::CompoundIdentifier(_)) => {
+            let parts = |expr: &Expression| match expr {
+                Expression::CompoundIdentifier(parts) => parts.iter().map(|part| part.to_lowercase()).collect(),
+                other => vec![column_name(other).unwrap_or_default().to_lowercase()],
+            };
+            let (a, b) = (parts(grouped), parts(expr));
+            a.ends_with(&b) || b.ends_with(&a)
+        }
+        _ => grouped == expr,
+    }
+}
+
+/// Checks a statement against a schema, the `CREATE TABLE` statements of the tables that exist (what `migration::squash` makes of a script), for names that do not refer to anything: tables that are not in the schema, and columns of a query, of the column list of an `INSERT` or of the condition of a `DELETE` that no table they can belong to has.
+///
+/// A qualified column is looked up in the table its qualifier names, an unqualified one in the tables of its query and of the queries around it, and the aliases of the select list count as columns. Columns are not checked against a `FROM` item whose columns are not known: a derived table, a common table expression, a table function, a `PIVOT` or `UNPIVOT`, a table created with `AS SELECT`, or a table that is not in the schema.
+pub fn check_schema(stmt: &Statement, tokens: &[SpannedToken], schema: &[Statement]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let created = match stmt {
+        Statement::CreateTable { table_name, .. } => Some(table_name),
+        _ => None,
+    };
+    //DROP ... IF EXISTS is written for tables that may not be there, views and indexes are not part of the schema
+    let dropped = matches!(stmt, Statement::Drop { object_type, if_exists, .. } if *if_exists || *object_type != ObjectType::Table);
+    let mut unknown: Vec<String> = Vec::new();
+    for table in table_uses(stmt) {
+        let known = schema_columns(schema, &table.name).is_some() || created.is_some_and(|created| created.eq_ignore_ascii_case(&table.name));
+        if dropped || known || unknown.iter().any(|name| name.eq_ignore_ascii_case(&table.name)) {
+            continue;
+        }
+        diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::UnknownTable,
+            message: format!("Table {} is not in the schema", table.name),
+            span: name_span(tokens, &table.name),
+        });
+        unknown.push(table.name);
+    }
+
+    let mut scopes = Vec::new();
+    match stmt {
+        Statement::Select { .. } | Statement::SetOperation { .. } => check_query_columns(stmt, schema, &mut scopes, tokens, &mut diagnostics),
+        Statement::CreateTable { query: Some(query), .. } => check_query_columns(query, schema, &mut scopes, tokens, &mut diagnostics),
+        Statement::Insert { table_name, columns, source, on_conflict } => {
+            let mut names: Vec<&String> = columns.iter().flatten().collect();
+            if let Some(on_conflict) = on_conflict {
+                names.extend(&on_conflict.target);
+                if let ConflictAction::Update { assignments, .. } = &on_conflict.action {
+                    names.extend(assignments.iter().map(|assignment| &assignment.column));
+                }
+            }
+            if let Some(Some(table_columns)) = schema_columns(schema, table_name) {
+                for name in names.into_iter().filter(|name| !has_column(table_columns, name)) {
+                    diagnostics.push(unknown_column(name, table_name, tokens));
+                }
+            }
+            match source {
+                //VALUES has no FROM clause, only its subqueries have columns to check
+                InsertSource::Values(rows) => {
+                    for expr in rows.iter().flatten() {
+                        check_expression_columns(expr, schema, &mut scopes, &[], tokens, &mut diagnostics);
+                    }
+                }
+                InsertSource::Query(query) => check_query_columns(query, schema, &mut scopes, tokens, &mut diagnostics),
+            }
+            //DO UPDATE sees the row in the table, and as `excluded` the row that was to be inserted into it
+            if let Some(on_conflict) = on_conflict {
+                let columns = schema_columns(schema, table_name).flatten();
+                scopes.push(vec![
+                    Visible { qualifier: table_name, name: table_name, columns },
+                    Visible { qualifier: "excluded", name: table_name, columns },
+                ]);
+                for expr in on_conflict.expressions() {
+                    check_expression_columns(expr, schema, &mut scopes, &[], tokens, &mut diagnostics);
+                }
+            }
+        }
+        Statement::Delete { table, where_clause: Some(expr) } => {
+            scopes.push(vec![Visible { qualifier: table, name: table, columns: schema_columns(schema, table).flatten() }]);
+            check_expression_columns(expr, schema, &mut scopes, &[], tokens, &mut diagnostics);
+        }
+        //the columns the arms set or insert are columns of the target, the condition and the values see the target and the source
+        Statement::Merge { table_name, alias, source, on, clauses } => {
+            let columns = schema_columns(schema, table_name).flatten();
+            let names = clauses.iter().flat_map(|clause| match &clause.action {
+                MergeAction::Update { assignments } => assignments.iter().map(|assignment| &assignment.column).collect(),
+                MergeAction::Insert { columns, .. } => columns.iter().flatten().collect(),
+                MergeAction::Delete | MergeAction::Nothing => Vec::new(),
+            });
+            if let Some(table_columns) = columns {
+                for name in names.filter(|name| !has_column(table_columns, name)) {
+                    diagnostics.push(unknown_column(name, table_name, tokens));
+                }
+            }
+            check_table_columns(source, schema, &mut scopes, tokens, &mut diagnostics);
+            let mut scope = vec![Visible { qualifier: alias.as_ref().unwrap_or(table_name), name: table_name, columns }];
+            visible_names(source, schema, &mut scope);
+            scopes.push(scope);
+            for expr in std::iter::once(on).chain(clauses.iter().flat_map(|clause| clause.expressions())) {
+                check_expression_columns(expr, schema, &mut scopes, &[], tokens, &mut diagnostics);
+            }
+        }
+        _ => {}
+    }
+    diagnostics
+}
+
+//one name a FROM clause makes visible to the columns of a query: the qualifier, the table (or alias) it stands for
+//and the columns behind it when they are known
+struct Visible<'a> {
+    qualifier: &'a str,
+    name: &'a str,
+    columns: Option<&'a [TableColumn]>,
+}
+
+//helper, the columns of a table of the schema, `Some(None)` for a table created from a query and `None` for one that is not in it
+fn schema_columns<'a>(schema: &'a [Statement], name: &str) -> Option<Option<&'a [TableColumn]>> {
+    schema.iter().find_map(|stmt| match stmt {
+        Statement::CreateTable { table_name, column_list, query, .. } if table_name.eq_ignore_ascii_case(name) => {
+            Some(if query.is_some() { None } else { Some(column_list.as_slice()) })
+        }
+        _ => None,
+    })
+}
+
+//helper, case-insensitive lookup of a column
+fn has_column(columns: &[TableColumn], name: &str) -> bool {
+    columns.iter().any(|column| column.column_name.eq_ignore_ascii_case(name))
+}
+
+//helper, the diagnostic for a column that the one table it can belong to does not have
+fn unknown_column(name: &str, table: &str, tokens: &[SpannedToken]) -> Diagnostic {
+    Diagnostic {
+        kind: DiagnosticKind::UnknownColumn,
+        message: format!("Column {} is not a column of table {}", name, table),
+        span: name_span(tokens, name),
+    }
+}
+
+fn visible_names<'a>(table: &'a TableReference, schema: &'a [Statement], out: &mut Vec<Visible<'a>>) {
+    match table {
+        TableReference::Table { name, alias, .. } => out.push(Visible { qualifier: alias.as_ref().unwrap_or(name), name, columns: schema_columns(schema, name).flatten() }),
+        //without an alias the columns of a derived table can still be used unqualified
+        TableReference::Derived { alias, .. } => {
+            let alias = alias.as_deref().unwrap_or_default();
+            out.push(Visible { qualifier: alias, name: alias, columns: None });
+        }
+        TableReference::Function { name, alias, .. } => out.push(Visible { qualifier: alias.as_ref().unwrap_or(name), name, columns: None }),
+        TableReference::Pivot { table, alias, .. } | TableReference::Unpivot { table, alias, .. } => {
+            visible_names(table, schema, out);
+            let alias = alias.as_deref().unwrap_or_default();
+            out.push(Visible { qualifier: alias, name: alias, columns: None });
+        }
+        TableReference::Join { left, right, .. } => {
+            visible_names(left, schema, out);
+            visible_names(right, schema, out);
+        }
+    }
+}
+
+//the columns of one query against the tables of its FROM clause and of the queries around it, subqueries included
+fn check_query_columns<'a>(select: &'a Statement, schema: &'a [Statement], scopes: &mut Vec<Vec<Visible<'a>>>, tokens: &[SpannedToken], diagnostics: &mut Vec<Diagnostic>) {
+    //ORDER BY of a set operation names the columns of its result, not of a table, so only the queries it combines are checked
+    if let Statement::SetOperation { with, left, right, .. } = select {
+        for cte in with.iter().flat_map(|with| &with.ctes) {
+            check_query_columns(&cte.query, schema, scopes, tokens, diagnostics);
+        }
+        check_query_columns(left, schema, scopes, tokens, diagnostics);
+        check_query_columns(right, schema, scopes, tokens, diagnostics);
+        return;
+    }
+    let Statement::Select { with, columns, from, r#where, group_by, having, qualify, orderby, limit, .. } = select else { return };
+    for cte in with.iter().flat_map(|with| &with.ctes) {
+        check_query_columns(&cte.query, schema, scopes, tokens, diagnostics);
+    }
+    let mut scope = Vec::new();
+    for table in from {
+        visible_names(table, schema, &mut scope);
+    }
+    scopes.push(scope);
+    let aliases: Vec<&str> = columns.iter().filter_map(|column| match column {
+        Expression::Alias { alias, .. } => Some(alias.as_str()),
+        _ => None,
+    }).collect();
+    for table in from {
+        check_table_columns(table, schema, scopes, tokens, diagnostics);
+    }
+    let grouping = group_by.iter().flat_map(|element| match element {
+        GroupingElement::Expression(expr) => vec![expr],
+        GroupingElement::Rollup(sets) | GroupingElement::Cube(sets) | GroupingElement::GroupingSets(sets) => sets.iter().flatten().collect(),
+    });
+    let clauses = r#where.iter().chain(having).chain(qualify).chain(orderby).chain(limit);
+    for expr in columns.iter().chain(grouping).chain(clauses) {
+        check_expression_columns(expr, schema, scopes, &aliases, tokens, diagnostics);
+    }
+    scopes.pop();
+}
+
+//the expressions of a FROM item, with every item of the FROM clause visible, which is more than a database allows
+//outside of LATERAL but never reports a column that exists
+fn check_table_columns<'a>(table: &'a TableReference, schema: &'a [Statement], scopes: &mut Vec<Vec<Visible<'a>>>, tokens: &[SpannedToken], diagnostics: &mut Vec<Diagnostic>) {
+    match table {
+        TableReference::Table { .. } => {}
+        TableReference::Derived { subquery, .. } => check_query_columns(subquery, schema, scopes, tokens, diagnostics),
+        TableReference::Function { args, .. } => {
+            for arg in args {
+                check_expression_columns(arg, schema, scopes, &[], tokens, diagnostics);
+            }
+        }
+        TableReference::Pivot { table, pivot, .. } => {
+            check_table_columns(table, schema, scopes, tokens, diagnostics);
+            for expr in std::iter::once(&pivot.aggregate).chain(std::iter::once(&pivot.column)).chain(&pivot.values) {
+                check_expression_columns(expr, schema, scopes, &[], tokens, diagnostics);
+            }
+        }
+        TableReference::Unpivot { table, .. } => check_table_columns(table, schema, scopes, tokens, diagnostics),
+        TableReference::Join { left, right, on, .. } => {
+            check_table_columns(left, schema, scopes, tokens, diagnostics);
+            check_table_columns(right, schema, scopes, tokens, diagnostics);
+            if let Some(on) = on {
+                check_expression_columns(on, schema, scopes, &[], tokens, diagnostics);
+            }
+        }
+    }
+}
+
+fn check_expression_columns<'a>(expr: &'a Expression, schema: &'a [Statement], scopes: &mut Vec<Vec<Visible<'a>>>, aliases: &[&str], tokens: &[SpannedToken], diagnostics: &mut Vec<Diagnostic>) {
+    match expr {
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) => {
+            if let Some(diagnostic) = check_column(expr, scopes, aliases, tokens) {
+                diagnostics.push(diagnostic);
+            }
+        }
+        Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => check_query_columns(subquery, schema, scopes, tokens, diagnostics),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            check_expression_columns(left_operand, schema, scopes, aliases, tokens, diagnostics);
+            check_expression_columns(right_operand, schema, scopes, aliases, tokens, diagnostics);
+        }
+        Expression::UnaryOperation { operand, .. } => check_expression_columns(operand, schema, scopes, aliases, tokens, diagnostics),
+        Expression::Alias { expr, .. } => check_expression_columns(expr, schema, scopes, aliases, tokens, diagnostics),
+        Expression::Function { args, over, .. } => {
+            for arg in args {
+                check_expression_columns(arg, schema, scopes, aliases, tokens, diagnostics);
+            }
+            if let Some(window) = over {
+                for expr in window.partition_by.iter().chain(&window.order_by) {
+                    check_expression_columns(expr, schema, scopes, aliases, tokens, diagnostics);
+                }
+            }
+        }
+        Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) => {}
+    }
+}
+
+//one column reference, looked up from the innermost query outwards
+fn check_column(expr: &Expression, scopes: &[Vec<Visible>], aliases: &[&str], tokens: &[SpannedToken]) -> Option<Diagnostic> {
+    let name = column_name(expr)?;
+    match expr {
+        Expression::CompoundIdentifier(parts) => {
+            //a qualifier that no FROM item has may name a schema or something else the check does not know about
+            let qualifier = &parts[parts.len() - 2];
+            let visible = scopes.iter().rev().flatten().find(|visible| visible.qualifier.eq_ignore_ascii_case(qualifier))?;
+            let columns = visible.columns?;
+            (!has_column(columns, name)).then(|| unknown_column(name, visible.name, tokens))
+        }
+        _ => {
+            let visible: Vec<&Visible> = scopes.iter().flatten().collect();
+            if visible.is_empty() || aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name)) {
+                return None;
+            }
+            let mut tables = Vec::new();
+            for visible in visible {
+                match visible.columns {
+                    //a table joined to itself is named once
+                    Some(columns) if !has_column(columns, name) => if !tables.contains(&visible.name) { tables.push(visible.name) },
+                    _ => return None,
+                }
+            }
+            match tables.as_slice() {
+                [table] => Some(unknown_column(name, table, tokens)),
+                _ => Some(Diagnostic {
+                    kind: DiagnosticKind::UnknownColumn,
+                    message: format!("Column {} is not a column of any of the tables {}", name, tables.join(", ")),
+                    span: name_span(tokens, name),
+                }),
+            }
+        }
+    }
+}
+
+//the tokens of every column definition of a CREATE TABLE, the items between the outer parentheses
+fn column_token_ranges(tokens: &[SpannedToken]) -> Vec<&[SpannedToken]> {
+    let mut ranges = Vec::new();
+    let Some(open) = tokens.iter().position(|spanned| spanned.token == Token::LeftParentheses) else {
+        return ranges;
+    };
+    let mut depth = 0;
+    let mut start = open + 1;
+    for (i, spanned) in tokens.iter().enumerate().skip(open) {
+        match spanned.token {
+            Token::LeftParentheses => depth += 1,
+            Token::RightParentheses => {
+                depth -= 1;
+                if depth == 0 {
+                    ranges.push(&tokens[start..i]);
+                    break;
+                }
+            }
+            Token::Comma if depth == 1 => {
+                ranges.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+//the tokens between the parentheses of every CHECK, in order
+fn check_token_ranges(tokens: &[SpannedToken]) -> Vec<&[SpannedToken]> {
+    let mut ranges = Vec::new();
+    for (i, spanned) in tokens.iter().enumerate() {
+        if spanned.token != Token::Keyword(Keyword::Check) || tokens.get(i + 1).map(|next| &next.token) != Some(&Token::LeftParentheses) {
+            continue;
+        }
+        let mut depth = 0;
+        for (j, inner) in tokens.iter().enumerate().skip(i + 1) {
+            match inner.token {
+                Token::LeftParentheses => depth += 1,
+                Token::RightParentheses => {
+                    depth -= 1;
+                    if depth == 0 {
+                        ranges.push(&tokens[i + 2..j]);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    ranges
+}
+
+//names used as values in an expression, subqueries left out as their names belong to their own FROM clause
+fn column_references<'a>(expr: &'a Expression, references: &mut Vec<&'a Expression>) {
+    match expr {
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) => references.push(expr),
+        Expression::BinaryOperation { left_operand, right_operand, .. } | Expression::IsDistinctFrom { left_operand, right_operand, .. } => {
+            column_references(left_operand, references);
+            column_references(right_operand, references);
+        }
+        Expression::UnaryOperation { operand, .. } => column_references(operand, references),
+        Expression::Alias { expr, .. } => column_references(expr, references),
+        Expression::Function { args, over, .. } => {
+            for arg in args {
+                column_references(arg, references);
+            }
+            if let Some(window) = over {
+                for expr in window.partition_by.iter().chain(&window.order_by) {
+                    column_references(expr, references);
+                }
+            }
+        }
+        Expression::Subquery(_) | Expression::Exists { .. } | Expression::Wildcard | Expression::Literal(_) | Expression::Placeholder(_) => {}
+    }
+}
+
+//a column used as a condition that is not a BOOL column, `CHECK (age)` or `CHECK (active AND age)`
+//the parser has already turned down conditions that are not boolean whatever the types of the columns
+fn non_boolean_column<'a, 'b>(expr: &'a Expression, column_type: &dyn Fn(&str) -> Option<&'b DBType>) -> Option<(&'a str, &'b DBType)> {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator: BinaryOperator::And | BinaryOperator::Or, right_operand } => {
+            non_boolean_column(left_operand, column_type).or_else(|| non_boolean_column(right_operand, column_type))
+        }
+        Expression::UnaryOperation { operand, operator: UnaryOperator::Not } => non_boolean_column(operand, column_type),
+        _ => {
+            let name = column_name(expr)?;
+            column_type(name).filter(|ty| **ty != DBType::Bool).map(|ty| (name, ty))
+        }
+    }
+}
+
+//helper, the column a name refers to, the last part of a qualified one
+fn column_name(expr: &Expression) -> Option<&str> {
+    match expr {
+        Expression::Identifier(name) => Some(name),
+        Expression::CompoundIdentifier(parts) => parts.last().map(|name| name.as_str()),
+        _ => None,
+    }
+}
+
+//helper, the span of the first token in `tokens` that is the name
+//the name was parsed from these tokens, the first token is only a fallback
+fn name_span(tokens: &[SpannedToken], name: &str) -> Span {
+    let found = tokens.iter().find(|spanned| match &spanned.token {
+        Token::Identifier(text) => text == name,
+        Token::Keyword(keyword) => !keyword.is_reserved() && keyword.as_str().eq_ignore_ascii_case(name),
+        _ => false,
+    });
+    found.unwrap_or(&tokens[0]).span
+}
@@ -0,0 +1,79 @@
+//the schema an interactive session builds up from the statements typed into it, kept in a catalog file between sessions
+use crate::tokenizer::{Tokenizer, SpannedToken};
+use crate::parser::Parser;
+use crate::dialect::Dialect;
+use crate::statement::{Statement, ShowObject};
+use crate::engine::{ResultSet, table_list, describe};
+use crate::migration::squash;
+use crate::semantic::{check_schema, Diagnostic};
+use crate::completion::{parse_prefix_with_dialect, suggestions};
+use crate::formatter::format_statements;
+
+/// The tables a session knows about, one `CREATE TABLE` per table as `migration::squash` leaves them. The DDL of the session changes them, and statements are checked and completed against them.
+#[derive(Debug, Default, PartialEq)]
+pub struct Session {
+    schema: Vec<Statement>,
+}
+
+impl Session {
+    /// Reads a catalog, the SQL text `to_sql` writes. Any DDL script will do, it is squashed into its schema.
+    pub fn from_sql(sql: &str, dialect: Dialect) -> Result<Session, String> {
+        let tokens = Tokenizer::with_dialect(sql, dialect).tokenize().map_err(|err| err.to_string())?;
+        let stmts = Parser::with_dialect(tokens, dialect).parse_statements()?;
+        Ok(Session { schema: squash(&stmts)? })
+    }
+
+    /// The catalog as SQL text, the `CREATE TABLE` statements of the schema sorted by table name.
+    pub fn to_sql(&self) -> String {
+        if self.schema.is_empty() {
+            return String::new();
+        }
+        format!("{}\n", format_statements(&self.schema))
+    }
+
+    pub fn schema(&self) -> &[Statement] {
+        &self.schema
+    }
+
+    /// Applies a statement to the schema: `CREATE TABLE`, `ALTER TABLE`, `RENAME TABLE` and `DROP TABLE` change it, other statements leave it alone. Returns whether the schema changed, or the error `migration::squash` reports for a statement that does not fit the schema, such as altering a table that does not exist, and then leaves the schema as it was.
+    pub fn apply(&mut self, stmt: &Statement) -> Result<bool, String> {
+        let mut stmts = self.schema.clone();
+        stmts.push(stmt.clone());
+        let schema = squash(&stmts)?;
+        let changed = schema != self.schema;
+        self.schema = schema;
+        Ok(changed)
+    }
+
+    /// Answers `SHOW TABLES` and `DESCRIBE` from the schema, the same rows `engine::Engine` returns for them; `None` for any other statement. A session keeps a single database, so `SHOW DATABASES` is an error.
+    pub fn introspect(&self, stmt: &Statement) -> Option<Result<ResultSet, String>> {
+        let tables = self.schema.iter().filter_map(|stmt| match stmt {
+            Statement::CreateTable { table_name, column_list, .. } => Some((table_name, column_list)),
+            _ => None,
+        });
+        let result = match stmt {
+            Statement::Show { object: ShowObject::Tables } => Ok(table_list(tables.map(|(name, _)| name.as_str()))),
+            Statement::Show { object: ShowObject::Databases } => Err("A session has a single database, SHOW DATABASES is not supported".to_string()),
+            Statement::Describe { table_name } => {
+                let mut tables = tables;
+                match tables.find(|(name, _)| name.eq_ignore_ascii_case(table_name)) {
+                    Some((_, columns)) => Ok(describe(columns)),
+                    None => Err(format!("Table {} does not exist", table_name)),
+                }
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Checks a statement against the schema, see `semantic::check_schema`. `tokens` are the tokens the statement was parsed from.
+    pub fn check(&self, stmt: &Statement, tokens: &[SpannedToken]) -> Vec<Diagnostic> {
+        check_schema(stmt, tokens, &self.schema)
+    }
+
+    /// The words that can follow `sql`, the text before the cursor, with names taken from the schema, see `completion::suggestions`.
+    pub fn complete(&self, sql: &str, dialect: Dialect) -> Vec<String> {
+        let (partial, next) = parse_prefix_with_dialect(sql, dialect);
+        suggestions(&partial, &next, &self.schema)
+    }
+}
@@ -0,0 +1,141 @@
+//parsed statements shared between threads: the AST is plain owned data, without references or reference counts of its
+//own, so a parse can be kept behind an `Arc` and read by any number of workers at once
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use crate::tokenizer::{Tokenizer, SpannedToken};
+use crate::parser::{Parser, ParseError};
+use crate::dialect::Dialect;
+use crate::statement::{Statement, Expression, DBType};
+#[cfg(feature = "analyzer")]
+use crate::prepared::PreparedStatement;
+#[cfg(feature = "engine")]
+use crate::engine::ResultSet;
+
+//what can be shared between threads, checked when the crate is compiled: a type that stops being `Send + Sync`
+//(an `Rc` or a `Cell` somewhere inside it) fails the build here
+const _: fn() = || {
+    fn shared<T: Send + Sync + 'static>() {}
+    shared::<Statement>();
+    shared::<Expression>();
+    shared::<DBType>();
+    shared::<SpannedToken>();
+    #[cfg(feature = "analyzer")]
+    shared::<PreparedStatement>();
+    #[cfg(feature = "engine")]
+    shared::<ResultSet>();
+    shared::<StatementCache>();
+};
+
+/// Parses a script into statements that can be handed to other threads, cloning the `Arc` rather than the statements.
+pub fn parse_shared(sql: &str, dialect: Dialect) -> Result<Arc<[Statement]>, ParseError> {
+    let tokens = Tokenizer::with_dialect(sql, dialect).tokenize().map_err(|err| err.to_string())?;
+    Ok(Parser::with_dialect(tokens, dialect).parse_statements()?.into())
+}
+
+/// How many scripts `StatementCache::new` keeps.
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Parsed scripts by their SQL text, for servers that see the same statements over and over: a text is parsed the first time it is asked for, and every later lookup returns the same `Arc`. The cache holds at most `capacity` scripts, when it is full the one asked for longest ago makes room, so a server that gets SQL with its values written inline cannot grow it without end. The cache is shared by reference (or in an `Arc` of its own) between threads; it is locked while the map is read or filled, not while a script is parsed. Scripts that fail to parse are not kept.
+#[derive(Debug)]
+pub struct StatementCache {
+    dialect: Dialect,
+    capacity: usize,
+    parsed: Mutex<Entries>,
+}
+
+//the cached scripts, each with the tick of the lookup that last returned it
+#[derive(Debug, Default)]
+struct Entries {
+    scripts: HashMap<String, (Arc<[Statement]>, u64)>,
+    tick: u64,
+}
+
+impl Default for StatementCache {
+    fn default() -> Self {
+        StatementCache::new(Dialect::default())
+    }
+}
+
+impl StatementCache {
+    pub fn new(dialect: Dialect) -> Self {
+        StatementCache::with_capacity(dialect, DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// A cache that keeps at most `capacity` scripts, none when it is 0.
+    pub fn with_capacity(dialect: Dialect, capacity: usize) -> Self {
+        StatementCache { dialect, capacity, parsed: Mutex::new(Entries::default()) }
+    }
+
+    /// The statements of `sql`, parsed now or taken from the cache.
+    pub fn get(&self, sql: &str) -> Result<Arc<[Statement]>, ParseError> {
+        {
+            let mut parsed = self.parsed.lock().unwrap();
+            parsed.tick += 1;
+            let tick = parsed.tick;
+            if let Some((stmts, used)) = parsed.scripts.get_mut(sql) {
+                *used = tick;
+                return Ok(Arc::clone(stmts));
+            }
+        }
+        let stmts = parse_shared(sql, self.dialect)?;
+        if self.capacity == 0 {
+            return Ok(stmts);
+        }
+        //two threads parsing the same text at once both get the statements of the first to finish
+        let mut parsed = self.parsed.lock().unwrap();
+        parsed.tick += 1;
+        let tick = parsed.tick;
+        if !parsed.scripts.contains_key(sql) && parsed.scripts.len() >= self.capacity {
+            let oldest = parsed.scripts.iter().min_by_key(|(_, (_, used))| *used).map(|(text, _)| text.clone());
+            if let Some(oldest) = oldest {
+                parsed.scripts.remove(&oldest);
+            }
+        }
+        let (stmts, used) = parsed.scripts.entry(sql.to_string()).or_insert((stmts, tick));
+        *used = tick;
+        Ok(Arc::clone(stmts))
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.parsed.lock().unwrap().scripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached script; statements already handed out stay valid.
+    pub fn clear(&self) {
+        self.parsed.lock().unwrap().scripts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_cache_drops_the_script_asked_for_longest_ago() {
+        let cache = StatementCache::with_capacity(Dialect::default(), 2);
+        let first = cache.get("SELECT a FROM t1;").unwrap();
+        let second = cache.get("SELECT a FROM t2;").unwrap();
+        //t1 is asked for again, so t2 is the one to make room for t3
+        assert!(Arc::ptr_eq(&first, &cache.get("SELECT a FROM t1;").unwrap()));
+        cache.get("SELECT a FROM t3;").unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(Arc::ptr_eq(&first, &cache.get("SELECT a FROM t1;").unwrap()));
+        assert!(!Arc::ptr_eq(&second, &cache.get("SELECT a FROM t2;").unwrap()));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let cache = StatementCache::with_capacity(Dialect::default(), 0);
+        cache.get("SELECT a FROM t1;").unwrap();
+        assert!(cache.is_empty());
+    }
+}
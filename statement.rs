@@ -1,17 +1,44 @@
 use std::fmt::{Debug, Display, Formatter};
+use crate::token::Token;
+use crate::tokenizer::Span;
 
 /// The main entity of the whole parser. `Statement` is implemented as an enumeration because adding functionality is as easy as adding an enumeration constant and implementing functionality for that enumeration constant (implementation in the database command interpreter, which is not a part of this project). Parsing any correct `SELECT` or `CREATE`  (or `UPDATE`, `INSERT INTO`, ... hypothetically) statement should be turned into an instance of this enumeration. Ultimately, your main parser function (something like `build_statement(query: &str) -> Statement`) should return this enumeration.
 ///
-/// The `SELECT` statement has four components:
-/// 1. `columns` – A vector of columns from the selected table that the database should return.
-/// 2. `from` – A simple string, containing a table that is being queried (we aren't doing joins because they complicate stuff too much for this project).
-/// 3. `where` – A single expression that is the actual filter for the database query. It is wrapped in an `Option` because not every `SELECT` query contains a filter. The actual name is `r#where` because in Rust, `where` is a reserved keyword, and the prefix `r#` means: interpret this token as a raw string, do not check for keyword matches.
-/// 4. `orderby` – A vector of expressions that define how should the data be ordered. A vector is needed because the data can be ordered by the first column, and then all data that has the same first column can be ordered by the second column, ... Also, the data can be ordered not simply by columns, but by complex expressions as well.
+/// The `SELECT` statement has nine components:
+/// 1. `hints` – Optimizer hints written as a `/*+ ... */` comment right before or after the `SELECT` keyword (see `Hint`). They are only collected when the tokenizer keeps hint comments, otherwise this is empty.
+/// 2. `columns` – A vector of columns from the selected table that the database should return.
+/// 3. `from` – A vector of table references that are being queried, one per comma separated item of the `FROM` clause. A table reference is usually just a table name, but can also be a subquery, a table function or a join of other table references (see `TableReference`).
+/// 4. `where` – A single expression that is the actual filter for the database query. It is wrapped in an `Option` because not every `SELECT` query contains a filter. The actual name is `r#where` because in Rust, `where` is a reserved keyword, and the prefix `r#` means: interpret this token as a raw string, do not check for keyword matches.
+/// 5. `group_by` – A vector of grouping elements, empty when there is no `GROUP BY` clause (see `GroupingElement`).
+/// 6. `having` – A filter applied to the groups, `None` when there is no `HAVING` clause.
+/// 7. `qualify` – A filter applied after window functions are computed (`QUALIFY row_number() OVER (...) = 1`), `None` when there is no `QUALIFY` clause. Only some dialects accept it.
+/// 8. `orderby` – A vector of expressions that define how should the data be ordered. A vector is needed because the data can be ordered by the first column, and then all data that has the same first column can be ordered by the second column, ... Also, the data can be ordered not simply by columns, but by complex expressions as well.
+/// 9. `limit` – The maximum number of rows to return, `None` when there is no `LIMIT` clause.
 ///
 /// The `CREATE TABLE` statement has two components:
 /// 1. `table_name` – A simple string, the name of the table.
 /// 2. `column_list` – A vector of table column types, where each table column contains the definition of one column.
 ///
+/// The `INSERT INTO` statement has three components:
+/// 1. `table_name` – The name of the table the rows go into.
+/// 2. `columns` – The column list after the table name, `None` when there is none and the values go to the columns in table order.
+/// 3. `source` – Where the rows come from, the rows written after `VALUES` or a query, see `InsertSource`.
+///
+/// The `DELETE FROM` statement has two components:
+/// 1. `table` – The name of the table rows are deleted from.
+/// 2. `where_clause` – The filter that picks the rows to delete, `None` when there is no `WHERE` clause and every row goes.
+///
+/// The `DROP TABLE` statement has two components:
+/// 1. `tables` – The names of the tables to drop, one or more, comma separated in the statement.
+/// 2. `if_exists` – Whether `IF EXISTS` was written, which makes dropping a table that does not exist a no-op instead of an error.
+///
+/// The `ALTER TABLE` statement has two components:
+/// 1. `table_name` – The name of the table being changed.
+/// 2. `action` – The change, see `AlterAction`.
+///
+/// The `TRUNCATE [TABLE]` statement has one component:
+/// 1. `table_name` – The name of the table whose rows are all deleted.
+///
 /// Examples:
 ///
 /// ---
@@ -21,13 +48,19 @@ use std::fmt::{Debug, Display, Formatter};
 /// is a `SELECT` statement that,  when parsed, looks like this:
 /// ```rust
 /// Statement::Select {
+///     with: None,
+///     hints: vec![],
 ///     columns: vec![
 /// 		Expression::Identifier("name".to_string()),
 /// 		Expression:Identifier("surname".to_string())
 /// 	],
-///     from: "users".to_string(),
+///     from: vec![TableReference::Table { name: "users".to_string(), alias: None, sample: None }],
 ///     r#where: None,
-///     orderby: vec![]
+///     group_by: vec![],
+///     having: None,
+///     qualify: None,
+///     orderby: vec![],
+///     limit: None
 /// }
 /// ```
 /// ---
@@ -37,17 +70,23 @@ use std::fmt::{Debug, Display, Formatter};
 /// is a `SELECT` statement that,  when parsed, looks like this:
 /// ```rust
 /// Statement::Select {
+///     with: None,
+///     hints: vec![],
 ///     columns: vec![
 ///         Expression::BinaryOperation {
 ///             left_operand: Box::new(Expression::Identifier("age".to_string())),
 ///             operator: BinaryOperator::Multiply,
-///             right_operand: Box::new(Expression::Number(5)),
+///             right_operand: Box::new(Expression::Literal(Literal::Integer(5))),
 ///         },
-///         Expression::String("this is a string".to_string()),
+///         Expression::Literal(Literal::String("this is a string".to_string())),
 ///     ],
-///     from: "users".to_string(),
+///     from: vec![TableReference::Table { name: "users".to_string(), alias: None, sample: None }],
 ///     r#where: None,
-///     orderby: vec![]
+///     group_by: vec![],
+///     having: None,
+///     qualify: None,
+///     orderby: vec![],
+///     limit: None
 /// }
 /// ```
 /// ---
@@ -57,27 +96,33 @@ use std::fmt::{Debug, Display, Formatter};
 /// is a  `SELECT` statement that, when parsed, looks like this:
 /// ```rust
 /// Statement::Select {
+///     with: None,
+///     hints: vec![],
 ///     columns: [
 ///         Expression::Identifier("name".to_string()),
 ///         Expression::Identifier("surname".to_string()),
 ///     ],
-///     from: "users".to_string(),
+///     from: vec![TableReference::Table { name: "users".to_string(), alias: None, sample: None }],
 ///     r#where: Some(
 ///         Expression::BinaryOperation {
 ///             left_operand: Box::new(Expression::BinaryOperation {
 ///                 left_operand: Box::new(Expression::Identifier("name".to_string())),
 ///                 operand: BinaryOperator::Equals,
-///                 right_operand: Box::new(Expression::String("Voldemort".to_string())),
+///                 right_operand: Box::new(Expression::Literal(Literal::String("Voldemort".to_string()))),
 ///             }),
 ///             operand: BinaryOperator::And,
 ///             right_operand: Box::new(Expression::BinaryOperation {
 ///                 left_operand: Box::new(Expression::Identifier("surname".to_string())),
 ///                 operand: BinaryOperator::Equals,
-///                 right_operand: Box::new(Expression::String("Riddle".to_string())),
+///                 right_operand: Box::new(Expression::Literal(Literal::String("Riddle".to_string()))),
 ///             }),
 ///         },
 ///     ),
-///     orderby: vec![]
+///     group_by: vec![],
+///     having: None,
+///     qualify: None,
+///     orderby: vec![],
+///     limit: None
 /// }
 /// ```
 ///  ---
@@ -87,21 +132,26 @@ use std::fmt::{Debug, Display, Formatter};
 /// is a  `SELECT` statement that, when parsed, looks like this:
 /// ```rust
 /// Statement::Select {
+///     with: None,
+///     hints: vec![],
 ///     columns: vec![
 ///         Expression::Identifier("id".to_string()),
 ///         Expression::Identifier("salary".to_string()),
 ///     ],
-///     from: "users".to_string(),
+///     from: vec![TableReference::Table { name: "users".to_string(), alias: None, sample: None }],
 ///     r#where: None,
+///     group_by: vec![],
+///     having: None,
+///     qualify: None,
 ///     orderby: vec![
 ///         Expression::UnaryOperation {
 ///             operand: Box::new(Expression::BinaryOperation {
 ///                 left_operand: Box::new(Expression::Identifier("salary".to_string())),
 ///                 operator: BinaryOperator::Minus,
 ///                 right_operand: Box::new(Expression::BinaryOperation {
-///                     left_operand: Box::new(Expression::Number(2)),
+///                     left_operand: Box::new(Expression::Literal(Literal::Integer(2))),
 ///                     operator: BinaryOperator::Multiply,
-///                     right_operand: Box::new(Expression::Number(10)),
+///                     right_operand: Box::new(Expression::Literal(Literal::Integer(10))),
 ///                 }),
 ///             }),
 ///             operator: UnaryOperator::Asc,
@@ -111,6 +161,7 @@ use std::fmt::{Debug, Display, Formatter};
 ///             operator: UnaryOperator::Desc,
 ///         },
 ///     ],
+///     limit: None
 /// }
 /// ```
 ///  ---
@@ -120,23 +171,29 @@ use std::fmt::{Debug, Display, Formatter};
 /// is a  `SELECT` statement that, when parsed, looks like this:
 /// ```rust
 /// Statement::Select {
+///     with: None,
+///     hints: vec![],
 ///     columns: vec![
 ///         Expression::Identifier("id".to_string())
 ///     ],
-///     from: "registered_users".to_string(),
+///     from: vec![TableReference::Table { name: "registered_users".to_string(), alias: None, sample: None }],
 ///     r#where: Some(
 ///         Expression::BinaryOperation {
 ///             left_operand: Box::new(Expression::Identifier("password_encryption".to_string())),
 ///             operator: BinaryOperator::Equals,
-///             right_operand: Box::new(Expression::Bool(true))
+///             right_operand: Box::new(Expression::Literal(Literal::Boolean(true)))
 ///         }
 ///     ),
+///     group_by: vec![],
+///     having: None,
+///     qualify: None,
 ///     orderby: vec![
 ///         Expression::UnaryOperation {
 ///             operand: Box::new(Expression::Identifier("id".to_string())),
 ///             operator: UnaryOperator::Desc
 ///         }
-///     ]
+///     ],
+///     limit: None
 /// }
 /// ```
 /// ---
@@ -151,6 +208,8 @@ use std::fmt::{Debug, Display, Formatter};
 /// ```rust
 /// Statement::CreateTable {
 ///     table_name: "simple_table".to_string(),
+///     if_not_exists: false,
+///     temporary: false,
 ///     column_list: vec![
 ///         TableColumn {
 ///             column_name: "int_col".to_string(),
@@ -210,12 +269,12 @@ use std::fmt::{Debug, Display, Formatter};
 ///                 Constraint::Check(Expression::BinaryOperation {
 ///                     left_operand: Box::new(Expression::Identifier("age".to_string())),
 ///                     operator: BinaryOperator::GreaterThanOrEqual,
-///                     right_operand: Box::new(Expression::Number(18)),
+///                     right_operand: Box::new(Expression::Literal(Literal::Integer(18))),
 ///                 }),
 ///                 Constraint::Check(Expression::BinaryOperation {
 ///                     left_operand: Box::new(Expression::Identifier("age".to_string())),
 ///                     operator: BinaryOperator::LessThanOrEqual,
-///                     right_operand: Box::new(Expression::Number(65)),
+///                     right_operand: Box::new(Expression::Literal(Literal::Integer(65))),
 ///                 }),
 ///             ],
 ///         },
@@ -224,37 +283,619 @@ use std::fmt::{Debug, Display, Formatter};
 /// ```
 /// ---
 /// ```sql
+/// CREATE TABLE adults AS SELECT id, email FROM complex_table WHERE age >= 18;
+/// ```
+/// is a `CREATE TABLE` statement whose columns are those of the query, when parsed it looks like this:
+/// ```rust
+/// Statement::CreateTable {
+///     table_name: "adults".to_string(),
+///     column_list: vec![],
+///     query: Some(Box::new(Statement::Select {
+///         columns: vec![Expression::Identifier("id".to_string()), Expression::Identifier("email".to_string())],
+///         from: vec![TableReference::Table { name: "complex_table".to_string(), alias: None, sample: None }],
+///         r#where: Some(Expression::BinaryOperation {
+///             left_operand: Box::new(Expression::Identifier("age".to_string())),
+///             operator: BinaryOperator::GreaterThanOrEqual,
+///             right_operand: Box::new(Expression::Literal(Literal::Integer(18))),
+///         }),
+///         ..
+///     })),
+///     if_not_exists: false,
+/// }
+/// ```
+/// ---
+/// ```sql
 /// SELECT salary WHERE salary > 1000;
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it (no `FROM` clause).
 ///
 /// ---
 /// ```sql
+/// INSERT INTO users (id, name) VALUES (1, 'Harry'), (2, NULL);
+/// ```
+/// is an `INSERT` statement that, when parsed, looks like this:
+/// ```rust
+/// Statement::Insert {
+///     table_name: "users".to_string(),
+///     columns: Some(vec!["id".to_string(), "name".to_string()]),
+///     source: InsertSource::Values(vec![
+///         vec![Expression::Literal(Literal::Integer(1)), Expression::Literal(Literal::String("Harry".to_string()))],
+///         vec![Expression::Literal(Literal::Integer(2)), Expression::Literal(Literal::Null)],
+///     ]),
+///     on_conflict: None,
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// INSERT INTO archive (id, name) SELECT id, name FROM users WHERE active = false;
+/// ```
+/// takes its rows from a query instead, which is parsed like any other:
+/// ```rust
+/// source: InsertSource::Query(Box::new(Statement::Select { /* SELECT id, name FROM users WHERE active = false */ })),
+/// ```
+///
+/// ---
+/// ```sql
+/// INSERT INTO users (id, name) VALUES (1, 'Harry') ON CONFLICT (id) DO UPDATE SET name = excluded.name;
+/// ```
+/// is an upsert, an `INSERT` whose conflicting rows update the row already in the table instead. It looks like the `INSERT` above with:
+/// ```rust
+/// on_conflict: Some(OnConflict {
+///     target: vec!["id".to_string()],
+///     action: ConflictAction::Update {
+///         assignments: vec![Assignment {
+///             column: "name".to_string(),
+///             value: Expression::CompoundIdentifier(vec!["excluded".to_string(), "name".to_string()]),
+///         }],
+///         where_clause: None,
+///     },
+/// }),
+/// ```
+///
+/// ---
+/// ```sql
+/// DELETE FROM users WHERE id = 5;
+/// ```
+/// is a `DELETE` statement that, when parsed, looks like this:
+/// ```rust
+/// Statement::Delete {
+///     table: "users".to_string(),
+///     where_clause: Some(Expression::BinaryOperation {
+///         left_operand: Box::new(Expression::Identifier("id".to_string())),
+///         operator: BinaryOperator::Equal,
+///         right_operand: Box::new(Expression::Literal(Literal::Integer(5))),
+///     }),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// MERGE INTO customers c USING staged s ON c.id = s.id
+/// WHEN MATCHED AND s.deleted THEN DELETE
+/// WHEN MATCHED THEN UPDATE SET name = s.name
+/// WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name);
+/// ```
+/// is a `MERGE` statement, which applies the rows of a source to a target table arm by arm. When parsed it looks like this:
+/// ```rust
+/// Statement::Merge {
+///     table_name: "customers".to_string(),
+///     alias: Some("c".to_string()),
+///     source: TableReference::Table { name: "staged".to_string(), alias: Some("s".to_string()), sample: None },
+///     on: Expression::BinaryOperation { /* c.id = s.id */ },
+///     clauses: vec![
+///         MergeClause {
+///             matched: true,
+///             condition: Some(Expression::CompoundIdentifier(vec!["s".to_string(), "deleted".to_string()])),
+///             action: MergeAction::Delete,
+///         },
+///         MergeClause {
+///             matched: true,
+///             condition: None,
+///             action: MergeAction::Update {
+///                 assignments: vec![Assignment {
+///                     column: "name".to_string(),
+///                     value: Expression::CompoundIdentifier(vec!["s".to_string(), "name".to_string()]),
+///                 }],
+///             },
+///         },
+///         MergeClause {
+///             matched: false,
+///             condition: None,
+///             action: MergeAction::Insert {
+///                 columns: Some(vec!["id".to_string(), "name".to_string()]),
+///                 values: vec![/* s.id, s.name */],
+///             },
+///         },
+///     ],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// DROP TABLE IF EXISTS sessions, tokens;
+/// ```
+/// is a `DROP` statement that, when parsed, looks like this (`DROP INDEX` and `DROP VIEW` differ only in `object_type`):
+/// ```rust
+/// Statement::Drop {
+///     object_type: ObjectType::Table,
+///     names: vec!["sessions".to_string(), "tokens".to_string()],
+///     if_exists: true,
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// ALTER TABLE users ADD COLUMN email VARCHAR(255) NOT NULL;
+/// ```
+/// is an `ALTER TABLE` statement that, when parsed, looks like this:
+/// ```rust
+/// Statement::AlterTable {
+///     table_name: "users".to_string(),
+///     action: AlterAction::AddColumn(TableColumn {
+///         column_name: "email".to_string(),
+///         column_type: DBType::Varchar(255),
+///         constraints: vec![Constraint::NotNull],
+///     }),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// RENAME TABLE users TO accounts, sessions TO logins;
+/// ```
+/// is a MySQL `RENAME TABLE` statement, which renames several tables one after the other (`ALTER TABLE ... RENAME TO` renames one, and is `AlterAction::RenameTo`). When parsed it looks like this:
+/// ```rust
+/// Statement::RenameTable {
+///     renames: vec![
+///         TableRename { old_name: "users".to_string(), new_name: "accounts".to_string() },
+///         TableRename { old_name: "sessions".to_string(), new_name: "logins".to_string() },
+///     ],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// TRUNCATE TABLE sessions;
+/// ```
+/// is a `TRUNCATE` statement that, when parsed, looks like this:
+/// ```rust
+/// Statement::Truncate {
+///     table_name: "sessions".to_string(),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// VACUUM FULL sessions;
+/// ```
+/// is a `VACUUM` statement that, when parsed, looks like this (`ANALYZE sessions;` is `Statement::Analyze` with just the table, and both leave the table out to work on the whole database):
+/// ```rust
+/// Statement::Vacuum {
+///     full: true,
+///     table_name: Some("sessions".to_string()),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// CREATE SCHEMA IF NOT EXISTS reporting;
+/// ```
+/// is a `CREATE SCHEMA` statement that, when parsed, looks like this (`CREATE DATABASE` is `Statement::CreateDatabase` with the same fields):
+/// ```rust
+/// Statement::CreateSchema {
+///     name: "reporting".to_string(),
+///     if_not_exists: true,
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// CREATE UNIQUE INDEX users_email ON users (email, tenant_id DESC);
+/// ```
+/// is a `CREATE INDEX` statement that, when parsed, looks like this:
+/// ```rust
+/// Statement::CreateIndex {
+///     name: "users_email".to_string(),
+///     table_name: "users".to_string(),
+///     columns: vec![
+///         IndexColumn { name: "email".to_string(), descending: false },
+///         IndexColumn { name: "tenant_id".to_string(), descending: true },
+///     ],
+///     unique: true,
+///     if_not_exists: false,
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// SHOW TABLES;
+/// DESCRIBE users;
+/// ```
+/// are introspection statements, for a client to answer from what it knows about the database, that when parsed look like this (`SHOW DATABASES` has `ShowObject::Databases`, `DESC users` is the same as `DESCRIBE users`):
+/// ```rust
+/// Statement::Show {
+///     object: ShowObject::Tables,
+/// }
+/// Statement::Describe {
+///     table_name: "users".to_string(),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// SET client_encoding TO 'UTF8';
+/// ```
+/// is a `SET` statement, as dumps start with, that when parsed looks like this (`=` instead of `TO` is the same statement). The value is an expression, a bare word such as `warning` is an `Identifier` and so is `on`:
+/// ```rust
+/// Statement::SetVariable {
+///     name: "client_encoding".to_string(),
+///     value: Expression::Literal(Literal::String("UTF8".to_string())),
+///     rest: vec![],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// WITH RECURSIVE managers (id, boss) AS (SELECT id, boss FROM staff) SELECT id FROM managers;
+/// ```
+/// is a `SELECT` with a `WITH` clause, whose common table expressions the query (and the ones after them) can use like tables. It looks like the `SELECT` statements above with:
+/// ```rust
+/// with: Some(With {
+///     recursive: true,
+///     ctes: vec![Cte {
+///         name: "managers".to_string(),
+///         columns: vec!["id".to_string(), "boss".to_string()],
+///         query: Box::new(Statement::Select { .. }),
+///     }],
+/// }),
+/// ```
+///
+/// ---
+/// ```sql
+/// SELECT id FROM staff UNION ALL SELECT id FROM contractors ORDER BY id;
+/// ```
+/// is two queries combined into one, that when parsed looks like this. `INTERSECT` binds tighter than `UNION` and `EXCEPT`, which go from left to right, so `a UNION b INTERSECT c` has `b INTERSECT c` as its `right`. `ORDER BY` and `LIMIT` after the last query sort and cut the combined rows, a query in parentheses can have its own:
+/// ```rust
+/// Statement::SetOperation {
+///     with: None,
+///     left: Box::new(Statement::Select { .. }),
+///     operator: SetOperator::Union,
+///     all: true,
+///     right: Box::new(Statement::Select { .. }),
+///     orderby: vec![Expression::Identifier("id".to_string())],
+///     limit: None,
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// VALUES (1, 'a'), (2, 'b');
+/// ```
+/// is a query of its own, rows written out, that when parsed looks like this. Every row has as many values as the first one; it can also be one side of `UNION`, `INTERSECT` or `EXCEPT`, and a subquery:
+/// ```rust
+/// Statement::Values {
+///     rows: vec![
+///         vec![Expression::Literal(Literal::Integer(1)), Expression::Literal(Literal::String("a".to_string()))],
+///         vec![Expression::Literal(Literal::Integer(2)), Expression::Literal(Literal::String("b".to_string()))],
+///     ],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// CREATE TRIGGER audit_orders AFTER INSERT ON orders FOR EACH ROW INSERT INTO audit VALUES (NEW.id);
+/// ```
+/// is a `CREATE TRIGGER` statement that, when parsed, looks like this. The body is not parsed, it is kept as the tokens it is made of, up to the semicolon that ends the statement or, for a `BEGIN ... END` block, the one after its `END`:
+/// ```rust
+/// Statement::CreateTrigger {
+///     name: "audit_orders".to_string(),
+///     timing: TriggerTiming::After,
+///     event: TriggerEvent::Insert,
+///     table_name: "orders".to_string(),
+///     body: vec![Token::Keyword(Keyword::Insert), Token::Keyword(Keyword::Into), Token::Identifier("audit".to_string()), ..],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// CREATE OR REPLACE FUNCTION add_tax(amount DECIMAL(10, 2)) RETURNS DECIMAL(10, 2) AS 'SELECT amount * 1.2' LANGUAGE sql;
+/// ```
+/// is a `CREATE FUNCTION` statement that, when parsed, looks like this. The body is not parsed, it is the string after `AS` (in Postgres also `$$...$$`), and `LANGUAGE` can come before or after it. `CREATE PROCEDURE` is `Statement::CreateProcedure` with the same fields but `returns`:
+/// ```rust
+/// Statement::CreateFunction {
+///     or_replace: true,
+///     name: "add_tax".to_string(),
+///     args: vec![FunctionArg { name: Some("amount".to_string()), data_type: DBType::Decimal(10, 2) }],
+///     returns: DBType::Decimal(10, 2),
+///     body: "SELECT amount * 1.2".to_string(),
+///     language: Some("sql".to_string()),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// CALL archive_orders(2023, 'closed');
+/// ```
+/// is a `CALL` statement, which runs a stored procedure, that when parsed looks like this. The arguments are expressions, `CALL archive_orders;` without parentheses has none:
+/// ```rust
+/// Statement::Call {
+///     name: "archive_orders".to_string(),
+///     args: vec![Expression::Literal(Literal::Integer(2023)), Expression::Literal(Literal::String("closed".to_string()))],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// COPY users (id, name) FROM 'users.csv' WITH (FORMAT csv, HEADER, DELIMITER ';');
+/// ```
+/// is a PostgreSQL bulk load that, when parsed, looks like this. `COPY ... TO` writes a table out instead, and `STDIN` or `STDOUT` in place of the file leaves `file` as `None`. Option names are kept in upper case, their values as text whether they were written as a word, a number or a string:
+/// ```rust
+/// Statement::Copy {
+///     table_name: "users".to_string(),
+///     columns: vec!["id".to_string(), "name".to_string()],
+///     direction: CopyDirection::From,
+///     file: Some("users.csv".to_string()),
+///     options: vec![
+///         CopyOption { name: "FORMAT".to_string(), value: Some("csv".to_string()) },
+///         CopyOption { name: "HEADER".to_string(), value: None },
+///         CopyOption { name: "DELIMITER".to_string(), value: Some(";".to_string()) },
+///     ],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// LOAD DATA LOCAL INFILE 'users.csv' REPLACE INTO TABLE users FIELDS TERMINATED BY ',' OPTIONALLY ENCLOSED BY '"' IGNORE 1 LINES (id, name);
+/// ```
+/// is MySQL's bulk load, which when parsed looks like this (`COLUMNS` is read as `FIELDS`):
+/// ```rust
+/// Statement::LoadData {
+///     local: true,
+///     file: "users.csv".to_string(),
+///     duplicates: Some(DuplicateHandling::Replace),
+///     table_name: "users".to_string(),
+///     format: LoadFormat {
+///         fields_terminated_by: Some(",".to_string()),
+///         fields_enclosed_by: Some("\"".to_string()),
+///         optionally_enclosed: true,
+///         ..LoadFormat::default()
+///     },
+///     ignore_lines: Some(1),
+///     columns: vec!["id".to_string(), "name".to_string()],
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// PREPARE recent_orders (INT) AS SELECT id FROM orders WHERE customer_id = $1;
+/// EXECUTE recent_orders(42);
+/// DEALLOCATE recent_orders;
+/// ```
+/// are the statements that manage a prepared statement, which when parsed look like this. The statement that is prepared is parsed like any other, only queries, `INSERT` and `DELETE` can be; `DEALLOCATE ALL` has no name:
+/// ```rust
+/// Statement::Prepare {
+///     name: "recent_orders".to_string(),
+///     param_types: vec![DBType::Int],
+///     statement: Box::new(Statement::Select { /* SELECT id FROM orders WHERE customer_id = $1 */ }),
+/// }
+/// Statement::Execute {
+///     name: "recent_orders".to_string(),
+///     args: vec![Expression::Literal(Literal::Integer(42))],
+/// }
+/// Statement::Deallocate {
+///     name: Some("recent_orders".to_string()),
+/// }
+/// ```
+///
+/// ---
+/// ```sql
+/// GRANT SELECT ON orders TO reporting;
+/// ```
+/// is a statement the parser does not know. In permissive mode, with the text of the script given to the parser (`Parser::source`), it is kept as it is written, so a script can still be split, counted and processed around it:
+/// ```rust
+/// Statement::Unparsed {
+///     raw: "GRANT SELECT ON orders TO reporting".to_string(),
+///     span: Span { start: 0, end: 35, line: 1, column: 1 },
+/// }
+/// ```
+///
+/// ---
+/// ```sql
 /// CREATE TABLE work_hours(num_hours INT)
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it (no semicolon at the end).
-#[derive(Debug, PartialEq)]
+//statements are parsed one at a time, so the size of the SELECT variant does not matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Statement {
+    //`with` is the WITH clause in front of the query
     Select {
+        with: Option<With>,
+        hints: Vec<Hint>,
         columns: Vec<Expression>,
-        from: String,
+        from: Vec<TableReference>,
         r#where: Option<Expression>,
+        group_by: Vec<GroupingElement>,
+        having: Option<Expression>,
+        qualify: Option<Expression>,
+        orderby: Vec<Expression>,
+        limit: Option<Expression>,
+    },
+    //`all` keeps duplicate rows, which UNION, INTERSECT and EXCEPT remove by default
+    SetOperation {
+        with: Option<With>,
+        left: Box<Statement>,
+        operator: SetOperator,
+        all: bool,
+        right: Box<Statement>,
         orderby: Vec<Expression>,
+        limit: Option<Expression>,
     },
+    Values {
+        rows: Vec<Vec<Expression>>,
+    },
+    //`query` is the SELECT of `CREATE TABLE ... AS SELECT`, whose columns are those of the query, `column_list` is empty then
+    //`temporary` is `CREATE TEMP[ORARY] TABLE`, a table that only lasts as long as the session
     CreateTable {
         table_name: String,
         column_list: Vec<TableColumn>,
-    }
+        query: Option<Box<Statement>>,
+        if_not_exists: bool,
+        temporary: bool,
+    },
+    //`on_conflict` is the `ON CONFLICT` clause of an upsert
+    Insert {
+        table_name: String,
+        columns: Option<Vec<String>>,
+        source: InsertSource,
+        on_conflict: Option<OnConflict>,
+    },
+    Delete {
+        table: String,
+        where_clause: Option<Expression>,
+    },
+    //`alias` names the target table in `on` and in the arms, `source` is a table or a subquery with an alias of its own
+    Merge {
+        table_name: String,
+        alias: Option<String>,
+        source: TableReference,
+        on: Expression,
+        clauses: Vec<MergeClause>,
+    },
+    Drop {
+        object_type: ObjectType,
+        names: Vec<String>,
+        if_exists: bool,
+    },
+    AlterTable {
+        table_name: String,
+        action: AlterAction,
+    },
+    //the pairs in the order they are written, a later one sees the names the earlier ones gave
+    RenameTable {
+        renames: Vec<TableRename>,
+    },
+    Truncate {
+        table_name: String,
+    },
+    Analyze {
+        table_name: Option<String>,
+    },
+    Vacuum {
+        full: bool,
+        table_name: Option<String>,
+    },
+    CreateDatabase {
+        name: String,
+        if_not_exists: bool,
+    },
+    CreateSchema {
+        name: String,
+        if_not_exists: bool,
+    },
+    CreateIndex {
+        name: String,
+        table_name: String,
+        columns: Vec<IndexColumn>,
+        unique: bool,
+        if_not_exists: bool,
+    },
+    //`body` is the text of the routine, not parsed
+    CreateFunction {
+        or_replace: bool,
+        name: String,
+        args: Vec<FunctionArg>,
+        returns: DBType,
+        body: String,
+        language: Option<String>,
+    },
+    CreateProcedure {
+        or_replace: bool,
+        name: String,
+        args: Vec<FunctionArg>,
+        body: String,
+        language: Option<String>,
+    },
+    //`body` is the tokens after FOR EACH ROW, without the closing semicolon
+    CreateTrigger {
+        name: String,
+        timing: TriggerTiming,
+        event: TriggerEvent,
+        table_name: String,
+        body: Vec<Token>,
+    },
+    Show {
+        object: ShowObject,
+    },
+    Describe {
+        table_name: String,
+    },
+    //`rest` are the values after the first of a list, `SET search_path TO public, app`
+    SetVariable {
+        name: String,
+        value: Expression,
+        rest: Vec<Expression>,
+    },
+    Call {
+        name: String,
+        args: Vec<Expression>,
+    },
+    //`file` is None for STDIN (FROM) or STDOUT (TO)
+    Copy {
+        table_name: String,
+        columns: Vec<String>,
+        direction: CopyDirection,
+        file: Option<String>,
+        options: Vec<CopyOption>,
+    },
+    //`columns` is the column list at the end, empty when the file has every column of the table in order
+    LoadData {
+        local: bool,
+        file: String,
+        duplicates: Option<DuplicateHandling>,
+        table_name: String,
+        format: LoadFormat,
+        ignore_lines: Option<u64>,
+        columns: Vec<String>,
+    },
+    //`param_types` are the types in parentheses after the name, empty without them
+    Prepare {
+        name: String,
+        param_types: Vec<DBType>,
+        statement: Box<Statement>,
+    },
+    Execute {
+        name: String,
+        args: Vec<Expression>,
+    },
+    //`name` is None for DEALLOCATE ALL
+    Deallocate {
+        name: Option<String>,
+    },
+    //only in permissive mode, a statement of a kind the parser does not know: `raw` is its text from its first token to
+    //its last (the `;` left out) and `span` where that text is
+    Unparsed {
+        raw: String,
+        span: Span,
+    },
 }
 
 /// The main entity of the expression parser. The Expression enum is structured like this, where an expression can contain another expression. This naturally allows us to represent complex expressions as trees. `Box<T>` smart pointers are used on unary and binary types of expressions because the compiler needs to know the size of the enum at compile time which is impossible when an enum contains itself (infinite size).
 ///
 /// An expression can be:
 /// * complex - a number of other expressions (tree-like structure, unary and binary operations)
-/// * a single number
+/// * a constant – a number, string, boolean or `NULL` – described by the `Literal` enum
 /// * a single identifier (like a variable 'x')
-/// * a single string (when doing parsing of WHERE statements that do operations with strings, strings must be in matching quotes – either `""` or `''`)
-/// * a boolean (only true or false)
+/// * a qualified identifier, `orders.id`, stored as its dot separated parts
+/// * a null-safe comparison, `a IS DISTINCT FROM b` (or `a IS NOT DISTINCT FROM b` when `negated`), which treats two NULLs as equal and a NULL and a value as different, instead of yielding NULL like `=` does
+/// * a NULL test, `a IS NULL` or `a IS NOT NULL`, a `UnaryOperation` written after its operand
+/// * an `EXISTS (SELECT ...)` predicate (`NOT EXISTS` when `negated`), true when the subquery returns at least one row. The subquery is a `Statement::Select`, boxed for the same reason operands are
+/// * a scalar subquery, `(SELECT max(x) FROM t)`, which yields the single value returned by the inner `SELECT`. Parentheses around anything other than a `SELECT` only group and leave no trace in the tree
+/// * a function call, `max(x)`, with a name and a list of argument expressions
+/// * an aliased column, `expr AS name`, which like `Asc` and `Desc` only appears at the top of a projected column
+/// * a wildcard, `*`, either as a projected column or as the argument of `count(*)`
+/// * a query parameter, `?` or `$1`, kept as written and filled in by whoever runs the query
 ///
 /// Examples:
 ///
@@ -266,25 +907,25 @@ pub enum Statement {
 /// 1. `(13 + 7)` which is
 /// ```rust
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Number(13)),
+///     left_operand: Box::new(Expression::Literal(Literal::Integer(13))),
 ///     operator: BinaryOperator::Plus,
-///     right_operand: Box::new(Expression::Number(7))
+///     right_operand: Box::new(Expression::Literal(Literal::Integer(7)))
 /// }
 /// ```
 /// 2. `4` which is
 /// ```rust
-/// Expression::Number(4)
+/// Expression::Literal(Literal::Integer(4))
 /// ```
 /// Therefore, the whole expression after parsing should look like this:
 /// ```rust
 /// Expression::BinaryOperation {
 ///     left_operand: Expression::BinaryOperation {
-///         left_operand: Box::new(Expression::Number(13)),
+///         left_operand: Box::new(Expression::Literal(Literal::Integer(13))),
 ///         operator: BinaryOperator::Plus,
-///         right_operand: Box::new(Expression::Number(7))
+///         right_operand: Box::new(Expression::Literal(Literal::Integer(7)))
 ///     },
 ///     operator: BinaryOperator::Minus,
-///     right_operand: Box::new(Expression::Number(4))
+///     right_operand: Box::new(Expression::Literal(Literal::Integer(4)))
 /// }
 /// ```
 /// ---
@@ -295,7 +936,7 @@ pub enum Statement {
 /// 1. `(5 - x)` which is
 /// ```rust
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Number(5)),
+///     left_operand: Box::new(Expression::Literal(Literal::Integer(5))),
 ///     operator: BinaryOperator::Minus,
 ///     right_operand: Box::new(Expression::Identifier("x".to_string())),
 /// }
@@ -303,7 +944,7 @@ pub enum Statement {
 /// 2. `(4 - y)` which is
 /// ```rust
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Number(4)),
+///     left_operand: Box::new(Expression::Literal(Literal::Integer(4))),
 ///     operator: BinaryOperator::Plus,
 ///     right_operand: Box::new(Expression::Identifier("y".to_string()))
 /// }
@@ -313,7 +954,7 @@ pub enum Statement {
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::Identifier("name".to_string())),
 ///     operator: BinaryOperator::Equal,
-///     right_operand: Box::new(Expression::String("Donna".to_string()))
+///     right_operand: Box::new(Expression::Literal(Literal::String("Donna".to_string())))
 /// }
 /// ```
 /// Therefore, the whole expression after parsing should look like this:
@@ -321,13 +962,13 @@ pub enum Statement {
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::BinaryOperation {
 ///         left_operand: Box::new(Expression::BinaryOperation {
-///             left_operand: Box::new(Expression::Number(5)),
+///             left_operand: Box::new(Expression::Literal(Literal::Integer(5))),
 ///             operator: BinaryOperator::Minus,
 ///             right_operand: Box::new(Expression::Identifier("x".to_string()))
 ///         }),
 ///         operator: BinaryOperator::LessThan,
 ///         right_operand: Box::new(Expression::BinaryOperation {
-///             left_operand: Box::new(Expression::Number(4)),
+///             left_operand: Box::new(Expression::Literal(Literal::Integer(4))),
 ///             operator: BinaryOperator::Plus,
 ///             right_operand: Box::new(Expression::Identifier("y".to_string()))
 ///         })
@@ -336,7 +977,7 @@ pub enum Statement {
 ///     right_operand: Box::new(Expression::BinaryOperation {
 ///         left_operand: Box::new(Expression::Identifier("name".to_string())),
 ///         operator: BinaryOperator::Equal,
-///         right_operand: Box::new(Expression::String("Donna".to_string()))
+///         right_operand: Box::new(Expression::Literal(Literal::String("Donna".to_string())))
 ///     })
 /// }
 /// ```
@@ -344,15 +985,15 @@ pub enum Statement {
 /// ```
 /// NOT some_boolean = TRUE
 /// ```
-/// should look like this:
+/// should look like this (`NOT` binds looser than comparisons, as in standard SQL):
 /// ```rust
-/// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::UnaryOperation {
+/// Expression::UnaryOperation {
+///     operand: Box::new(Expression::BinaryOperation {
 ///         left_operand: Box::new(Expression::Identifier("some_boolean".to_string())),
-///         operator: UnaryOperator::Not
+///         operator: BinaryOperator::Equal,
+///         right_operand: Box::new(Expression::Literal(Literal::Boolean(true)))
 ///     }),
-///     operator: BinaryOperator::Equal,
-///     right_operand: Box::new(Expression::Bool(true))
+///     operator: UnaryOperator::Not
 /// }
 /// ```
 /// ---
@@ -361,7 +1002,7 @@ pub enum Statement {
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it.
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
     BinaryOperation {
         left_operand: Box<Expression>,
@@ -372,41 +1013,536 @@ pub enum Expression {
         operand: Box<Expression>,
         operator: UnaryOperator,
     },
-    Number(u64),
-    Bool(bool),
+    IsDistinctFrom {
+        left_operand: Box<Expression>,
+        right_operand: Box<Expression>,
+        negated: bool,
+    },
+    Exists {
+        subquery: Box<Statement>,
+        negated: bool,
+    },
+    Subquery(Box<Statement>),
+    Function {
+        name: String,
+        args: Vec<Expression>,
+        over: Option<WindowSpec>,
+    },
+    Alias {
+        expr: Box<Expression>,
+        alias: String,
+    },
+    Wildcard,
+    Literal(Literal),
+    Placeholder(String),
     Identifier(String),
+    CompoundIdentifier(Vec<String>),
+}
+
+/// A constant written in the query. Numbers are signed: a minus sign directly in front of a number is part of the literal, so `-5` is `Integer(-5)` rather than a unary minus applied to `5` (`-(5)` still is one).
+/// * `Integer` – a whole number that fits in 64 bits
+/// * `Decimal` – any other number, `1.5`, `.5`, `2e10` or an integer too large for `Integer`, kept as written so no precision is lost
+/// * `String` – a string in matching quotes; escape strings (`E'...'`) are plain strings once their escapes are decoded
+/// * `NationalString`, `BitString`, `HexString` – `N'...'`, `B'0101'` and `X'1F'`, without prefix and quotes
+/// * `Boolean` – `TRUE` or `FALSE`
+/// * `Null`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Literal {
+    Integer(i64),
+    Decimal(String),
     String(String),
+    NationalString(String),
+    BitString(String),
+    HexString(String),
+    Boolean(bool),
+    Null,
+}
+
+impl Literal {
+    //true for a number with a minus sign, which needs parentheses after another operator (`3 - (-5)`, not `3 - -5`)
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Literal::Integer(n) => *n < 0,
+            Literal::Decimal(text) => text.starts_with('-'),
+            _ => false,
+        }
+    }
+}
+
+/// One optimizer hint from a `/*+ ... */` comment, `/*+ INDEX(t idx) NO_MERGE */` holds two of them. The database decides what a hint means, so the parser only splits the comment into names and their parenthesized arguments (separated by spaces or commas) and keeps both as written.
+/// 1. `name` – The hint name, `INDEX`.
+/// 2. `args` – The arguments, `["t", "idx"]`, empty when the hint has none.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Hint {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+/// The `WITH` clause of a query.
+/// 1. `recursive` – Whether it is `WITH RECURSIVE`, in which a common table expression may refer to itself. Without it, one can only use the ones before it.
+/// 2. `ctes` – The common table expressions, in the order they are written.
+#[derive(Debug, PartialEq, Clone)]
+pub struct With {
+    pub recursive: bool,
+    pub ctes: Vec<Cte>,
+}
+
+/// One common table expression, `name (columns) AS (SELECT ...)`, a named query the rest of the statement reads like a table.
+/// 1. `name` – The name it is read by.
+/// 2. `columns` – Names for the columns of the query, empty when the query's own names are used.
+/// 3. `query` – The `SELECT` giving its rows.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cte {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub query: Box<Statement>,
+}
+
+/// Where the rows of an `INSERT` come from.
+/// 1. `Values` – The rows written after `VALUES`, one vector of expressions per parenthesized list. Every row has as many values as the first one.
+/// 2. `Query` – A query, `INSERT INTO t (a, b) SELECT x, y FROM s`: a `SELECT`, a set operation or a query with a `WITH` clause, whose columns go to the columns of the table in order.
+#[derive(Debug, PartialEq, Clone)]
+pub enum InsertSource {
+    Values(Vec<Vec<Expression>>),
+    Query(Box<Statement>),
+}
+
+impl InsertSource {
+    //the rows written after VALUES, none for a query, for the code that looks at the values of an insert one by one
+    pub fn rows(&self) -> &[Vec<Expression>] {
+        match self {
+            InsertSource::Values(rows) => rows,
+            InsertSource::Query(_) => &[],
+        }
+    }
+
+    pub fn rows_mut(&mut self) -> &mut [Vec<Expression>] {
+        match self {
+            InsertSource::Values(rows) => rows,
+            InsertSource::Query(_) => &mut [],
+        }
+    }
+}
+
+/// The `ON CONFLICT` clause of an `INSERT`, what to do with a row that would break a unique constraint of the table.
+/// 1. `target` – The columns of the constraint, `ON CONFLICT (id)`, empty when none are given, which only `DO NOTHING` allows.
+/// 2. `action` – What to do with such a row.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OnConflict {
+    pub target: Vec<String>,
+    pub action: ConflictAction,
+}
+
+impl OnConflict {
+    //the values of DO UPDATE and its condition, for the code that visits every expression of a statement
+    pub fn expressions(&self) -> Vec<&Expression> {
+        match &self.action {
+            ConflictAction::Nothing => Vec::new(),
+            ConflictAction::Update { assignments, where_clause } => assignments.iter().map(|assignment| &assignment.value).chain(where_clause).collect(),
+        }
+    }
+
+    pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+        match &mut self.action {
+            ConflictAction::Nothing => Vec::new(),
+            ConflictAction::Update { assignments, where_clause } => assignments.iter_mut().map(|assignment| &mut assignment.value).chain(where_clause).collect(),
+        }
+    }
+}
+
+/// What `ON CONFLICT` does with a conflicting row.
+/// * `Nothing` – `DO NOTHING`, the row is left out
+/// * `Update` – `DO UPDATE SET ...`, the row already in the table is updated instead. The values can refer to the row that was to be inserted as `excluded`, `where_clause` limits which rows are updated
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConflictAction {
+    Nothing,
+    Update {
+        assignments: Vec<Assignment>,
+        where_clause: Option<Expression>,
+    },
+}
+
+/// One `WHEN` arm of a `MERGE`. The arms are tried in order and a row of the source takes the first one that applies to it.
+/// 1. `matched` – Whether the arm is for rows of the source that have a matching row in the target, `WHEN MATCHED`, or for those that have none, `WHEN NOT MATCHED`.
+/// 2. `condition` – The condition after `AND`, `WHEN MATCHED AND s.deleted`; the arm only applies to the rows it holds for.
+/// 3. `action` – What is done with the row.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MergeClause {
+    pub matched: bool,
+    pub condition: Option<Expression>,
+    pub action: MergeAction,
+}
+
+impl MergeClause {
+    //the condition of the arm and the values of its action, for the code that visits every expression of a statement
+    pub fn expressions(&self) -> Vec<&Expression> {
+        let values: Vec<&Expression> = match &self.action {
+            MergeAction::Update { assignments } => assignments.iter().map(|assignment| &assignment.value).collect(),
+            MergeAction::Insert { values, .. } => values.iter().collect(),
+            MergeAction::Delete | MergeAction::Nothing => Vec::new(),
+        };
+        self.condition.iter().chain(values).collect()
+    }
+
+    pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+        let values: Vec<&mut Expression> = match &mut self.action {
+            MergeAction::Update { assignments } => assignments.iter_mut().map(|assignment| &mut assignment.value).collect(),
+            MergeAction::Insert { values, .. } => values.iter_mut().collect(),
+            MergeAction::Delete | MergeAction::Nothing => Vec::new(),
+        };
+        self.condition.iter_mut().chain(values).collect()
+    }
+}
+
+/// What a `WHEN` arm of a `MERGE` does with a row.
+/// * `Update` – `UPDATE SET column = value, ...`, the matching row of the target is updated, only in a `WHEN MATCHED` arm. The values can refer to the target and the source by their names or aliases
+/// * `Delete` – `DELETE`, the matching row of the target is deleted, only in a `WHEN MATCHED` arm
+/// * `Insert` – `INSERT [(column, ...)] VALUES (...)`, one row is inserted into the target, only in a `WHEN NOT MATCHED` arm. `columns` is `None` when no column list is given
+/// * `Nothing` – `DO NOTHING`, the row is left alone
+#[derive(Debug, PartialEq, Clone)]
+pub enum MergeAction {
+    Update {
+        assignments: Vec<Assignment>,
+    },
+    Delete,
+    Insert {
+        columns: Option<Vec<String>>,
+        values: Vec<Expression>,
+    },
+    Nothing,
+}
+
+/// One `column = value` of a `SET` list.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Assignment {
+    pub column: String,
+    pub value: Expression,
+}
+
+/// The window of a window function call, the part in parentheses after `OVER`.
+/// 1. `partition_by` – Expressions splitting the rows into independent partitions, empty when there is no `PARTITION BY`.
+/// 2. `order_by` – The ordering of rows within a partition, items may carry `ASC`/`DESC` like in `Statement::Select::orderby`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindowSpec {
+    pub partition_by: Vec<Expression>,
+    pub order_by: Vec<Expression>,
+}
+
+/// What can be told about the type of an expression from the expression alone, without a schema. Column references, function calls, subqueries and `NULL` could be anything and are `Unknown`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ValueType {
+    Boolean,
+    Number,
+    String,
+    Unknown,
+}
+
+impl Expression {
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Expression::BinaryOperation { operator, .. } => match operator.category() {
+                OperatorCategory::Comparison | OperatorCategory::Logical | OperatorCategory::Regex => ValueType::Boolean,
+                OperatorCategory::Arithmetic => ValueType::Number,
+                OperatorCategory::String => ValueType::String,
+                OperatorCategory::Json => match operator {
+                    BinaryOperator::JsonContains | BinaryOperator::JsonContainedBy => ValueType::Boolean,
+                    BinaryOperator::JsonGetText | BinaryOperator::JsonPathText => ValueType::String,
+                    _ => ValueType::Unknown,
+                },
+            },
+            Expression::UnaryOperation { operand, operator } => match operator {
+                UnaryOperator::Not => ValueType::Boolean,
+                UnaryOperator::Plus | UnaryOperator::Minus => ValueType::Number,
+                UnaryOperator::Asc | UnaryOperator::Desc => operand.value_type(),
+                UnaryOperator::IsNull | UnaryOperator::IsNotNull => ValueType::Boolean,
+            },
+            Expression::IsDistinctFrom { .. } | Expression::Exists { .. } => ValueType::Boolean,
+            Expression::Alias { expr, .. } => expr.value_type(),
+            Expression::Literal(literal) => match literal {
+                Literal::Boolean(_) => ValueType::Boolean,
+                Literal::Integer(_) | Literal::Decimal(_) => ValueType::Number,
+                Literal::String(_) | Literal::NationalString(_) => ValueType::String,
+                _ => ValueType::Unknown,
+            },
+            _ => ValueType::Unknown,
+        }
+    }
+}
+
+/// One item of a `FROM` clause. Table references nest like expressions do: a join combines two table references (each of which can be a join again) into one, so `a JOIN b ON ... JOIN c ON ...` becomes `Join { left: Join { left: a, right: b }, right: c }`.
+///
+/// * `Table` – a plain table name, `users` or `users AS u`, optionally sampled with `TABLESAMPLE` (see `TableSample`)
+/// * `Derived` – a subquery in parentheses, `(SELECT ...) AS x`. With `lateral` (`LATERAL (SELECT ...) x`) the subquery may refer to columns of the table references before it
+/// * `Function` – a table-valued function, `unnest(arr)` or `generate_series(1, 10) AS g`, optionally `LATERAL` as well
+/// * `Pivot` – a table reference turned by `PIVOT (...)`, rows become columns (see `PivotClause`)
+/// * `Unpivot` – a table reference turned by `UNPIVOT (...)`, columns become rows (see `UnpivotClause`)
+/// * `Join` – two table references combined by a join. `on` is the join condition, it is `None` for `CROSS JOIN` and for joins written without a condition
+///
+/// Comma separated items (`FROM t, LATERAL (...) x`) are kept as separate entries of `Statement::Select::from` rather than turned into cross joins.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TableReference {
+    Table {
+        name: String,
+        alias: Option<String>,
+        sample: Option<TableSample>,
+    },
+    Derived {
+        lateral: bool,
+        subquery: Box<Statement>,
+        alias: Option<String>,
+    },
+    Function {
+        lateral: bool,
+        name: String,
+        args: Vec<Expression>,
+        alias: Option<String>,
+    },
+    Pivot {
+        table: Box<TableReference>,
+        pivot: PivotClause,
+        alias: Option<String>,
+    },
+    Unpivot {
+        table: Box<TableReference>,
+        unpivot: UnpivotClause,
+        alias: Option<String>,
+    },
+    Join {
+        left: Box<TableReference>,
+        right: Box<TableReference>,
+        kind: JoinKind,
+        on: Option<Expression>,
+    },
+}
+
+/// A `TABLESAMPLE` clause on a table reference, `orders TABLESAMPLE SYSTEM (10) REPEATABLE (42)`.
+/// 1. `method` – The sampling method as written, `SYSTEM` and `BERNOULLI` are standard, databases add their own.
+/// 2. `percentage` – The expression in parentheses, the share of the table to return (or the argument of a custom method).
+/// 3. `repeatable` – The optional seed that makes the sample reproducible.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableSample {
+    pub method: String,
+    pub percentage: Expression,
+    pub repeatable: Option<Expression>,
+}
+
+/// The parenthesized part of `PIVOT (sum(amount) FOR month IN ('JAN', 'FEB'))`, as used by T-SQL and Snowflake.
+/// 1. `aggregate` – The aggregate computed for every new column, `sum(amount)`.
+/// 2. `column` – The column whose values become the new columns, `month`.
+/// 3. `values` – The values of `column` that get a column of their own, in order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PivotClause {
+    pub aggregate: Expression,
+    pub column: Expression,
+    pub values: Vec<Expression>,
+}
+
+/// The parenthesized part of `UNPIVOT (amount FOR month IN (jan, feb))`, the reverse of a pivot.
+/// 1. `value_column` – The new column holding the values of the unpivoted columns, `amount`.
+/// 2. `name_column` – The new column holding the name of the column each value came from, `month`.
+/// 3. `columns` – The columns turned into rows.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnpivotClause {
+    pub value_column: String,
+    pub name_column: String,
+    pub columns: Vec<String>,
+}
+
+/// One item of a `GROUP BY` clause. Besides plain expressions, the clause accepts constructs that group by several column sets at once, which are kept as structured variants so that consumers can tell them apart from a flat list:
+/// * `Rollup` – `ROLLUP (a, b)` groups by `(a, b)`, `(a)` and `()`
+/// * `Cube` – `CUBE (a, b)` groups by every subset of its elements
+/// * `GroupingSets` – `GROUPING SETS ((a, b), (a), ())` groups by exactly the listed sets
+///
+/// Each element of these lists is itself a list of expressions, because `ROLLUP (a, (b, c))` treats `(b, c)` as one unit and grouping sets may be empty.
+#[derive(Debug, PartialEq, Clone)]
+pub enum GroupingElement {
+    Expression(Expression),
+    Rollup(Vec<Vec<Expression>>),
+    Cube(Vec<Vec<Expression>>),
+    GroupingSets(Vec<Vec<Expression>>),
+}
+
+/// The kind of a join between two table references.
+#[derive(Debug, PartialEq, Clone)]
+pub enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+/// The kind of schema object a `DROP` statement removes.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ObjectType {
+    Table,
+    Index,
+    View,
+}
+
+/// How a `Statement::SetOperation` combines the rows of its two queries: all rows of both, the rows that are in both, or the rows of the left one that are not in the right one.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl SetOperator {
+    //binding power, a higher number binds tighter
+    pub fn precedence(&self) -> u8 {
+        match self {
+            SetOperator::Union | SetOperator::Except => 1,
+            SetOperator::Intersect => 2,
+        }
+    }
+}
+
+/// When a trigger runs, before or after the row is changed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TriggerTiming {
+    Before,
+    After,
+}
+
+/// The change to a row that runs a trigger.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TriggerEvent {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Whether `COPY` loads a table from a file (`FROM`) or writes it to one (`TO`).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CopyDirection {
+    From,
+    To,
+}
+
+/// An option of `COPY ... WITH (...)`.
+/// 1. `name` – The name of the option in upper case, such as `FORMAT` or `DELIMITER`.
+/// 2. `value` – The value as text, `None` for an option given without one (`HEADER`).
+#[derive(Debug, PartialEq, Clone)]
+pub struct CopyOption {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// What `LOAD DATA` does with a row whose key is already in the table: `REPLACE` it or `IGNORE` the new one.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DuplicateHandling {
+    Replace,
+    Ignore,
+}
+
+/// How `LOAD DATA` splits its file into rows and columns, `None` for what MySQL defaults to.
+/// 1. `fields_terminated_by` – What separates the columns, `FIELDS TERMINATED BY`.
+/// 2. `fields_enclosed_by` – The quote around values, `FIELDS ENCLOSED BY`.
+/// 3. `optionally_enclosed` – Whether only string values are quoted, `OPTIONALLY ENCLOSED BY`.
+/// 4. `fields_escaped_by` – The escape character, `FIELDS ESCAPED BY`.
+/// 5. `lines_starting_by` – The prefix of every row that is skipped, `LINES STARTING BY`.
+/// 6. `lines_terminated_by` – What ends a row, `LINES TERMINATED BY`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LoadFormat {
+    pub fields_terminated_by: Option<String>,
+    pub fields_enclosed_by: Option<String>,
+    pub optionally_enclosed: bool,
+    pub fields_escaped_by: Option<String>,
+    pub lines_starting_by: Option<String>,
+    pub lines_terminated_by: Option<String>,
+}
+
+/// What a `SHOW` statement lists.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ShowObject {
+    Tables,
+    Databases,
 }
 
 /// A structure containing a definition for one column, when creating a table.
 /// 1. `column_name` – A simple string, representing a name.
 /// 2. `column_type` – The type of the column. Types are defined in the `DBType` enum.
 /// 3.  `constraints` – A vector of constraints on the column. Types of constraints are defined in the `Constraint` enum.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct TableColumn {
     pub column_name: String,
     pub column_type: DBType,
     pub constraints: Vec<Constraint>,
 }
 
-/// A column in the database can be any of these types. `Int` and `Bool` types have no additional info, while the `Varchar(n)` type has an additional argument – the length of the string. Adding a type, such as `DECIMAL(n, m)` is boiled down to adding tokens for that type, parsing that type and adding it to this enum.
-#[derive(Debug, PartialEq)]
+/// An argument of `CREATE FUNCTION` or `CREATE PROCEDURE`.
+/// 1. `name` – The name of the argument, `None` when only its type is given.
+/// 2. `data_type` – The type of the argument.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionArg {
+    pub name: Option<String>,
+    pub data_type: DBType,
+}
+
+/// A column in the database can be any of these types. `Int` and `Bool` types have no additional info, the `Varchar(n)` type has an additional argument – the length of the string – `Decimal(p, s)` has the precision (total number of digits) and scale (digits after the decimal point), `Char(n)` is a string padded to `n` characters and `Timestamp` records whether the time zone is kept. Types are written under several names (`INTEGER`, `CHARACTER VARYING(n)`, `DOUBLE PRECISION`, `TIMESTAMP WITHOUT TIME ZONE`), adding a type is boiled down to adding it to this enum and its names to the parser's table of type names. `Custom(name, args)` is a type the parser does not know, a domain or a type from an extension such as `CITEXT` or `GEOGRAPHY(Point)`, with the expressions in parentheses after its name; the parser only reads it when asked to (`Parser::custom_types`).
+#[derive(Debug, PartialEq, Clone)]
 pub enum DBType {
     Int,
     Varchar(usize),
     Bool,
+    Decimal(usize, usize),
+    Double,
+    Char(usize),
+    Timestamp { with_time_zone: bool },
+    Custom(String, Vec<Expression>),
 }
 
 /// A column can be limited to a domain of values, which is defined by constraints on that column. `PrimaryKey` and `NotNull` constraints have no additional info, while the `Check` constraints has an additional argument – the expression which every table row must satisfy.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Constraint {
     NotNull,
     PrimaryKey,
     Check(Expression)
 }
 
-/// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
-#[derive(Debug, PartialEq)]
+/// What an `ALTER TABLE` statement changes about its table.
+/// 1. `AddColumn` – Adds a column, defined like a column of `CREATE TABLE`: `ALTER TABLE users ADD COLUMN email VARCHAR(255) NOT NULL` (the `COLUMN` keyword is optional).
+/// 2. `DropColumn` – Removes the named column: `ALTER TABLE users DROP COLUMN email` (`COLUMN` optional here too).
+/// 3. `RenameColumn` – Gives a column a new name: `ALTER TABLE users RENAME COLUMN email TO mail` (`COLUMN` optional).
+/// 4. `RenameTo` – Gives the table a new name: `ALTER TABLE users RENAME TO accounts`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AlterAction {
+    AddColumn(TableColumn),
+    DropColumn(String),
+    RenameColumn {
+        column_name: String,
+        new_name: String,
+    },
+    RenameTo(String),
+}
+
+/// One `old TO new` of a `RENAME TABLE` statement.
+/// 1. `old_name` – The table to rename, by the name it has when the pair is reached.
+/// 2. `new_name` – The name it gets.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableRename {
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// One column of a `CREATE INDEX` statement.
+/// 1. `name` – The column.
+/// 2. `descending` – Whether it is written with `DESC`, the index keeping its values from the highest down. `ASC` is the order without it and is not kept.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IndexColumn {
+    pub name: String,
+    pub descending: bool,
+}
+
+/// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-price`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
+///
+/// The `Json*` operators access JSON values: `->` and `->>` get a field (as JSON or as text), `#>` and `#>>` follow a path, `@>` and `<@` test containment. Like in Postgres they bind tighter than comparisons but looser than arithmetic, so `data->'age' > 18` compares the extracted field.
+///
+/// The `Regex*` operators match a string against a regular expression: `~` and `~*` (case insensitive) with their negations `!~` and `!~*` in Postgres, `REGEXP` and its synonym `RLIKE` in MySQL with `NOT REGEXP` and `NOT RLIKE`. How tightly they bind depends on the dialect: `BinaryOperator::precedence` puts them with the comparisons, and `Dialect::precedence` overrides that where a database differs (Postgres makes them ordinary operators, tighter than comparisons).
+///
+/// `Concat` is the standard string concatenation `||`. Operator precedence is not fixed here: the parser asks its `Dialect`, which starts from `BinaryOperator::precedence` and overrides it where a database differs (MySQL reads `||` as `OR`).
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum BinaryOperator {
     Plus,
     Minus,
@@ -420,16 +1556,116 @@ pub enum BinaryOperator {
     NotEqual,
     And,
     Or,
+    JsonGet,
+    JsonGetText,
+    JsonPath,
+    JsonPathText,
+    JsonContains,
+    JsonContainedBy,
+    RegexMatch,
+    RegexIMatch,
+    RegexNotMatch,
+    RegexNotIMatch,
+    Regexp,
+    Rlike,
+    NotRegexp,
+    NotRlike,
+    Concat,
 }
 
-/// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
-#[derive(Debug, PartialEq)]
+/// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-price`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum UnaryOperator {
     Not,
     Plus,
     Minus,
     Asc,
     Desc,
+    //postfix IS NULL and IS NOT NULL, true or false even for a NULL operand
+    IsNull,
+    IsNotNull,
+}
+
+/// Binding powers of the expression grammar, a higher number binds tighter. Operators take their precedence from these levels through `BinaryOperator::precedence` and `UnaryOperator::precedence` instead of spelling out numbers, so operators on the same level can never drift apart. These are the standard levels, `Dialect::precedence` adjusts them per dialect.
+pub mod precedence {
+    //ASC and DESC, applied to a whole ORDER BY item
+    pub const ORDERING: u8 = 5;
+    pub const OR: u8 = 10;
+    pub const AND: u8 = 15;
+    //unary NOT, below comparisons so that NOT a = 1 means NOT (a = 1)
+    pub const NOT: u8 = 17;
+    //comparisons, IS [NOT] DISTINCT FROM, IS [NOT] NULL and regular expression matches
+    pub const COMPARISON: u8 = 20;
+    //JSON operators, || and other symbolic operators
+    pub const OTHER: u8 = 22;
+    pub const ADDITIVE: u8 = 25;
+    pub const MULTIPLICATIVE: u8 = 30;
+    //unary + and -
+    pub const PREFIX: u8 = 100;
+}
+
+/// How a chain of operators with the same precedence groups: `a - b - c` is `(a - b) - c` because `-` is left associative.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// The family a binary operator belongs to, for code that treats whole groups of operators alike (e.g. everything that produces a boolean).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OperatorCategory {
+    Arithmetic,
+    Comparison,
+    Logical,
+    Json,
+    Regex,
+    String,
+}
+
+impl BinaryOperator {
+    pub fn category(&self) -> OperatorCategory {
+        match self {
+            BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Multiply | BinaryOperator::Divide => OperatorCategory::Arithmetic,
+            BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqual | BinaryOperator::Equal | BinaryOperator::NotEqual => OperatorCategory::Comparison,
+            BinaryOperator::And | BinaryOperator::Or => OperatorCategory::Logical,
+            BinaryOperator::JsonGet | BinaryOperator::JsonGetText | BinaryOperator::JsonPath
+            | BinaryOperator::JsonPathText | BinaryOperator::JsonContains | BinaryOperator::JsonContainedBy => OperatorCategory::Json,
+            BinaryOperator::RegexMatch | BinaryOperator::RegexIMatch | BinaryOperator::RegexNotMatch
+            | BinaryOperator::RegexNotIMatch | BinaryOperator::Regexp | BinaryOperator::Rlike
+            | BinaryOperator::NotRegexp | BinaryOperator::NotRlike => OperatorCategory::Regex,
+            BinaryOperator::Concat => OperatorCategory::String,
+        }
+    }
+
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Multiply | BinaryOperator::Divide => precedence::MULTIPLICATIVE,
+            BinaryOperator::Plus | BinaryOperator::Minus => precedence::ADDITIVE,
+            BinaryOperator::And => precedence::AND,
+            BinaryOperator::Or => precedence::OR,
+            _ => match self.category() {
+                OperatorCategory::Json | OperatorCategory::String => precedence::OTHER,
+                _ => precedence::COMPARISON,
+            },
+        }
+    }
+
+    //every binary operator in this grammar groups to the left
+    pub fn associativity(&self) -> Associativity {
+        Associativity::Left
+    }
+}
+
+impl UnaryOperator {
+    pub fn precedence(&self) -> u8 {
+        match self {
+            UnaryOperator::Not => precedence::NOT,
+            UnaryOperator::Plus | UnaryOperator::Minus => precedence::PREFIX,
+            UnaryOperator::Asc | UnaryOperator::Desc => precedence::ORDERING,
+            UnaryOperator::IsNull | UnaryOperator::IsNotNull => precedence::COMPARISON,
+        }
+    }
 }
 
 // Example manual implementations for Display traits.
@@ -445,6 +1681,77 @@ impl Display for UnaryOperator {
             UnaryOperator::Desc => write!(f, "DESC"),
             UnaryOperator::Asc => write!(f, "ASC"),
             UnaryOperator::Not => write!(f, "NOT"),
+            UnaryOperator::IsNull => write!(f, "IS NULL"),
+            UnaryOperator::IsNotNull => write!(f, "IS NOT NULL"),
+        }
+    }
+}
+
+impl Display for JoinKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinKind::Inner => write!(f, "JOIN"),
+            JoinKind::Left => write!(f, "LEFT JOIN"),
+            JoinKind::Right => write!(f, "RIGHT JOIN"),
+            JoinKind::Full => write!(f, "FULL JOIN"),
+            JoinKind::Cross => write!(f, "CROSS JOIN"),
+        }
+    }
+}
+
+impl Display for ObjectType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectType::Table => write!(f, "TABLE"),
+            ObjectType::Index => write!(f, "INDEX"),
+            ObjectType::View => write!(f, "VIEW"),
+        }
+    }
+}
+
+impl Display for SetOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl Display for TriggerTiming {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriggerTiming::Before => write!(f, "BEFORE"),
+            TriggerTiming::After => write!(f, "AFTER"),
+        }
+    }
+}
+
+impl Display for CopyDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CopyDirection::From => write!(f, "FROM"),
+            CopyDirection::To => write!(f, "TO"),
+        }
+    }
+}
+
+impl Display for DuplicateHandling {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuplicateHandling::Replace => write!(f, "REPLACE"),
+            DuplicateHandling::Ignore => write!(f, "IGNORE"),
+        }
+    }
+}
+
+impl Display for TriggerEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriggerEvent::Insert => write!(f, "INSERT"),
+            TriggerEvent::Update => write!(f, "UPDATE"),
+            TriggerEvent::Delete => write!(f, "DELETE"),
         }
     }
 }
@@ -464,6 +1771,21 @@ impl Display for BinaryOperator {
             BinaryOperator::Plus => write!(f, "+"),
             BinaryOperator::And => write!(f, "AND"),
             BinaryOperator::Or => write!(f, "OR"),
+            BinaryOperator::JsonGet => write!(f, "->"),
+            BinaryOperator::JsonGetText => write!(f, "->>"),
+            BinaryOperator::JsonPath => write!(f, "#>"),
+            BinaryOperator::JsonPathText => write!(f, "#>>"),
+            BinaryOperator::JsonContains => write!(f, "@>"),
+            BinaryOperator::JsonContainedBy => write!(f, "<@"),
+            BinaryOperator::RegexMatch => write!(f, "~"),
+            BinaryOperator::RegexIMatch => write!(f, "~*"),
+            BinaryOperator::RegexNotMatch => write!(f, "!~"),
+            BinaryOperator::RegexNotIMatch => write!(f, "!~*"),
+            BinaryOperator::Regexp => write!(f, "REGEXP"),
+            BinaryOperator::Rlike => write!(f, "RLIKE"),
+            BinaryOperator::NotRegexp => write!(f, "NOT REGEXP"),
+            BinaryOperator::NotRlike => write!(f, "NOT RLIKE"),
+            BinaryOperator::Concat => write!(f, "||"),
         }
     }
 }
@@ -477,10 +1799,38 @@ impl Display for Expression {
             Expression::UnaryOperation { operand, operator } => {
                 write!(f, "({:?} {:?})", operator, operand)
             }
-            Expression::Number(num) => write!(f, "{num}"),
+            Expression::IsDistinctFrom { left_operand, right_operand, negated } => {
+                let not = if *negated { " NOT" } else { "" };
+                write!(f, "({:?} IS{} DISTINCT FROM {:?})", left_operand, not, right_operand)
+            }
+            Expression::Exists { subquery, negated } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "({}EXISTS {:?})", not, subquery)
+            }
+            Expression::Subquery(subquery) => write!(f, "({:?})", subquery),
+            Expression::Function { name, args, over: None } => write!(f, "{}({:?})", name, args),
+            Expression::Function { name, args, over: Some(window) } => write!(f, "{}({:?}) OVER {:?}", name, args, window),
+            Expression::Alias { expr, alias } => write!(f, "({:?} AS {})", expr, alias),
+            Expression::Wildcard => write!(f, "*"),
+            Expression::Literal(literal) => write!(f, "{}", literal),
+            Expression::Placeholder(text) => write!(f, "{}", text),
             Expression::Identifier(iden) => write!(f, "{}", iden),
-            Expression::String(str) => write!(f, "\"{}\"", str),
-            Expression::Bool(b) => write!(f, "{}", b)
+            Expression::CompoundIdentifier(parts) => write!(f, "{}", parts.join(".")),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Integer(num) => write!(f, "{num}"),
+            Literal::Decimal(num) => write!(f, "{}", num),
+            Literal::String(str) => write!(f, "\"{}\"", str),
+            Literal::NationalString(str) => write!(f, "N\"{}\"", str),
+            Literal::BitString(bits) => write!(f, "B\"{}\"", bits),
+            Literal::HexString(hex) => write!(f, "X\"{}\"", hex),
+            Literal::Boolean(b) => write!(f, "{}", b),
+            Literal::Null => write!(f, "NULL")
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,137 @@
+//draws rows of text as a table with borders, for the results of the engine and other listings of the command line
+use crate::statement::Literal;
+use crate::engine::{ResultSet, value_text};
+
+/// Which side of its column a cell is written against, numbers read best on the right.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// The characters the borders are drawn with: `+`, `-` and `|`, or the Unicode box-drawing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Border {
+    #[default]
+    Ascii,
+    Unicode,
+}
+
+/// How the rows of a query are written out: drawn as a table, or exported as CSV or JSON (see `ResultSet::to_csv` and `ResultSet::to_json`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ResultFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+impl ResultFormat {
+    pub fn from_name(name: &str) -> Option<ResultFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "table" => Some(ResultFormat::Table),
+            "csv" => Some(ResultFormat::Csv),
+            "json" => Some(ResultFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// How a table is drawn.
+/// 1. `border` – The characters of the borders.
+/// 2. `max_width` – The widest a column can be, in characters; longer cells are cut and end with `...` (`…` with Unicode borders). `None` keeps every cell whole.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TableStyle {
+    pub border: Border,
+    pub max_width: Option<usize>,
+}
+
+//the characters of one kind of border: horizontal line, vertical line, then the corners and joints of the top, middle and bottom lines (left, inner, right)
+struct Lines {
+    horizontal: &'static str,
+    vertical: &'static str,
+    top: [&'static str; 3],
+    middle: [&'static str; 3],
+    bottom: [&'static str; 3],
+    ellipsis: &'static str,
+}
+
+const ASCII: Lines = Lines { horizontal: "-", vertical: "|", top: ["+", "+", "+"], middle: ["+", "+", "+"], bottom: ["+", "+", "+"], ellipsis: "..." };
+const UNICODE: Lines = Lines { horizontal: "─", vertical: "│", top: ["┌", "┬", "┐"], middle: ["├", "┼", "┤"], bottom: ["└", "┴", "┘"], ellipsis: "…" };
+
+/// Draws a table with a header line: one `String` per column in `headers` and in every row, and one `Align` per column (columns without one are aligned left). Each column is as wide as its widest cell or header, up to `style.max_width`. The result ends with a newline.
+pub fn render_table(headers: &[String], rows: &[Vec<String>], align: &[Align], style: &TableStyle) -> String {
+    let lines = match style.border {
+        Border::Ascii => &ASCII,
+        Border::Unicode => &UNICODE,
+    };
+    let headers: Vec<String> = headers.iter().map(|cell| truncate(cell, style.max_width, lines.ellipsis)).collect();
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| row.iter().map(|cell| truncate(cell, style.max_width, lines.ellipsis)).collect()).collect();
+    let widths: Vec<usize> = headers.iter().enumerate()
+        .map(|(i, header)| rows.iter().filter_map(|row| row.get(i)).map(|cell| cell.chars().count()).fold(header.chars().count(), usize::max))
+        .collect();
+
+    let mut out = String::new();
+    out.push_str(&rule(&widths, lines.horizontal, lines.top));
+    out.push_str(&row_line(&headers, &widths, &[], lines.vertical));
+    out.push_str(&rule(&widths, lines.horizontal, lines.middle));
+    for row in &rows {
+        out.push_str(&row_line(row, &widths, align, lines.vertical));
+    }
+    out.push_str(&rule(&widths, lines.horizontal, lines.bottom));
+    out
+}
+
+/// Draws the rows of a query. A column whose values are all numbers (or `NULL`) is aligned right, any other column left; strings are written without their quotes.
+pub fn render_result(result: &ResultSet, style: &TableStyle) -> String {
+    let align: Vec<Align> = (0..result.columns.len())
+        .map(|i| {
+            let numeric = result.rows.iter().all(|row| matches!(row.get(i), Some(Literal::Integer(_)) | Some(Literal::Decimal(_)) | Some(Literal::Null) | None));
+            if numeric && !result.rows.is_empty() { Align::Right } else { Align::Left }
+        })
+        .collect();
+    let rows: Vec<Vec<String>> = result.rows.iter().map(|row| row.iter().map(value_text).collect()).collect();
+    render_table(&result.columns, &rows, &align, style)
+}
+
+/// The rows of a query in the given format, ending with a newline. `style` only matters for `ResultFormat::Table`.
+pub fn render_result_as(result: &ResultSet, format: ResultFormat, style: &TableStyle) -> String {
+    match format {
+        ResultFormat::Table => render_result(result, style),
+        ResultFormat::Csv => result.to_csv(),
+        ResultFormat::Json => format!("{}\n", result.to_json().to_pretty_string()),
+    }
+}
+
+//helper, cut a cell down to the widest a column can be, the ellipsis counting towards it
+fn truncate(cell: &str, max_width: Option<usize>, ellipsis: &str) -> String {
+    let max = match max_width {
+        Some(max) if cell.chars().count() > max => max,
+        _ => return cell.to_string(),
+    };
+    let kept = max.saturating_sub(ellipsis.chars().count());
+    let mut cut: String = cell.chars().take(kept).collect();
+    //a column narrower than the ellipsis gets as much of it as fits
+    cut.extend(ellipsis.chars().take(max - kept));
+    cut
+}
+
+//helper, a horizontal line across all columns with the given corners and joints
+fn rule(widths: &[usize], horizontal: &str, joints: [&str; 3]) -> String {
+    let segments: Vec<String> = widths.iter().map(|width| horizontal.repeat(width + 2)).collect();
+    format!("{}{}{}\n", joints[0], segments.join(joints[1]), joints[2])
+}
+
+//helper, one line of cells padded to their column widths
+fn row_line(cells: &[String], widths: &[usize], align: &[Align], vertical: &str) -> String {
+    let padded: Vec<String> = widths.iter().enumerate()
+        .map(|(i, width)| {
+            let cell = cells.get(i).map(String::as_str).unwrap_or("");
+            match align.get(i) {
+                Some(Align::Right) => format!(" {:>1$} ", cell, width),
+                _ => format!(" {:<1$} ", cell, width),
+            }
+        })
+        .collect();
+    format!("{}{}{}\n", vertical, padded.join(vertical), vertical)
+}
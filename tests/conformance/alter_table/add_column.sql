@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN email VARCHAR(255) NOT NULL;
+ALTER TABLE users ADD active BOOL CHECK (active OR active = FALSE);
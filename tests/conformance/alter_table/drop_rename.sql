@@ -0,0 +1,5 @@
+ALTER TABLE users DROP COLUMN legacy_id;
+ALTER TABLE users DROP nickname;
+ALTER TABLE users RENAME COLUMN mail TO email;
+ALTER TABLE users RENAME to TO recipient;
+ALTER TABLE users RENAME TO accounts;
@@ -0,0 +1 @@
+SELECT 'it''s', 'C:\temp\new' FROM notes;
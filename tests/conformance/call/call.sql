@@ -0,0 +1,3 @@
+CALL archive_orders(2023, 'closed');
+CALL refresh_stats;
+CALL recompute((SELECT max(id) FROM orders), ?);
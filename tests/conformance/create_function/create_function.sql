@@ -0,0 +1,3 @@
+CREATE OR REPLACE FUNCTION add_tax(amount DECIMAL(10, 2)) RETURNS DECIMAL(10, 2) AS 'SELECT amount * 1.2' LANGUAGE sql;
+CREATE FUNCTION is_adult(INT) RETURNS BOOL LANGUAGE sql AS 'SELECT $1 >= 18';
+CREATE PROCEDURE archive_orders() AS 'DELETE FROM orders WHERE shipped' LANGUAGE 'sql';
@@ -0,0 +1,3 @@
+CREATE INDEX orders_customer ON orders (customer_id);
+CREATE UNIQUE INDEX IF NOT EXISTS users_email ON users (email, tenant_id);
+CREATE INDEX events_recent ON events (created_at DESC, kind ASC);
@@ -0,0 +1,4 @@
+CREATE DATABASE shop;
+CREATE SCHEMA IF NOT EXISTS reporting;
+CREATE DATABASE IF NOT EXISTS if;
+CREATE SCHEMA if;
@@ -0,0 +1,2 @@
+CREATE TABLE adults AS SELECT id, email FROM complex_table WHERE age >= 18;
+CREATE TABLE IF NOT EXISTS totals AS SELECT customer_id, sum(amount) AS total FROM orders GROUP BY customer_id;
@@ -0,0 +1,6 @@
+CREATE TABLE complex_table(
+    id INT PRIMARY KEY,
+    email VARCHAR(255) NOT NULL,
+    is_junior BOOL,
+    age INT CHECK(age >= 18) CHECK(age <= 65)
+);
@@ -0,0 +1,5 @@
+CREATE TABLE prices (
+    id INT PRIMARY KEY,
+    amount DECIMAL(10, 2) NOT NULL,
+    ratio DECIMAL(5)
+);
@@ -0,0 +1,2 @@
+CREATE TABLE IF NOT EXISTS sessions (id INT PRIMARY KEY, token VARCHAR(64) NOT NULL);
+CREATE TABLE IF NOT EXISTS if (a INT);
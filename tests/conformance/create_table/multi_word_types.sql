@@ -0,0 +1,11 @@
+CREATE TABLE readings (
+    id INTEGER PRIMARY KEY,
+    label CHARACTER VARYING(20),
+    code CHAR(3),
+    flag CHARACTER,
+    value DOUBLE PRECISION,
+    exact NUMERIC(12, 4),
+    active BOOLEAN NOT NULL,
+    taken_at TIMESTAMP WITHOUT TIME ZONE,
+    logged_at TIMESTAMP WITH TIME ZONE
+);
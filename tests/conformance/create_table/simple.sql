@@ -0,0 +1,5 @@
+CREATE TABLE simple_table(
+    int_col INT,
+    string_col VARCHAR(255),
+    bool_col BOOL
+);
@@ -0,0 +1,3 @@
+CREATE TEMP TABLE staging (id INT, amount DECIMAL(10, 2));
+CREATE TEMPORARY TABLE IF NOT EXISTS recent AS SELECT id FROM orders WHERE created_at > '2024-01-01';
+CREATE TABLE temp (temporary INT);
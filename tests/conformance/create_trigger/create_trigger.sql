@@ -0,0 +1,5 @@
+CREATE TRIGGER audit_orders AFTER INSERT ON orders FOR EACH ROW INSERT INTO audit (order_id, note) VALUES (NEW.id, 'created');
+CREATE TRIGGER check_stock BEFORE UPDATE ON items FOR EACH ROW
+BEGIN
+    IF NEW.stock < 0 THEN SET NEW.stock = 0; END IF;
+END;
@@ -0,0 +1,3 @@
+DELETE FROM users WHERE id = 5;
+DELETE FROM sessions;
+DELETE FROM orders WHERE status = 'cancelled' AND NOT EXISTS (SELECT id FROM invoices WHERE invoices.order_id = orders.id);
@@ -0,0 +1,3 @@
+DROP TABLE sessions;
+DROP TABLE IF EXISTS sessions, tokens;
+DROP TABLE if;
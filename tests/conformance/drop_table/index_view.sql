@@ -0,0 +1,3 @@
+DROP INDEX idx_users_email;
+DROP VIEW IF EXISTS active_users, recent_orders;
+DROP INDEX IF EXISTS `index`, view;
@@ -0,0 +1 @@
+SELECT a AS FROM t;
@@ -0,0 +1 @@
+COPY users FROM STDOUT;
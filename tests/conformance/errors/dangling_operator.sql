@@ -0,0 +1 @@
+SELECT 5 * 3 - 4 + c / (13 -) FROM t;
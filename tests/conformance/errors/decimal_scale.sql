@@ -0,0 +1 @@
+CREATE TABLE t (x DECIMAL(3, 4));
@@ -0,0 +1 @@
+WITH a AS (SELECT 1 FROM t), a AS (SELECT 2 FROM t) SELECT 1 FROM a;
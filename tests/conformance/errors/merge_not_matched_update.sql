@@ -0,0 +1 @@
+MERGE INTO t USING s ON t.id = s.id WHEN NOT MATCHED THEN UPDATE SET n = s.n;
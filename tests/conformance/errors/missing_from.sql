@@ -0,0 +1 @@
+SELECT salary WHERE salary > 1000;
@@ -0,0 +1 @@
+CREATE TABLE work_hours(num_hours INT)
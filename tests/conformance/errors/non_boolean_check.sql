@@ -0,0 +1 @@
+CREATE TABLE users (name VARCHAR(20) CHECK ('yes'));
@@ -0,0 +1 @@
+SELECT id FROM users WHERE active AND 1 + 1;
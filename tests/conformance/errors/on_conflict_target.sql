@@ -0,0 +1 @@
+INSERT INTO tags VALUES (1) ON CONFLICT DO UPDATE SET n = 2;
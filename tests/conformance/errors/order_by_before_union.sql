@@ -0,0 +1 @@
+SELECT id FROM users ORDER BY id UNION SELECT user_id FROM orders;
@@ -0,0 +1 @@
+PREPARE q AS DROP TABLE orders;
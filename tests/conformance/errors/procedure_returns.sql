@@ -0,0 +1 @@
+CREATE PROCEDURE archive_orders() RETURNS INT AS 'DELETE FROM orders' LANGUAGE sql;
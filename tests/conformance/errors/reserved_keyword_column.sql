@@ -0,0 +1 @@
+SELECT id, select FROM t;
@@ -0,0 +1 @@
+CREATE TRIGGER audit_orders AFTER INSERT ON orders FOR EACH ROW;
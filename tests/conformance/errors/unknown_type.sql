@@ -0,0 +1,3 @@
+CREATE TABLE users (
+    email CITEXT NOT NULL
+);
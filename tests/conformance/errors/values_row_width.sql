@@ -0,0 +1 @@
+VALUES (1, 'a'), (2);
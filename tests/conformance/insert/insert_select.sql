@@ -0,0 +1,4 @@
+INSERT INTO archive (id, name) SELECT id, name FROM users WHERE active = FALSE;
+INSERT INTO totals SELECT region, SUM(amount) FROM orders GROUP BY region UNION ALL SELECT 'all', SUM(amount) FROM orders;
+INSERT INTO recent (id) WITH latest AS (SELECT id FROM events ORDER BY id DESC LIMIT 10) SELECT id FROM latest;
+INSERT INTO pairs (a, b) (VALUES (1, 2), (3, 4));
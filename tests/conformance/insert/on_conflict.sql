@@ -0,0 +1,2 @@
+INSERT INTO users (id, name) VALUES (1, 'Harry') ON CONFLICT (id) DO UPDATE SET name = excluded.name, visits = users.visits + 1 WHERE users.active = TRUE;
+INSERT INTO tags VALUES ('sql') ON CONFLICT DO NOTHING;
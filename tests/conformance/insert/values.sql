@@ -0,0 +1,2 @@
+INSERT INTO users (id, name) VALUES (1, 'Harry'), (2, NULL);
+INSERT INTO scores VALUES (-1, 2.5 * 2, (SELECT max(points) FROM games));
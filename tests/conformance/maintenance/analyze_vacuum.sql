@@ -0,0 +1,6 @@
+ANALYZE;
+ANALYZE users;
+VACUUM;
+VACUUM FULL;
+VACUUM FULL users;
+vacuum orders;
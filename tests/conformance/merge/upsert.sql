@@ -0,0 +1,7 @@
+MERGE INTO customers c USING staged s ON c.id = s.id
+WHEN MATCHED AND s.deleted THEN DELETE
+WHEN MATCHED THEN UPDATE SET name = s.name
+WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name);
+MERGE INTO totals USING (SELECT id, sum(amount) AS amount FROM orders GROUP BY id) AS o ON totals.id = o.id
+WHEN NOT MATCHED THEN INSERT VALUES (o.id, o.amount)
+WHEN MATCHED THEN DO NOTHING;
@@ -0,0 +1 @@
+SELECT 'c\\', 'a\\b', 'tab\there' FROM t;
@@ -0,0 +1 @@
+SELECT 'bad \u12' FROM notes;
@@ -0,0 +1 @@
+SELECT 'it''s', 'line\nbreak', 'caf\u00e9', 'tab\tstop', '100\%' FROM notes;
@@ -0,0 +1,6 @@
+LOAD DATA LOCAL INFILE '/data/users.csv' REPLACE INTO TABLE users
+  FIELDS TERMINATED BY ',' OPTIONALLY ENCLOSED BY '"' ESCAPED BY '\\'
+  LINES TERMINATED BY '\n'
+  IGNORE 1 LINES
+  (id, name);
+LOAD DATA INFILE 'orders.tsv' INTO TABLE orders;
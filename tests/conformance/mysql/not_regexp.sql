@@ -0,0 +1,2 @@
+SELECT name FROM users WHERE name NOT REGEXP '^J' AND name not rlike 'son$';
+SELECT title FROM books WHERE NOT title NOT REGEXP 'draft' OR title = 'x';
@@ -0,0 +1 @@
+SELECT `order`.`select`, "a string" FROM `order` AS `group`;
@@ -0,0 +1,2 @@
+SELECT name FROM users WHERE name REGEXP '^J' OR name RLIKE 'son$';
+SELECT title FROM books WHERE NOT title regexp 'draft';
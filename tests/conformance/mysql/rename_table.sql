@@ -0,0 +1,2 @@
+RENAME TABLE users TO accounts;
+RENAME TABLE `order` TO orders, orders_archive TO `order`, tmp TO to;
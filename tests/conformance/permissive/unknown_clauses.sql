@@ -0,0 +1,8 @@
+CREATE TABLE users (
+    id INT PRIMARY KEY,
+    email CITEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT now(),
+    UNIQUE (email)
+) ENGINE = InnoDB;
+INSERT INTO users (id, email) VALUES (1, 'a@example.com') RETURNING id;
+SELECT id FROM users;
@@ -0,0 +1,5 @@
+CREATE EXTENSION IF NOT EXISTS citext;
+SELECT id FROM users;
+GRANT SELECT ON users TO reporting;
+/*+ hint */ UPDATE users SET name = 'x;y' WHERE id = 1;
+COMMIT;
@@ -0,0 +1,3 @@
+COPY users (id, name) FROM '/data/users.csv' WITH (FORMAT csv, HEADER, DELIMITER ';', NULL '');
+COPY orders TO STDOUT (FORMAT 'binary');
+COPY staging FROM STDIN;
@@ -0,0 +1,5 @@
+CREATE FUNCTION touch() RETURNS INT LANGUAGE plpgsql AS $body$
+BEGIN
+    RETURN $$it's 'quoted'$$;
+END;
+$body$;
@@ -0,0 +1 @@
+SELECT "order".id, "Weird ""name""" AS "select" FROM "order" WHERE "group" = 1;
@@ -0,0 +1 @@
+SELECT data->'name', data->>'email', data#>'{a,b}', data#>>'{a,b}' FROM people WHERE data @> '{"admin":true}' AND tags <@ '["x"]' AND data->'profile'->>'age' > 18;
@@ -0,0 +1,2 @@
+/* outer /* inner */ still a comment */
+SELECT a /* x /* y */ z */ FROM t;
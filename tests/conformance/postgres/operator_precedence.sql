@@ -0,0 +1 @@
+SELECT first_name || ' ' || last_name FROM users WHERE flag = name ~ 'a';
@@ -0,0 +1 @@
+SELECT N'naïve', E'it\'s\n', B'0101', X'1F', n'lower', name FROM notes WHERE e = x;
@@ -0,0 +1,12 @@
+PREPARE recent_orders (INT, VARCHAR(20)) AS
+SELECT id, total FROM orders WHERE customer_id = $1 AND status = $2;
+
+PREPARE add_order AS INSERT INTO orders (id, customer_id) VALUES ($1, $2);
+
+EXECUTE recent_orders(42, 'open');
+
+EXECUTE add_order (1, 42);
+
+DEALLOCATE recent_orders;
+
+DEALLOCATE PREPARE ALL;
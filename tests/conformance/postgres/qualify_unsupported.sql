@@ -0,0 +1 @@
+SELECT id FROM employees QUALIFY row_number() OVER (PARTITION BY department ORDER BY id) = 1;
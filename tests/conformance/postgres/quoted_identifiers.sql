@@ -0,0 +1 @@
+SELECT "order"."select", "Weird ""name""" AS "group" FROM "order";
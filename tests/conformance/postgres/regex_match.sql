@@ -0,0 +1,2 @@
+SELECT name FROM users WHERE name ~ '^J' AND email !~* '@example\.com$';
+SELECT regexp FROM patterns WHERE regexp ~* 'abc' OR regexp !~ 'x';
@@ -0,0 +1 @@
+CREATE TABLE users (id INT, from VARCHAR(10));
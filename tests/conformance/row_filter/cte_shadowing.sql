@@ -0,0 +1,2 @@
+-- row filter on orders: tenant_id = ?
+WITH orders AS (SELECT id, total FROM orders WHERE total > 10) SELECT o.id FROM orders o WHERE o.total < 100;
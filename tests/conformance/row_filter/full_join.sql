@@ -0,0 +1,2 @@
+-- row filter on orders, customers: tenant_id = ?
+SELECT o.id, c.name FROM orders o FULL JOIN customers c ON o.customer_id = c.id WHERE o.total > 10;
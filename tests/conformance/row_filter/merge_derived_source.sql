@@ -0,0 +1,3 @@
+-- row filter on payments: tenant_id = ?
+MERGE INTO ledger l USING (SELECT order_id, sum(amount) AS paid FROM payments GROUP BY order_id) AS p ON l.order_id = p.order_id
+WHEN MATCHED THEN UPDATE SET paid = p.paid;
@@ -0,0 +1,3 @@
+-- row filter on orders, payments: tenant_id = ?
+MERGE INTO orders USING payments p ON orders.id = p.order_id
+WHEN MATCHED THEN DELETE;
@@ -0,0 +1,4 @@
+-- row filter on orders: tenant_id = ?
+MERGE INTO orders o USING staged s ON o.id = s.id
+WHEN MATCHED THEN UPDATE SET total = s.total
+WHEN NOT MATCHED THEN INSERT (id, total) VALUES (s.id, s.total);
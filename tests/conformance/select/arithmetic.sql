@@ -0,0 +1 @@
+SELECT age * 5, 'this is a string' FROM users;
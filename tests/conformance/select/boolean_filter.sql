@@ -0,0 +1 @@
+SELECT name, surname FROM users WHERE name = "Voldemort" AND surname = 'Riddle';
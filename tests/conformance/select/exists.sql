@@ -0,0 +1,2 @@
+SELECT id FROM users WHERE EXISTS (SELECT 1 FROM orders WHERE orders_user = id) AND NOT EXISTS (SELECT 1 FROM bans WHERE bans_user = id);
+SELECT id FROM users WHERE NOT (EXISTS (SELECT 1 FROM orders));
@@ -0,0 +1,5 @@
+SELECT region, count(*) FROM sales GROUP BY region HAVING count(*) > 10 ORDER BY region;
+SELECT region, product, sum(amount) FROM sales GROUP BY ROLLUP (region, product);
+SELECT a, b, c FROM t GROUP BY x, CUBE (a, (b, c));
+SELECT a, b FROM t GROUP BY GROUPING SETS ((a, b), a, ());
+SELECT rollup, cube FROM t GROUP BY rollup, cube;
@@ -0,0 +1,4 @@
+-- hints are collected from /*+ ... */ comments around the SELECT keyword
+SELECT /*+ INDEX(orders idx_orders_date) NO_MERGE */ id FROM orders;
+/*+ MAX_EXECUTION_TIME(1000) */ SELECT id FROM orders; /* not a hint */
+SELECT a FROM t WHERE EXISTS (SELECT /*+ HASH_SJ */ b FROM u WHERE u.b = t.a);
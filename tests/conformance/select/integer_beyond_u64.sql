@@ -0,0 +1,3 @@
+SELECT id
+FROM orders
+WHERE total > 184467440737095516160 OR total < -184467440737095516160;
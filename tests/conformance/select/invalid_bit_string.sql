@@ -0,0 +1 @@
+SELECT B'0102' FROM notes;
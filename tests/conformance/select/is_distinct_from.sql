@@ -0,0 +1 @@
+SELECT a IS DISTINCT FROM b, c IS NOT DISTINCT FROM NULL FROM t WHERE x + 1 IS NOT DISTINCT FROM y AND z IS DISTINCT FROM 'q';
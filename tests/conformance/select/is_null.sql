@@ -0,0 +1 @@
+SELECT a IS NULL, b + 1 IS NOT NULL FROM t WHERE NOT c IS NULL AND d IS NOT NULL OR e = 1;
@@ -0,0 +1,2 @@
+SELECT c.name, o.total FROM customers c JOIN orders o ON o.customer_id = c.id LEFT OUTER JOIN refunds r ON r.order_id = o.id;
+SELECT * FROM a FULL JOIN (b INNER JOIN c ON b.id = c.id) ON a.id = b.id RIGHT JOIN d ON TRUE;
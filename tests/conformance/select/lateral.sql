@@ -0,0 +1,3 @@
+SELECT t.id, x.total FROM t, LATERAL (SELECT sum(o.amount) AS total FROM orders o WHERE o.customer = t.id) x;
+SELECT u.name, tag FROM users AS u CROSS JOIN LATERAL unnest(u.tags) AS tag;
+SELECT * FROM unnest(arr), generate_series(1, 10) g;
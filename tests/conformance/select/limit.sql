@@ -0,0 +1,2 @@
+SELECT id FROM orders ORDER BY id DESC LIMIT 10;
+SELECT id FROM orders LIMIT ?;
@@ -0,0 +1,2 @@
+SELECT key, partition AS repeatable FROM settings int WHERE int.key = 'theme';
+CREATE TABLE settings (key VARCHAR(40) PRIMARY KEY, varchar INT);
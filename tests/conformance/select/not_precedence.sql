@@ -0,0 +1,2 @@
+SELECT id FROM users WHERE NOT active = TRUE AND NOT deleted OR admin;
+SELECT id FROM users WHERE a OR b AND c;
@@ -0,0 +1,3 @@
+SELECT -5, 2.50, .5 * -1.25e-3, 3 - -2, -(7), 9223372036854775808
+FROM measurements
+WHERE reading > -0.5;
@@ -0,0 +1 @@
+SELECT id, salary FROM users ORDER BY salary - 2 * 10 ASC, id DESC;
@@ -0,0 +1,3 @@
+SELECT * FROM monthly_sales PIVOT (sum(amount) FOR month IN ('JAN', 'FEB', 'MAR')) AS p;
+SELECT * FROM quarterly UNPIVOT (sales FOR quarter IN (q1, q2, q3, q4)) u ORDER BY quarter;
+SELECT * FROM (orders JOIN customers ON orders.customer_id = customers.id) PIVOT (count(*) FOR region IN ('EU', 'US'));
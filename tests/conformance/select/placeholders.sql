@@ -0,0 +1 @@
+SELECT id FROM orders WHERE tenant_id = ? AND id > $1;
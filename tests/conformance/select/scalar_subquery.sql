@@ -0,0 +1,2 @@
+SELECT (SELECT max(x) FROM t2) AS m, count(*) total, (a + 1) FROM t1;
+SELECT * FROM t1 WHERE id = (SELECT min(id) FROM t2 WHERE now() > 0);
@@ -0,0 +1,3 @@
+SELECT id FROM users UNION SELECT user_id FROM orders;
+SELECT id FROM users UNION ALL SELECT id FROM admins INTERSECT SELECT user_id FROM orders;
+(SELECT id FROM users ORDER BY id LIMIT 10) EXCEPT DISTINCT SELECT user_id FROM orders ORDER BY 1 LIMIT 5;
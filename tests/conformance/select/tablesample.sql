@@ -0,0 +1,2 @@
+SELECT * FROM orders TABLESAMPLE SYSTEM (10);
+SELECT o.id FROM orders AS o TABLESAMPLE BERNOULLI (2 + 3) REPEATABLE (42) JOIN customers c ON c.id = o.customer;
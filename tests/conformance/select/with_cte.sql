@@ -0,0 +1,2 @@
+WITH RECURSIVE recent (id, total) AS (SELECT id, total FROM orders WHERE total > 100), big AS (SELECT id FROM recent)
+SELECT b.id FROM big AS b JOIN users AS u ON u.id = b.id;
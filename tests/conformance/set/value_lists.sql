@@ -0,0 +1,4 @@
+SET search_path TO public, app;
+SET TIME ZONE 'UTC';
+SET time zone LOCAL;
+SET datestyle = iso, on;
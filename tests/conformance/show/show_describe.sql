@@ -0,0 +1,4 @@
+SHOW TABLES;
+show databases;
+DESCRIBE users;
+DESC audit_log;
@@ -0,0 +1,3 @@
+SELECT id, name FROM employees QUALIFY row_number() OVER (PARTITION BY department ORDER BY salary DESC) = 1;
+SELECT department, count(*) FROM employees GROUP BY department HAVING count(*) > 5 QUALIFY rank() OVER (ORDER BY count(*) DESC) <= 3 ORDER BY department;
+SELECT sum(amount) OVER () AS total FROM orders;
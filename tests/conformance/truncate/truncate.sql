@@ -0,0 +1,2 @@
+TRUNCATE TABLE sessions;
+TRUNCATE audit_log;
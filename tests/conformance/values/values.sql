@@ -0,0 +1,5 @@
+VALUES (1, 'a'), (2, 'b');
+
+SELECT id FROM staff UNION ALL VALUES (0);
+
+SELECT name FROM users WHERE id = (VALUES (1));
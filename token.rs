@@ -1,11 +1,28 @@
 use std::fmt::{Debug, Display, Formatter};
+use crate::dialect::Dialect;
+use crate::statement::BinaryOperator;
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum Token {
     Keyword(Keyword),
     Identifier(String),
-    String(String),
+    //decoded value and the raw lexeme as written in the input (quotes and escapes included)
+    String { value: String, raw: String },
+    //prefixed string literals: N'...', E'...', B'...' and X'...'
+    NationalString { value: String, raw: String },
+    EscapeString { value: String, raw: String },
+    BitString { value: String, raw: String },
+    HexString { value: String, raw: String },
     Number(u64),
+    //a number with a fraction or an exponent, as written: 1.5, .5, 2e10
+    Decimal(String),
+    //query parameter, ? or $1, as written
+    Placeholder(String),
+    //text of an optimizer hint comment, /*+ ... */, only produced when the tokenizer keeps hints
+    Hint(String),
+    //text of a comment without its -- or /* */ and the comment as written, only produced when the tokenizer keeps comments
+    //a hint the tokenizer does not keep is a comment too, its raw text keeps the +
+    Comment { text: String, raw: String },
     Invalid(char),
     RightParentheses,
     LeftParentheses,
@@ -19,34 +36,243 @@ pub enum Token {
     Divide,
     Minus,
     Plus,
+    Arrow,
+    LongArrow,
+    HashArrow,
+    HashLongArrow,
+    AtArrow,
+    ArrowAt,
+    //regular expression match operators: ~, ~*, !~ and !~*
+    Tilde,
+    TildeStar,
+    NotTilde,
+    NotTildeStar,
+    DoublePipe,
     Comma,
+    Period,
     Semicolon,
     Eof,
 }
 
-#[derive(PartialEq, Clone, Debug)]
-pub enum Keyword {
-    Select,
-    Create,
-    Table,
-    Where,
-    Order,
-    By,
-    Asc,
-    Desc,
-    From,
-    And,
-    Or,
-    Not,
-    True,
-    False,
-    Primary,
-    Key,
-    Check,
-    Int,
-    Bool,
-    Varchar,
-    Null,
+//generates the `Keyword` enum and its lookup tables from one line per keyword:
+//variant, SQL text, class, and the dialects that have the keyword (an empty list means every dialect)
+macro_rules! keywords {
+    ($($variant:ident $text:literal $class:ident [$($dialect:ident),*];)*) => {
+        #[derive(PartialEq, Clone, Copy, Debug)]
+        pub enum Keyword {
+            $($variant,)*
+        }
+
+        impl Keyword {
+            //every keyword, in table order
+            pub const ALL: &'static [Keyword] = &[$(Keyword::$variant),*];
+
+            //keyword spelled by a word (case insensitive), regardless of dialect
+            pub fn lookup(word: &str) -> Option<Keyword> {
+                match word.to_uppercase().as_str() {
+                    $($text => Some(Keyword::$variant),)*
+                    _ => None,
+                }
+            }
+
+            //the keyword as written in SQL, in upper case
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Keyword::$variant => $text,)*
+                }
+            }
+
+            pub fn class(&self) -> KeywordClass {
+                match self {
+                    $(Keyword::$variant => KeywordClass::$class,)*
+                }
+            }
+
+            //whether the word is a keyword in the given dialect, elsewhere it is an ordinary name
+            pub fn in_dialect(&self, dialect: Dialect) -> bool {
+                match self {
+                    $(Keyword::$variant => {
+                        let dialects: &[Dialect] = &[$(Dialect::$dialect),*];
+                        dialects.is_empty() || dialects.contains(&dialect)
+                    })*
+                }
+            }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    $(Keyword::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+/// Whether a keyword can double as a name. `Reserved` keywords always have their keyword meaning, `NonReserved` ones are also accepted wherever the parser expects an identifier (a column called `key`, a table alias `partition`), as long as that position cannot be mistaken for the keyword itself.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum KeywordClass {
+    Reserved,
+    NonReserved,
+}
+
+impl Keyword {
+    pub fn is_reserved(&self) -> bool {
+        self.class() == KeywordClass::Reserved
+    }
+}
+
+keywords! {
+    Select      "SELECT"      Reserved    [];
+    Create      "CREATE"      Reserved    [];
+    Insert      "INSERT"      Reserved    [];
+    Into        "INTO"        Reserved    [];
+    Values      "VALUES"      Reserved    [];
+    Delete      "DELETE"      Reserved    [];
+    Drop        "DROP"        Reserved    [];
+    If          "IF"          NonReserved [];
+    Alter       "ALTER"       Reserved    [];
+    Add         "ADD"         NonReserved [];
+    Column      "COLUMN"      Reserved    [];
+    Rename      "RENAME"      NonReserved [];
+    To          "TO"          NonReserved [];
+    Truncate    "TRUNCATE"    NonReserved [];
+    Analyze     "ANALYZE"     NonReserved [];
+    Vacuum      "VACUUM"      NonReserved [];
+    Table       "TABLE"       Reserved    [];
+    Database    "DATABASE"    NonReserved [];
+    Schema      "SCHEMA"      NonReserved [];
+    Index       "INDEX"       NonReserved [];
+    Unique      "UNIQUE"      NonReserved [];
+    View        "VIEW"        NonReserved [];
+    Where       "WHERE"       Reserved    [];
+    Order       "ORDER"       Reserved    [];
+    Limit       "LIMIT"       Reserved    [];
+    By          "BY"          Reserved    [];
+    Asc         "ASC"         Reserved    [];
+    Desc        "DESC"        Reserved    [];
+    From        "FROM"        Reserved    [];
+    And         "AND"         Reserved    [];
+    Or          "OR"          Reserved    [];
+    Not         "NOT"         Reserved    [];
+    True        "TRUE"        Reserved    [];
+    False       "FALSE"       Reserved    [];
+    Primary     "PRIMARY"     Reserved    [];
+    Key         "KEY"         NonReserved [];
+    Check       "CHECK"       Reserved    [];
+    Int         "INT"         NonReserved [];
+    Bool        "BOOL"        NonReserved [];
+    Varchar     "VARCHAR"     NonReserved [];
+    Decimal     "DECIMAL"     NonReserved [];
+    Null        "NULL"        Reserved    [];
+    Is          "IS"          Reserved    [];
+    Distinct    "DISTINCT"    Reserved    [];
+    Exists      "EXISTS"      Reserved    [];
+    As          "AS"          Reserved    [];
+    Lateral     "LATERAL"     Reserved    [];
+    Join        "JOIN"        Reserved    [];
+    Inner       "INNER"       Reserved    [];
+    Left        "LEFT"        Reserved    [];
+    Right       "RIGHT"       Reserved    [];
+    Full        "FULL"        Reserved    [];
+    Outer       "OUTER"       Reserved    [];
+    Cross       "CROSS"       Reserved    [];
+    On          "ON"          Reserved    [];
+    Tablesample "TABLESAMPLE" Reserved    [];
+    Repeatable  "REPEATABLE"  NonReserved [];
+    Group       "GROUP"       Reserved    [];
+    Having      "HAVING"      Reserved    [];
+    Qualify     "QUALIFY"     Reserved    [];
+    Over        "OVER"        Reserved    [];
+    Partition   "PARTITION"   NonReserved [];
+    Pivot       "PIVOT"       Reserved    [];
+    Unpivot     "UNPIVOT"     Reserved    [];
+    For         "FOR"         Reserved    [];
+    In          "IN"          Reserved    [];
+    Regexp      "REGEXP"      Reserved    [Generic, MySql];
+    Rlike       "RLIKE"       Reserved    [Generic, MySql];
+    Conflict    "CONFLICT"    NonReserved [];
+    Do          "DO"          NonReserved [];
+    Nothing     "NOTHING"     NonReserved [];
+    Update      "UPDATE"      NonReserved [];
+    Set         "SET"         NonReserved [];
+    Show        "SHOW"        NonReserved [];
+    Tables      "TABLES"      NonReserved [];
+    Databases   "DATABASES"   NonReserved [];
+    Describe    "DESCRIBE"    NonReserved [];
+    With        "WITH"        Reserved    [];
+    Recursive   "RECURSIVE"   NonReserved [];
+    Union       "UNION"       Reserved    [];
+    Intersect   "INTERSECT"   Reserved    [];
+    Except      "EXCEPT"      Reserved    [];
+    All         "ALL"         Reserved    [];
+    Trigger     "TRIGGER"     NonReserved [];
+    Before      "BEFORE"      NonReserved [];
+    After       "AFTER"       NonReserved [];
+    Each        "EACH"        NonReserved [];
+    Row         "ROW"         NonReserved [];
+    Begin       "BEGIN"       NonReserved [];
+    End         "END"         NonReserved [];
+    Replace     "REPLACE"     NonReserved [];
+    Function    "FUNCTION"    NonReserved [];
+    Procedure   "PROCEDURE"   NonReserved [];
+    Returns     "RETURNS"     NonReserved [];
+    Language    "LANGUAGE"    NonReserved [];
+    Call        "CALL"        NonReserved [];
+    Copy        "COPY"        NonReserved [];
+    Load        "LOAD"        NonReserved [];
+    Prepare     "PREPARE"     NonReserved [];
+    Execute     "EXECUTE"     NonReserved [];
+    Deallocate  "DEALLOCATE"  NonReserved [];
+    Merge       "MERGE"       NonReserved [];
+    Using       "USING"       Reserved    [];
+}
+
+impl Token {
+    //how many BEGIN ... END blocks of a trigger body are open after this token, `depth` before it and `next` the token after it.
+    //BEGIN opens a block and so does CASE inside one, which ends with END as well; END closes one unless it is the END IF,
+    //END LOOP, END WHILE or END REPEAT of a statement in the block
+    pub fn block_depth(&self, depth: usize, next: &Token) -> usize {
+        let word = |tok: &Token, words: &[&str]| matches!(tok, Token::Identifier(name) if words.iter().any(|word| name.eq_ignore_ascii_case(word)));
+        match self {
+            Token::Keyword(Keyword::Begin) => depth + 1,
+            tok if depth > 0 && word(tok, &["case"]) => depth + 1,
+            Token::Keyword(Keyword::End) if *next != Token::Keyword(Keyword::If) && !word(next, &["loop", "while", "repeat"]) => depth.saturating_sub(1),
+            _ => depth,
+        }
+    }
+
+    //the binary operator a token stands for when it appears between two operands, in standard SQL
+    //dialects that read a token differently override this in Dialect::binary_operator
+    pub fn binary_operator(&self) -> Option<BinaryOperator> {
+        let operator = match self {
+            Token::Plus => BinaryOperator::Plus,
+            Token::Minus => BinaryOperator::Minus,
+            Token::Star => BinaryOperator::Multiply,
+            Token::Divide => BinaryOperator::Divide,
+            Token::GreaterThan => BinaryOperator::GreaterThan,
+            Token::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
+            Token::LessThan => BinaryOperator::LessThan,
+            Token::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
+            Token::Equal => BinaryOperator::Equal,
+            Token::NotEqual => BinaryOperator::NotEqual,
+            Token::Keyword(Keyword::And) => BinaryOperator::And,
+            Token::Keyword(Keyword::Or) => BinaryOperator::Or,
+            Token::Arrow => BinaryOperator::JsonGet,
+            Token::LongArrow => BinaryOperator::JsonGetText,
+            Token::HashArrow => BinaryOperator::JsonPath,
+            Token::HashLongArrow => BinaryOperator::JsonPathText,
+            Token::AtArrow => BinaryOperator::JsonContains,
+            Token::ArrowAt => BinaryOperator::JsonContainedBy,
+            Token::Tilde => BinaryOperator::RegexMatch,
+            Token::TildeStar => BinaryOperator::RegexIMatch,
+            Token::NotTilde => BinaryOperator::RegexNotMatch,
+            Token::NotTildeStar => BinaryOperator::RegexNotIMatch,
+            Token::Keyword(Keyword::Regexp) => BinaryOperator::Regexp,
+            Token::Keyword(Keyword::Rlike) => BinaryOperator::Rlike,
+            Token::DoublePipe => BinaryOperator::Concat,
+            _ => return None,
+        };
+        Some(operator)
+    }
 }
 
 impl Display for Token {
@@ -54,8 +280,14 @@ impl Display for Token {
         match self {
             Token::Keyword(keyword) => write!(f, "{}", keyword),
             Token::Identifier(iden) => write!(f, "{:?}", iden),
-            Token::String(str) => write!(f, "{:?}", str),
+            Token::String { value, .. } => write!(f, "{:?}", value),
+            Token::NationalString { raw, .. } | Token::EscapeString { raw, .. }
+            | Token::BitString { raw, .. } | Token::HexString { raw, .. } => write!(f, "{}", raw),
             Token::Number(num) => write!(f, "{:?}", num),
+            Token::Decimal(num) => write!(f, "{}", num),
+            Token::Placeholder(text) => write!(f, "{}", text),
+            Token::Hint(text) => write!(f, "/*+ {} */", text),
+            Token::Comment { raw, .. } => write!(f, "{}", raw),
             Token::RightParentheses => write!(f, "("),
             Token::LeftParentheses => write!(f, ")"),
             Token::GreaterThan => write!(f, ">"),
@@ -68,7 +300,19 @@ impl Display for Token {
             Token::Divide => write!(f, "/"),
             Token::Minus => write!(f, "-"),
             Token::Plus => write!(f, "+"),
+            Token::Arrow => write!(f, "->"),
+            Token::LongArrow => write!(f, "->>"),
+            Token::HashArrow => write!(f, "#>"),
+            Token::HashLongArrow => write!(f, "#>>"),
+            Token::AtArrow => write!(f, "@>"),
+            Token::ArrowAt => write!(f, "<@"),
+            Token::Tilde => write!(f, "~"),
+            Token::TildeStar => write!(f, "~*"),
+            Token::NotTilde => write!(f, "!~"),
+            Token::NotTildeStar => write!(f, "!~*"),
+            Token::DoublePipe => write!(f, "||"),
             Token::Comma => write!(f, ","),
+            Token::Period => write!(f, "."),
             Token::Semicolon => write!(f, ";"),
             Token::Eof => write!(f, "Eof"),
             Token::Invalid(c) => write!(f, "{}", c),
@@ -78,28 +322,6 @@ impl Display for Token {
 
 impl Display for Keyword {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Keyword::Select => write!(f, "Select"),
-            Keyword::Create => write!(f, "Create"),
-            Keyword::Table => write!(f, "Table"),
-            Keyword::Where => write!(f, "Where"),
-            Keyword::Order => write!(f, "Order"),
-            Keyword::By => write!(f, "By"),
-            Keyword::Asc => write!(f, "Asc"),
-            Keyword::Desc => write!(f, "Desc"),
-            Keyword::From => write!(f, "From"),
-            Keyword::And => write!(f, "And"),
-            Keyword::Or => write!(f, "Or"),
-            Keyword::Not => write!(f, "Not"),
-            Keyword::True => write!(f, "True"),
-            Keyword::False => write!(f, "False"),
-            Keyword::Primary => write!(f, "Primary"),
-            Keyword::Key => write!(f, "Key"),
-            Keyword::Check => write!(f, "Check"),
-            Keyword::Int => write!(f, "Int"),
-            Keyword::Bool => write!(f, "Bool"),
-            Keyword::Varchar => write!(f, "Varchar"),
-            Keyword::Null => write!(f, "Null"),
-        }
+        write!(f, "{}", self.name())
     }
 }
\ No newline at end of file
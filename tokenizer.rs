@@ -1,183 +1,785 @@
-use crate::token::{Token, Keyword};
-use std::str::Chars;
-use std::iter::Peekable;
-
-pub struct Tokenizer<'a> {
-    input: Peekable<Chars<'a>>,
-}
-
-impl<'a> Tokenizer<'a> {
-    //constructor
-    //make new tokenizer by turning the input string into a peekable character iterator
-    pub fn new(input: &'a str) -> Self {
-        Tokenizer {
-            input: input.chars().peekable(),
-        }
-    }
-
-    //read characters and returns the next token
-    fn next_token(&mut self) -> Token {
-        while let Some(&ch) = self.input.peek() {
-            match ch {
-                //skip whitespace
-                ' ' | '\n' | '\t' | '\r' => {
-                    self.input.next();
-                }
-
-                //single character tokens
-                '+' => return self.consume_single(Token::Plus),
-                '-' => return self.consume_single(Token::Minus),
-                '*' => return self.consume_single(Token::Star),
-                '/' => return self.consume_single(Token::Divide),
-                '(' => return self.consume_single(Token::LeftParentheses),
-                ')' => return self.consume_single(Token::RightParentheses),
-                ',' => return self.consume_single(Token::Comma),
-                ';' => return self.consume_single(Token::Semicolon),
-                '=' => return self.consume_single(Token::Equal),
-
-                //two-character tokens
-                '>' => {
-                    self.input.next();
-                    if self.consume_if('=') {
-                        return Token::GreaterThanOrEqual;
-                    }
-                    return Token::GreaterThan;
-                }
-
-                '<' => {
-                    self.input.next();
-                    if self.consume_if('=') {
-                        return Token::LessThanOrEqual;
-                    }
-                    return Token::LessThan;
-                }
-
-                '!' => {
-                    self.input.next();
-                    if self.consume_if('=') {
-                        return Token::NotEqual;
-                    }
-                    return Token::Invalid('!');
-                }
-
-                // String literals
-                '"' | '\'' => return self.read_string(),
-
-                // Numbers
-                ch if ch.is_ascii_digit() => return self.read_number(),
-
-                // Identifiers or Keywords
-                ch if ch.is_ascii_alphabetic() || ch == '_' => return self.read_word(),
-
-                _ => {
-                    self.input.next();
-                    return Token::Invalid(ch);
-                }
-            }
-        }
-
-        Token::Eof
-    }
-
-    //helper, used for simple one-character tokens
-    fn consume_single(&mut self, token: Token) -> Token {
-        self.input.next();
-        token
-    }
-
-    //helper, used to check if the next character matches expected
-    fn consume_if(&mut self, expected: char) -> bool {
-        if self.input.peek() == Some(&expected) {
-            self.input.next();
-            true
-        } else {
-            false
-        }
-    }
-
-    //helper, read a sequence of digits and returns number token
-    fn read_number(&mut self) -> Token {
-        let mut number = String::new();
-        while let Some(&ch) = self.input.peek() {
-            if ch.is_ascii_digit() {
-                number.push(ch);
-                self.input.next();
-            } else {
-                break;
-            }
-        }
-
-        Token::Number(number.parse::<u64>().unwrap())
-    }
-
-    //helper, reads string enclosed in matching quotes
-    fn read_string(&mut self) -> Token {
-        let quote = self.input.next().unwrap(); //opening quote
-        let mut content = String::new();
-
-        while let Some(&ch) = self.input.peek() {
-            if ch == quote {
-                self.input.next(); // closing quote
-                return Token::String(content);
-            } else {
-                content.push(ch);
-                self.input.next();
-            }
-        }
-
-        //reached end without closing quote
-        Token::Invalid(quote)
-    }
-
-    //helper, reads a word consisting of letters/digits/underscores
-    fn read_word(&mut self) -> Token {
-        let mut word = String::new();
-        while let Some(&ch) = self.input.peek() {
-            if ch.is_ascii_alphanumeric() || ch == '_' {
-                word.push(ch);
-                self.input.next();
-            } else {
-                break;
-            }
-        }
-
-        match word.to_uppercase().as_str() {
-            "SELECT" => Token::Keyword(Keyword::Select),
-            "FROM" => Token::Keyword(Keyword::From),
-            "WHERE" => Token::Keyword(Keyword::Where),
-            "CREATE" => Token::Keyword(Keyword::Create),
-            "TABLE" => Token::Keyword(Keyword::Table),
-            "ORDER" => Token::Keyword(Keyword::Order),
-            "BY" => Token::Keyword(Keyword::By),
-            "ASC" => Token::Keyword(Keyword::Asc),
-            "DESC" => Token::Keyword(Keyword::Desc),
-            "AND" => Token::Keyword(Keyword::And),
-            "OR" => Token::Keyword(Keyword::Or),
-            "NOT" => Token::Keyword(Keyword::Not),
-            "TRUE" => Token::Keyword(Keyword::True),
-            "FALSE" => Token::Keyword(Keyword::False),
-            "PRIMARY" => Token::Keyword(Keyword::Primary),
-            "KEY" => Token::Keyword(Keyword::Key),
-            "CHECK" => Token::Keyword(Keyword::Check),
-            "INT" => Token::Keyword(Keyword::Int),
-            "BOOL" => Token::Keyword(Keyword::Bool),
-            "VARCHAR" => Token::Keyword(Keyword::Varchar),
-            "NULL" => Token::Keyword(Keyword::Null),
-            _ => Token::Identifier(word),
-        }
-    }
-}
-
-//making tokenizer an iterator
-impl<'a> Iterator for Tokenizer<'a> {
-    type Item = Token;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let token = self.next_token();
-        if token == Token::Eof {
-            None // signal that iteration is finished
-        } else {
-            Some(token)
-        }
-    }
-}
\ No newline at end of file
+use crate::token::{Token, Keyword};
+use crate::dialect::Dialect;
+use std::fmt;
+use std::str;
+
+/// Turns SQL text into tokens. Iterating a `Tokenizer` directly yields bare `Token`s, lexical errors included as `Token::Invalid`, which is what the parser consumes. `spanned()` turns it into an iterator of `Result<SpannedToken, LexError>` that also tells where each token is in the input, for tools such as highlighters or statement splitters that work on tokens without parsing.
+///
+/// ```
+/// let tokens: Vec<_> = Tokenizer::new("SELECT a\nFROM t").spanned().collect();
+/// //Ok(SpannedToken { token: Keyword(Select), span: Span { start: 0, end: 6, line: 1, column: 1 } })
+/// //Ok(SpannedToken { token: Identifier("a"), span: Span { start: 7, end: 8, line: 1, column: 8 } })
+/// //Ok(SpannedToken { token: Keyword(From), span: Span { start: 9, end: 13, line: 2, column: 1 } })
+/// //...
+/// ```
+pub struct Tokenizer<'a> {
+    input: Cursor<'a>,
+    dialect: Dialect,
+    keep_hints: bool,
+    keep_comments: bool,
+    //where the input is in the file it was taken from, added to every span
+    origin: Origin,
+    //where the token being read starts, after any whitespace and comments before it
+    start: Position,
+    //description of the last Invalid token, reported by the spanned iterator
+    error: Option<String>,
+}
+
+/// Location of a token in the input. `start` and `end` are byte offsets (`&sql[span.start..span.end]` is the token's text), `line` and `column` are where the token starts, both counting from 1, columns in characters.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Where SQL taken out of a larger file starts in that file, such as the text of a string literal in a Rust source file: `offset` is the byte offset of the first character of the SQL in the host file, `line` and `column` its line and column there, both counting from 1. A tokenizer given an origin reports every span in host file coordinates, so diagnostics point into the host file rather than into the extracted text. The mapping assumes the SQL is the host text as it is, byte for byte, which holds for string literals without escapes in them. The default origin is the start of a file, where spans stay as they are.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Origin {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Origin {
+    pub fn new(offset: usize, line: usize, column: usize) -> Self {
+        Origin { offset, line, column }
+    }
+
+    /// The location in the host file of a span of the extracted SQL: offsets move by `offset` and lines by `line - 1`; columns move by `column - 1` on the first line of the SQL only, since every later line of it starts at the start of a host file line.
+    pub fn map(&self, span: Span) -> Span {
+        Span {
+            start: span.start + self.offset,
+            end: span.end + self.offset,
+            line: span.line + self.line - 1,
+            column: if span.line == 1 { span.column + self.column - 1 } else { span.column },
+        }
+    }
+}
+
+impl Default for Origin {
+    fn default() -> Self {
+        Origin::new(0, 1, 1)
+    }
+}
+
+/// A token together with its location in the input.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+/// Input that does not form a token, such as an unterminated string or a character that is not part of SQL. `span` covers the offending text.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LexError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.span.line, self.span.column)
+    }
+}
+
+//offset, line and column of a character in the input
+#[derive(Clone, Copy)]
+struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+//character iterator over UTF-8 bytes that keeps track of its position
+//a byte that is not part of valid UTF-8 is read as the character with the same code (U+0080 to U+00FF)
+#[derive(Clone)]
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: Position,
+}
+
+impl<'a> Cursor<'a> {
+    //helper, the next character and the number of bytes it takes, None at the end of the input
+    fn decode(&self) -> Option<(char, usize)> {
+        let rest = &self.bytes[self.position.offset..];
+        let first = *rest.first()?;
+        let prefix = &rest[..rest.len().min(4)];
+        let valid = match str::from_utf8(prefix) {
+            Ok(text) => text,
+            Err(err) => str::from_utf8(&prefix[..err.valid_up_to()]).unwrap(),
+        };
+        match valid.chars().next() {
+            Some(ch) => Some((ch, ch.len_utf8())),
+            None => Some((first as char, 1)),
+        }
+    }
+
+    //true when the next character is an invalid UTF-8 byte rather than a decoded character
+    fn at_invalid_byte(&self) -> bool {
+        match self.decode() {
+            Some((ch, 1)) => !ch.is_ascii(),
+            _ => false,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.decode().map(|(ch, _)| ch)
+    }
+
+    //the character n positions ahead, peek_nth(0) is peek()
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        let mut ahead = self.clone();
+        for _ in 0..n {
+            ahead.next()?;
+        }
+        ahead.peek()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let (ch, len) = self.decode()?;
+        self.position.offset += len;
+        if ch == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn next_if(&mut self, f: impl Fn(&char) -> bool) -> Option<char> {
+        match self.peek() {
+            Some(ch) if f(&ch) => self.next(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Tokenizer<'a> {
+    //constructor
+    //make new tokenizer by turning the input string into a peekable character iterator
+    pub fn new(input: &'a str) -> Self {
+        Self::with_dialect(input, Dialect::default())
+    }
+
+    //make new tokenizer that follows the lexical rules of the given dialect
+    pub fn with_dialect(input: &'a str, dialect: Dialect) -> Self {
+        Self::from_bytes(input.as_bytes(), dialect)
+    }
+
+    /// Makes a tokenizer for raw bytes that are mostly UTF-8 but may not be valid, such as old dumps with Latin-1 text in string literals. Instead of rejecting the whole input, every byte that is not part of valid UTF-8 is read as the character with the same code, so inside string literals and quoted identifiers Latin-1 text comes through unchanged (`0xE9` reads as `é`). Anywhere else such a byte is an `Invalid` token.
+    pub fn from_bytes(input: &'a [u8], dialect: Dialect) -> Self {
+        let start = Position { offset: 0, line: 1, column: 1 };
+        Tokenizer {
+            input: Cursor { bytes: input, position: start },
+            dialect,
+            keep_hints: false,
+            keep_comments: false,
+            origin: Origin::default(),
+            start,
+            error: None,
+        }
+    }
+
+    //turn /*+ ... */ comments into Hint tokens instead of skipping them like other comments
+    pub fn keep_hints(mut self, keep: bool) -> Self {
+        self.keep_hints = keep;
+        self
+    }
+
+    //turn other comments into Comment tokens instead of skipping them, for tools that read directives in comments
+    //the parser ignores Comment tokens
+    pub fn keep_comments(mut self, keep: bool) -> Self {
+        self.keep_comments = keep;
+        self
+    }
+
+    /// Reports spans relative to where the input was taken from, see `Origin`.
+    pub fn origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// All tokens of the input, ready for the parser, or the first lexical error with its location.
+    pub fn tokenize(self) -> Result<Vec<Token>, LexError> {
+        self.spanned().map(|result| result.map(|spanned| spanned.token)).collect()
+    }
+
+    /// Tokens with their locations, or a `LexError` for input that does not form a token. Lexing carries on after an error, so all errors in the input are reported.
+    pub fn spanned(self) -> SpannedTokens<'a> {
+        SpannedTokens { tokenizer: self }
+    }
+
+    //helper, an Invalid token for `ch` with a description of what is wrong
+    fn invalid(&mut self, ch: char, message: String) -> Token {
+        self.error = Some(message);
+        Token::Invalid(ch)
+    }
+
+    //helper, an Invalid token for a character that does not start any token
+    fn unexpected(&mut self, ch: char) -> Token {
+        self.invalid(ch, format!("Unexpected character {:?}", ch))
+    }
+
+    //read characters and returns the next token
+    fn next_token(&mut self) -> Token {
+        self.error = None;
+        while let Some(ch) = self.input.peek() {
+            self.start = self.input.position;
+            match ch {
+                //skip whitespace
+                ' ' | '\n' | '\t' | '\r' => {
+                    self.input.next();
+                }
+
+                //single character tokens
+                '+' => return self.consume_single(Token::Plus),
+                '*' => return self.consume_single(Token::Star),
+                '/' => {
+                    self.input.next();
+                    if !self.consume_if('*') {
+                        return Token::Divide;
+                    }
+                    if let Some(token) = self.read_block_comment() {
+                        return token;
+                    }
+                }
+                '(' => return self.consume_single(Token::LeftParentheses),
+                ')' => return self.consume_single(Token::RightParentheses),
+                ',' => return self.consume_single(Token::Comma),
+                //.5 is a number, any other . separates the parts of a qualified name
+                '.' if self.input.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) => return self.read_number(),
+                '.' => return self.consume_single(Token::Period),
+                ';' => return self.consume_single(Token::Semicolon),
+                '=' => return self.consume_single(Token::Equal),
+
+                //two-character tokens
+                '>' => {
+                    self.input.next();
+                    if self.consume_if('=') {
+                        return Token::GreaterThanOrEqual;
+                    }
+                    return Token::GreaterThan;
+                }
+
+                '<' => {
+                    self.input.next();
+                    if self.consume_if('=') {
+                        return Token::LessThanOrEqual;
+                    }
+                    if self.consume_if('@') {
+                        return Token::ArrowAt;
+                    }
+                    return Token::LessThan;
+                }
+
+                //json operators
+                '-' => {
+                    self.input.next();
+                    //line comment, skipped up to the end of the line
+                    if self.consume_if('-') {
+                        let mut text = String::new();
+                        while let Some(c) = self.input.next_if(|&c| c != '\n') {
+                            text.push(c);
+                        }
+                        if self.keep_comments {
+                            return Token::Comment { raw: format!("--{}", text.trim_end()), text: text.trim().to_string() };
+                        }
+                        continue;
+                    }
+                    if self.consume_if('>') {
+                        if self.consume_if('>') {
+                            return Token::LongArrow;
+                        }
+                        return Token::Arrow;
+                    }
+                    return Token::Minus;
+                }
+
+                '#' => {
+                    self.input.next();
+                    if self.consume_if('>') {
+                        if self.consume_if('>') {
+                            return Token::HashLongArrow;
+                        }
+                        return Token::HashArrow;
+                    }
+                    return self.unexpected('#');
+                }
+
+                '@' => {
+                    self.input.next();
+                    if self.consume_if('>') {
+                        return Token::AtArrow;
+                    }
+                    return self.unexpected('@');
+                }
+
+                '!' => {
+                    self.input.next();
+                    if self.consume_if('=') {
+                        return Token::NotEqual;
+                    }
+                    if self.consume_if('~') {
+                        if self.consume_if('*') {
+                            return Token::NotTildeStar;
+                        }
+                        return Token::NotTilde;
+                    }
+                    return self.unexpected('!');
+                }
+
+                '|' => {
+                    self.input.next();
+                    if self.consume_if('|') {
+                        return Token::DoublePipe;
+                    }
+                    return self.unexpected('|');
+                }
+
+                //query parameters, ? and numbered $1, $2, ...
+                '?' => return self.consume_single(Token::Placeholder("?".to_string())),
+                '$' => {
+                    self.input.next();
+                    if self.dialect.dollar_quoted_strings() && matches!(self.input.peek(), Some(ch) if ch == '$' || ch == '_' || ch.is_ascii_alphabetic()) {
+                        return self.read_dollar_string();
+                    }
+                    let mut text = "$".to_string();
+                    self.read_digits(&mut text);
+                    if text.len() == 1 {
+                        return self.unexpected('$');
+                    }
+                    return Token::Placeholder(text);
+                }
+
+                //regular expression match, ~* is the case insensitive one
+                '~' => {
+                    self.input.next();
+                    if self.consume_if('*') {
+                        return Token::TildeStar;
+                    }
+                    return Token::Tilde;
+                }
+
+                //quoted identifiers, never keywords
+                ch if ch == self.dialect.identifier_quote() => return self.read_quoted_identifier(),
+
+                // String literals
+                '"' | '\'' => return self.read_string(),
+
+                // Numbers
+                ch if ch.is_ascii_digit() => return self.read_number(),
+
+                // Identifiers or Keywords
+                ch if ch.is_ascii_alphabetic() || ch == '_' => return self.read_word(),
+
+                _ if self.input.at_invalid_byte() => {
+                    self.input.next();
+                    return self.invalid(ch, format!("Invalid UTF-8 byte 0x{:02X}", ch as u32));
+                }
+
+                _ => {
+                    self.input.next();
+                    return self.unexpected(ch);
+                }
+            }
+        }
+
+        self.start = self.input.position;
+        Token::Eof
+    }
+
+    //helper, used for simple one-character tokens
+    fn consume_single(&mut self, token: Token) -> Token {
+        self.input.next();
+        token
+    }
+
+    //helper, used to check if the next character matches expected
+    fn consume_if(&mut self, expected: char) -> bool {
+        if self.input.peek() == Some(expected) {
+            self.input.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    //helper, the rest of a comment after its opening /*
+    //returns a Hint token for a kept hint comment, a Comment token for other kept comments, None for a comment that is skipped
+    //in dialects with nested comments every /* inside needs a */ of its own, and both stay part of the text
+    fn read_block_comment(&mut self) -> Option<Token> {
+        let hint = self.consume_if('+');
+        let mut text = String::new();
+        let mut depth = 0;
+        loop {
+            match self.input.next() {
+                Some('*') if self.consume_if('/') => {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                    text.push_str("*/");
+                }
+                Some('/') if self.dialect.nested_comments() && self.consume_if('*') => {
+                    depth += 1;
+                    text.push_str("/*");
+                }
+                Some(ch) => text.push(ch),
+                None => return Some(self.invalid('/', "Unterminated block comment".to_string())),
+            }
+        }
+        if hint && self.keep_hints {
+            Some(Token::Hint(text.trim().to_string()))
+        } else if self.keep_comments {
+            let raw = format!("/*{}{}*/", if hint { "+" } else { "" }, text);
+            Some(Token::Comment { text: text.trim().to_string(), raw })
+        } else {
+            None
+        }
+    }
+
+    //helper, a dollar-quoted string, $$...$$ or $tag$...$tag$, the first $ is already consumed
+    //nothing inside is an escape, the string ends where its opening delimiter comes again
+    fn read_dollar_string(&mut self) -> Token {
+        let mut tag = "$".to_string();
+        while let Some(ch) = self.input.peek().filter(|ch| ch.is_ascii_alphanumeric() || *ch == '_') {
+            tag.push(ch);
+            self.input.next();
+        }
+        if !self.consume_if('$') {
+            return self.unexpected('$');
+        }
+        tag.push('$');
+        let mut value = String::new();
+        while !value.ends_with(&tag) {
+            match self.input.next() {
+                Some(ch) => value.push(ch),
+                None => return self.invalid('$', "Unterminated dollar-quoted string".to_string()),
+            }
+        }
+        value.truncate(value.len() - tag.len());
+        let raw = format!("{}{}{}", tag, value, tag);
+        Token::String { value, raw }
+    }
+
+    //helper, read a sequence of digits and returns number token
+    //a fraction or an exponent makes it a decimal, kept as text
+    fn read_number(&mut self) -> Token {
+        let mut number = String::new();
+        self.read_digits(&mut number);
+        let mut decimal = false;
+        if self.input.peek() == Some('.') {
+            decimal = true;
+            number.push('.');
+            self.input.next();
+            self.read_digits(&mut number);
+        }
+        //an exponent needs digits, otherwise the e starts the next word
+        if let Some(e @ ('e' | 'E')) = self.input.peek() {
+            let digits_at = match self.input.peek_nth(1) {
+                Some('+') | Some('-') => 2,
+                _ => 1,
+            };
+            if self.input.peek_nth(digits_at).is_some_and(|c| c.is_ascii_digit()) {
+                decimal = true;
+                number.push(e);
+                self.input.next();
+                if digits_at == 2 {
+                    number.push(self.input.next().unwrap());
+                }
+                self.read_digits(&mut number);
+            }
+        }
+        //an integer too large for u64 is kept as text like a decimal, so no number is out of range
+        match number.parse::<u64>() {
+            Ok(n) if !decimal => Token::Number(n),
+            _ => Token::Decimal(number),
+        }
+    }
+
+    //helper, appends the digits at the current position to `number`
+    fn read_digits(&mut self, number: &mut String) {
+        while let Some(ch) = self.input.next_if(|c| c.is_ascii_digit()) {
+            number.push(ch);
+        }
+    }
+
+    //helper, reads string enclosed in matching quotes
+    fn read_string(&mut self) -> Token {
+        match self.read_quoted(self.dialect.backslash_escapes()) {
+            Ok((value, raw)) => Token::String { value, raw },
+            Err(invalid) => invalid,
+        }
+    }
+
+    //helper, reads an identifier between the quote characters of the dialect, doubled quotes stand for one
+    fn read_quoted_identifier(&mut self) -> Token {
+        let quote = self.dialect.identifier_quote();
+        match self.read_quoted(false) {
+            Ok((value, _)) if !value.is_empty() => Token::Identifier(value),
+            Ok(_) => self.invalid(quote, "Empty quoted identifier".to_string()),
+            Err(invalid) => invalid,
+        }
+    }
+
+    //helper, reads a string literal with a one letter prefix: N'national', E'escape', B'0101' or X'1F'
+    fn read_prefixed_string(&mut self, prefix: char) -> Token {
+        let kind = prefix.to_ascii_uppercase();
+        //E strings always decode backslash escapes, bit and hex strings never contain any
+        let escapes = match kind {
+            'E' => true,
+            'N' => self.dialect.backslash_escapes(),
+            _ => false,
+        };
+        let (value, raw) = match self.read_quoted(escapes) {
+            Ok((value, raw)) => (value, format!("{}{}", prefix, raw)),
+            Err(invalid) => return invalid,
+        };
+
+        match kind {
+            'N' => Token::NationalString { value, raw },
+            'E' => Token::EscapeString { value, raw },
+            'B' if value.chars().all(|c| c == '0' || c == '1') => Token::BitString { value, raw },
+            'X' if value.chars().all(|c| c.is_ascii_hexdigit()) => Token::HexString { value, raw },
+            'B' => self.invalid(prefix, format!("Invalid bit string {}, only 0 and 1 are allowed", raw)),
+            _ => self.invalid(prefix, format!("Invalid hex string {}, only hexadecimal digits are allowed", raw)),
+        }
+    }
+
+    //helper, reads the characters between matching quotes
+    //returns the decoded value and the raw lexeme including quotes, or an Invalid token
+    fn read_quoted(&mut self, escapes: bool) -> Result<(String, String), Token> {
+        let quote = self.input.next().unwrap(); //opening quote
+        let mut value = String::new();
+        let mut raw = quote.to_string();
+        let mut bad_escape = false;
+
+        while let Some(ch) = self.input.next() {
+            raw.push(ch);
+            if ch == quote {
+                //two quotes in a row stand for one quote character
+                if self.consume_if(quote) {
+                    raw.push(quote);
+                    value.push(quote);
+                    continue;
+                }
+                if bad_escape {
+                    return Err(self.invalid('\\', "Invalid escape sequence".to_string()));
+                }
+                return Ok((value, raw)); //closing quote
+            } else if ch == '\\' && escapes {
+                match self.read_escape(&mut raw) {
+                    Some(decoded) => value.push_str(&decoded),
+                    None => bad_escape = true,
+                }
+            } else {
+                value.push(ch);
+            }
+        }
+
+        //reached end without closing quote
+        Err(self.invalid(quote, format!("Missing closing quote {}", quote)))
+    }
+
+    //helper, decodes the escape sequence after a backslash, None if it is malformed
+    fn read_escape(&mut self, raw: &mut String) -> Option<String> {
+        let ch = self.input.next()?;
+        raw.push(ch);
+        let decoded = match ch {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            '0' => '\0',
+            'Z' => '\u{1a}',
+            //kept with the backslash because they are LIKE pattern escapes
+            '%' | '_' => return Some(format!("\\{}", ch)),
+            'u' => {
+                let mut hex = String::new();
+                for _ in 0..4 {
+                    let digit = self.input.peek()?;
+                    if !digit.is_ascii_hexdigit() {
+                        return None;
+                    }
+                    hex.push(digit);
+                    raw.push(digit);
+                    self.input.next();
+                }
+                std::char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+            }
+            //any other character (quotes, backslash) stands for itself
+            other => other,
+        };
+        Some(decoded.to_string())
+    }
+
+    //helper, reads a word consisting of letters/digits/underscores
+    fn read_word(&mut self) -> Token {
+        let first = self.input.peek().unwrap();
+        if "NnEeBbXx".contains(first) {
+            self.input.next();
+            if self.input.peek() == Some('\'') {
+                return self.read_prefixed_string(first);
+            }
+            return self.finish_word(first.to_string());
+        }
+        self.finish_word(String::new())
+    }
+
+    //helper, reads the rest of a word whose beginning is already consumed
+    fn finish_word(&mut self, mut word: String) -> Token {
+        while let Some(ch) = self.input.peek() {
+            if ch.is_ascii_alphanumeric() || ch == '_' {
+                word.push(ch);
+                self.input.next();
+            } else {
+                break;
+            }
+        }
+
+        match Keyword::lookup(&word) {
+            Some(keyword) if keyword.in_dialect(self.dialect) => Token::Keyword(keyword),
+            _ => Token::Identifier(word),
+        }
+    }
+}
+
+//making tokenizer an iterator
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.next_token();
+        if token == Token::Eof {
+            None // signal that iteration is finished
+        } else {
+            Some(token)
+        }
+    }
+}
+
+/// Iterator over the tokens of a `Tokenizer` with their locations, see `Tokenizer::spanned`.
+pub struct SpannedTokens<'a> {
+    tokenizer: Tokenizer<'a>,
+}
+
+impl<'a> Iterator for SpannedTokens<'a> {
+    type Item = Result<SpannedToken, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.tokenizer.next_token();
+        let start = self.tokenizer.start;
+        let span = self.tokenizer.origin.map(Span {
+            start: start.offset,
+            end: self.tokenizer.input.position.offset,
+            line: start.line,
+            column: start.column,
+        });
+        match token {
+            Token::Eof => None,
+            Token::Invalid(_) => {
+                let message = self.tokenizer.error.take().unwrap_or_else(|| "Invalid token".to_string());
+                Some(Err(LexError { message, span }))
+            }
+            token => Some(Ok(SpannedToken { token, span })),
+        }
+    }
+}
+
+/// Tells the semicolons that end a statement from the ones inside the BEGIN ... END body of a `CREATE TRIGGER ... FOR EACH ROW`, which belong to the trigger, so every tool that chunks a script splits it where the parser does. It is given the tokens of a script in order, comments left out.
+#[derive(Debug, Clone, Default)]
+pub struct StatementEnds {
+    //how many tokens of the statement have been seen, whether they start with CREATE TRIGGER, and how much of FOR EACH
+    //the last ones were
+    length: usize,
+    create_trigger: bool,
+    for_each: usize,
+    trigger_body: bool,
+    //the BEGIN ... END blocks of the trigger body that are open
+    block: usize,
+}
+
+impl StatementEnds {
+    /// Takes the next token of the script and the one after it (`Token::Eof` at the end), true when it is a semicolon that ends a statement.
+    pub fn ends_statement(&mut self, tok: &Token, next: &Token) -> bool {
+        if self.trigger_body {
+            self.block = tok.block_depth(self.block, next);
+        }
+        match tok {
+            Token::Semicolon if self.block == 0 => {
+                *self = StatementEnds::default();
+                return true;
+            }
+            Token::Keyword(Keyword::Row) if !self.trigger_body => self.trigger_body = self.create_trigger && self.for_each == 2,
+            _ => {}
+        }
+        self.create_trigger = match self.length {
+            0 => *tok == Token::Keyword(Keyword::Create),
+            1 => self.create_trigger && *tok == Token::Keyword(Keyword::Trigger),
+            _ => self.create_trigger,
+        };
+        self.for_each = match tok {
+            Token::Keyword(Keyword::For) => 1,
+            Token::Keyword(Keyword::Each) if self.for_each == 1 => 2,
+            _ => 0,
+        };
+        self.length += 1;
+        false
+    }
+}
+
+/// Splits a script into its statements without parsing them, for tools that only need to chunk scripts. Semicolons end a statement unless they are inside a string, a quoted identifier or a comment, which the tokenizer tells apart, or inside the body of a trigger (see `StatementEnds`). Every statement comes with its text (from its first token to its last, the `;` left out) and the `Span` of that text, so `&sql[span.start..span.end]` is the same text. Empty statements (`;;`) are skipped, text after the last semicolon is a statement of its own. Input that does not form a token stays part of the statement it is in, the parser reports it when the statement is parsed.
+pub fn split_statements(sql: &str) -> Vec<(&str, Span)> {
+    split_statements_with_dialect(sql, Dialect::default())
+}
+
+/// `split_statements` with the lexical rules of a dialect, which decide for example whether a backslash can escape a quote.
+pub fn split_statements_with_dialect(sql: &str, dialect: Dialect) -> Vec<(&str, Span)> {
+    let mut statements = Vec::new();
+    let mut current: Option<Span> = None;
+    let tokens: Vec<(Token, Span)> = Tokenizer::with_dialect(sql, dialect)
+        .keep_hints(true)
+        .spanned()
+        .map(|result| match result {
+            Ok(spanned) => (spanned.token, spanned.span),
+            Err(err) => (Token::Invalid(' '), err.span),
+        })
+        .collect();
+    let mut ends = StatementEnds::default();
+    for (i, (tok, span)) in tokens.iter().enumerate() {
+        if ends.ends_statement(tok, tokens.get(i + 1).map_or(&Token::Eof, |(next, _)| next)) {
+            statements.extend(current.take());
+        } else if let Some(current) = &mut current {
+            current.end = span.end;
+        } else {
+            current = Some(*span);
+        }
+    }
+    statements.extend(current);
+    statements.into_iter().map(|span| (&sql[span.start..span.end], span)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIGGER: &str = "CREATE TRIGGER t1_audit BEFORE INSERT ON t1 FOR EACH ROW BEGIN\n  IF NEW.a < 0 THEN\n    SET NEW.a = 0;\n  END IF;\n  SET NEW.b = NEW.a;\nEND;\nSELECT a FROM t1;";
+
+    #[test]
+    fn trigger_bodies_stay_in_their_statement() {
+        let statements = split_statements_with_dialect(TRIGGER, Dialect::MySql);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].0.ends_with("SET NEW.b = NEW.a;\nEND"));
+        assert_eq!(statements[1].0, "SELECT a FROM t1");
+    }
+
+    #[test]
+    fn semicolons_end_other_statements() {
+        let statements = split_statements("CREATE TRIGGER x BEFORE INSERT ON t1 FOR EACH STATEMENT EXECUTE f(); SELECT a FROM t1;; BEGIN;");
+        let texts: Vec<&str> = statements.iter().map(|(text, _)| *text).collect();
+        assert_eq!(texts, ["CREATE TRIGGER x BEFORE INSERT ON t1 FOR EACH STATEMENT EXECUTE f()", "SELECT a FROM t1", "BEGIN"]);
+    }
+}
@@ -0,0 +1,248 @@
+//vectorized evaluation for the engine: an expression is computed over a chunk of rows at a time, column by column, so its
+//tree is walked once per chunk rather than once per row and the operators run as plain loops over values of one type
+use crate::statement::{Expression, Literal, BinaryOperator, UnaryOperator};
+use crate::engine::binary;
+
+/// How many rows the engine evaluates at a time.
+pub const CHUNK_SIZE: usize = 1024;
+
+/// The values of a column or of an expression over the rows of a chunk, `None` standing for NULL. Values of one type are stored as that type so the operators can work on them directly, `Float` holds the results of arithmetic on decimals, and anything else (a column mixing types, decimals as written) is kept as `Values`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vector {
+    Integer(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Boolean(Vec<Option<bool>>),
+    Text(Vec<Option<String>>),
+    Values(Vec<Literal>),
+}
+
+impl Vector {
+    /// A column of values, typed when all of them that are not NULL have the same type.
+    pub fn from_values(values: Vec<Literal>) -> Vector {
+        let typed = |matches: fn(&Literal) -> bool| values.iter().all(|value| *value == Literal::Null || matches(value));
+        if typed(|value| matches!(value, Literal::Integer(_))) {
+            Vector::Integer(values.iter().map(|value| if let Literal::Integer(n) = value { Some(*n) } else { None }).collect())
+        } else if typed(|value| matches!(value, Literal::Boolean(_))) {
+            Vector::Boolean(values.iter().map(|value| if let Literal::Boolean(b) = value { Some(*b) } else { None }).collect())
+        } else if typed(|value| matches!(value, Literal::String(_))) {
+            Vector::Text(values.into_iter().map(|value| if let Literal::String(s) = value { Some(s) } else { None }).collect())
+        } else {
+            Vector::Values(values)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Vector::Integer(values) => values.len(),
+            Vector::Float(values) => values.len(),
+            Vector::Boolean(values) => values.len(),
+            Vector::Text(values) => values.len(),
+            Vector::Values(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The value of one row, as the engine has it when it evaluates row by row: a float is a decimal.
+    pub fn value(&self, index: usize) -> Literal {
+        match self {
+            Vector::Integer(values) => values[index].map_or(Literal::Null, Literal::Integer),
+            Vector::Float(values) => values[index].map_or(Literal::Null, |value| Literal::Decimal(value.to_string())),
+            Vector::Boolean(values) => values[index].map_or(Literal::Null, Literal::Boolean),
+            Vector::Text(values) => values[index].clone().map_or(Literal::Null, Literal::String),
+            Vector::Values(values) => values[index].clone(),
+        }
+    }
+
+    //the values as numbers, an error when one of them is not a number
+    fn floats(&self) -> Result<Vec<Option<f64>>, String> {
+        match self {
+            Vector::Integer(values) => Ok(values.iter().map(|value| value.map(|n| n as f64)).collect()),
+            Vector::Float(values) => Ok(values.clone()),
+            _ => (0..self.len()).map(|index| match self.value(index) {
+                Literal::Null => Ok(None),
+                Literal::Integer(n) => Ok(Some(n as f64)),
+                Literal::Decimal(digits) => digits.parse().map(Some).map_err(|_| format!("Invalid number {}", digits)),
+                other => Err(format!("Expected a number, found {}", other)),
+            }).collect(),
+        }
+    }
+
+    //the values as truth values, an error when one of them is not a boolean or NULL
+    fn booleans(&self) -> Result<Vec<Option<bool>>, String> {
+        match self {
+            Vector::Boolean(values) => Ok(values.clone()),
+            _ => (0..self.len()).map(|index| match self.value(index) {
+                Literal::Null => Ok(None),
+                other => Err(format!("Expected a boolean, found {}", other)),
+            }).collect(),
+        }
+    }
+}
+
+/// Whether `evaluate` can compute an expression: column references and constants combined by the arithmetic, comparison, logical and concatenation operators, `NOT`, signs, `IS [NOT] NULL` and `IS [NOT] DISTINCT FROM`. Subqueries, functions and parameters are left to the engine's row by row evaluation.
+pub fn vectorizable(expr: &Expression) -> bool {
+    match expr {
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) | Expression::Literal(_) => true,
+        Expression::Alias { expr, .. } => vectorizable(expr),
+        Expression::UnaryOperation { operand, operator } => !matches!(operator, UnaryOperator::Asc | UnaryOperator::Desc) && vectorizable(operand),
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            matches!(operator, BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Multiply | BinaryOperator::Divide
+                | BinaryOperator::Equal | BinaryOperator::NotEqual | BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual
+                | BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual | BinaryOperator::Concat)
+                && vectorizable(left_operand) && vectorizable(right_operand)
+        }
+        Expression::IsDistinctFrom { left_operand, right_operand, .. } => vectorizable(left_operand) && vectorizable(right_operand),
+        _ => false,
+    }
+}
+
+/// Computes an expression over a chunk of `len` rows: `column` gives the values of the column a column reference stands for. The values are the ones the engine computes row by row. Both sides of `AND` and `OR` are computed for every row though, so a chunk can fail on a row the engine would have skipped (`x != 0 AND 10 / x > 1`), the engine evaluates such a chunk again row by row.
+pub fn evaluate(expr: &Expression, len: usize, column: &dyn Fn(&Expression) -> Result<Vector, String>) -> Result<Vector, String> {
+    match expr {
+        Expression::Identifier(_) | Expression::CompoundIdentifier(_) => column(expr),
+        Expression::Literal(literal) => Ok(Vector::from_values(vec![literal.clone(); len])),
+        Expression::Alias { expr, .. } => evaluate(expr, len, column),
+        Expression::UnaryOperation { operand, operator } => unary(*operator, evaluate(operand, len, column)?),
+        Expression::BinaryOperation { left_operand, operator: operator @ (BinaryOperator::And | BinaryOperator::Or), right_operand } => {
+            let left = evaluate(left_operand, len, column)?.booleans()?;
+            let right = evaluate(right_operand, len, column)?.booleans()?;
+            //three-valued logic: the absorbing value wins (false for AND, true for OR), NULL otherwise makes it unknown
+            let absorbing = *operator == BinaryOperator::Or;
+            Ok(Vector::Boolean(left.iter().zip(&right).map(|(left, right)| match (left, right) {
+                (Some(value), _) | (_, Some(value)) if *value == absorbing => Some(absorbing),
+                (Some(_), Some(_)) => Some(!absorbing),
+                _ => None,
+            }).collect()))
+        }
+        Expression::BinaryOperation { left_operand, operator, right_operand } => {
+            binary_vector(*operator, &evaluate(left_operand, len, column)?, &evaluate(right_operand, len, column)?)
+        }
+        Expression::IsDistinctFrom { left_operand, right_operand, negated } => {
+            let (left, right) = (evaluate(left_operand, len, column)?, evaluate(right_operand, len, column)?);
+            let mut distinct = Vec::with_capacity(len);
+            for index in 0..len {
+                let value = match (left.value(index), right.value(index)) {
+                    (Literal::Null, Literal::Null) => false,
+                    (Literal::Null, _) | (_, Literal::Null) => true,
+                    (left, right) => binary(BinaryOperator::NotEqual, &left, &right)? == Literal::Boolean(true),
+                };
+                distinct.push(Some(value != *negated));
+            }
+            Ok(Vector::Boolean(distinct))
+        }
+        other => Err(format!("{} can not be evaluated a chunk at a time", crate::formatter::format_expression(other))),
+    }
+}
+
+//a sign or NOT applied to every value, NULL stays NULL
+fn unary(operator: UnaryOperator, operand: Vector) -> Result<Vector, String> {
+    match (operator, operand) {
+        (UnaryOperator::Not, operand) => Ok(Vector::Boolean(operand.booleans()?.into_iter().map(|value| value.map(|b| !b)).collect())),
+        (UnaryOperator::Minus, Vector::Integer(values)) => {
+            let negated = values.into_iter().map(|value| value.map(|n| n.checked_neg().ok_or_else(|| "Integer out of range".to_string())).transpose());
+            Ok(Vector::Integer(negated.collect::<Result<_, _>>()?))
+        }
+        (UnaryOperator::Minus, operand) => Ok(Vector::Float(operand.floats()?.into_iter().map(|value| value.map(|n| -n)).collect())),
+        //a plus sign leaves a number as it is, decimals keep how they are written
+        (UnaryOperator::Plus, operand) => operand.floats().map(|_| operand),
+        //the only operators that give a value other than NULL for NULL
+        (UnaryOperator::IsNull, operand) => Ok(Vector::Boolean((0..operand.len()).map(|index| Some(operand.value(index) == Literal::Null)).collect())),
+        (UnaryOperator::IsNotNull, operand) => Ok(Vector::Boolean((0..operand.len()).map(|index| Some(operand.value(index) != Literal::Null)).collect())),
+        (_, _) => Err("ASC and DESC are only allowed in ORDER BY".to_string()),
+    }
+}
+
+//an operator other than AND and OR applied row by row to two vectors, with loops of their own for the common
+//cases (integers, numbers, strings) and the engine's operator on each pair of values for the rest
+fn binary_vector(operator: BinaryOperator, left: &Vector, right: &Vector) -> Result<Vector, String> {
+    let arithmetic = matches!(operator, BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Multiply | BinaryOperator::Divide);
+    match (left, right) {
+        (Vector::Integer(left), Vector::Integer(right)) if arithmetic => {
+            let mut result = Vec::with_capacity(left.len());
+            for (a, b) in left.iter().zip(right) {
+                result.push(match (a, b) {
+                    (Some(a), Some(b)) => Some(integer_arithmetic(operator, *a, *b)?),
+                    _ => None,
+                });
+            }
+            return Ok(Vector::Integer(result));
+        }
+        (Vector::Integer(left), Vector::Integer(right)) if !arithmetic && operator != BinaryOperator::Concat => {
+            return Ok(Vector::Boolean(left.iter().zip(right).map(|(a, b)| Some(comparison(operator, a.as_ref()?.cmp(b.as_ref()?)))).collect()));
+        }
+        (Vector::Text(left), Vector::Text(right)) if operator == BinaryOperator::Concat => {
+            return Ok(Vector::Text(left.iter().zip(right).map(|(a, b)| Some(format!("{}{}", a.as_ref()?, b.as_ref()?))).collect()));
+        }
+        (Vector::Text(left), Vector::Text(right)) if !arithmetic => {
+            return Ok(Vector::Boolean(left.iter().zip(right).map(|(a, b)| Some(comparison(operator, a.as_ref()?.cmp(b.as_ref()?)))).collect()));
+        }
+        _ => {}
+    }
+    //numbers of mixed kinds are computed and compared as floats, as the engine does
+    if let (false, Ok(left), Ok(right)) = (operator == BinaryOperator::Concat, left.floats(), right.floats()) {
+        if arithmetic {
+            let mut result = Vec::with_capacity(left.len());
+            for (a, b) in left.iter().zip(&right) {
+                result.push(match (a, b) {
+                    (Some(a), Some(b)) => Some(float_arithmetic(operator, *a, *b)?),
+                    _ => None,
+                });
+            }
+            return Ok(Vector::Float(result));
+        }
+        let mut result = Vec::with_capacity(left.len());
+        for (a, b) in left.iter().zip(&right) {
+            result.push(match (a, b) {
+                (Some(a), Some(b)) => Some(comparison(operator, a.partial_cmp(b).ok_or_else(|| "Numbers can not be compared".to_string())?)),
+                _ => None,
+            });
+        }
+        return Ok(Vector::Boolean(result));
+    }
+    let mut result = Vec::with_capacity(left.len());
+    for index in 0..left.len() {
+        result.push(match (left.value(index), right.value(index)) {
+            (Literal::Null, _) | (_, Literal::Null) => Literal::Null,
+            (a, b) => binary(operator, &a, &b)?,
+        });
+    }
+    Ok(Vector::from_values(result))
+}
+
+//helper, integer arithmetic as the engine does it, division truncating
+fn integer_arithmetic(operator: BinaryOperator, a: i64, b: i64) -> Result<i64, String> {
+    let result = match operator {
+        BinaryOperator::Plus => a.checked_add(b),
+        BinaryOperator::Minus => a.checked_sub(b),
+        BinaryOperator::Multiply => a.checked_mul(b),
+        _ if b == 0 => return Err("Division by zero".to_string()),
+        _ => a.checked_div(b),
+    };
+    result.ok_or_else(|| "Integer out of range".to_string())
+}
+
+fn float_arithmetic(operator: BinaryOperator, a: f64, b: f64) -> Result<f64, String> {
+    match operator {
+        BinaryOperator::Plus => Ok(a + b),
+        BinaryOperator::Minus => Ok(a - b),
+        BinaryOperator::Multiply => Ok(a * b),
+        _ if b == 0.0 => Err("Division by zero".to_string()),
+        _ => Ok(a / b),
+    }
+}
+
+//helper, whether a comparison operator holds for two values ordered as given
+fn comparison(operator: BinaryOperator, order: std::cmp::Ordering) -> bool {
+    use std::cmp::Ordering::*;
+    match operator {
+        BinaryOperator::Equal => order == Equal,
+        BinaryOperator::NotEqual => order != Equal,
+        BinaryOperator::LessThan => order == Less,
+        BinaryOperator::LessThanOrEqual => order != Greater,
+        BinaryOperator::GreaterThan => order == Greater,
+        _ => order != Less,
+    }
+}
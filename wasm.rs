@@ -0,0 +1,112 @@
+//entry points for a WebAssembly build (`--target wasm32-unknown-unknown --crate-type cdylib`), plain C functions so the
+//module can be used without generated bindings: the host copies the SQL into memory it got from `sqlparser_alloc`,
+//calls `sqlparser_parse` and reads the JSON it gets back as a NUL-terminated string, then frees both
+//the module owns every allocation it hands out and the host gives each one back exactly once, with the function
+//that matches the one that made it: `sqlparser_dealloc` with the same length for `sqlparser_alloc`, `sqlparser_free`
+//for `sqlparser_parse`. `sqlparser_parse` only reads the SQL, the host may free it as soon as the call returns
+use std::ffi::CString;
+use std::os::raw::c_char;
+use crate::tokenizer::Tokenizer;
+use crate::parser::Parser;
+use crate::dialect::Dialect;
+use crate::json::{self, JsonValue};
+use crate::statement::Statement;
+
+/// Reserves `len` bytes, set to zero, for the host to write into, released with `sqlparser_dealloc`. With `len` 0 the pointer is not null but there is nothing to write to.
+#[no_mangle]
+pub extern "C" fn sqlparser_alloc(len: usize) -> *mut u8 {
+    //a boxed slice is exactly `len` long, so the length is all sqlparser_dealloc needs to give it back
+    Box::into_raw(vec![0u8; len].into_boxed_slice()) as *mut u8
+}
+
+/// Releases memory reserved with `sqlparser_alloc`.
+///
+/// # Safety
+///
+/// `ptr` and `len` have to be the pointer `sqlparser_alloc` returned and the length it was called with, and the memory can not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sqlparser_dealloc(ptr: *mut u8, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+/// Parses the `sql_len` bytes of UTF-8 at `sql` in the dialect named by the `dialect_len` bytes at `dialect` (none, `dialect_len` 0, for the generic one). The result is the document of `json::to_document`, `{"schema_version": 1, "statements": [...]}`, or `{"error": "..."}`; it is released with `sqlparser_free`.
+///
+/// # Safety
+///
+/// `sql` and `dialect` have to point to at least `sql_len` and `dialect_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sqlparser_parse(sql: *const u8, sql_len: usize, dialect: *const u8, dialect_len: usize) -> *mut c_char {
+    let sql = std::slice::from_raw_parts(sql, sql_len);
+    let dialect = if dialect_len == 0 { &[][..] } else { std::slice::from_raw_parts(dialect, dialect_len) };
+    let result = match parse(sql, dialect) {
+        Ok(stmts) => json::to_document(&stmts),
+        Err(err) => JsonValue::object(vec![("error", JsonValue::String(err))]),
+    };
+    //the writer escapes control characters, so the text has no NUL in it
+    CString::new(result.to_pretty_string()).unwrap_or_default().into_raw()
+}
+
+/// Releases a string returned by `sqlparser_parse`.
+///
+/// # Safety
+///
+/// `ptr` has to be a pointer `sqlparser_parse` returned, and the string can not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sqlparser_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+//helper, the statements of a script
+fn parse(sql: &[u8], dialect: &[u8]) -> Result<Vec<Statement>, String> {
+    let sql = std::str::from_utf8(sql).map_err(|_| "The SQL text is not valid UTF-8".to_string())?;
+    let dialect = match std::str::from_utf8(dialect) {
+        Ok("") => Dialect::default(),
+        Ok(name) => Dialect::from_name(name).ok_or_else(|| format!("Unknown dialect: {:?}", name))?,
+        Err(_) => return Err("The dialect name is not valid UTF-8".to_string()),
+    };
+    let tokens = Tokenizer::with_dialect(sql, dialect).tokenize().map_err(|err| err.to_string())?;
+    Parser::with_dialect(tokens, dialect).parse_statements()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    //what a host does: copy the SQL in, parse, read the result, give both back
+    fn parse_as_host(sql: &str, dialect: &str) -> String {
+        let sql_ptr = sqlparser_alloc(sql.len());
+        let dialect_ptr = sqlparser_alloc(dialect.len());
+        unsafe {
+            std::ptr::copy_nonoverlapping(sql.as_ptr(), sql_ptr, sql.len());
+            std::ptr::copy_nonoverlapping(dialect.as_ptr(), dialect_ptr, dialect.len());
+            let result = sqlparser_parse(sql_ptr, sql.len(), dialect_ptr, dialect.len());
+            sqlparser_dealloc(sql_ptr, sql.len());
+            sqlparser_dealloc(dialect_ptr, dialect.len());
+            let text = CStr::from_ptr(result).to_str().unwrap().to_string();
+            sqlparser_free(result);
+            text
+        }
+    }
+
+    #[test]
+    fn parses_sql_the_host_wrote() {
+        let document = parse_as_host("SELECT a FROM t;", "postgres");
+        assert!(document.contains("\"schema_version\": 1"), "{}", document);
+        assert!(document.contains("\"Select\""), "{}", document);
+    }
+
+    #[test]
+    fn reports_errors_as_json() {
+        assert!(parse_as_host("SELECT a FROM;", "").contains("\"error\""));
+        assert!(parse_as_host("SELECT a FROM t;", "cobol").contains("Unknown dialect"));
+        assert!(parse_as_host("", "").contains("\"statements\": []"));
+    }
+
+    #[test]
+    fn freeing_null_is_allowed() {
+        unsafe { sqlparser_free(std::ptr::null_mut()) };
+    }
+}
@@ -0,0 +1,134 @@
+//reports over query logs: which query shapes are run most, which tables they use and which statements do not parse
+use std::cmp::Reverse;
+use crate::tokenizer::{Tokenizer, split_statements_with_dialect};
+use crate::token::Token;
+use crate::parser::Parser;
+use crate::dialect::Dialect;
+use crate::statement::{Statement, InsertSource};
+use crate::normalize::{normalize, ColumnOrder};
+use crate::analysis::{table_uses, TableAccess};
+use crate::formatter::{format_statement_with, Layout};
+
+/// Statements of a log that share a fingerprint.
+/// 1. `fingerprint` – The shape of the statements, see `fingerprint`.
+/// 2. `count` – How many statements of the log have it.
+/// 3. `first_line` – Line of the log where the first of them starts.
+#[derive(Debug, PartialEq)]
+pub struct Shape {
+    pub fingerprint: String,
+    pub count: usize,
+    pub first_line: usize,
+}
+
+/// How many statements of a log read a table and how many write it, a statement using a table several times counts once.
+#[derive(Debug, PartialEq)]
+pub struct TableCount {
+    pub name: String,
+    pub reads: usize,
+    pub writes: usize,
+}
+
+/// A statement of a log that did not parse, with the line it starts on, its text and the error.
+#[derive(Debug, PartialEq)]
+pub struct Failure {
+    pub line: usize,
+    pub text: String,
+    pub error: String,
+}
+
+/// Report over the statements of a log. `shapes` are ordered by count, most frequent first, `tables` by the number of statements using them, both with ties in order of first appearance; `failures` are in log order.
+#[derive(Debug, Default, PartialEq)]
+pub struct WorkloadReport {
+    pub statements: usize,
+    pub shapes: Vec<Shape>,
+    pub tables: Vec<TableCount>,
+    pub failures: Vec<Failure>,
+}
+
+/// The shape of a statement, the same for statements that differ only in their constants or in how they are written. The statement is normalized (see `normalize::normalize`), an `INSERT` keeps only its first row, and in the formatted text every number, string and parameter becomes `?` and names written without quotes are put in lower case: `select * from t where b = 'x' and a = 1` and `SELECT * FROM t WHERE a = 2 AND b = 'y'` both have the shape `SELECT * FROM t WHERE (a = ?) AND (b = ?);`.
+pub fn fingerprint(stmt: &Statement) -> String {
+    let mut stmt = stmt.clone();
+    normalize(&mut stmt, ColumnOrder::AsWritten);
+    if let Statement::Insert { source: InsertSource::Values(rows), .. } = &mut stmt {
+        rows.truncate(1);
+    }
+    let text = format_statement_with(&stmt, Layout::SingleLine);
+    let mut shape = String::new();
+    let mut copied = 0;
+    for spanned in Tokenizer::new(&text).spanned().flatten() {
+        match spanned.token {
+            Token::String { .. } | Token::NationalString { .. } | Token::EscapeString { .. } | Token::BitString { .. } | Token::HexString { .. } | Token::Number(_) | Token::Decimal(_) | Token::Placeholder(_) => {
+                shape.push_str(&text[copied..spanned.span.start]);
+                shape.push('?');
+                copied = spanned.span.end;
+            }
+            //names the formatter writes without quotes are compared case-insensitively, as most databases do
+            Token::Identifier(_) if !text[spanned.span.start..].starts_with('`') => {
+                shape.push_str(&text[copied..spanned.span.start]);
+                shape.push_str(&text[spanned.span.start..spanned.span.end].to_lowercase());
+                copied = spanned.span.end;
+            }
+            _ => {}
+        }
+    }
+    shape.push_str(&text[copied..]);
+    shape
+}
+
+/// Parses a log of statements one at a time, so a statement that does not parse is reported and the rest of the log is still read, and counts the shapes of the statements and the tables they use.
+pub fn analyze_workload(sql: &str, dialect: Dialect, keep_hints: bool) -> WorkloadReport {
+    let mut report = WorkloadReport::default();
+    for (text, span) in split_statements_with_dialect(sql, dialect) {
+        //the text of a statement leaves out its semicolon, which the parser wants
+        let tokens = Tokenizer::with_dialect(text, dialect).keep_hints(keep_hints).tokenize().map_err(|err| err.to_string()).map(|mut tokens| {
+            tokens.push(Token::Semicolon);
+            tokens
+        });
+        let stmts = match tokens.and_then(|tokens| Parser::with_dialect(tokens, dialect).parse_statements()) {
+            Ok(stmts) => stmts,
+            Err(error) => {
+                report.failures.push(Failure { line: span.line, text: text.to_string(), error });
+                continue;
+            }
+        };
+        for stmt in stmts {
+            report.statements += 1;
+            count_shape(&mut report.shapes, fingerprint(&stmt), span.line);
+            count_tables(&mut report.tables, &stmt);
+        }
+    }
+    //stable sorts, ties stay in order of first appearance
+    report.shapes.sort_by_key(|shape| Reverse(shape.count));
+    report.tables.sort_by_key(|table| Reverse(table.reads + table.writes));
+    report
+}
+
+fn count_shape(shapes: &mut Vec<Shape>, fingerprint: String, line: usize) {
+    match shapes.iter_mut().find(|shape| shape.fingerprint == fingerprint) {
+        Some(shape) => shape.count += 1,
+        None => shapes.push(Shape { fingerprint, count: 1, first_line: line }),
+    }
+}
+
+//table names are compared case-insensitively, the spelling of the first use is kept
+fn count_tables(tables: &mut Vec<TableCount>, stmt: &Statement) {
+    let mut seen: Vec<(String, TableAccess)> = Vec::new();
+    for table in table_uses(stmt) {
+        let key = (table.name.to_lowercase(), table.access);
+        if seen.contains(&key) {
+            continue;
+        }
+        let index = match tables.iter().position(|count| count.name.to_lowercase() == key.0) {
+            Some(index) => index,
+            None => {
+                tables.push(TableCount { name: table.name, reads: 0, writes: 0 });
+                tables.len() - 1
+            }
+        };
+        match table.access {
+            TableAccess::Read => tables[index].reads += 1,
+            TableAccess::Write => tables[index].writes += 1,
+        }
+        seen.push(key);
+    }
+}